@@ -0,0 +1,295 @@
+use crate::db::database::Database;
+use crate::db::json::decode_hex;
+use crate::db::table::core::value::{DataType, Value};
+use crate::interpreter::ast::{InsertIntoStatement, SqlStatement};
+use std::io::BufRead;
+
+// Reads CSV records one at a time, so a file never has to be loaded in full.
+// Fields may be quoted with double quotes, in which case they can contain commas,
+// newlines and doubled quotes.
+pub struct CsvReader<R: BufRead> {
+    reader: R,
+    // Lines read so far, and the line on which the last record started.
+    lines_read: usize,
+    line: usize,
+}
+
+impl<R: BufRead> CsvReader<R> {
+    pub fn new(reader: R) -> Self {
+        Self {
+            reader,
+            lines_read: 0,
+            line: 0,
+        }
+    }
+
+    // Line on which the last record returned by `next_record` started.
+    pub fn line(&self) -> usize {
+        self.line
+    }
+
+    // Returns the fields of the next record, or None at the end of the input. An empty
+    // unquoted field is returned as None, so it can be told apart from an empty string.
+    pub fn next_record(&mut self) -> Result<Option<Vec<Option<String>>>, String> {
+        let mut input = String::new();
+        if self.read_line(&mut input)? == 0 {
+            return Ok(None);
+        }
+        let start_line = self.lines_read;
+
+        let mut fields = vec![];
+        let mut field = String::new();
+        let mut quoted = false;
+        let mut in_quotes = false;
+        let mut chars = input.chars().collect::<Vec<char>>();
+        let mut i = 0;
+        loop {
+            let Some(&c) = chars.get(i) else {
+                if !in_quotes {
+                    break;
+                }
+                // A quoted field continues on the next line.
+                let mut next = String::new();
+                if self.read_line(&mut next)? == 0 {
+                    return Err(format!("Unterminated quoted field on line {}", start_line));
+                }
+                chars.extend(next.chars());
+                continue;
+            };
+            i += 1;
+            match c {
+                '"' if in_quotes && chars.get(i) == Some(&'"') => {
+                    field.push('"');
+                    i += 1;
+                }
+                '"' if in_quotes => in_quotes = false,
+                '"' if field.is_empty() && !quoted => {
+                    in_quotes = true;
+                    quoted = true;
+                }
+                ',' if !in_quotes => {
+                    fields.push(to_field(std::mem::take(&mut field), quoted));
+                    quoted = false;
+                }
+                '\n' | '\r' if !in_quotes => {}
+                c => field.push(c),
+            }
+        }
+        fields.push(to_field(field, quoted));
+        self.line = start_line;
+        Ok(Some(fields))
+    }
+
+    fn read_line(&mut self, buffer: &mut String) -> Result<usize, String> {
+        let read = self
+            .reader
+            .read_line(buffer)
+            .map_err(|error| format!("Error reading CSV: {}", error))?;
+        if read > 0 {
+            self.lines_read += 1;
+        }
+        Ok(read)
+    }
+}
+
+fn to_field(field: String, quoted: bool) -> Option<String> {
+    if field.is_empty() && !quoted {
+        None
+    } else {
+        Some(field)
+    }
+}
+
+// Imports a CSV whose first record holds the column names into an existing table.
+// Every field is converted to the type of its column, and the rows are inserted with
+// a single INSERT so a bad record leaves the table untouched.
+pub fn import_csv<R: BufRead>(
+    database: &mut Database,
+    reader: R,
+    table_name: &str,
+) -> Result<usize, String> {
+    let mut reader = CsvReader::new(reader);
+    let headers = reader
+        .next_record()?
+        .ok_or_else(|| "CSV file is empty".to_string())?
+        .into_iter()
+        .map(|header| header.unwrap_or_default().trim().to_string())
+        .collect::<Vec<String>>();
+
+    let table = database.get_table(table_name)?;
+    let mut data_types = vec![];
+    for header in headers.iter() {
+        if !table.has_column(header)? {
+            return Err(format!(
+                "Column `{}` does not exist in table `{}`",
+                header, table_name
+            ));
+        }
+        let index = table.get_index_of_column(header)?;
+        data_types.push(table.get_columns()?[index].data_type.clone());
+    }
+
+    let mut values = vec![];
+    while let Some(record) = reader.next_record()? {
+        // Blank lines carry no data.
+        if record == vec![None] {
+            continue;
+        }
+        if record.len() != headers.len() {
+            return Err(format!(
+                "Expected {} fields but found {} on line {}",
+                headers.len(),
+                record.len(),
+                reader.line()
+            ));
+        }
+        let mut row = vec![];
+        for ((field, header), data_type) in record.iter().zip(headers.iter()).zip(data_types.iter())
+        {
+            let value = match field {
+                Some(field) => field_to_value(field, data_type).ok_or_else(|| {
+                    format!(
+                        "Invalid value `{}` for column `{}` of type {:?} on line {}",
+                        field,
+                        header,
+                        data_type,
+                        reader.line()
+                    )
+                })?,
+                None => Value::Null,
+            };
+            row.push(value);
+        }
+        values.push(row);
+    }
+
+    let rows_imported = values.len();
+    if rows_imported > 0 {
+        database.execute(SqlStatement::InsertInto(InsertIntoStatement {
+            table_name: table_name.to_string(),
+            columns: Some(headers),
+            values,
+            last_insert_rowids: vec![],
+            returning: None,
+        }))?;
+    }
+    Ok(rows_imported)
+}
+
+// Converts a field to the type of its column. Blobs are written in hex, and columns declared
+// without a type take the most specific type the field can be read as.
+fn field_to_value(field: &str, data_type: &DataType) -> Option<Value> {
+    let text = Value::Text(field.to_string());
+    match data_type {
+        DataType::Integer | DataType::Real | DataType::Text => {
+            text.try_coerce_to(data_type.clone()).ok()
+        }
+        DataType::Blob => decode_hex(field.trim()).ok().map(Value::Blob),
+        DataType::Null => Some(
+            field_to_value(field, &DataType::Integer)
+                .or_else(|| field_to_value(field, &DataType::Real))
+                .unwrap_or_else(|| Value::Text(field.to_string())),
+        ),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::table::core::row::Row;
+    use crate::db::table::test_utils::default_database;
+    use crate::interpreter::run_sql;
+    use std::io::Cursor;
+
+    #[test]
+    fn csv_reader_handles_quoted_fields() {
+        let input = "a,\"b, c\",\"say \"\"hi\"\"\"\r\n,\"\",\"two\nlines\"\n";
+        let mut reader = CsvReader::new(Cursor::new(input));
+        assert_eq!(
+            reader.next_record(),
+            Ok(Some(vec![
+                Some("a".to_string()),
+                Some("b, c".to_string()),
+                Some("say \"hi\"".to_string()),
+            ]))
+        );
+        assert_eq!(
+            reader.next_record(),
+            Ok(Some(vec![
+                None,
+                Some("".to_string()),
+                Some("two\nlines".to_string()),
+            ]))
+        );
+        assert_eq!(reader.line(), 2);
+        assert_eq!(reader.next_record(), Ok(None));
+
+        let mut reader = CsvReader::new(Cursor::new("\"open"));
+        assert!(reader.next_record().is_err());
+    }
+
+    #[test]
+    fn import_csv_inserts_rows_into_the_table() {
+        let mut database = default_database();
+        let input = "id,name,money,age\n5,\"Smith, Jo\",5000,50\n6,,6000.5,\n";
+        let result = import_csv(&mut database, Cursor::new(input), "users");
+        assert_eq!(result, Ok(2));
+
+        let rows = run_sql(&mut database, "SELECT * FROM users WHERE id > 4;")
+            .pop()
+            .unwrap()
+            .unwrap()
+            .unwrap();
+        let expected = vec![
+            Row(vec![
+                Value::Integer(5),
+                Value::Text("Smith, Jo".to_string()),
+                Value::Integer(50),
+                Value::Real(5000.0),
+            ]),
+            Row(vec![
+                Value::Integer(6),
+                Value::Null,
+                Value::Null,
+                Value::Real(6000.5),
+            ]),
+        ];
+        assert_eq!(rows.len(), expected.len());
+        for (row, expected) in rows.iter().zip(expected.iter()) {
+            assert!(
+                row.iter()
+                    .zip(expected.iter())
+                    .all(|(value, expected)| value.exactly_equal(expected)),
+                "{:?}",
+                row
+            );
+        }
+    }
+
+    #[test]
+    fn import_csv_rejects_values_of_the_wrong_type() {
+        let mut database = default_database();
+        let input = "id,name\n5,Jo\nseven,Jim\n";
+        let result = import_csv(&mut database, Cursor::new(input), "users");
+        assert_eq!(
+            result,
+            Err("Invalid value `seven` for column `id` of type Integer on line 3".to_string())
+        );
+        assert_eq!(database.get_table("users").unwrap().len(), 4);
+
+        let input = "id,email\n5,jo@example.com\n";
+        assert!(import_csv(&mut database, Cursor::new(input), "users").is_err());
+        let input = "id,name\n5\n";
+        assert_eq!(
+            import_csv(&mut database, Cursor::new(input), "users"),
+            Err("Expected 2 fields but found 1 on line 2".to_string())
+        );
+
+        // A field spanning lines doesn't shift the lines of the records after it.
+        let input = "id,name\n5,\"two\nlines\"\nseven,Jim\n";
+        assert_eq!(
+            import_csv(&mut database, Cursor::new(input), "users"),
+            Err("Invalid value `seven` for column `id` of type Integer on line 4".to_string())
+        );
+    }
+}
@@ -1,15 +1,35 @@
+pub mod csv;
+
 use crate::db;
-use crate::interpreter::run_sql;
+use crate::db::table::core::row::Row;
+use crate::interpreter::{has_statements, run_sql_each};
+use std::fs::File;
 use std::io;
 
+const DEFAULT_MAX_ROWS: usize = 1000;
+
+// Settings changed through meta-commands, kept for the lifetime of the CLI session.
+pub struct CliSettings {
+    // Maximum number of rows printed for a single result, 0 prints every row.
+    pub max_rows: usize,
+}
+
+impl Default for CliSettings {
+    fn default() -> Self {
+        Self {
+            max_rows: DEFAULT_MAX_ROWS,
+        }
+    }
+}
+
 pub fn cli(database: &mut db::database::Database) {
     clear_screen();
     println!("Welcome to the MollyCache CLI");
     let mut line_count = 1;
+    let mut settings = CliSettings::default();
 
     loop {
         print!("({:03}) > ", line_count);
-        line_count += 1;
 
         io::Write::flush(&mut io::stdout()).unwrap();
 
@@ -17,6 +37,12 @@ pub fn cli(database: &mut db::database::Database) {
         io::stdin().read_line(&mut input).unwrap();
         let input = input.trim();
 
+        // Blank and comment-only input is ignored and does not use up a line number
+        if !has_statements(input) {
+            continue;
+        }
+        line_count += 1;
+
         if input.eq_ignore_ascii_case("quit") || input.eq_ignore_ascii_case("exit") {
             break;
         }
@@ -27,23 +53,335 @@ pub fn cli(database: &mut db::database::Database) {
             continue;
         }
 
-        let results = run_sql(database, input);
-        for result in results {
-            if let Ok(Some(rows)) = result {
-                for row in rows {
-                    println!("{:?}", row);
-                }
-            } else if let Ok(None) = result {
-                println!("Executed Successfully");
-            } else {
-                println!("Error: {}", result.unwrap_err());
+        if input.starts_with('.') {
+            match run_meta_command(database, &mut settings, input) {
+                Ok(output) => println!("{}", output),
+                Err(error) => println!("Error: {}", error),
             }
+            continue;
+        }
+
+        for line in run_input(database, &settings, input) {
+            println!("{}", line);
         }
     }
 }
 
+// Runs the SQL statements of a line of input and returns the lines to print. INSERT, UPDATE
+// and DELETE report the number of rows they changed, also inside a transaction.
+pub fn run_input(
+    database: &mut db::database::Database,
+    settings: &CliSettings,
+    input: &str,
+) -> Vec<String> {
+    let mut lines = vec![];
+    run_sql_each(database, input, |result, changes| match (result, changes) {
+        (Ok(Some(rows)), _) => lines.extend(format_rows(&rows, settings.max_rows)),
+        (Ok(None), Some(changes)) => {
+            lines.push(format!("Executed Successfully ({} rows changed)", changes))
+        }
+        (Ok(None), None) => lines.push("Executed Successfully".to_string()),
+        (Err(error), _) => lines.push(format!("Error: {}", error)),
+    });
+    lines
+}
+
+// Meta-commands start with a dot and are handled by the CLI rather than the SQL interpreter.
+pub fn run_meta_command(
+    database: &mut db::database::Database,
+    settings: &mut CliSettings,
+    input: &str,
+) -> Result<String, String> {
+    let mut parts = input.split_whitespace();
+    let command = parts.next().unwrap_or_default();
+    let arguments = parts.collect::<Vec<&str>>();
+    match command {
+        ".json" => match arguments.as_slice() {
+            [table_name] => database.table_to_json(table_name),
+            _ => Err("Usage: .json <table>".to_string()),
+        },
+        ".import" => match arguments.as_slice() {
+            [file_name, table_name] => {
+                let file = File::open(file_name)
+                    .map_err(|error| format!("Cannot open {}: {}", file_name, error))?;
+                let rows = csv::import_csv(database, io::BufReader::new(file), table_name)?;
+                Ok(format!("Imported {} rows into {}", rows, table_name))
+            }
+            _ => Err("Usage: .import <file> <table>".to_string()),
+        },
+        ".dump" => match arguments.as_slice() {
+            [] => database.dump(),
+            _ => Err("Usage: .dump".to_string()),
+        },
+        ".tables" => match arguments.as_slice() {
+            [] => Ok(database.table_names_matching("%").join("\n")),
+            [pattern] => Ok(database.table_names_matching(pattern).join("\n")),
+            _ => Err("Usage: .tables [pattern]".to_string()),
+        },
+        ".schema" => match arguments.as_slice() {
+            [] => database.schema(),
+            _ => Err("Usage: .schema".to_string()),
+        },
+        ".dbinfo" => match arguments.as_slice() {
+            [] => database.memory_usage(),
+            _ => Err("Usage: .dbinfo".to_string()),
+        },
+        ".save" => match arguments.as_slice() {
+            [path] => {
+                database.save_to_file(path)?;
+                Ok(format!("Saved to {}", path))
+            }
+            _ => Err("Usage: .save <file>".to_string()),
+        },
+        // The loaded database replaces the current one, which is kept if loading fails.
+        ".load" => match arguments.as_slice() {
+            [path] => {
+                *database = db::database::Database::load_from_file(path)?;
+                Ok(format!("Loaded {}", path))
+            }
+            _ => Err("Usage: .load <file>".to_string()),
+        },
+        ".reset" => match arguments.as_slice() {
+            [] => {
+                database.clear()?;
+                Ok("Database reset".to_string())
+            }
+            _ => Err("Usage: .reset".to_string()),
+        },
+        ".maxrows" => match arguments.as_slice() {
+            [] => Ok(format!("maxrows: {}", settings.max_rows)),
+            [max_rows] => {
+                settings.max_rows = max_rows
+                    .parse::<usize>()
+                    .map_err(|_| format!("Invalid row limit: {}", max_rows))?;
+                Ok(format!("maxrows: {}", settings.max_rows))
+            }
+            _ => Err("Usage: .maxrows [N]".to_string()),
+        },
+        _ => Err(format!("Unknown command: {}", command)),
+    }
+}
+
+// Formats the rows of a result for display. Only the first `max_rows` rows are shown,
+// followed by a notice with the number of rows left out.
+pub fn format_rows(rows: &[Row], max_rows: usize) -> Vec<String> {
+    let shown = if max_rows == 0 {
+        rows.len()
+    } else {
+        rows.len().min(max_rows)
+    };
+    let mut lines = rows[..shown]
+        .iter()
+        .map(|row| format!("{:?}", row))
+        .collect::<Vec<String>>();
+    if shown < rows.len() {
+        lines.push(format!("... ({} more rows)", rows.len() - shown));
+    }
+    lines
+}
+
 fn clear_screen() {
     // Clear screen and move cursor to top-left
     print!("\x1B[2J\x1B[1;1H");
     io::Write::flush(&mut io::stdout()).unwrap();
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::table::test_utils::default_database;
+    use crate::interpreter::run_sql;
+
+    #[test]
+    fn blank_and_comment_only_input_has_no_statements() {
+        assert!(!has_statements(""));
+        assert!(!has_statements("   \t"));
+        assert!(!has_statements("-- note"));
+        assert!(!has_statements("--"));
+        assert!(!has_statements("/* note */ -- another note"));
+        assert!(has_statements("SELECT 1; -- note"));
+        assert!(has_statements(";"));
+    }
+
+    #[test]
+    fn json_meta_command_prints_the_table() {
+        let mut database = default_database();
+        let mut settings = CliSettings::default();
+        let output = run_meta_command(&mut database, &mut settings, ".json users");
+        assert_eq!(output, database.table_to_json("users"));
+        assert!(run_meta_command(&mut database, &mut settings, ".json").is_err());
+        assert!(run_meta_command(&mut database, &mut settings, ".json missing").is_err());
+        assert!(run_meta_command(&mut database, &mut settings, ".unknown").is_err());
+    }
+
+    #[test]
+    fn dump_meta_command_prints_the_database() {
+        let mut database = default_database();
+        let mut settings = CliSettings::default();
+        let output = run_meta_command(&mut database, &mut settings, ".dump");
+        assert_eq!(output, database.dump());
+        assert!(
+            output
+                .unwrap()
+                .contains("INSERT INTO users VALUES (4, NULL, 40, 4000.0);")
+        );
+        assert!(run_meta_command(&mut database, &mut settings, ".dump users").is_err());
+    }
+
+    #[test]
+    fn schema_meta_command_prints_the_tables() {
+        let mut database = default_database();
+        let mut settings = CliSettings::default();
+        let output = run_meta_command(&mut database, &mut settings, ".schema");
+        assert_eq!(output, database.schema());
+        assert!(output.unwrap().starts_with("CREATE TABLE users ("));
+        assert!(run_meta_command(&mut database, &mut settings, ".schema users").is_err());
+
+        let _ = run_sql(
+            &mut database,
+            "CREATE TABLE sales (id INTEGER, \"order\" TEXT);",
+        );
+        let output = run_meta_command(&mut database, &mut settings, ".schema").unwrap();
+        assert!(output.contains("CREATE TABLE sales (id INTEGER, \"order\" TEXT);"));
+    }
+
+    #[test]
+    fn dbinfo_meta_command_prints_memory_estimates() {
+        let mut database = default_database();
+        let mut settings = CliSettings::default();
+        let output = run_meta_command(&mut database, &mut settings, ".dbinfo").unwrap();
+        let users = database.get_table("users").unwrap().estimate_memory();
+        assert_eq!(
+            output,
+            format!("users: {} bytes\ntotal: {} bytes", users, users)
+        );
+        assert!(run_meta_command(&mut database, &mut settings, ".dbinfo users").is_err());
+
+        let _ = run_sql(&mut database, "INSERT INTO users (id) VALUES (5);");
+        assert!(database.get_table("users").unwrap().estimate_memory() > users);
+    }
+
+    #[test]
+    fn reset_meta_command_empties_the_database() {
+        let mut database = default_database();
+        let mut settings = CliSettings::default();
+        let output = run_meta_command(&mut database, &mut settings, ".reset");
+        assert_eq!(output, Ok("Database reset".to_string()));
+        assert!(database.tables.is_empty());
+
+        let _ = run_sql(&mut database, "CREATE TABLE users (id INTEGER); BEGIN;");
+        assert!(run_meta_command(&mut database, &mut settings, ".reset").is_err());
+        assert!(database.has_table("users"));
+    }
+
+    #[test]
+    fn maxrows_meta_command_updates_the_row_limit() {
+        let mut database = default_database();
+        let mut settings = CliSettings::default();
+        assert_eq!(settings.max_rows, 1000);
+        let output = run_meta_command(&mut database, &mut settings, ".maxrows 2");
+        assert_eq!(output, Ok("maxrows: 2".to_string()));
+        assert_eq!(settings.max_rows, 2);
+        let output = run_meta_command(&mut database, &mut settings, ".maxrows");
+        assert_eq!(output, Ok("maxrows: 2".to_string()));
+        assert!(run_meta_command(&mut database, &mut settings, ".maxrows -1").is_err());
+        assert!(run_meta_command(&mut database, &mut settings, ".maxrows 1 2").is_err());
+        assert_eq!(settings.max_rows, 2);
+    }
+
+    #[test]
+    fn format_rows_truncates_output_with_a_notice() {
+        let mut database = default_database();
+        let rows = run_sql(&mut database, "SELECT * FROM users;")
+            .remove(0)
+            .unwrap()
+            .unwrap();
+        assert_eq!(rows.len(), 4);
+
+        let lines = format_rows(&rows, 3);
+        assert_eq!(lines.len(), 4);
+        assert_eq!(lines[0], format!("{:?}", rows[0]));
+        assert_eq!(lines[3], "... (1 more rows)");
+
+        assert_eq!(format_rows(&rows, 4).len(), 4);
+        assert_eq!(format_rows(&rows, 0).len(), 4);
+        assert_eq!(format_rows(&rows, 1)[1], "... (3 more rows)");
+        assert!(format_rows(&[], 1).is_empty());
+    }
+
+    #[test]
+    fn run_input_reports_changed_rows_inside_a_transaction() {
+        let mut database = default_database();
+        let settings = CliSettings::default();
+        let input = "BEGIN; INSERT INTO users (id) VALUES (5), (6); SELECT changes(), total_changes(); \
+                     UPDATE users SET age = 50 WHERE id > 2; DELETE FROM users WHERE id = 1; \
+                     SELECT id FROM users WHERE id = 2; SELECT changes(), total_changes(); COMMIT;";
+        let lines = run_input(&mut database, &settings, input);
+        assert_eq!(
+            lines,
+            vec![
+                "Executed Successfully",
+                "Executed Successfully (2 rows changed)",
+                "Row([Integer(2), Integer(2)])",
+                "Executed Successfully (4 rows changed)",
+                "Executed Successfully (1 rows changed)",
+                "Row([Integer(2)])",
+                "Row([Integer(1), Integer(7)])",
+                "Executed Successfully",
+            ]
+        );
+        // The counts survive the commit and statements that do not change rows.
+        assert_eq!(database.changes, 1);
+        assert_eq!(database.total_changes, 7);
+
+        let lines = run_input(&mut database, &settings, "DELETE FROM users WHERE id = 1;");
+        assert_eq!(lines, vec!["Executed Successfully (0 rows changed)"]);
+        assert_eq!(database.changes, 0);
+        assert_eq!(database.total_changes, 7);
+    }
+
+    #[test]
+    fn save_and_load_meta_commands_replace_the_database() {
+        let mut database = default_database();
+        let mut settings = CliSettings::default();
+        let path = std::env::temp_dir().join(format!("mollycache_cli_{}.db", std::process::id()));
+        let path = path.to_str().unwrap();
+        let output = run_meta_command(&mut database, &mut settings, &format!(".save {}", path));
+        assert_eq!(output, Ok(format!("Saved to {}", path)));
+        let saved = database.dump();
+
+        let _ = run_sql(
+            &mut database,
+            "DROP TABLE users; CREATE TABLE other (id INTEGER);",
+        );
+        let output = run_meta_command(&mut database, &mut settings, &format!(".load {}", path));
+        assert_eq!(output, Ok(format!("Loaded {}", path)));
+        assert_eq!(database.dump(), saved);
+        assert!(!database.has_table("other"));
+
+        std::fs::remove_file(path).unwrap();
+        assert!(
+            run_meta_command(&mut database, &mut settings, &format!(".load {}", path)).is_err()
+        );
+        assert_eq!(database.dump(), saved);
+        assert!(run_meta_command(&mut database, &mut settings, ".save").is_err());
+        assert!(run_meta_command(&mut database, &mut settings, ".load").is_err());
+    }
+
+    #[test]
+    fn tables_meta_command_lists_matching_tables() {
+        let mut database = default_database();
+        let mut settings = CliSettings::default();
+        let _ = run_sql(
+            &mut database,
+            "CREATE TABLE orders (id INTEGER); CREATE TABLE user_roles (id INTEGER);",
+        );
+        let output = run_meta_command(&mut database, &mut settings, ".tables");
+        assert_eq!(output, Ok("orders\nuser_roles\nusers".to_string()));
+        let output = run_meta_command(&mut database, &mut settings, ".tables user%");
+        assert_eq!(output, Ok("user_roles\nusers".to_string()));
+        let output = run_meta_command(&mut database, &mut settings, ".tables missing");
+        assert_eq!(output, Ok(String::new()));
+        assert!(run_meta_command(&mut database, &mut settings, ".tables a b").is_err());
+    }
+}
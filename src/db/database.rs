@@ -1,15 +1,64 @@
-use crate::db::table::core::{row::Row, table::Table};
+use crate::db::json::JsonValue;
+use crate::db::table::core::column::{ColumnDefinition, ConflictAction, ConstraintType};
+use crate::db::table::core::{
+    row::Row,
+    table::Table,
+    value::{DataType, Value},
+};
+use crate::db::table::operations::helpers::common::get_returning_rows;
+use crate::db::table::operations::helpers::qualified_columns::{
+    strip_clause_qualifiers, table_qualifiers,
+};
+use crate::db::table::operations::helpers::string_functions::{like_match, quote};
+use crate::db::table::operations::insert::foreign_keys::check_foreign_keys;
 use crate::db::table::operations::{
-    alter_table, create_table, delete, drop_table, insert, select, update,
+    alter_table, create_index, create_table, delete, drop_index, drop_table, insert, select, update,
 };
 use crate::db::transactions::TransactionLog;
 use crate::db::transactions::{commit::commit_transaction, rollback::rollback_statement};
-use crate::interpreter::ast::{SqlStatement, TableAliases};
+use crate::interpreter::ast::{InsertIntoStatement, PragmaStatement, SqlStatement, TableAliases};
+use crate::interpreter::{parse_sql, quote_identifier};
 use std::collections::HashMap;
 
+// Outcome of running a single statement: the rows it returned, if any, or its error.
+pub type StatementResult = Result<Option<Vec<Row>>, String>;
+
+// Name of the read-only table describing the schema, see `Database::master_table`.
+pub const MASTER_TABLE_NAME: &str = "sqlite_master";
+
+// Name of the table `dump` writes the AUTOINCREMENT sequences into, as SQLite's
+// `sqlite_sequence`. Inserting `(table name, sequence)` rows sets them, it can't be read.
+pub const SEQUENCE_TABLE_NAME: &str = "sqlite_sequence";
+
+// Whether the name is taken by one of the tables above, compared ignoring case as SQLite does.
+pub fn is_reserved_table_name(name: &str) -> bool {
+    [MASTER_TABLE_NAME, SEQUENCE_TABLE_NAME]
+        .iter()
+        .any(|reserved| name.eq_ignore_ascii_case(reserved))
+}
+
+// First line of the files written by `Database::save_to_file`, followed by the database's dump.
+const SAVE_FILE_HEADER: &str = "-- MollyCache database\n";
+
 pub struct Database {
     pub tables: HashMap<String, Vec<Option<Table>>>,
     pub transaction: TransactionLog,
+    // Rows changed by the most recent INSERT, UPDATE or DELETE, and by all of them since the
+    // database was created. Statements inside a transaction count as soon as they execute.
+    pub changes: usize,
+    pub total_changes: usize,
+    // Rowid of the last row inserted, see `Table::get_rowid`, 0 before any. Returned by
+    // `last_insert_rowid()`.
+    pub last_insert_rowid: i64,
+    // When set, `=` and `!=` treat a real as equal to any number within this distance of it.
+    // This is not standard SQL, SQLite always compares reals exactly, so it is off by default.
+    pub real_equality_epsilon: Option<f64>,
+    // Set with `PRAGMA foreign_keys`. Like SQLite, foreign keys are only enforced when it is on.
+    pub foreign_keys: bool,
+    // Deleting rows moves other rows around, so their order after a transaction commits depends on
+    // what it deleted. When set, COMMIT puts the rows of the tables it changed back in the order
+    // they were inserted in.
+    pub restore_insertion_order_on_commit: bool,
 }
 
 impl Database {
@@ -17,10 +66,28 @@ impl Database {
         Self {
             tables: HashMap::new(),
             transaction: TransactionLog { entries: None },
+            changes: 0,
+            total_changes: 0,
+            last_insert_rowid: 0,
+            real_equality_epsilon: None,
+            foreign_keys: false,
+            restore_insertion_order_on_commit: false,
         }
     }
 
-    pub fn execute(&mut self, sql_statement: SqlStatement) -> Result<Option<Vec<Row>>, String> {
+    pub fn execute(&mut self, mut sql_statement: SqlStatement) -> Result<Option<Vec<Row>>, String> {
+        // Expressions are evaluated against a single table, so the tables carry the settings
+        // that affect evaluation.
+        for table in self.tables.values_mut().flatten().flatten() {
+            table.real_equality_epsilon = self.real_equality_epsilon;
+        }
+        // Fill in the `last_insert_rowid()` calls before the statement is recorded, so replaying
+        // it inserts the same values.
+        if let SqlStatement::InsertInto(statement) = &mut sql_statement {
+            for (row, column) in std::mem::take(&mut statement.last_insert_rowids) {
+                statement.values[row][column] = Value::Integer(self.last_insert_rowid);
+            }
+        }
         let sql_statement_clone = sql_statement.clone();
         return match sql_statement {
             SqlStatement::CreateTable(statement) => {
@@ -28,40 +95,97 @@ impl Database {
                 self.transaction.append_entry(sql_statement_clone, vec![])?;
                 Ok(None)
             }
+            SqlStatement::InsertInto(statement)
+                if statement
+                    .table_name
+                    .eq_ignore_ascii_case(SEQUENCE_TABLE_NAME) =>
+            {
+                self.set_autoincrement_sequences(statement)?;
+                Ok(None)
+            }
             SqlStatement::InsertInto(statement) => {
+                if self.foreign_keys {
+                    check_foreign_keys(self, &statement)?;
+                }
+                let is_transaction = self.transaction.in_transaction();
+                let returning = statement.returning.clone();
                 let table = self.get_table_mut(&statement.table_name)?;
-                let rows_inserted = insert::insert(table, statement)?;
+                let rows_inserted = insert::insert(table, statement, is_transaction)?;
+                let returned_rows = match returning {
+                    Some(columns) => Some(get_returning_rows(table, &rows_inserted, &columns)?),
+                    None => None,
+                };
+                if let Some(position) = rows_inserted.last() {
+                    self.last_insert_rowid = table.get_rowid(*position)?;
+                }
+                self.record_changes(rows_inserted.len());
                 self.transaction
                     .append_entry(sql_statement_clone, rows_inserted)?;
-                Ok(None)
+                Ok(returned_rows)
             }
             SqlStatement::Select(statement) => {
                 let result = select::select_statement_stack(self, statement)?;
                 Ok(Some(result))
             }
-            SqlStatement::UpdateStatement(statement) => {
+            SqlStatement::UpdateStatement(mut statement) => {
+                let qualifiers = table_qualifiers(&statement.table_name, &statement.table_aliases);
+                strip_clause_qualifiers(
+                    &qualifiers,
+                    &mut statement.where_clause,
+                    &mut statement.order_by_clause,
+                    &mut statement.returning,
+                );
                 let is_transaction = self.transaction.in_transaction();
                 let table = self
                     .get_table_with_aliases_mut(&statement.table_name, &statement.table_aliases)?;
+                let returning = statement.returning.clone();
                 let rows_updated = update::update(table, statement, is_transaction)?;
+                let returned_rows = match returning {
+                    Some(columns) => Some(get_returning_rows(table, &rows_updated, &columns)?),
+                    None => None,
+                };
+                self.record_changes(rows_updated.len());
                 self.transaction
                     .append_entry(sql_statement_clone, rows_updated)?;
-                Ok(None)
+                Ok(returned_rows)
             }
-            SqlStatement::DeleteStatement(statement) => {
+            SqlStatement::DeleteStatement(mut statement) => {
+                let qualifiers = table_qualifiers(&statement.table_name, &statement.table_aliases);
+                strip_clause_qualifiers(
+                    &qualifiers,
+                    &mut statement.where_clause,
+                    &mut statement.order_by_clause,
+                    &mut statement.returning,
+                );
                 let is_transaction = self.transaction.in_transaction();
                 let table = self
                     .get_table_with_aliases_mut(&statement.table_name, &statement.table_aliases)?;
-                let rows_deleted = delete::delete(table, statement, is_transaction)?;
+                let (rows_deleted, returned_rows) =
+                    delete::delete(table, statement, is_transaction)?;
+                self.record_changes(rows_deleted.len());
                 self.transaction
                     .append_entry(sql_statement_clone, rows_deleted)?;
-                Ok(None)
+                Ok(returned_rows)
             }
             SqlStatement::DropTable(statement) => {
                 drop_table::drop_table(self, statement, self.transaction.in_transaction())?;
                 self.transaction.append_entry(sql_statement_clone, vec![])?;
                 Ok(None)
             }
+            SqlStatement::CreateIndex(statement) => {
+                if create_index::create_index(self, statement)? {
+                    self.transaction.append_entry(sql_statement_clone, vec![])?;
+                }
+                Ok(None)
+            }
+            SqlStatement::DropIndex(statement) => {
+                let is_transaction = self.transaction.in_transaction();
+                if let Some(table_name) = drop_index::drop_index(self, statement, is_transaction)? {
+                    self.transaction
+                        .append_table_entry(sql_statement_clone, table_name, vec![])?;
+                }
+                Ok(None)
+            }
             SqlStatement::AlterTable(statement) => {
                 alter_table::alter_table(self, statement, self.transaction.in_transaction())?;
                 self.transaction.append_entry(sql_statement_clone, vec![])?;
@@ -69,6 +193,14 @@ impl Database {
             }
             SqlStatement::BeginTransaction(_) => {
                 self.transaction.begin_transaction()?;
+                for table in self
+                    .tables
+                    .values_mut()
+                    .filter_map(|versions| versions.last_mut())
+                    .flatten()
+                {
+                    table.begin_transaction();
+                }
                 Ok(None)
             }
             SqlStatement::Commit => {
@@ -88,9 +220,81 @@ impl Database {
                     .release_savepoint(&statement.savepoint_name)?;
                 Ok(None)
             }
+            SqlStatement::ExplainQueryPlan(statement) => {
+                Ok(Some(select::explain_query_plan(self, &statement)?))
+            }
+            SqlStatement::Pragma(statement) => self.pragma(statement),
         };
     }
 
+    // Parses the whole script before running any statement, so a syntax error anywhere leaves the
+    // database untouched, unlike `run_sql` which runs every statement it could parse. Returns the
+    // result of each statement once they have all run.
+    pub fn execute_batch(&mut self, sql: &str) -> Result<Vec<StatementResult>, String> {
+        let mut statements = vec![];
+        for statement in parse_sql(sql) {
+            let statement = statement.map_err(|error| format!("Parsing Error: {}", error))?;
+            statements.push(statement.sql_statement);
+        }
+        Ok(statements
+            .into_iter()
+            .map(|statement| self.execute(statement))
+            .collect())
+    }
+
+    // Reads or sets a pragma. Only `foreign_keys` is supported, it is read as a single row holding
+    // 1 or 0.
+    fn pragma(&mut self, statement: PragmaStatement) -> Result<Option<Vec<Row>>, String> {
+        if !statement.name.eq_ignore_ascii_case("foreign_keys") {
+            return Err(format!("Unsupported pragma: {}", statement.name));
+        }
+        let Some(value) = statement.value else {
+            return Ok(Some(vec![Row(vec![Value::Integer(
+                self.foreign_keys as i64,
+            )])]));
+        };
+        self.foreign_keys = match value.to_ascii_uppercase().as_str() {
+            "ON" | "TRUE" | "YES" | "1" => true,
+            "OFF" | "FALSE" | "NO" | "0" => false,
+            _ => {
+                return Err(format!(
+                    "Invalid value `{}` for pragma {}",
+                    value, statement.name
+                ));
+            }
+        };
+        Ok(None)
+    }
+
+    // Runs a single SELECT and returns the first column of its first row, or None if it returned
+    // no rows.
+    pub fn query_scalar(&mut self, sql: &str) -> Result<Option<Value>, String> {
+        let mut statements = parse_sql(sql);
+        if statements.len() != 1 {
+            return Err(format!(
+                "query_scalar expects a single statement, found {}",
+                statements.len()
+            ));
+        }
+        let statement = statements
+            .remove(0)
+            .map_err(|error| format!("Parsing Error: {}", error))?
+            .sql_statement;
+        if !matches!(statement, SqlStatement::Select(_)) {
+            return Err("query_scalar expects a SELECT statement".to_string());
+        }
+        let rows = self.execute(statement)?.unwrap_or_default();
+        Ok(rows
+            .into_iter()
+            .next()
+            .and_then(|row| row.0.into_iter().next()))
+    }
+
+    fn record_changes(&mut self, changes: usize) {
+        self.changes = changes;
+        self.total_changes += changes;
+    }
+
     pub fn has_table(&self, table_name: &str) -> bool {
         self.tables.contains_key(table_name)
             && !self.tables.get(table_name).is_none()
@@ -150,6 +354,266 @@ impl Database {
         )
     }
 
+    // Index names are unique across the whole database, returns the table owning the index.
+    pub fn get_index_table_name(&self, index_name: &str) -> Option<String> {
+        self.tables
+            .values()
+            .filter_map(|table_versions| table_versions.last().and_then(|table| table.as_ref()))
+            .find(|table| table.has_index(index_name))
+            .and_then(|table| table.name().ok().cloned())
+    }
+
+    // Lists the names of the tables matching a LIKE pattern, as `.tables` does in SQLite, in name
+    // order.
+    pub fn table_names_matching(&self, pattern: &str) -> Vec<String> {
+        let mut table_names = self
+            .tables
+            .keys()
+            .filter(|table_name| {
+                self.has_table(table_name) && like_match(pattern, table_name, None)
+            })
+            .cloned()
+            .collect::<Vec<String>>();
+        table_names.sort();
+        table_names
+    }
+
+    // Exports the rows of a table as a JSON array of objects keyed by column name.
+    // See `JsonValue::from_value` for how values without a JSON counterpart are encoded.
+    pub fn table_to_json(&self, table_name: &str) -> Result<String, String> {
+        let table = self.get_table(table_name)?;
+        let column_names = table.get_column_names()?;
+        let rows = table
+            .iter()
+            .map(|row| {
+                JsonValue::Object(
+                    column_names
+                        .iter()
+                        .zip(row.iter())
+                        .map(|(name, value)| (name.to_string(), JsonValue::from_value(value)))
+                        .collect(),
+                )
+            })
+            .collect();
+        Ok(JsonValue::Array(rows).to_string())
+    }
+
+    // Drops every table, along with their indexes, leaving an empty database.
+    // Refused during a transaction, since the transaction log refers to the current tables.
+    pub fn clear(&mut self) -> Result<(), String> {
+        if self.transaction.in_transaction() {
+            return Err("Cannot reset the database while a transaction is active".to_string());
+        }
+        self.tables.clear();
+        Ok(())
+    }
+
+    // Renders the whole database as SQL statements that recreate it, tables in name order.
+    pub fn dump(&self) -> Result<String, String> {
+        let mut table_names = self
+            .tables
+            .keys()
+            .filter(|table_name| self.has_table(table_name))
+            .collect::<Vec<&String>>();
+        table_names.sort();
+
+        let mut statements = vec!["BEGIN;".to_string()];
+        for table_name in table_names {
+            let table = self.get_table(table_name)?;
+            statements.push(create_table_sql(table_name, table)?);
+            // Generated columns are computed again when the rows are read back, they can't be
+            // inserted so the other columns are listed.
+            let columns = table.get_columns()?;
+            let stored = (0..columns.len())
+                .filter(|i| columns[*i].generated_expression().is_none())
+                .collect::<Vec<usize>>();
+            let into = if stored.len() == columns.len() {
+                quote_identifier(table_name)
+            } else {
+                let names = stored
+                    .iter()
+                    .map(|i| columns[*i].name.clone())
+                    .collect::<Vec<String>>();
+                format!(
+                    "{} ({})",
+                    quote_identifier(table_name),
+                    quote_identifiers(&names)
+                )
+            };
+            for row in table.iter() {
+                let values = stored
+                    .iter()
+                    .map(|i| quote(&row[*i]))
+                    .collect::<Vec<String>>();
+                statements.push(format!(
+                    "INSERT INTO {} VALUES ({});",
+                    into,
+                    values.join(", ")
+                ));
+            }
+            // The sequence can be above every row left, when the largest were deleted.
+            if table.autoincrement_column()?.is_some() && table.autoincrement_sequence > 0 {
+                statements.push(format!(
+                    "INSERT INTO {} VALUES ({}, {});",
+                    SEQUENCE_TABLE_NAME,
+                    quote(&Value::Text(table_name.clone())),
+                    table.autoincrement_sequence
+                ));
+            }
+            statements.extend(create_index_sql(table_name, table));
+        }
+        statements.push("COMMIT;".to_string());
+        Ok(statements.join("\n"))
+    }
+
+    // Sets `Table::autoincrement_sequence` from rows `(table name, sequence)` inserted into
+    // `SEQUENCE_TABLE_NAME`.
+    fn set_autoincrement_sequences(
+        &mut self,
+        statement: InsertIntoStatement,
+    ) -> Result<(), String> {
+        if let Some(columns) = &statement.columns
+            && *columns != ["name", "seq"]
+        {
+            return Err(format!(
+                "{} only has the columns name and seq",
+                SEQUENCE_TABLE_NAME
+            ));
+        }
+        for row in statement.values {
+            let [Value::Text(table_name), Value::Integer(sequence)] = row.as_slice() else {
+                return Err(format!(
+                    "Expected a table name and an integer for {}, found: {:?}",
+                    SEQUENCE_TABLE_NAME, row
+                ));
+            };
+            let table = self.get_table_mut(table_name)?;
+            if table.autoincrement_column()?.is_none() {
+                return Err(format!("Table {} has no AUTOINCREMENT column", table_name));
+            }
+            table.autoincrement_sequence = *sequence;
+        }
+        Ok(())
+    }
+
+    // Saves the tables as they currently are, including changes of an ongoing transaction, in a
+    // file holding the statements of `dump`.
+    pub fn save_to_file(&self, path: &str) -> Result<(), String> {
+        let contents = format!("{}{}\n", SAVE_FILE_HEADER, self.dump()?);
+        std::fs::write(path, contents).map_err(|error| format!("Cannot write {}: {}", path, error))
+    }
+
+    // Reads a database saved with `save_to_file`. A file that was not written by it, or that was
+    // modified or cut short since, is refused as a whole.
+    pub fn load_from_file(path: &str) -> Result<Database, String> {
+        let contents = std::fs::read_to_string(path)
+            .map_err(|error| format!("Cannot read {}: {}", path, error))?;
+        let corrupt = |error: String| format!("Corrupt database file {}: {}", path, error);
+        let statements = contents
+            .strip_prefix(SAVE_FILE_HEADER)
+            .ok_or_else(|| corrupt("missing header".to_string()))?;
+        let mut database = Database::new();
+        for result in database.execute_batch(statements).map_err(corrupt)? {
+            result.map_err(corrupt)?;
+        }
+        // The dump is wrapped in a transaction, a file cut short leaves it open.
+        if database.transaction.in_transaction() {
+            return Err(corrupt("unexpected end of file".to_string()));
+        }
+        Ok(database)
+    }
+
+    // Renders the statements creating every table and index, without the rows.
+    pub fn schema(&self) -> Result<String, String> {
+        let mut table_names = self
+            .tables
+            .keys()
+            .filter(|table_name| self.has_table(table_name))
+            .collect::<Vec<&String>>();
+        table_names.sort();
+
+        let mut statements = vec![];
+        for table_name in table_names {
+            let table = self.get_table(table_name)?;
+            statements.push(create_table_sql(table_name, table)?);
+            statements.extend(create_index_sql(table_name, table));
+        }
+        Ok(statements.join("\n"))
+    }
+
+    // Builds the table describing the schema, as SQLite's `sqlite_master`: one row per table and
+    // index, with the columns type, name, tbl_name and sql. It is rebuilt for every query and
+    // can't be modified.
+    pub fn master_table(&self) -> Result<Table, String> {
+        let mut table_names = self
+            .tables
+            .keys()
+            .filter(|table_name| self.has_table(table_name))
+            .collect::<Vec<&String>>();
+        table_names.sort();
+
+        let column = |name: &str| ColumnDefinition {
+            name: name.to_string(),
+            data_type: DataType::Text,
+            constraints: vec![],
+        };
+        let mut master = Table::new(
+            MASTER_TABLE_NAME.to_string(),
+            vec![
+                column("type"),
+                column("name"),
+                column("tbl_name"),
+                column("sql"),
+            ],
+        );
+        let text = |value: &str| Value::Text(value.to_string());
+        // SQLite keeps the statements without their semicolon.
+        let sql = |statement: &str| text(statement.trim_end_matches(';'));
+        for table_name in table_names {
+            let table = self.get_table(table_name)?;
+            master.push(Row(vec![
+                text("table"),
+                text(table_name),
+                text(table_name),
+                sql(&create_table_sql(table_name, table)?),
+            ]));
+            for (index, statement) in table
+                .indexes
+                .iter()
+                .zip(create_index_sql(table_name, table))
+            {
+                master.push(Row(vec![
+                    text("index"),
+                    text(&index.name),
+                    text(table_name),
+                    sql(&statement),
+                ]));
+            }
+        }
+        Ok(master)
+    }
+
+    // Lists the approximate memory used by each table, see `Table::estimate_memory`, and their
+    // total.
+    pub fn memory_usage(&self) -> Result<String, String> {
+        let mut table_names = self
+            .tables
+            .keys()
+            .filter(|table_name| self.has_table(table_name))
+            .collect::<Vec<&String>>();
+        table_names.sort();
+
+        let mut lines = vec![];
+        let mut total = 0;
+        for table_name in table_names {
+            let bytes = self.get_table(table_name)?.estimate_memory();
+            total += bytes;
+            lines.push(format!("{}: {} bytes", table_name, bytes));
+        }
+        lines.push(format!("total: {} bytes", total));
+        Ok(lines.join("\n"))
+    }
+
     pub fn push_table_change(&mut self, table_name: &str, table: Table) {
         if !self.has_table(table_name) {
             self.tables
@@ -179,10 +643,118 @@ impl Database {
     }
 }
 
+fn create_table_sql(table_name: &str, table: &Table) -> Result<String, String> {
+    let mut columns = table
+        .get_columns()?
+        .into_iter()
+        .map(column_definition_to_sql)
+        .collect::<Vec<String>>();
+    for table_constraint in table.table_constraints.iter() {
+        let mut sql = match table_constraint.constraint_type {
+            ConstraintType::PrimaryKey => "PRIMARY KEY",
+            _ => "UNIQUE",
+        }
+        .to_string();
+        sql.push_str(&format!(
+            " ({})",
+            quote_identifiers(&table_constraint.columns)
+        ));
+        if let Some(conflict_action) = &table_constraint.conflict_action {
+            sql.push_str(conflict_action_to_sql(conflict_action));
+        }
+        columns.push(sql);
+    }
+    Ok(format!(
+        "CREATE TABLE {} ({}){};",
+        quote_identifier(table_name),
+        columns.join(", "),
+        if table.without_rowid {
+            " WITHOUT ROWID"
+        } else {
+            ""
+        }
+    ))
+}
+
+fn create_index_sql(table_name: &str, table: &Table) -> Vec<String> {
+    table
+        .indexes
+        .iter()
+        .map(|index| {
+            format!(
+                "CREATE {}INDEX {} ON {} ({});",
+                if index.unique { "UNIQUE " } else { "" },
+                quote_identifier(&index.name),
+                quote_identifier(table_name),
+                quote_identifiers(&index.columns)
+            )
+        })
+        .collect()
+}
+
+fn quote_identifiers(names: &[String]) -> String {
+    names
+        .iter()
+        .map(|name| quote_identifier(name))
+        .collect::<Vec<String>>()
+        .join(", ")
+}
+
+fn conflict_action_to_sql(conflict_action: &ConflictAction) -> &'static str {
+    match conflict_action {
+        ConflictAction::Rollback => " ON CONFLICT ROLLBACK",
+        ConflictAction::Abort => " ON CONFLICT ABORT",
+        ConflictAction::Fail => " ON CONFLICT FAIL",
+        ConflictAction::Ignore => " ON CONFLICT IGNORE",
+        ConflictAction::Replace => " ON CONFLICT REPLACE",
+    }
+}
+
+fn column_definition_to_sql(column: &ColumnDefinition) -> String {
+    let data_type = match column.data_type {
+        DataType::Integer => "INTEGER",
+        DataType::Real => "REAL",
+        DataType::Text => "TEXT",
+        DataType::Blob => "BLOB",
+        DataType::Null => "NULL",
+    };
+    let mut sql = format!("{} {}", quote_identifier(&column.name), data_type);
+    for constraint in column.constraints.iter() {
+        match &constraint.constraint_type {
+            ConstraintType::PrimaryKey => sql.push_str(" PRIMARY KEY"),
+            ConstraintType::NotNull => sql.push_str(" NOT NULL"),
+            ConstraintType::Unique => sql.push_str(" UNIQUE"),
+            ConstraintType::AutoIncrement => sql.push_str(" AUTOINCREMENT"),
+            ConstraintType::Default(value) => sql.push_str(&format!(" DEFAULT {}", quote(value))),
+            ConstraintType::ForeignKey { table_name, column } => {
+                sql.push_str(&format!(" REFERENCES {}", quote_identifier(table_name)));
+                if let Some(column) = column {
+                    sql.push_str(&format!("({})", quote_identifier(column)));
+                }
+            }
+            ConstraintType::Generated(expression) => sql.push_str(&format!(
+                " GENERATED ALWAYS AS ({}) VIRTUAL",
+                expression.column_name
+            )),
+        }
+        if let Some(conflict_action) = &constraint.conflict_action {
+            sql.push_str(conflict_action_to_sql(conflict_action));
+        }
+        if constraint.deferred {
+            sql.push_str(" DEFERRABLE INITIALLY DEFERRED");
+        }
+    }
+    sql
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::db::table::core::{column::ColumnDefinition, value::DataType};
+    use crate::db::table::core::{
+        column::ColumnDefinition,
+        value::{DataType, Value},
+    };
+    use crate::interpreter::run_sql;
 
     fn default_database() -> Database {
         Database {
@@ -205,9 +777,327 @@ mod tests {
                 ))],
             )]),
             transaction: TransactionLog { entries: None },
+            changes: 0,
+            total_changes: 0,
+            last_insert_rowid: 0,
+            real_equality_epsilon: None,
+            foreign_keys: false,
+            restore_insertion_order_on_commit: false,
+        }
+    }
+
+    #[test]
+    fn table_to_json_exports_rows_as_objects() {
+        let mut database = default_database();
+        let table = database.get_table_mut("users").unwrap();
+        table.push_column(
+            ColumnDefinition {
+                name: "avatar".to_string(),
+                data_type: DataType::Blob,
+                constraints: vec![],
+            },
+            false,
+        );
+        table.set_rows(vec![
+            Row(vec![
+                Value::Integer(1),
+                Value::Text("John \"Jo\"".to_string()),
+                Value::Blob(vec![0xca, 0xfe]),
+            ]),
+            Row(vec![Value::Integer(2), Value::Null, Value::Null]),
+        ]);
+
+        let json = database.table_to_json("users").unwrap();
+        assert_eq!(
+            json,
+            "[{\"id\":1,\"name\":\"John \\\"Jo\\\"\",\"avatar\":\"cafe\"},{\"id\":2,\"name\":null,\"avatar\":null}]"
+        );
+
+        // Parsing the export back yields the original values.
+        let parsed = JsonValue::parse(&json).unwrap();
+        let JsonValue::Array(rows) = parsed else {
+            panic!("Expected an array");
+        };
+        assert_eq!(rows.len(), 2);
+        assert_eq!(rows[0].get("id"), Some(&JsonValue::Integer(1)));
+        assert_eq!(
+            rows[0].get("name"),
+            Some(&JsonValue::String("John \"Jo\"".to_string()))
+        );
+        assert_eq!(
+            rows[0].get("avatar"),
+            Some(&JsonValue::String("cafe".to_string()))
+        );
+        assert_eq!(rows[1].get("name"), Some(&JsonValue::Null));
+
+        assert_eq!(
+            database.table_to_json("not_users"),
+            Err("Table `not_users` does not exist".to_string())
+        );
+    }
+
+    #[test]
+    fn table_to_json_round_trips_the_default_table() {
+        let database = crate::db::table::test_utils::default_database();
+        let table = database.get_table("users").unwrap();
+        let json = database.table_to_json("users").unwrap();
+        let JsonValue::Array(objects) = JsonValue::parse(&json).unwrap() else {
+            panic!("Expected an array");
+        };
+        let rows = objects
+            .iter()
+            .map(|object| {
+                Row(table
+                    .get_columns()
+                    .unwrap()
+                    .iter()
+                    .map(|column| match object.get(&column.name) {
+                        Some(JsonValue::Integer(val)) => Value::Integer(*val),
+                        Some(JsonValue::Real(val)) => Value::Real(*val),
+                        Some(JsonValue::String(val)) => Value::Text(val.clone()),
+                        _ => Value::Null,
+                    })
+                    .collect())
+            })
+            .collect::<Vec<Row>>();
+        assert_eq!(rows.len(), table.len());
+        for (row, expected) in rows.iter().zip(table.iter()) {
+            assert!(
+                row.iter()
+                    .zip(expected.iter())
+                    .all(|(a, b)| a.exactly_equal(b))
+            );
         }
     }
 
+    #[test]
+    fn clear_drops_every_table() {
+        let mut database = default_database();
+        assert!(
+            run_sql(&mut database, "CREATE INDEX idx_id ON users (id);")
+                .remove(0)
+                .is_ok()
+        );
+        assert!(database.clear().is_ok());
+        assert!(database.tables.is_empty());
+        assert!(database.get_index_table_name("idx_id").is_none());
+
+        assert!(
+            run_sql(
+                &mut database,
+                "CREATE TABLE users (id INTEGER, email TEXT);"
+            )
+            .remove(0)
+            .is_ok()
+        );
+        assert_eq!(
+            database.get_table("users").unwrap().get_column_names(),
+            Ok(vec![&"id".to_string(), &"email".to_string()])
+        );
+    }
+
+    #[test]
+    fn clear_is_refused_during_a_transaction() {
+        let mut database = default_database();
+        assert!(run_sql(&mut database, "BEGIN;").remove(0).is_ok());
+        assert_eq!(
+            database.clear(),
+            Err("Cannot reset the database while a transaction is active".to_string())
+        );
+        assert!(database.has_table("users"));
+    }
+
+    #[test]
+    fn dump_recreates_the_database() {
+        let mut database = Database::new();
+        let sql = "
+        CREATE TABLE users (id INTEGER PRIMARY KEY, name TEXT NOT NULL UNIQUE ON CONFLICT IGNORE DEFERRABLE INITIALLY DEFERRED, money REAL, data BLOB);
+        INSERT INTO users VALUES (1, 'O''Brien', 1.5, X'00ff'), (2, 'Jane', NULL, NULL);
+        CREATE INDEX idx_name ON users (name);
+        CREATE TABLE empty (id INTEGER);
+        ";
+        assert!(
+            run_sql(&mut database, sql)
+                .iter()
+                .all(|result| result.is_ok())
+        );
+
+        let dump = database.dump().unwrap();
+        assert_eq!(
+            dump,
+            "BEGIN;\n\
+            CREATE TABLE empty (id INTEGER);\n\
+            CREATE TABLE users (id INTEGER PRIMARY KEY, name TEXT NOT NULL UNIQUE ON CONFLICT IGNORE DEFERRABLE INITIALLY DEFERRED, money REAL, data BLOB);\n\
+            INSERT INTO users VALUES (1, 'O''Brien', 1.5, X'00FF');\n\
+            INSERT INTO users VALUES (2, 'Jane', NULL, NULL);\n\
+            CREATE INDEX idx_name ON users (name);\n\
+            COMMIT;"
+        );
+
+        let mut restored = Database::new();
+        assert!(
+            run_sql(&mut restored, &dump)
+                .iter()
+                .all(|result| result.is_ok())
+        );
+        assert_eq!(restored.dump(), Ok(dump));
+        assert_eq!(
+            restored.get_table("users").unwrap().get_rows_clone()[0][1],
+            Value::Text("O'Brien".to_string())
+        );
+    }
+
+    #[test]
+    fn dump_round_trips_reals_in_scientific_notation() {
+        let mut database = Database::new();
+        let sql = "
+        CREATE TABLE reals (value REAL);
+        INSERT INTO reals VALUES (1e20), (1.5e-10), (-2.5E+300), (5e-324), (0.1);
+        ";
+        assert!(
+            run_sql(&mut database, sql)
+                .iter()
+                .all(|result| result.is_ok())
+        );
+
+        let mut restored = Database::new();
+        let dump = database.dump().unwrap();
+        assert!(
+            run_sql(&mut restored, &dump)
+                .iter()
+                .all(|result| result.is_ok())
+        );
+        let expected = vec![1e20, 1.5e-10, -2.5e300, 5e-324, 0.1];
+        let rows = restored.get_table("reals").unwrap().get_rows_clone();
+        assert_eq!(rows.len(), expected.len());
+        for (row, expected) in rows.iter().zip(expected) {
+            assert!(row[0].exactly_equal(&Value::Real(expected)), "{:?}", row);
+        }
+    }
+
+    #[test]
+    fn dump_round_trips_names_with_quotes() {
+        let mut database = Database::new();
+        let sql = "
+        CREATE TABLE \"say \"\"hi\"\"\" (`it``s` INTEGER);
+        INSERT INTO \"say \"\"hi\"\"\" VALUES (1);
+        CREATE INDEX \"idx \"\"q\"\"\" ON \"say \"\"hi\"\"\" (\"it`s\");
+        ";
+        assert!(
+            run_sql(&mut database, sql)
+                .iter()
+                .all(|result| result.is_ok())
+        );
+        assert!(database.has_table("say \"hi\""));
+
+        let dump = database.dump().unwrap();
+        let mut restored = Database::new();
+        assert!(
+            run_sql(&mut restored, &dump)
+                .iter()
+                .all(|result| result.is_ok())
+        );
+        assert_eq!(restored.dump().unwrap(), dump);
+        let table = restored.get_table("say \"hi\"").unwrap();
+        assert_eq!(table.get_column_names().unwrap(), vec!["it`s"]);
+        assert!(table.has_index("idx \"q\""));
+    }
+
+    #[test]
+    fn dump_skips_generated_columns() {
+        let mut database = Database::new();
+        let sql = "
+        CREATE TABLE p (id INTEGER, name TEXT, label TEXT GENERATED ALWAYS AS (name || '!'));
+        INSERT INTO p (id, name) VALUES (1, 'a'), (2, 'b');
+        ";
+        assert!(
+            run_sql(&mut database, sql)
+                .iter()
+                .all(|result| result.is_ok())
+        );
+
+        let dump = database.dump().unwrap();
+        assert!(
+            dump.contains("INSERT INTO p (id, name) VALUES (1, 'a');"),
+            "{}",
+            dump
+        );
+        let mut restored = Database::new();
+        assert!(
+            run_sql(&mut restored, &dump)
+                .iter()
+                .all(|result| result.is_ok())
+        );
+        let result = run_sql(&mut restored, "SELECT id, label FROM p;");
+        let rows = result[0].as_ref().unwrap().as_ref().unwrap().clone();
+        assert_eq!(
+            rows,
+            vec![
+                Row(vec![Value::Integer(1), Value::Text("a!".to_string())]),
+                Row(vec![Value::Integer(2), Value::Text("b!".to_string())]),
+            ]
+        );
+        assert_eq!(restored.dump().unwrap(), dump);
+    }
+
+    #[test]
+    fn schema_lists_tables_and_indexes_without_rows() {
+        let mut database = Database::new();
+        let sql = "
+        CREATE TABLE users (id INTEGER PRIMARY KEY, name TEXT) WITHOUT ROWID;
+        INSERT INTO users VALUES (1, 'John');
+        CREATE INDEX idx_name ON users (name);
+        CREATE TABLE accounts (id INTEGER);
+        ";
+        assert!(
+            run_sql(&mut database, sql)
+                .iter()
+                .all(|result| result.is_ok())
+        );
+        assert_eq!(
+            database.schema(),
+            Ok("CREATE TABLE accounts (id INTEGER);\n\
+            CREATE TABLE users (id INTEGER PRIMARY KEY, name TEXT) WITHOUT ROWID;\n\
+            CREATE INDEX idx_name ON users (name);"
+                .to_string())
+        );
+    }
+
+    #[test]
+    fn schema_and_dump_quote_identifiers_that_need_it() {
+        let mut database = Database::new();
+        let sql = "
+        CREATE TABLE \"group\" (id INTEGER, \"order\" INTEGER, `first name` TEXT);
+        INSERT INTO \"group\" VALUES (1, 2, 'Jo');
+        CREATE INDEX \"index\" ON \"group\" (\"order\");
+        ";
+        assert!(
+            run_sql(&mut database, sql)
+                .iter()
+                .all(|result| result.is_ok())
+        );
+        assert_eq!(
+            database.schema(),
+            Ok(
+                "CREATE TABLE \"group\" (id INTEGER, \"order\" INTEGER, \"first name\" TEXT);\n\
+            CREATE INDEX \"index\" ON \"group\" (\"order\");"
+                    .to_string()
+            )
+        );
+
+        let mut restored = Database::new();
+        assert!(
+            run_sql(&mut restored, &database.dump().unwrap())
+                .iter()
+                .all(|result| result.is_ok())
+        );
+        assert_eq!(restored.schema(), database.schema());
+        assert_eq!(
+            restored.get_table("group").unwrap().get_rows_clone(),
+            database.get_table("group").unwrap().get_rows_clone()
+        );
+    }
+
     #[test]
     fn has_table_returns_proper_response() {
         let database = default_database();
@@ -245,4 +1135,208 @@ mod tests {
         assert!(table.is_err());
         assert_eq!("Table `not_users` does not exist", table.unwrap_err());
     }
+
+    #[test]
+    fn real_equality_epsilon_makes_close_reals_equal() {
+        let mut database = Database::new();
+        let sql = "CREATE TABLE t (id INTEGER, money REAL); \
+                   INSERT INTO t VALUES (1, 0.1), (2, 0.2), (3, 1.0), (4, 2.0), (5, 3.0); \
+                   CREATE INDEX idx_money ON t (money);";
+        assert!(
+            run_sql(&mut database, sql)
+                .iter()
+                .all(|result| result.is_ok())
+        );
+        let query = "SELECT id FROM t WHERE money + 0.2 = 0.3; \
+                     SELECT id FROM t WHERE money = 0.1000000000001;";
+
+        // Off by default, 0.1 + 0.2 is not exactly 0.3.
+        let results = run_sql(&mut database, query);
+        assert_eq!(results[0], Ok(Some(vec![])));
+        assert_eq!(results[1], Ok(Some(vec![])));
+
+        database.real_equality_epsilon = Some(1e-9);
+        let results = run_sql(&mut database, query);
+        assert_eq!(results[0], Ok(Some(vec![Row(vec![Value::Integer(1)])])));
+        // The index on money is not used, since it only finds exactly equal keys.
+        assert_eq!(results[1], Ok(Some(vec![Row(vec![Value::Integer(1)])])));
+        let results = run_sql(
+            &mut database,
+            "SELECT id FROM t WHERE money != 0.1 + 1e-12;",
+        );
+        assert_eq!(results[0].as_ref().unwrap().as_ref().unwrap().len(), 4);
+
+        // So do the SELECTs without a table and the joins.
+        let results = run_sql(
+            &mut database,
+            "SELECT 0.1 + 0.2 = 0.3; \
+             SELECT a.id, b.id FROM t a JOIN t b ON a.money + 0.2 = b.money * 0.3;",
+        );
+        assert_eq!(results[0], Ok(Some(vec![Row(vec![Value::Integer(1)])])));
+        assert_eq!(
+            results[1],
+            Ok(Some(vec![Row(vec![Value::Integer(1), Value::Integer(3)])]))
+        );
+    }
+
+    #[test]
+    fn execute_batch_runs_nothing_if_a_statement_fails_to_parse() {
+        let mut database = Database::new();
+        let sql = "CREATE TABLE t (id INTEGER);
+                   INSERT INTO t VALUES (1);
+                   INSERT INTO t VALUS (2);
+                   INSERT INTO t VALUES (3);";
+        let result = database.execute_batch(sql);
+        assert!(result.unwrap_err().starts_with("Parsing Error"));
+        assert!(!database.has_table("t"));
+
+        let result = database
+            .execute_batch("CREATE TABLE t (id INTEGER); INSERT INTO t VALUES (1); SELECT * FROM t; SELECT * FROM missing;")
+            .unwrap();
+        assert_eq!(result.len(), 4);
+        assert_eq!(result[2], Ok(Some(vec![Row(vec![Value::Integer(1)])])));
+        assert!(result[3].is_err());
+    }
+
+    #[test]
+    fn query_scalar_returns_the_first_value_of_a_select() {
+        let mut database = default_database();
+        run_sql(
+            &mut database,
+            "INSERT INTO users VALUES (1, 'John'), (2, 'Jane');",
+        );
+        assert_eq!(
+            database.query_scalar("SELECT COUNT(*) FROM users;"),
+            Ok(Some(Value::Integer(2)))
+        );
+        assert_eq!(
+            database.query_scalar("SELECT name, id FROM users WHERE id = 2;"),
+            Ok(Some(Value::Text("Jane".to_string())))
+        );
+        assert_eq!(
+            database.query_scalar("SELECT name FROM users WHERE id = 3;"),
+            Ok(None)
+        );
+
+        assert!(database.query_scalar("DELETE FROM users;").is_err());
+        assert!(
+            database
+                .query_scalar("SELECT id FROM users; SELECT name FROM users;")
+                .is_err()
+        );
+        assert!(database.query_scalar("SELECT FROM users;").is_err());
+        assert!(database.query_scalar("SELECT * FROM missing;").is_err());
+        assert_eq!(database.get_table("users").unwrap().len(), 2);
+    }
+
+    #[test]
+    fn save_to_file_and_load_from_file_round_trip_the_database() {
+        let mut database = Database::new();
+        let sql = "
+        CREATE TABLE users (id INTEGER PRIMARY KEY, name TEXT NOT NULL UNIQUE, money REAL);
+        INSERT INTO users VALUES (1, 'O''Brien', 1.5), (2, 'Jane', NULL);
+        CREATE INDEX idx_money ON users (money);
+        BEGIN;
+        INSERT INTO users (id, name) VALUES (3, 'Uncommitted');
+        ";
+        assert!(
+            run_sql(&mut database, sql)
+                .iter()
+                .all(|result| result.is_ok())
+        );
+        let path = std::env::temp_dir().join(format!("mollycache_save_{}.db", std::process::id()));
+        let path = path.to_str().unwrap();
+
+        database.save_to_file(path).unwrap();
+        let mut loaded = Database::load_from_file(path).unwrap();
+        assert!(!loaded.transaction.in_transaction());
+        assert_eq!(loaded.dump(), database.dump());
+        assert!(
+            run_sql(
+                &mut loaded,
+                "INSERT INTO users (id, name) VALUES (4, 'Jane');"
+            )[0]
+            .is_err()
+        );
+
+        let contents = std::fs::read_to_string(path).unwrap();
+        std::fs::write(path, &contents[..contents.len() - "COMMIT;\n".len()]).unwrap();
+        assert!(Database::load_from_file(path).is_err());
+        std::fs::write(path, contents.replace("INSERT INTO", "INSERT INT")).unwrap();
+        assert!(Database::load_from_file(path).is_err());
+        std::fs::write(path, "CREATE TABLE users (id INTEGER);").unwrap();
+        assert!(Database::load_from_file(path).is_err());
+        std::fs::remove_file(path).unwrap();
+        assert!(Database::load_from_file(path).is_err());
+    }
+
+    #[test]
+    fn save_to_file_keeps_generated_columns_and_autoincrement_sequences() {
+        let mut database = Database::new();
+        let sql = "
+        CREATE TABLE items (id INTEGER PRIMARY KEY AUTOINCREMENT, price INTEGER, total INTEGER GENERATED ALWAYS AS (price * 2));
+        INSERT INTO items (price) VALUES (5), (7);
+        DELETE FROM items WHERE id = 2;
+        ";
+        assert!(
+            run_sql(&mut database, sql)
+                .iter()
+                .all(|result| result.is_ok())
+        );
+        let path = std::env::temp_dir().join(format!(
+            "mollycache_save_generated_{}.db",
+            std::process::id()
+        ));
+        let path = path.to_str().unwrap();
+
+        database.save_to_file(path).unwrap();
+        let mut loaded = Database::load_from_file(path).unwrap();
+        std::fs::remove_file(path).unwrap();
+        assert_eq!(loaded.dump(), database.dump());
+        let result = run_sql(
+            &mut loaded,
+            "INSERT INTO items (price) VALUES (9); SELECT * FROM items;",
+        );
+        assert_eq!(
+            result[1],
+            Ok(Some(vec![
+                Row(vec![
+                    Value::Integer(1),
+                    Value::Integer(5),
+                    Value::Integer(10)
+                ]),
+                Row(vec![
+                    Value::Integer(3),
+                    Value::Integer(9),
+                    Value::Integer(18)
+                ]),
+            ]))
+        );
+    }
+
+    #[test]
+    fn table_names_matching_filters_with_a_like_pattern() {
+        let mut database = Database::new();
+        let sql = "
+        CREATE TABLE user_roles (id INTEGER);
+        CREATE TABLE users (id INTEGER);
+        CREATE TABLE orders (id INTEGER);
+        CREATE TABLE user (id INTEGER);
+        ";
+        assert!(
+            run_sql(&mut database, sql)
+                .iter()
+                .all(|result| result.is_ok())
+        );
+        assert_eq!(
+            database.table_names_matching("user%"),
+            vec!["user", "user_roles", "users"]
+        );
+        assert_eq!(database.table_names_matching("user_"), vec!["users"]);
+        assert_eq!(
+            database.table_names_matching("%"),
+            vec!["orders", "user", "user_roles", "users"]
+        );
+        assert!(database.table_names_matching("missing%").is_empty());
+    }
 }
@@ -0,0 +1,332 @@
+use crate::db::table::core::value::Value;
+use std::fmt;
+
+// A minimal JSON document model, enough to export and re-import table contents.
+#[derive(Debug, PartialEq, Clone)]
+pub enum JsonValue {
+    Null,
+    Integer(i64),
+    Real(f64),
+    String(String),
+    Array(Vec<JsonValue>),
+    // Keeps the insertion order of the keys, so columns stay in table order.
+    Object(Vec<(String, JsonValue)>),
+}
+
+impl JsonValue {
+    // Blobs have no JSON counterpart and are encoded as a string of lowercase hex digits.
+    // NaN and infinite reals are not valid JSON numbers and become null.
+    pub fn from_value(value: &Value) -> Self {
+        match value {
+            Value::Null => JsonValue::Null,
+            Value::Integer(val) => JsonValue::Integer(*val),
+            Value::Real(val) if val.is_finite() => JsonValue::Real(*val),
+            Value::Real(_) => JsonValue::Null,
+            Value::Text(val) => JsonValue::String(val.clone()),
+            Value::Blob(val) => JsonValue::String(encode_hex(val)),
+        }
+    }
+
+    pub fn get(&self, key: &str) -> Option<&JsonValue> {
+        match self {
+            JsonValue::Object(entries) => entries
+                .iter()
+                .find(|(entry_key, _)| entry_key == key)
+                .map(|(_, value)| value),
+            _ => None,
+        }
+    }
+
+    pub fn parse(input: &str) -> Result<JsonValue, String> {
+        let mut parser = JsonParser {
+            chars: input.chars().collect(),
+            current: 0,
+        };
+        let value = parser.parse_value()?;
+        parser.skip_whitespace();
+        if parser.current < parser.chars.len() {
+            return Err(format!(
+                "Unexpected character in JSON at position {}",
+                parser.current
+            ));
+        }
+        Ok(value)
+    }
+}
+
+impl fmt::Display for JsonValue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            JsonValue::Null => write!(f, "null"),
+            JsonValue::Integer(val) => write!(f, "{}", val),
+            // The debug representation always keeps a fractional part or an exponent,
+            // so reals are read back as reals.
+            JsonValue::Real(val) => write!(f, "{:?}", val),
+            JsonValue::String(val) => write_escaped(f, val),
+            JsonValue::Array(values) => {
+                write!(f, "[")?;
+                for (i, value) in values.iter().enumerate() {
+                    if i > 0 {
+                        write!(f, ",")?;
+                    }
+                    write!(f, "{}", value)?;
+                }
+                write!(f, "]")
+            }
+            JsonValue::Object(entries) => {
+                write!(f, "{{")?;
+                for (i, (key, value)) in entries.iter().enumerate() {
+                    if i > 0 {
+                        write!(f, ",")?;
+                    }
+                    write_escaped(f, key)?;
+                    write!(f, ":{}", value)?;
+                }
+                write!(f, "}}")
+            }
+        }
+    }
+}
+
+pub fn encode_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|byte| format!("{:02x}", byte)).collect()
+}
+
+pub fn decode_hex(hex: &str) -> Result<Vec<u8>, String> {
+    if !hex.len().is_multiple_of(2) || !hex.is_ascii() {
+        return Err(format!("Invalid hex string: {}", hex));
+    }
+    (0..hex.len())
+        .step_by(2)
+        .map(|i| {
+            u8::from_str_radix(&hex[i..i + 2], 16)
+                .map_err(|_| format!("Invalid hex string: {}", hex))
+        })
+        .collect()
+}
+
+fn write_escaped(f: &mut fmt::Formatter<'_>, value: &str) -> fmt::Result {
+    write!(f, "\"")?;
+    for c in value.chars() {
+        match c {
+            '"' => write!(f, "\\\"")?,
+            '\\' => write!(f, "\\\\")?,
+            '\n' => write!(f, "\\n")?,
+            '\r' => write!(f, "\\r")?,
+            '\t' => write!(f, "\\t")?,
+            c if (c as u32) < 0x20 => write!(f, "\\u{:04x}", c as u32)?,
+            c => write!(f, "{}", c)?,
+        }
+    }
+    write!(f, "\"")
+}
+
+struct JsonParser {
+    chars: Vec<char>,
+    current: usize,
+}
+
+impl JsonParser {
+    fn peek(&self) -> Option<char> {
+        self.chars.get(self.current).copied()
+    }
+
+    fn skip_whitespace(&mut self) {
+        while self.peek().is_some_and(|c| c.is_whitespace()) {
+            self.current += 1;
+        }
+    }
+
+    fn expect(&mut self, expected: char) -> Result<(), String> {
+        self.skip_whitespace();
+        if self.peek() != Some(expected) {
+            return Err(format!(
+                "Expected '{}' in JSON at position {}",
+                expected, self.current
+            ));
+        }
+        self.current += 1;
+        Ok(())
+    }
+
+    fn parse_value(&mut self) -> Result<JsonValue, String> {
+        self.skip_whitespace();
+        match self.peek() {
+            Some('{') => self.parse_object(),
+            Some('[') => self.parse_array(),
+            Some('"') => Ok(JsonValue::String(self.parse_string()?)),
+            Some(c) if c == '-' || c.is_ascii_digit() => self.parse_number(),
+            Some(_) => self.parse_literal(),
+            None => Err("Unexpected end of JSON input".to_string()),
+        }
+    }
+
+    fn parse_object(&mut self) -> Result<JsonValue, String> {
+        self.expect('{')?;
+        let mut entries = vec![];
+        self.skip_whitespace();
+        if self.peek() == Some('}') {
+            self.current += 1;
+            return Ok(JsonValue::Object(entries));
+        }
+        loop {
+            self.skip_whitespace();
+            let key = self.parse_string()?;
+            self.expect(':')?;
+            entries.push((key, self.parse_value()?));
+            self.skip_whitespace();
+            match self.peek() {
+                Some(',') => self.current += 1,
+                Some('}') => {
+                    self.current += 1;
+                    return Ok(JsonValue::Object(entries));
+                }
+                _ => {
+                    return Err(format!(
+                        "Expected ',' or '}}' in JSON at position {}",
+                        self.current
+                    ));
+                }
+            }
+        }
+    }
+
+    fn parse_array(&mut self) -> Result<JsonValue, String> {
+        self.expect('[')?;
+        let mut values = vec![];
+        self.skip_whitespace();
+        if self.peek() == Some(']') {
+            self.current += 1;
+            return Ok(JsonValue::Array(values));
+        }
+        loop {
+            values.push(self.parse_value()?);
+            self.skip_whitespace();
+            match self.peek() {
+                Some(',') => self.current += 1,
+                Some(']') => {
+                    self.current += 1;
+                    return Ok(JsonValue::Array(values));
+                }
+                _ => {
+                    return Err(format!(
+                        "Expected ',' or ']' in JSON at position {}",
+                        self.current
+                    ));
+                }
+            }
+        }
+    }
+
+    fn parse_string(&mut self) -> Result<String, String> {
+        self.expect('"')?;
+        let mut result = String::new();
+        loop {
+            let c = self
+                .peek()
+                .ok_or_else(|| "Unterminated string in JSON".to_string())?;
+            self.current += 1;
+            match c {
+                '"' => return Ok(result),
+                '\\' => {
+                    let escaped = self
+                        .peek()
+                        .ok_or_else(|| "Unterminated string in JSON".to_string())?;
+                    self.current += 1;
+                    match escaped {
+                        '"' => result.push('"'),
+                        '\\' => result.push('\\'),
+                        '/' => result.push('/'),
+                        'b' => result.push('\u{8}'),
+                        'f' => result.push('\u{c}'),
+                        'n' => result.push('\n'),
+                        'r' => result.push('\r'),
+                        't' => result.push('\t'),
+                        'u' => {
+                            let hex: String =
+                                self.chars.iter().skip(self.current).take(4).collect();
+                            let code = u32::from_str_radix(&hex, 16)
+                                .map_err(|_| format!("Invalid unicode escape in JSON: {}", hex))?;
+                            self.current += 4;
+                            result.push(char::from_u32(code).unwrap_or('\u{fffd}'));
+                        }
+                        _ => return Err(format!("Invalid escape in JSON: \\{}", escaped)),
+                    }
+                }
+                c => result.push(c),
+            }
+        }
+    }
+
+    fn parse_number(&mut self) -> Result<JsonValue, String> {
+        let start = self.current;
+        while self
+            .peek()
+            .is_some_and(|c| c.is_ascii_digit() || matches!(c, '-' | '+' | '.' | 'e' | 'E'))
+        {
+            self.current += 1;
+        }
+        let text: String = self.chars[start..self.current].iter().collect();
+        if let Ok(val) = text.parse::<i64>() {
+            return Ok(JsonValue::Integer(val));
+        }
+        text.parse::<f64>()
+            .map(JsonValue::Real)
+            .map_err(|_| format!("Invalid number in JSON: {}", text))
+    }
+
+    fn parse_literal(&mut self) -> Result<JsonValue, String> {
+        let start = self.current;
+        while self.peek().is_some_and(|c| c.is_ascii_alphabetic()) {
+            self.current += 1;
+        }
+        let text: String = self.chars[start..self.current].iter().collect();
+        match text.as_str() {
+            "null" => Ok(JsonValue::Null),
+            "true" => Ok(JsonValue::Integer(1)),
+            "false" => Ok(JsonValue::Integer(0)),
+            _ => Err(format!("Unexpected token in JSON: {}", text)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn json_values_are_serialized_and_parsed_back() {
+        let value = JsonValue::Array(vec![JsonValue::Object(vec![
+            ("id".to_string(), JsonValue::Integer(-1)),
+            ("money".to_string(), JsonValue::Real(1000.0)),
+            ("small".to_string(), JsonValue::Real(1e-7)),
+            (
+                "name".to_string(),
+                JsonValue::String("Jo \"J\"\n\\".to_string()),
+            ),
+            ("nothing".to_string(), JsonValue::Null),
+        ])]);
+        let serialized = value.to_string();
+        assert_eq!(
+            serialized,
+            "[{\"id\":-1,\"money\":1000.0,\"small\":1e-7,\"name\":\"Jo \\\"J\\\"\\n\\\\\",\"nothing\":null}]"
+        );
+        assert_eq!(JsonValue::parse(&serialized), Ok(value));
+    }
+
+    #[test]
+    fn invalid_json_is_an_error() {
+        assert!(JsonValue::parse("[1, 2").is_err());
+        assert!(JsonValue::parse("{\"a\" 1}").is_err());
+        assert!(JsonValue::parse("[1] 2").is_err());
+        assert!(JsonValue::parse("nope").is_err());
+    }
+
+    #[test]
+    fn blobs_are_encoded_as_hex() {
+        let value = JsonValue::from_value(&Value::Blob(vec![0x00, 0xab, 0x10]));
+        assert_eq!(value, JsonValue::String("00ab10".to_string()));
+        assert_eq!(decode_hex("00ab10"), Ok(vec![0x00, 0xab, 0x10]));
+        assert!(decode_hex("0g").is_err());
+    }
+}
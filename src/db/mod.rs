@@ -1,3 +1,4 @@
 pub mod database;
+pub mod json;
 pub mod table;
 pub mod transactions;
@@ -1,4 +1,5 @@
-use crate::db::table::core::value::DataType;
+use crate::db::table::core::value::{DataType, Value};
+use crate::interpreter::ast::SelectableColumn;
 
 #[derive(Debug, PartialEq, Clone)]
 pub struct ColumnDefinition {
@@ -7,9 +8,83 @@ pub struct ColumnDefinition {
     pub constraints: Vec<ColumnConstraint>,
 }
 
+impl ColumnDefinition {
+    pub fn estimate_memory(&self) -> usize {
+        size_of::<ColumnDefinition>()
+            + self.name.len()
+            + self.constraints.len() * size_of::<ColumnConstraint>()
+    }
+
+    // The expression of a generated column, None for stored columns.
+    pub fn generated_expression(&self) -> Option<&SelectableColumn> {
+        self.constraints
+            .iter()
+            .find_map(|constraint| match &constraint.constraint_type {
+                ConstraintType::Generated(expression) => Some(expression),
+                _ => None,
+            })
+    }
+
+    // The value given to the column when a row is inserted without it, NULL without a DEFAULT.
+    pub fn default_value(&self) -> Value {
+        self.constraints
+            .iter()
+            .find_map(|constraint| match &constraint.constraint_type {
+                ConstraintType::Default(value) => Some(value.clone()),
+                _ => None,
+            })
+            .unwrap_or(Value::Null)
+    }
+}
+
 #[derive(Debug, PartialEq, Clone)]
 pub struct ColumnConstraint {
-    pub constraint_type: String,
+    pub constraint_type: ConstraintType,
+    // The `ON CONFLICT` action declared on the constraint, ABORT when not given.
+    pub conflict_action: Option<ConflictAction>,
+    // Set by `DEFERRABLE INITIALLY DEFERRED`, the constraint is then only checked at COMMIT
+    // when the statement runs inside a transaction.
+    pub deferred: bool,
+}
+
+// A PRIMARY KEY or UNIQUE constraint declared after the columns of a table, e.g.
+// `PRIMARY KEY (a, b)`. Such constraints over a single column are kept with the column instead.
+#[derive(Debug, PartialEq, Clone)]
+pub struct TableConstraint {
+    pub constraint_type: ConstraintType,
+    pub columns: Vec<String>,
+    pub conflict_action: Option<ConflictAction>,
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub enum ConstraintType {
+    PrimaryKey,
+    NotNull,
+    Unique,
+    // `REFERENCES table(column)`, the column defaults to the primary key of the parent table.
+    // Only checked on INSERT when `PRAGMA foreign_keys` is on, and only to reject rows without a
+    // parent: ON DELETE and ON UPDATE actions such as CASCADE are not implemented.
+    ForeignKey {
+        table_name: String,
+        column: Option<String>,
+    },
+    // `AUTOINCREMENT`, following the PRIMARY KEY of an INTEGER column. Rows inserted without a
+    // value get one above any the column ever held, see `Table::autoincrement_sequence`.
+    AutoIncrement,
+    // `GENERATED ALWAYS AS (expression) VIRTUAL`. The value is never stored: rows hold NULL in
+    // its place and the expression is evaluated against the other columns of the row when read.
+    Generated(SelectableColumn),
+    // `DEFAULT value`, only literal values are supported, not expressions.
+    Default(Value),
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub enum ConflictAction {
+    Rollback,
+    Abort,
+    Fail,
+    Ignore,
+    Replace,
 }
 
 #[derive(Debug, PartialEq, Clone)]
@@ -42,19 +117,18 @@ impl ColumnStack {
         new_column_name: &String,
         is_transaction: bool,
     ) -> Result<(), String> {
+        // Validated before the columns are cloned for the transaction, so a failed rename leaves
+        // the stack untouched.
+        let index = self
+            .get_index_of_column(old_column_name)
+            .map_err(|_| "Column does not exist".to_string())?;
+        if self.get_index_of_column(new_column_name).is_ok() {
+            return Err("Column already exists".to_string());
+        }
         if is_transaction {
             self.append_clone()?;
         }
-        let columns = self
-            .peek_mut()?
-            .iter_mut()
-            .find(|column| column.name == *old_column_name);
-        match columns {
-            Some(column) => column.name = new_column_name.clone(),
-            None => {
-                return Err("Column does not exist".to_string());
-            }
-        }
+        self.peek_mut()?[index].name = new_column_name.clone();
         Ok(())
     }
 
@@ -75,6 +149,33 @@ impl ColumnStack {
         Ok(())
     }
 
+    pub fn set_not_null(
+        &mut self,
+        column_name: &String,
+        is_transaction: bool,
+    ) -> Result<(), String> {
+        if is_transaction {
+            self.append_clone()?;
+        }
+        let column = self
+            .peek_mut()?
+            .iter_mut()
+            .find(|column| column.name == *column_name)
+            .ok_or_else(|| "Column does not exist".to_string())?;
+        if !column
+            .constraints
+            .iter()
+            .any(|constraint| constraint.constraint_type == ConstraintType::NotNull)
+        {
+            column.constraints.push(ColumnConstraint {
+                constraint_type: ConstraintType::NotNull,
+                conflict_action: None,
+                deferred: false,
+            });
+        }
+        Ok(())
+    }
+
     pub fn get_index_of_column(&self, column_name: &String) -> Result<usize, String> {
         let columns = self.peek();
         match columns {
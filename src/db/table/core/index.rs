@@ -0,0 +1,157 @@
+use crate::db::table::core::row::Row;
+use crate::db::table::core::value::Value;
+use std::cmp::Ordering;
+use std::collections::BTreeMap;
+
+#[derive(Debug, Clone)]
+pub struct Index {
+    pub name: String,
+    pub columns: Vec<String>,
+    // Set by `CREATE UNIQUE INDEX`, no two rows may share a key.
+    pub unique: bool,
+    // Kept in key order, so the smallest and largest keys can be read without a scan.
+    entries: BTreeMap<IndexKey, Vec<usize>>,
+}
+
+// Keys never contain NULL, the only value without a total order, so they can be sorted.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct IndexKey(Row);
+
+impl Ord for IndexKey {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.0.partial_cmp(&other.0).unwrap_or(Ordering::Equal)
+    }
+}
+
+impl PartialOrd for IndexKey {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Index {
+    pub fn new(name: String, columns: Vec<String>) -> Self {
+        Self {
+            name,
+            columns,
+            unique: false,
+            entries: BTreeMap::new(),
+        }
+    }
+
+    // Builds the lookup key for a row given the positions of the indexed columns.
+    // Rows with a NULL in any indexed column are never equal to anything so they are not indexed.
+    pub fn key(row: &Row, column_indices: &[usize]) -> Option<Row> {
+        let mut key = vec![];
+        for index in column_indices {
+            let value = row.get(*index)?;
+            if value.is_null() {
+                return None;
+            }
+            key.push(normalize_key_value(value));
+        }
+        Some(Row(key))
+    }
+
+    pub fn insert(&mut self, key: Row, position: usize) {
+        self.entries
+            .entry(IndexKey(key))
+            .or_default()
+            .push(position);
+    }
+
+    pub fn remove(&mut self, key: &Row, position: usize) {
+        let key = IndexKey(key.clone());
+        if let Some(positions) = self.entries.get_mut(&key) {
+            positions.retain(|p| *p != position);
+            if positions.is_empty() {
+                self.entries.remove(&key);
+            }
+        }
+    }
+
+    pub fn lookup(&self, key: &Row) -> Vec<usize> {
+        let key = IndexKey(Row(key.iter().map(normalize_key_value).collect()));
+        self.entries.get(&key).cloned().unwrap_or_default()
+    }
+
+    // Positions of the rows holding the smallest key, or None if the index is empty.
+    pub fn min_positions(&self) -> Option<&Vec<usize>> {
+        self.entries
+            .first_key_value()
+            .map(|(_, positions)| positions)
+    }
+
+    // Positions of the rows holding the largest key, or None if the index is empty.
+    pub fn max_positions(&self) -> Option<&Vec<usize>> {
+        self.entries
+            .last_key_value()
+            .map(|(_, positions)| positions)
+    }
+
+    pub fn clear(&mut self) {
+        self.entries.clear();
+    }
+
+    // Approximate number of bytes used by the index, its keys and the positions they point to.
+    pub fn estimate_memory(&self) -> usize {
+        size_of::<Index>()
+            + self.name.len()
+            + self.columns.iter().map(String::len).sum::<usize>()
+            + self
+                .entries
+                .iter()
+                .map(|(key, positions)| {
+                    key.0.estimate_memory() + positions.len() * size_of::<usize>()
+                })
+                .sum::<usize>()
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.values().map(|positions| positions.len()).sum()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    pub fn distinct_keys(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn has_duplicate_keys(&self) -> bool {
+        self.distinct_keys() != self.len()
+    }
+}
+
+// Integers and integral reals compare equal, so they are stored under the same key.
+fn normalize_key_value(value: &Value) -> Value {
+    match value {
+        Value::Real(real)
+            if real.fract() == 0.0 && *real >= i64::MIN as f64 && *real < i64::MAX as f64 =>
+        {
+            Value::Integer(*real as i64)
+        }
+        _ => value.clone(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn index_lookup_normalizes_integral_reals() {
+        let mut index = Index::new("idx".to_string(), vec!["money".to_string()]);
+        let key = Index::key(&Row(vec![Value::Real(1000.0)]), &[0]).unwrap();
+        index.insert(key, 3);
+        assert_eq!(index.lookup(&Row(vec![Value::Integer(1000)])), vec![3]);
+        assert_eq!(index.lookup(&Row(vec![Value::Real(1000.0)])), vec![3]);
+        assert!(index.lookup(&Row(vec![Value::Integer(1)])).is_empty());
+    }
+
+    #[test]
+    fn index_key_skips_null_values() {
+        assert_eq!(Index::key(&Row(vec![Value::Null]), &[0]), None);
+    }
+}
@@ -1,4 +1,5 @@
 pub mod column;
+pub mod index;
 pub mod row;
 pub mod table;
 pub mod value;
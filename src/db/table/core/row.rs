@@ -1,5 +1,6 @@
 use crate::db::table::core::value::Value;
 use std::cmp::Ordering;
+use std::hash::{Hash, Hasher};
 use std::ops::{Deref, DerefMut};
 
 #[derive(Debug, Hash, Clone)]
@@ -9,9 +10,15 @@ pub struct Row(pub Vec<Value>);
 #[derive(Debug, Clone)]
 pub struct RowStack {
     pub stack: Vec<Row>,
+    // Assigned by the table in increasing order as rows are inserted, see `Table::push`.
+    pub rowid: i64,
 }
 
 impl Row {
+    pub fn estimate_memory(&self) -> usize {
+        size_of::<Row>() + self.iter().map(Value::estimate_memory).sum::<usize>()
+    }
+
     pub fn exactly_equal(self, other: &Self) -> bool {
         if self.len() != other.len() {
             return false;
@@ -25,6 +32,49 @@ impl Row {
     }
 }
 
+impl Row {
+    // Equality used by DISTINCT and set operations, where NULLs are considered equal to each other
+    // while every other value compares as it does in expressions, so 1 and 1.0 are the same.
+    pub fn is_not_distinct_from(&self, other: &Self) -> bool {
+        if self.len() != other.len() {
+            return false;
+        }
+        self.iter()
+            .zip(other.iter())
+            .all(|(first, second)| match (first, second) {
+                (Value::Null, Value::Null) => true,
+                (Value::Null, _) | (_, Value::Null) => false,
+                _ => first == second,
+            })
+    }
+}
+
+// Wraps a row so hashed collections compare rows with `Row::is_not_distinct_from`.
+#[derive(Debug, Clone)]
+pub struct DistinctRow(pub Row);
+
+impl PartialEq for DistinctRow {
+    fn eq(&self, other: &Self) -> bool {
+        self.0.is_not_distinct_from(&other.0)
+    }
+}
+
+impl Eq for DistinctRow {}
+
+impl Hash for DistinctRow {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        for value in self.0.iter() {
+            match value {
+                // Integral reals hash like the integer they are equal to.
+                Value::Real(real) if real.fract() == 0.0 && real.abs() < i64::MAX as f64 => {
+                    Value::Integer(*real as i64).hash(state)
+                }
+                _ => value.hash(state),
+            }
+        }
+    }
+}
+
 impl Deref for Row {
     type Target = Vec<Value>;
     fn deref(&self) -> &Self::Target {
@@ -67,17 +117,25 @@ impl Eq for Row {}
 
 impl RowStack {
     pub fn new(stack: Row) -> Self {
-        Self { stack: vec![stack] }
+        Self {
+            stack: vec![stack],
+            rowid: 0,
+        }
     }
 
     pub fn new_with_stack(stack: Vec<Row>) -> Self {
-        Self { stack }
+        Self { stack, rowid: 0 }
     }
 
     pub fn append_clone(&mut self) {
         self.stack.push(self.stack.last().unwrap().clone());
     }
 
+    // Counts every version of the row kept for the current transaction.
+    pub fn estimate_memory(&self) -> usize {
+        size_of::<RowStack>() + self.stack.iter().map(Row::estimate_memory).sum::<usize>()
+    }
+
     pub fn exactly_equal(&self, other: &Self) -> bool {
         if self.stack.len() != other.stack.len() {
             return false;
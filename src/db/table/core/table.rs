@@ -1,18 +1,35 @@
 use crate::db::table::core::column::ColumnDefinition;
 use crate::db::table::core::column::ColumnStack;
+use crate::db::table::core::column::ConstraintType;
+use crate::db::table::core::column::TableConstraint;
+use crate::db::table::core::index::Index as TableIndex;
 use crate::db::table::core::row::Row;
 use crate::db::table::core::row::RowStack;
+use crate::db::table::core::value::{DataType, Value};
 use std::ops::{Index, IndexMut};
 
-#[cfg(test)]
-use crate::db::table::core::value::Value;
-
 #[derive(Debug)]
 pub struct Table {
     pub name: NameStack,
     pub columns: ColumnStack,
     pub rows: Vec<RowStack>,
+    pub indexes: Vec<TableIndex>,
+    pub table_constraints: Vec<TableConstraint>,
+    // Declared with `WITHOUT ROWID`. Rows are still stored in insertion order, the flag is only
+    // kept so the table is recreated the same way.
+    pub without_rowid: bool,
+    // The largest value inserted or updated into the AUTOINCREMENT column, if the table has one.
+    // Kept when its row is deleted so the value is never given to another row.
+    pub autoincrement_sequence: i64,
+    // Copied from the database setting of the same name, see `Database::real_equality_epsilon`,
+    // into the stored tables before each statement and into the tables a SELECT builds.
+    pub real_equality_epsilon: Option<f64>,
+    // Indexes dropped during a transaction, kept so a rollback can restore them.
+    dropped_indexes: Vec<TableIndex>,
     length: usize,
+    next_rowid: i64,
+    // The rowid of the first row inserted during the current transaction, see `changed_positions`.
+    transaction_first_rowid: i64,
 }
 
 #[derive(Debug)]
@@ -40,7 +57,15 @@ impl Table {
             name: NameStack { stack: vec![name] },
             columns: ColumnStack::new(columns),
             rows: vec![],
+            indexes: vec![],
+            table_constraints: vec![],
+            without_rowid: false,
+            autoincrement_sequence: 0,
+            real_equality_epsilon: None,
+            dropped_indexes: vec![],
             length: 0,
+            next_rowid: 1,
+            transaction_first_rowid: 1,
         }
     }
 
@@ -77,12 +102,66 @@ impl Table {
         self.length
     }
 
+    // Approximate number of bytes used by the table: its rows, including the versions and deleted
+    // rows kept for a transaction, its column definitions and its indexes.
+    pub fn estimate_memory(&self) -> usize {
+        size_of::<Table>()
+            + self
+                .rows
+                .iter()
+                .map(RowStack::estimate_memory)
+                .sum::<usize>()
+            + self
+                .columns
+                .stack
+                .iter()
+                .flatten()
+                .map(ColumnDefinition::estimate_memory)
+                .sum::<usize>()
+            + self
+                .indexes
+                .iter()
+                .chain(self.dropped_indexes.iter())
+                .map(TableIndex::estimate_memory)
+                .sum::<usize>()
+    }
+
+    // Number of rows visible to queries, used by the query planner to estimate costs.
+    pub fn row_count(&self) -> usize {
+        self.length
+    }
+
+    // Estimated fraction of the rows sharing a single key of the index, assuming keys are
+    // evenly distributed. Returns None if the index does not exist.
+    pub fn index_selectivity(&self, index_name: &str) -> Option<f64> {
+        let index = self.get_index(index_name)?;
+        if self.row_count() == 0 || index.distinct_keys() == 0 {
+            return Some(0.0);
+        }
+        let rows_per_key = index.len() as f64 / index.distinct_keys() as f64;
+        Some(rows_per_key / self.row_count() as f64)
+    }
+
     pub fn set_length(&mut self, length: usize) {
+        // Rows hidden or revealed by the new length leave or re-enter the indexes.
+        for position in length..self.length {
+            self.update_indexes_for_row(position, false);
+        }
+        for position in self.length..length {
+            self.update_indexes_for_row(position, true);
+        }
         self.length = length;
     }
 
     pub fn swap(&mut self, a: usize, b: usize) {
+        if a == b {
+            return;
+        }
+        self.update_indexes_for_row(a, false);
+        self.update_indexes_for_row(b, false);
         self.rows.swap(a, b);
+        self.update_indexes_for_row(a, true);
+        self.update_indexes_for_row(b, true);
     }
 
     #[cfg(test)]
@@ -122,17 +201,29 @@ impl Table {
     pub fn set_rows(&mut self, rows: Vec<Row>) {
         self.length = rows.len();
         self.rows = rows.into_iter().map(|r| RowStack::new(r)).collect();
+        for (i, row_stack) in self.rows.iter_mut().enumerate() {
+            row_stack.rowid = i as i64 + 1;
+        }
+        self.next_rowid = self.rows.len() as i64 + 1;
+        let _ = self.rebuild_indexes();
     }
 
+    // Rows deleted during a transaction are kept past the length of the table until it commits,
+    // so a new row goes before them to be visible.
     pub fn push(&mut self, row: Row) {
+        let mut row_stack = RowStack::new(row);
+        row_stack.rowid = self.next_rowid;
+        self.next_rowid += 1;
+        self.rows.insert(self.length, row_stack);
         self.length += 1;
-        self.rows.push(RowStack::new(row));
+        self.update_indexes_for_row(self.length - 1, true);
     }
 
     pub fn pop(&mut self) -> Option<Row> {
         if self.length == 0 {
             return None;
         }
+        self.update_indexes_for_row(self.rows.len() - 1, false);
         self.length -= 1;
         self.rows.pop().and_then(|mut value| value.stack.pop())
     }
@@ -150,6 +241,7 @@ impl Table {
             let last_column_stack = self.columns.stack.pop().unwrap();
             self.columns = ColumnStack::new(last_column_stack);
         }
+        self.dropped_indexes.clear();
         if self.name.stack.len() > 1 {
             let last_name = self.name.stack.pop().unwrap();
             self.name = NameStack {
@@ -159,6 +251,36 @@ impl Table {
         Ok(())
     }
 
+    // Drops the rows deleted during the transaction, which were only hidden past the length of the
+    // table so a rollback could bring them back. Deleting moves rows around, so the remaining rows
+    // can also be put back in the order they were inserted in.
+    pub fn commit_rows(&mut self, restore_insertion_order: bool) -> Result<(), String> {
+        self.rows.truncate(self.length);
+        if restore_insertion_order {
+            self.rows.sort_by_key(|row_stack| row_stack.rowid);
+            self.rebuild_indexes()?;
+        }
+        Ok(())
+    }
+
+    pub fn begin_transaction(&mut self) {
+        self.transaction_first_rowid = self.next_rowid;
+    }
+
+    // Positions of the rows inserted or updated since the transaction began. Positions logged by
+    // the statements can't be used as a DELETE moves rows around.
+    pub fn changed_positions(&self) -> Vec<usize> {
+        self.rows
+            .iter()
+            .take(self.length)
+            .enumerate()
+            .filter(|(_, row_stack)| {
+                row_stack.stack.len() > 1 || row_stack.rowid >= self.transaction_first_rowid
+            })
+            .map(|(position, _)| position)
+            .collect()
+    }
+
     pub fn rollback_columns(&mut self) {
         self.columns.stack.pop();
     }
@@ -194,6 +316,39 @@ impl Table {
         ));
     }
 
+    pub fn has_generated_columns(&self) -> bool {
+        self.columns.stack.last().is_some_and(|columns| {
+            columns
+                .iter()
+                .any(|column| column.generated_expression().is_some())
+        })
+    }
+
+    // Index of the column declared with AUTOINCREMENT, see `autoincrement_sequence`.
+    pub fn autoincrement_column(&self) -> Result<Option<usize>, String> {
+        Ok(self.get_columns()?.iter().position(|column| {
+            column
+                .constraints
+                .iter()
+                .any(|constraint| constraint.constraint_type == ConstraintType::AutoIncrement)
+        }))
+    }
+
+    // Index of the INTEGER PRIMARY KEY column, which is an alias of the rowid unless the table is
+    // WITHOUT ROWID.
+    pub fn rowid_alias_column(&self) -> Result<Option<usize>, String> {
+        if self.without_rowid {
+            return Ok(None);
+        }
+        Ok(self.get_columns()?.iter().position(|column| {
+            column.data_type == DataType::Integer
+                && column
+                    .constraints
+                    .iter()
+                    .any(|constraint| constraint.constraint_type == ConstraintType::PrimaryKey)
+        }))
+    }
+
     pub fn get_columns(&self) -> Result<Vec<&ColumnDefinition>, String> {
         Ok(self
             .columns
@@ -204,6 +359,22 @@ impl Table {
             .collect())
     }
 
+    // The rowid of the row at a position. As in SQLite, an INTEGER PRIMARY KEY column is an alias
+    // of the rowid, otherwise it is the number the row was given when inserted.
+    pub fn get_rowid(&self, position: usize) -> Result<i64, String> {
+        let row_stack = self
+            .rows
+            .get(position)
+            .ok_or_else(|| format!("Row {} does not exist", position))?;
+        match self
+            .rowid_alias_column()?
+            .map(|index| &self[position][index])
+        {
+            Some(Value::Integer(rowid)) => Ok(*rowid),
+            _ => Ok(row_stack.rowid),
+        }
+    }
+
     pub fn get_columns_mut(&mut self) -> Result<Vec<&mut ColumnDefinition>, String> {
         Ok(self
             .columns
@@ -226,6 +397,112 @@ impl Table {
         self.columns.push_column(column, is_transaction);
     }
 
+    pub fn has_index(&self, index_name: &str) -> bool {
+        self.indexes.iter().any(|index| index.name == index_name)
+    }
+
+    pub fn get_index(&self, index_name: &str) -> Option<&TableIndex> {
+        self.indexes.iter().find(|index| index.name == index_name)
+    }
+
+    pub fn create_index(&mut self, mut index: TableIndex) -> Result<(), String> {
+        for column in index.columns.iter() {
+            if !self.has_column(column)? {
+                return Err(format!(
+                    "Column `{}` does not exist in table `{}`",
+                    column,
+                    self.name()?
+                ));
+            }
+            // Indexes are built from the stored values, which generated columns don't have.
+            if self.get_columns()?[self.get_index_of_column(column)?]
+                .generated_expression()
+                .is_some()
+            {
+                return Err(format!(
+                    "Indexes on generated column `{}` are not supported",
+                    column
+                ));
+            }
+        }
+        self.build_index(&mut index)?;
+        if index.unique && index.has_duplicate_keys() {
+            return Err(format!("UNIQUE constraint failed: index {}", index.name));
+        }
+        self.indexes.push(index);
+        Ok(())
+    }
+
+    pub fn drop_index(&mut self, index_name: &str, is_transaction: bool) -> Result<(), String> {
+        let position = self
+            .indexes
+            .iter()
+            .position(|index| index.name == index_name)
+            .ok_or_else(|| format!("Index `{}` does not exist", index_name))?;
+        let index = self.indexes.remove(position);
+        if is_transaction {
+            self.dropped_indexes.push(index);
+        }
+        Ok(())
+    }
+
+    pub fn rollback_drop_index(&mut self) -> Result<(), String> {
+        let mut index = self
+            .dropped_indexes
+            .pop()
+            .ok_or_else(|| "No dropped index to restore".to_string())?;
+        self.build_index(&mut index)?;
+        self.indexes.push(index);
+        Ok(())
+    }
+
+    // Recomputes every index from the visible rows, used after bulk changes such as rollbacks.
+    pub fn rebuild_indexes(&mut self) -> Result<(), String> {
+        let mut indexes = std::mem::take(&mut self.indexes);
+        for index in indexes.iter_mut() {
+            self.build_index(index)?;
+        }
+        self.indexes = indexes;
+        Ok(())
+    }
+
+    fn build_index(&self, index: &mut TableIndex) -> Result<(), String> {
+        let column_indices = self.get_index_column_indices(index)?;
+        index.clear();
+        for (position, row) in self.iter().enumerate() {
+            if let Some(key) = TableIndex::key(row, &column_indices) {
+                index.insert(key, position);
+            }
+        }
+        Ok(())
+    }
+
+    // Adds or removes the index entries of the row at the given position.
+    fn update_indexes_for_row(&mut self, position: usize, add: bool) {
+        if position >= self.rows.len() {
+            return;
+        }
+        for i in 0..self.indexes.len() {
+            if let Ok(column_indices) = self.get_index_column_indices(&self.indexes[i])
+                && let Some(key) = TableIndex::key(&self[position], &column_indices)
+            {
+                if add {
+                    self.indexes[i].insert(key, position);
+                } else {
+                    self.indexes[i].remove(&key, position);
+                }
+            }
+        }
+    }
+
+    fn get_index_column_indices(&self, index: &TableIndex) -> Result<Vec<usize>, String> {
+        index
+            .columns
+            .iter()
+            .map(|column| self.get_index_of_column(column))
+            .collect()
+    }
+
     #[cfg(test)]
     pub fn get_columns_clone(&self) -> Result<Vec<ColumnDefinition>, String> {
         Ok(self.get_columns()?.iter().map(|c| (*c).clone()).collect())
@@ -257,4 +534,27 @@ mod tests {
         assert_eq!(result, None);
         assert_eq!(table.len(), 0);
     }
+
+    #[test]
+    fn estimate_memory_grows_with_rows_and_indexes() {
+        let columns = vec![ColumnDefinition {
+            name: "name".to_string(),
+            data_type: DataType::Text,
+            constraints: vec![],
+        }];
+        let mut table = Table::new("test".to_string(), columns);
+        let empty = table.estimate_memory();
+        table.push(Row(vec![Value::Text("a".to_string())]));
+        let one_row = table.estimate_memory();
+        assert!(one_row > empty);
+        table.push(Row(vec![Value::Text("a".repeat(100))]));
+        assert!(table.estimate_memory() >= one_row + 100);
+
+        let before_index = table.estimate_memory();
+        table
+            .indexes
+            .push(TableIndex::new("idx".to_string(), vec!["name".to_string()]));
+        table.rebuild_indexes().unwrap();
+        assert!(table.estimate_memory() > before_index);
+    }
 }
@@ -20,6 +20,16 @@ pub enum Value {
 }
 
 impl Value {
+    // Approximate number of bytes used by the value, text and blobs counting their length.
+    pub fn estimate_memory(&self) -> usize {
+        size_of::<Value>()
+            + match self {
+                Value::Text(text) => text.len(),
+                Value::Blob(bytes) => bytes.len(),
+                _ => 0,
+            }
+    }
+
     pub fn get_type(&self) -> DataType {
         match self {
             Value::Integer(_) => DataType::Integer,
@@ -57,6 +67,11 @@ impl Value {
             Value::Text(val) => Some(val.clone()),
             Value::Blob(val) => String::from_utf8(val.clone()).ok(),
             Value::Integer(val) => Some(val.to_string()),
+            // -0.0 is equal to 0.0, so it is displayed the same way.
+            Value::Real(val) if *val == 0.0 => Some("0".to_string()),
+            // Very large and very small magnitudes are written in scientific notation, which
+            // is shorter and still parses back to the same real.
+            Value::Real(val) if val.abs() >= 1e15 || val.abs() < 1e-4 => Some(format!("{:e}", val)),
             Value::Real(val) => Some(val.to_string()),
         }
     }
@@ -70,26 +85,42 @@ impl Value {
                 .cast_to_text()
                 .map_or(None, |text| Value::Text(text).cast_to_real()),
             Value::Text(val) => {
+                let text = val.trim();
+                let bytes = text.as_bytes();
                 let mut index: usize = 0;
                 let mut has_period = false;
-                for c in val.trim().chars() {
-                    if c == '-' && index == 0
-                        || c == '+' && index == 0
+                for c in bytes {
+                    if *c == b'-' && index == 0
+                        || *c == b'+' && index == 0
                         || c.is_ascii_digit()
-                        || c == '.' && !has_period
+                        || *c == b'.' && !has_period
                     {
                         index += 1;
-                        if c == '.' {
+                        if *c == b'.' {
                             has_period = true;
                         }
                         continue;
                     }
                     break;
                 }
+                // Like SQLite, an exponent only counts if it has digits, so `1e` reads as 1.
+                if matches!(bytes.get(index), Some(b'e' | b'E')) {
+                    let mut end = index + 1;
+                    if matches!(bytes.get(end), Some(b'-' | b'+')) {
+                        end += 1;
+                    }
+                    let digits = bytes[end..]
+                        .iter()
+                        .take_while(|c| c.is_ascii_digit())
+                        .count();
+                    if digits > 0 {
+                        index = end + digits;
+                    }
+                }
                 if index == 0 {
                     Some(0.0)
                 } else {
-                    Some(val.trim()[0..index].parse::<f64>().unwrap_or(0.0))
+                    Some(text[0..index].parse::<f64>().unwrap_or(0.0))
                 }
             }
         }
@@ -157,6 +188,77 @@ impl Value {
         }
     }
 
+    // Converts the value to the given type, failing instead of guessing when the conversion would
+    // lose information, e.g. text that is not a number to an integer. Reals are truncated to
+    // integers, and NULL stays NULL whatever the type. Values keep their type when converted to
+    // DataType::Null, which stands for a column declared without a type.
+    pub fn try_coerce_to(&self, target: DataType) -> Result<Value, String> {
+        let error = || format!("Cannot convert {} to {:?}", self.describe(), target);
+        match (self, &target) {
+            (Value::Null, _) | (_, DataType::Null) => Ok(self.clone()),
+            (Value::Integer(_), DataType::Integer)
+            | (Value::Real(_), DataType::Real)
+            | (Value::Text(_), DataType::Text)
+            | (Value::Blob(_), DataType::Blob) => Ok(self.clone()),
+            (Value::Integer(val), DataType::Real) => Ok(Value::Real(*val as f64)),
+            (Value::Real(val), DataType::Integer) if val.is_finite() => {
+                Ok(Value::Integer(self.cast_to_int().unwrap_or(*val as i64)))
+            }
+            (Value::Real(_), DataType::Integer) => Err(error()),
+            (Value::Integer(_) | Value::Real(_), DataType::Text) => {
+                self.cast_to_text().map(Value::Text).ok_or_else(error)
+            }
+            (Value::Integer(_) | Value::Real(_) | Value::Text(_), DataType::Blob) => {
+                self.cast_to_blob().map(Value::Blob).ok_or_else(error)
+            }
+            (Value::Text(val), DataType::Integer) => {
+                if let Ok(val) = val.trim().parse::<i64>() {
+                    return Ok(Value::Integer(val));
+                }
+                // Reals without a fractional part, e.g. '5.0' or '1e3', are integers too.
+                match val.trim().parse::<f64>() {
+                    Ok(real)
+                        if real.fract() == 0.0
+                            && real >= i64::MIN as f64
+                            && real < i64::MAX as f64 =>
+                    {
+                        Ok(Value::Integer(real as i64))
+                    }
+                    _ => Err(error()),
+                }
+            }
+            (Value::Text(val), DataType::Real) => match val.trim().parse::<f64>() {
+                Ok(real) if real.is_finite() => Ok(Value::Real(real)),
+                _ => Err(error()),
+            },
+            (Value::Blob(val), DataType::Text) => String::from_utf8(val.clone())
+                .map(Value::Text)
+                .map_err(|_| error()),
+            (Value::Blob(_), DataType::Integer | DataType::Real) => Err(error()),
+        }
+    }
+
+    // Converts the value as CAST does, which unlike `try_coerce_to` never fails: text that doesn't
+    // start with a number converts to 0. NULL stays NULL whatever the type.
+    pub fn cast_to(&self, target: &DataType) -> Value {
+        match target {
+            _ if self.is_null() => Value::Null,
+            DataType::Integer => Value::Integer(self.cast_to_int().unwrap_or(0)),
+            DataType::Real => Value::Real(self.cast_to_real().unwrap_or(0.0)),
+            DataType::Text => Value::Text(self.cast_to_text().unwrap_or_default()),
+            DataType::Blob => Value::Blob(self.cast_to_blob().unwrap_or_default()),
+            DataType::Null => self.clone(),
+        }
+    }
+
+    fn describe(&self) -> String {
+        match self {
+            Value::Text(val) => format!("'{}'", val),
+            Value::Blob(_) => "BLOB".to_string(),
+            _ => self.cast_to_text().unwrap_or_else(|| "NULL".to_string()),
+        }
+    }
+
     pub fn exactly_equal(&self, other: &Self) -> bool {
         match (self, other) {
             (Value::Null, Value::Null) => true,
@@ -177,27 +279,18 @@ impl PartialOrd for Value {
             (_, Value::Null) => Some(Ordering::Greater),
             // Same data types & int/real mixing
             (Value::Integer(a), Value::Integer(b)) => a.partial_cmp(b),
-            (Value::Real(a), Value::Real(b)) => {
-                if a.is_nan() && b.is_nan() {
-                    Some(Ordering::Equal)
-                } else if a.is_nan() {
-                    Some(Ordering::Less)
-                } else if b.is_nan() {
-                    Some(Ordering::Greater)
-                } else {
-                    a.partial_cmp(b)
-                }
-            }
+            (Value::Real(a), Value::Real(b)) => Some(compare_reals(*a, *b)),
             (Value::Text(a), Value::Text(b)) => a.partial_cmp(b),
             (Value::Blob(a), Value::Blob(b)) => a.partial_cmp(b),
             // Int/Real mixing
             (Value::Integer(_), Value::Real(b)) => {
-                self.cast_to_real().unwrap_or(0.0).partial_cmp(b)
+                Some(compare_reals(self.cast_to_real().unwrap_or(0.0), *b))
             }
             (Value::Real(a), Value::Integer(_)) => {
-                a.partial_cmp(&other.cast_to_real().unwrap_or(0.0))
+                Some(compare_reals(*a, other.cast_to_real().unwrap_or(0.0)))
             }
-            // Mixing of incompatible data types
+            // Mixing of incompatible data types, ordered by storage class as in SQLite:
+            // NULL < numbers < text < blob
             (Value::Integer(_), Value::Text(_))
             | (Value::Integer(_), Value::Blob(_))
             | (Value::Real(_), Value::Text(_))
@@ -212,6 +305,17 @@ impl PartialOrd for Value {
     }
 }
 
+// NaN is equal to itself and sorts before every other number, so reals always have a total order.
+// 0.0 and -0.0 are equal.
+fn compare_reals(a: f64, b: f64) -> Ordering {
+    match (a.is_nan(), b.is_nan()) {
+        (true, true) => Ordering::Equal,
+        (true, false) => Ordering::Less,
+        (false, true) => Ordering::Greater,
+        (false, false) => a.partial_cmp(&b).unwrap_or(Ordering::Equal),
+    }
+}
+
 impl PartialEq for Value {
     fn eq(&self, other: &Self) -> bool {
         self.partial_cmp(other) == Some(Ordering::Equal)
@@ -231,6 +335,9 @@ impl Hash for Value {
                 1u8.hash(state);
                 if f.is_nan() {
                     u64::MAX.hash(state);
+                } else if *f == 0.0 {
+                    // -0.0 is equal to 0.0, so both need the same hash.
+                    0.0f64.to_bits().hash(state);
                 } else {
                     f.to_bits().hash(state);
                 }
@@ -254,6 +361,140 @@ impl Hash for Value {
 mod tests {
     use super::*;
 
+    #[test]
+    fn try_coerce_to_converts_between_every_pair_of_types() {
+        let cases = vec![
+            // Integer
+            (Value::Integer(5), DataType::Integer, Value::Integer(5)),
+            (Value::Integer(5), DataType::Real, Value::Real(5.0)),
+            (
+                Value::Integer(-5),
+                DataType::Text,
+                Value::Text("-5".to_string()),
+            ),
+            (
+                Value::Integer(5),
+                DataType::Blob,
+                Value::Blob(b"5".to_vec()),
+            ),
+            (Value::Integer(5), DataType::Null, Value::Integer(5)),
+            // Real
+            (Value::Real(2.9), DataType::Integer, Value::Integer(2)),
+            (Value::Real(-2.9), DataType::Integer, Value::Integer(-2)),
+            (
+                Value::Real(1e300),
+                DataType::Integer,
+                Value::Integer(i64::MAX),
+            ),
+            (Value::Real(2.5), DataType::Real, Value::Real(2.5)),
+            (
+                Value::Real(2.5),
+                DataType::Text,
+                Value::Text("2.5".to_string()),
+            ),
+            (
+                Value::Real(2.5),
+                DataType::Blob,
+                Value::Blob(b"2.5".to_vec()),
+            ),
+            (Value::Real(2.5), DataType::Null, Value::Real(2.5)),
+            // Text
+            (
+                Value::Text(" 42 ".to_string()),
+                DataType::Integer,
+                Value::Integer(42),
+            ),
+            (
+                Value::Text("1e3".to_string()),
+                DataType::Integer,
+                Value::Integer(1000),
+            ),
+            (
+                Value::Text("4.0".to_string()),
+                DataType::Integer,
+                Value::Integer(4),
+            ),
+            (
+                Value::Text("4.5".to_string()),
+                DataType::Real,
+                Value::Real(4.5),
+            ),
+            (
+                Value::Text("-7".to_string()),
+                DataType::Real,
+                Value::Real(-7.0),
+            ),
+            (
+                Value::Text("hi".to_string()),
+                DataType::Text,
+                Value::Text("hi".to_string()),
+            ),
+            (
+                Value::Text("hi".to_string()),
+                DataType::Blob,
+                Value::Blob(b"hi".to_vec()),
+            ),
+            (
+                Value::Text("hi".to_string()),
+                DataType::Null,
+                Value::Text("hi".to_string()),
+            ),
+            // Blob
+            (
+                Value::Blob(b"hi".to_vec()),
+                DataType::Text,
+                Value::Text("hi".to_string()),
+            ),
+            (Value::Blob(vec![1]), DataType::Blob, Value::Blob(vec![1])),
+            (Value::Blob(vec![1]), DataType::Null, Value::Blob(vec![1])),
+        ];
+        for (value, target, expected) in cases {
+            let result = value.try_coerce_to(target.clone());
+            assert!(
+                result
+                    .as_ref()
+                    .is_ok_and(|result| result.exactly_equal(&expected)),
+                "{:?} to {:?}: {:?}",
+                value,
+                target,
+                result
+            );
+        }
+
+        // NULL stays NULL.
+        for target in [
+            DataType::Integer,
+            DataType::Real,
+            DataType::Text,
+            DataType::Blob,
+            DataType::Null,
+        ] {
+            assert!(Value::Null.try_coerce_to(target).unwrap().is_null());
+        }
+    }
+
+    #[test]
+    fn try_coerce_to_rejects_impossible_conversions() {
+        let cases = vec![
+            (Value::Text("abc".to_string()), DataType::Integer),
+            (Value::Text("4.5".to_string()), DataType::Integer),
+            (Value::Text("12abc".to_string()), DataType::Real),
+            (Value::Text("1e400".to_string()), DataType::Real),
+            (Value::Real(f64::NAN), DataType::Integer),
+            (Value::Real(f64::INFINITY), DataType::Integer),
+            (Value::Blob(vec![1]), DataType::Integer),
+            (Value::Blob(vec![1]), DataType::Real),
+            (Value::Blob(vec![0xff]), DataType::Text),
+        ];
+        for (value, target) in cases {
+            assert!(value.try_coerce_to(target.clone()).is_err(), "{:?}", value);
+        }
+        assert_eq!(
+            Value::Text("abc".to_string()).try_coerce_to(DataType::Integer),
+            Err("Cannot convert 'abc' to Integer".to_string())
+        );
+    }
+
     #[test]
     fn value_partialord_behaves_as_expected() {
         // NULL with anything
@@ -335,6 +576,59 @@ mod tests {
         assert!(Value::Integer(567) != Value::Text("567".to_string()));
     }
 
+    #[test]
+    fn nan_and_negative_zero_compare_and_hash_consistently() {
+        use std::collections::hash_map::DefaultHasher;
+
+        fn hash(value: &Value) -> u64 {
+            let mut hasher = DefaultHasher::new();
+            value.hash(&mut hasher);
+            hasher.finish()
+        }
+
+        assert!(Value::Real(0.0) == Value::Real(-0.0));
+        assert_eq!(hash(&Value::Real(0.0)), hash(&Value::Real(-0.0)));
+        assert!(Value::Real(-0.0) == Value::Integer(0));
+
+        assert!(Value::Real(f64::NAN) == Value::Real(f64::NAN));
+        assert_eq!(hash(&Value::Real(f64::NAN)), hash(&Value::Real(-f64::NAN)));
+        assert_eq!(
+            Value::Real(f64::NAN).partial_cmp(&Value::Integer(i64::MIN)),
+            Some(Ordering::Less)
+        );
+        assert_eq!(
+            Value::Integer(0).partial_cmp(&Value::Real(f64::NAN)),
+            Some(Ordering::Greater)
+        );
+        assert_eq!(
+            Value::Null.partial_cmp(&Value::Real(f64::NAN)),
+            Some(Ordering::Less)
+        );
+
+        assert_eq!(Value::Real(-0.0).cast_to_text(), Some("0".to_string()));
+        assert_eq!(
+            Value::Real(f64::NAN).cast_to_text(),
+            Some("NaN".to_string())
+        );
+    }
+
+    #[test]
+    fn reals_of_extreme_magnitude_are_displayed_in_scientific_notation() {
+        let cases = vec![
+            (1e20, "1e20"),
+            (-1.5e-10, "-1.5e-10"),
+            (1e300, "1e300"),
+            (5e-324, "5e-324"),
+            (123456.5, "123456.5"),
+            (0.001, "0.001"),
+        ];
+        for (value, expected) in cases {
+            let text = Value::Real(value).cast_to_text().unwrap();
+            assert_eq!(text, expected);
+            assert_eq!(text.parse::<f64>(), Ok(value));
+        }
+    }
+
     #[test]
     fn value_exactlyeq_behaves_as_expected() {
         assert!(Value::Null.exactly_equal(&Value::Null));
@@ -415,6 +709,30 @@ mod tests {
         assert_eq!(Value::Integer(-42).cast_to_real(), Some(-42.0));
     }
 
+    #[test]
+    fn cast_to_real_reads_the_longest_numeric_prefix() {
+        let cases = vec![
+            ("1.5e3", 1500.0),
+            ("  2.5E-1xyz", 0.25),
+            ("-1e+2", -100.0),
+            (".5", 0.5),
+            ("1e", 1.0),
+            ("3e+abc", 3.0),
+            ("\t\n 42 ", 42.0),
+            ("abc", 0.0),
+            ("e5", 0.0),
+            ("-", 0.0),
+        ];
+        for (text, expected) in cases {
+            assert_eq!(
+                Value::Text(text.to_string()).cast_to_real(),
+                Some(expected),
+                "{}",
+                text
+            );
+        }
+    }
+
     #[test]
     fn cast_to_real_lossless_behaves_as_expected() {
         assert!(Value::Null.cast_to_real_lossless().is_none());
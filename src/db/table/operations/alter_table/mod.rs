@@ -1,5 +1,4 @@
-use crate::db::database::Database;
-use crate::db::table::core::value::Value;
+use crate::db::database::{Database, is_reserved_table_name};
 use crate::interpreter::ast::{AlterTableAction, AlterTableStatement};
 
 pub fn alter_table(
@@ -9,6 +8,12 @@ pub fn alter_table(
 ) -> Result<(), String> {
     return match statement.action {
         AlterTableAction::RenameTable { new_table_name } => {
+            if is_reserved_table_name(&new_table_name) {
+                return Err(format!(
+                    "Object name reserved for internal use: {}",
+                    new_table_name
+                ));
+            }
             let mut table = database.pop_table_change(&statement.table_name)?;
             table.change_name(new_table_name.clone(), is_transaction);
             database.push_table_change(&new_table_name, table);
@@ -25,6 +30,22 @@ pub fn alter_table(
                     old_column_name, statement.table_name
                 ));
             }
+            if table
+                .table_constraints
+                .iter()
+                .any(|constraint| constraint.columns.contains(&old_column_name))
+            {
+                return Err(format!(
+                    "Can't rename column `{}`, it is part of a table constraint",
+                    old_column_name
+                ));
+            }
+            if table.has_column(&new_column_name)? {
+                return Err(format!(
+                    "Column `{}` already exists in table `{}`",
+                    new_column_name, statement.table_name
+                ));
+            }
             let res =
                 table
                     .columns
@@ -50,9 +71,10 @@ pub fn alter_table(
                     row_stack.append_clone();
                 });
             }
+            let default_value = column_def.default_value();
             table.push_column(column_def, is_transaction);
             table.get_rows_mut().iter_mut().for_each(|row| {
-                row.push(Value::Null);
+                row.push(default_value.clone());
             });
             Ok(())
         }
@@ -64,6 +86,16 @@ pub fn alter_table(
                     column_name, statement.table_name
                 ));
             }
+            if table
+                .table_constraints
+                .iter()
+                .any(|constraint| constraint.columns.contains(&column_name))
+            {
+                return Err(format!(
+                    "Can't drop column `{}`, it is part of a table constraint",
+                    column_name
+                ));
+            }
             let index = table.columns.get_index_of_column(&column_name)?;
             let res = table.columns.drop_column(&column_name, is_transaction);
             if res.is_err() {
@@ -84,6 +116,21 @@ pub fn alter_table(
             });
             Ok(())
         }
+        AlterTableAction::SetNotNull { column_name } => {
+            let table = database.get_table_mut(&statement.table_name)?;
+            if !table.has_column(&column_name)? {
+                return Err(format!(
+                    "Column `{}` does not exist in table `{}`",
+                    column_name, statement.table_name
+                ));
+            }
+            // Existing rows must already satisfy the constraint being added.
+            let index = table.get_index_of_column(&column_name)?;
+            if table.iter().any(|row| row[index].is_null()) {
+                return Err("NOT NULL constraint failed: existing NULL values".to_string());
+            }
+            table.columns.set_not_null(&column_name, is_transaction)
+        }
     };
 }
 
@@ -93,9 +140,9 @@ mod tests {
     use crate::db::table::test_utils::default_database;
     use crate::db::table::{
         core::{
-            column::ColumnDefinition,
+            column::{ColumnDefinition, ConstraintType},
             row::{Row, RowStack},
-            value::DataType,
+            value::{DataType, Value},
         },
         test_utils::assert_table_rows_eq,
     };
@@ -164,6 +211,43 @@ mod tests {
         );
     }
 
+    #[test]
+    fn alter_table_set_not_null_checks_existing_rows() {
+        let mut database = default_database();
+        let statement = AlterTableStatement {
+            table_name: "users".to_string(),
+            action: AlterTableAction::SetNotNull {
+                column_name: "id".to_string(),
+            },
+        };
+        let result = alter_table(&mut database, statement, false);
+        assert!(result.is_ok());
+        let table = database.get_table("users").unwrap();
+        let index = table.get_index_of_column(&"id".to_string()).unwrap();
+        assert!(
+            table.get_columns().unwrap()[index]
+                .constraints
+                .iter()
+                .any(|constraint| constraint.constraint_type == ConstraintType::NotNull)
+        );
+
+        // One of the users has no name.
+        let statement = AlterTableStatement {
+            table_name: "users".to_string(),
+            action: AlterTableAction::SetNotNull {
+                column_name: "name".to_string(),
+            },
+        };
+        let result = alter_table(&mut database, statement, false);
+        assert_eq!(
+            result,
+            Err("NOT NULL constraint failed: existing NULL values".to_string())
+        );
+        let table = database.get_table("users").unwrap();
+        let index = table.get_index_of_column(&"name".to_string()).unwrap();
+        assert!(table.get_columns().unwrap()[index].constraints.is_empty());
+    }
+
     #[test]
     fn alter_table_drop_column_works_correctly() {
         let mut database = default_database();
@@ -247,6 +331,42 @@ mod tests {
         assert!(table.columns.stack[1][index_of_column].name == "new_name");
     }
 
+    #[test]
+    fn alter_table_rename_column_to_an_existing_name_changes_nothing() {
+        let mut database = default_database();
+        let columns = database.get_table("users").unwrap().columns.clone();
+        for is_transaction in [false, true] {
+            let statement = AlterTableStatement {
+                table_name: "users".to_string(),
+                action: AlterTableAction::RenameColumn {
+                    old_column_name: "name".to_string(),
+                    new_column_name: "age".to_string(),
+                },
+            };
+            let result = alter_table(&mut database, statement, is_transaction);
+            assert_eq!(
+                result,
+                Err("Column `age` already exists in table `users`".to_string())
+            );
+            assert_eq!(database.get_table("users").unwrap().columns, columns);
+        }
+
+        let table = database.get_table_mut("users").unwrap();
+        assert!(
+            table
+                .columns
+                .rename_column(&"missing".to_string(), &"other".to_string(), true)
+                .is_err()
+        );
+        assert!(
+            table
+                .columns
+                .rename_column(&"id".to_string(), &"age".to_string(), true)
+                .is_err()
+        );
+        assert_eq!(table.columns, columns);
+    }
+
     #[test]
     fn alter_table_drop_column_works_correctly_with_transaction() {
         let mut database = default_database();
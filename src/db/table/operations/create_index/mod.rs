@@ -0,0 +1,93 @@
+use crate::db::database::Database;
+use crate::db::table::core::index::Index;
+use crate::interpreter::ast::{CreateIndexStatement, ExistenceCheck};
+
+// Returns whether a new index was created, `IF NOT EXISTS` on an existing index creates nothing.
+pub fn create_index(
+    database: &mut Database,
+    statement: CreateIndexStatement,
+) -> Result<bool, String> {
+    if database
+        .get_index_table_name(&statement.index_name)
+        .is_some()
+    {
+        match statement.existence_check {
+            Some(ExistenceCheck::IfNotExists) => {
+                return Ok(false);
+            }
+            _ => {
+                return Err(format!("Index `{}` already exists", statement.index_name));
+            }
+        }
+    }
+    let table = database.get_table_mut(&statement.table_name)?;
+    let mut index = Index::new(statement.index_name, statement.columns);
+    index.unique = statement.unique;
+    table.create_index(index)?;
+    Ok(true)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::table::core::{row::Row, value::Value};
+    use crate::db::table::test_utils::default_database;
+
+    fn create_index_statement() -> CreateIndexStatement {
+        CreateIndexStatement {
+            index_name: "idx_users_name".to_string(),
+            table_name: "users".to_string(),
+            existence_check: None,
+            columns: vec!["name".to_string()],
+            unique: false,
+        }
+    }
+
+    #[test]
+    fn create_index_indexes_existing_rows() {
+        let mut database = default_database();
+        let result = create_index(&mut database, create_index_statement());
+        assert_eq!(result, Ok(true));
+        let table = database.get_table("users").unwrap();
+        let index = table.get_index("idx_users_name").unwrap();
+        assert_eq!(
+            index.lookup(&Row(vec![Value::Text("Jane".to_string())])),
+            vec![1]
+        );
+        // The row with a NULL name is not indexed
+        assert_eq!(index.len(), 3);
+    }
+
+    #[test]
+    fn create_index_errors_when_index_already_exists() {
+        let mut database = default_database();
+        assert!(create_index(&mut database, create_index_statement()).is_ok());
+        let result = create_index(&mut database, create_index_statement());
+        assert_eq!(
+            result,
+            Err("Index `idx_users_name` already exists".to_string())
+        );
+
+        let mut statement = create_index_statement();
+        statement.existence_check = Some(ExistenceCheck::IfNotExists);
+        assert_eq!(create_index(&mut database, statement), Ok(false));
+    }
+
+    #[test]
+    fn create_index_errors_on_missing_table_or_column() {
+        let mut database = default_database();
+        let mut statement = create_index_statement();
+        statement.table_name = "orders".to_string();
+        assert_eq!(
+            create_index(&mut database, statement),
+            Err("Table `orders` does not exist".to_string())
+        );
+
+        let mut statement = create_index_statement();
+        statement.columns = vec!["email".to_string()];
+        assert_eq!(
+            create_index(&mut database, statement),
+            Err("Column `email` does not exist in table `users`".to_string())
+        );
+    }
+}
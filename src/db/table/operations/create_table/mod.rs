@@ -1,4 +1,4 @@
-use crate::db::database::Database;
+use crate::db::database::{Database, is_reserved_table_name};
 use crate::db::table::core::table::Table;
 use crate::interpreter::ast::{CreateTableStatement, ExistenceCheck};
 
@@ -7,6 +7,12 @@ pub fn create_table(
     statement: CreateTableStatement,
     is_transaction: bool,
 ) -> Result<(), String> {
+    if is_reserved_table_name(&statement.table_name) {
+        return Err(format!(
+            "Object name reserved for internal use: {}",
+            statement.table_name
+        ));
+    }
     if database.has_table(&statement.table_name) {
         match statement.existence_check {
             Some(ExistenceCheck::IfNotExists) => {
@@ -17,7 +23,9 @@ pub fn create_table(
             }
         }
     }
-    let table = Table::new(statement.table_name.clone(), statement.columns);
+    let mut table = Table::new(statement.table_name.clone(), statement.columns);
+    table.table_constraints = statement.table_constraints;
+    table.without_rowid = statement.without_rowid;
     if is_transaction && database.tables.contains_key(&statement.table_name) {
         database
             .tables
@@ -49,6 +57,8 @@ mod tests {
                 data_type: DataType::Integer,
                 constraints: vec![],
             }],
+            table_constraints: vec![],
+            without_rowid: false,
         };
         let mut database = Database::new();
         assert!(create_table(&mut database, statement, false).is_ok());
@@ -65,6 +75,8 @@ mod tests {
                 data_type: DataType::Integer,
                 constraints: vec![],
             }],
+            table_constraints: vec![],
+            without_rowid: false,
         };
         let mut database = default_database();
         let result = create_table(&mut database, statement, false);
@@ -82,6 +94,8 @@ mod tests {
                 data_type: DataType::Integer,
                 constraints: vec![],
             }],
+            table_constraints: vec![],
+            without_rowid: false,
         };
         let mut database = default_database();
         let result = create_table(&mut database, statement, false);
@@ -98,6 +112,8 @@ mod tests {
                 data_type: DataType::Integer,
                 constraints: vec![],
             }],
+            table_constraints: vec![],
+            without_rowid: false,
         };
         let mut database = Database::new();
         let result = create_table(&mut database, statement, true);
@@ -1,26 +1,32 @@
-use std::collections::HashSet;
-
-use crate::db::table::core::table::Table;
-use crate::db::table::operations::helpers::common::get_row_indicies_matching_clauses;
+use crate::db::table::core::{row::Row, table::Table};
+use crate::db::table::operations::helpers::common::{
+    get_returning_rows, get_row_indicies_matching_clauses,
+};
 use crate::interpreter::ast::DeleteStatement;
 
+// Returns the positions of the deleted rows, and the RETURNING rows if the statement has that
+// clause. Unlike for INSERT and UPDATE they are computed here, before the rows are removed.
 pub fn delete(
     table: &mut Table,
     statement: DeleteStatement,
     is_transaction: bool,
-) -> Result<Vec<usize>, String> {
+) -> Result<(Vec<usize>, Option<Vec<Row>>), String> {
     let mut row_indicies_to_delete = get_row_indicies_matching_clauses(
         table,
         &statement.where_clause,
         &statement.order_by_clause,
         &statement.limit_clause,
     )?;
-    // We get omega saved here by the fact that we don't need to guarentee the order of the rows after rollbacks.
-    // This means we can swap the semi-deleted rows to the end of the table and then set the length of the table
-    // to the length of the table minus the number of semi-deleted rows. Then on rollback we can just extend the length of the table.
-    // to then include the deleted rows. if we commit, we pop off the end of the table until at the desired length.
+    let returned_rows = match &statement.returning {
+        Some(columns) => Some(get_returning_rows(table, &row_indicies_to_delete, columns)?),
+        None => None,
+    };
+    // We swap the semi-deleted rows to the end of the table and then set the length of the table
+    // to the length of the table minus the number of semi-deleted rows. Then on rollback we extend the length of the table
+    // to include the deleted rows again and undo the swaps, see `undo_swap_remove`. If we commit, we pop off the end of the
+    // table until at the desired length.
     swap_remove_bulk(table, &mut row_indicies_to_delete, is_transaction)?;
-    Ok(row_indicies_to_delete)
+    Ok((row_indicies_to_delete, returned_rows))
 }
 
 fn swap_remove_bulk(
@@ -34,41 +40,53 @@ fn swap_remove_bulk(
         }
         return Ok(());
     }
-    let table_len = table.len() - 1;
-    let mut row_indicies_set = row_indicies.iter().collect::<HashSet<&usize>>();
-    let mut right_pointer = 0;
-    let mut iter = row_indicies.iter().rev(); // We recieve the indexes in ascending order,
-    // We reverse them to get rid of the furtherst indexes first.
-
-    while let Some(to_swap) = iter.next() {
-        if *to_swap == (table_len - right_pointer) {
-            row_indicies_set.remove(to_swap);
-            right_pointer += 1;
-        } else {
-            table.swap(*to_swap, table_len - right_pointer);
-            row_indicies_set.remove(to_swap);
-            right_pointer += 1;
-        }
+    for (a, b) in get_swaps(row_indicies, table.len()) {
+        table.swap(a, b);
     }
     if is_transaction {
-        table.set_length(table.len() - right_pointer);
+        table.set_length(table.len() - row_indicies.len());
     } else {
-        for _ in 0..right_pointer {
+        for _ in 0..row_indicies.len() {
             table.pop();
         }
     }
     Ok(())
 }
 
+// Brings back the rows hidden by a DELETE in a transaction, at the positions they had before it,
+// so the positions recorded by the statements before it are valid again.
+pub fn undo_swap_remove(table: &mut Table, row_indicies: &[usize]) {
+    let table_len = table.len() + row_indicies.len();
+    table.set_length(table_len);
+    for (a, b) in get_swaps(row_indicies, table_len).into_iter().rev() {
+        table.swap(a, b);
+    }
+}
+
+// The swaps moving the deleted rows to the end of a table of `table_len` rows.
+fn get_swaps(row_indicies: &[usize], table_len: usize) -> Vec<(usize, usize)> {
+    // We recieve the indexes in ascending order, we reverse them to get rid of the furtherst
+    // indexes first.
+    row_indicies
+        .iter()
+        .rev()
+        .enumerate()
+        .map(|(right_pointer, to_swap)| (*to_swap, table_len - 1 - right_pointer))
+        .filter(|(a, b)| a != b)
+        .collect()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::db::table::core::{row::Row, value::Value};
-    use crate::db::table::test_utils::{assert_table_rows_eq_unordered, default_table};
+    use crate::db::table::core::{index::Index, row::Row, value::Value};
+    use crate::db::table::test_utils::{
+        assert_table_rows_eq, assert_table_rows_eq_unordered, default_table,
+    };
     use crate::interpreter::ast::LimitClause;
     use crate::interpreter::ast::{
-        Operator, OrderByClause, OrderByDirection, SelectableColumn, SelectableStackElement,
-        TableAliases,
+        LogicalOperator, Operator, OrderByClause, OrderByDirection, SelectableColumn,
+        SelectableStackElement, TableAliases,
     };
     use std::collections::HashMap;
 
@@ -88,6 +106,7 @@ mod tests {
             }),
             order_by_clause: None,
             limit_clause: None,
+            returning: None,
         };
         let result = delete(&mut table, statement, false);
         assert!(result.is_ok());
@@ -183,6 +202,7 @@ mod tests {
                 limit: 1,
                 offset: Some(2),
             }),
+            returning: None,
         };
         let result = delete(&mut table, statement, false);
         assert!(result.is_ok());
@@ -243,10 +263,11 @@ mod tests {
             }),
             order_by_clause: None,
             limit_clause: None,
+            returning: None,
         };
         let result = delete(&mut table, statement, false);
         assert!(result.is_ok());
-        let row_indicies = result.unwrap();
+        let (row_indicies, _) = result.unwrap();
         assert_eq!(vec![1, 2, 3], row_indicies);
         let expected = vec![Row(vec![
             Value::Integer(1),
@@ -266,6 +287,7 @@ mod tests {
             where_clause: None,
             order_by_clause: None,
             limit_clause: None,
+            returning: None,
         };
         let result = delete(&mut table, statement, false);
         assert!(result.is_ok());
@@ -283,6 +305,7 @@ mod tests {
             where_clause: None,
             order_by_clause: None,
             limit_clause: None,
+            returning: None,
         };
         let result = delete(&mut table, statement, false);
         assert!(result.is_ok());
@@ -345,10 +368,11 @@ mod tests {
                 limit: 2,
                 offset: Some(1),
             }),
+            returning: None,
         };
         let result = delete(&mut table, statement, false);
         assert!(result.is_ok());
-        let deleted_indices = result.unwrap();
+        let (deleted_indices, _) = result.unwrap();
         assert_eq!(deleted_indices.len(), 2);
         let expected = vec![
             Row(vec![
@@ -395,11 +419,82 @@ mod tests {
             }),
             order_by_clause: None,
             limit_clause: None,
+            returning: None,
         };
         let result = delete(&mut table, statement, false);
         assert!(result.is_ok());
-        let deleted_indices = result.unwrap();
+        let (deleted_indices, _) = result.unwrap();
         assert_eq!(deleted_indices, vec![0]);
         assert_eq!(table.get_rows_clone().len(), 0);
     }
+
+    #[test]
+    fn delete_keeps_indexes_in_sync_with_surviving_rows() {
+        for is_transaction in [false, true] {
+            let mut table = default_table();
+            table
+                .create_index(Index::new(
+                    "idx_users_age".to_string(),
+                    vec!["age".to_string()],
+                ))
+                .unwrap();
+            // DELETE FROM users WHERE id = 1 OR id = 3;
+            let statement = DeleteStatement {
+                table_name: "users".to_string(),
+                table_aliases: TableAliases(HashMap::new()),
+                where_clause: Some(SelectableColumn {
+                    selectables: vec![
+                        SelectableStackElement::Column("id".to_string()),
+                        SelectableStackElement::Value(Value::Integer(1)),
+                        SelectableStackElement::Operator(Operator::Equals),
+                        SelectableStackElement::Column("id".to_string()),
+                        SelectableStackElement::Value(Value::Integer(3)),
+                        SelectableStackElement::Operator(Operator::Equals),
+                        SelectableStackElement::LogicalOperator(LogicalOperator::Or),
+                    ],
+                    column_name: "id = 1 OR id = 3".to_string(),
+                }),
+                order_by_clause: None,
+                limit_clause: None,
+                returning: None,
+            };
+            assert!(delete(&mut table, statement, is_transaction).is_ok());
+            let index = table.get_index("idx_users_age").unwrap();
+            assert_eq!(index.len(), 2);
+            assert!(index.lookup(&Row(vec![Value::Integer(25)])).is_empty());
+            assert!(index.lookup(&Row(vec![Value::Integer(35)])).is_empty());
+            for age in [30, 40] {
+                let positions = index.lookup(&Row(vec![Value::Integer(age)]));
+                assert_eq!(positions.len(), 1);
+                assert!(positions[0] < table.len());
+                assert!(table[positions[0]][2].exactly_equal(&Value::Integer(age)));
+            }
+        }
+    }
+
+    #[test]
+    fn undo_swap_remove_restores_the_original_positions() {
+        let mut table = default_table();
+        let original = table.get_rows_clone();
+        let statement = DeleteStatement {
+            table_name: "users".to_string(),
+            table_aliases: TableAliases(HashMap::new()),
+            where_clause: Some(SelectableColumn {
+                selectables: vec![
+                    SelectableStackElement::Column("id".to_string()),
+                    SelectableStackElement::Value(Value::Integer(3)),
+                    SelectableStackElement::Operator(Operator::NotEquals),
+                ],
+                column_name: "id != 3".to_string(),
+            }),
+            order_by_clause: None,
+            limit_clause: None,
+            returning: None,
+        };
+        let (deleted, _) = delete(&mut table, statement, true).unwrap();
+        assert_eq!(deleted, vec![0, 1, 3]);
+        assert_eq!(table.get_rows_clone(), vec![original[2].clone()]);
+        undo_swap_remove(&mut table, &deleted);
+        assert_table_rows_eq(original, table.get_rows_clone());
+    }
 }
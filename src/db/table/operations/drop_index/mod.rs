@@ -0,0 +1,75 @@
+use crate::db::database::Database;
+use crate::interpreter::ast::{DropIndexStatement, ExistenceCheck};
+
+// Returns the name of the table that owned the dropped index, if one was dropped.
+pub fn drop_index(
+    database: &mut Database,
+    statement: DropIndexStatement,
+    is_transaction: bool,
+) -> Result<Option<String>, String> {
+    let table_name = match database.get_index_table_name(&statement.index_name) {
+        Some(table_name) => table_name,
+        None => match statement.existence_check {
+            Some(ExistenceCheck::IfExists) => {
+                return Ok(None);
+            }
+            _ => {
+                return Err(format!("Index `{}` does not exist", statement.index_name));
+            }
+        },
+    };
+    let table = database.get_table_mut(&table_name)?;
+    table.drop_index(&statement.index_name, is_transaction)?;
+    Ok(Some(table_name))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::table::core::index::Index;
+    use crate::db::table::test_utils::default_database;
+
+    #[test]
+    fn drop_index_removes_index_from_owning_table() {
+        let mut database = default_database();
+        let table = database.get_table_mut("users").unwrap();
+        table
+            .create_index(Index::new(
+                "idx_users_name".to_string(),
+                vec!["name".to_string()],
+            ))
+            .unwrap();
+        let statement = DropIndexStatement {
+            index_name: "idx_users_name".to_string(),
+            existence_check: None,
+        };
+        let result = drop_index(&mut database, statement, false);
+        assert_eq!(result, Ok(Some("users".to_string())));
+        assert!(
+            !database
+                .get_table("users")
+                .unwrap()
+                .has_index("idx_users_name")
+        );
+    }
+
+    #[test]
+    fn drop_index_errors_when_index_does_not_exist() {
+        let mut database = default_database();
+        let statement = DropIndexStatement {
+            index_name: "idx_users_name".to_string(),
+            existence_check: None,
+        };
+        let result = drop_index(&mut database, statement, false);
+        assert_eq!(
+            result,
+            Err("Index `idx_users_name` does not exist".to_string())
+        );
+
+        let statement = DropIndexStatement {
+            index_name: "idx_users_name".to_string(),
+            existence_check: Some(ExistenceCheck::IfExists),
+        };
+        assert_eq!(drop_index(&mut database, statement, false), Ok(None));
+    }
+}
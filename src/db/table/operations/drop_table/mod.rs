@@ -16,6 +16,8 @@ pub fn drop_table(
             }
         }
     }
+    // Indexes belong to their table, so they are dropped with it. In a transaction the previous
+    // version of the table is kept, and a rollback brings it back along with its indexes.
     if is_transaction {
         database
             .tables
@@ -32,6 +34,7 @@ pub fn drop_table(
 mod tests {
     use super::*;
     use crate::db::table::test_utils::default_database;
+    use crate::interpreter::run_sql;
 
     #[test]
     fn drop_table_drops_proper_table() {
@@ -82,4 +85,35 @@ mod tests {
         assert!(table.first().unwrap().is_some());
         assert!(table.last().unwrap().is_none());
     }
+
+    #[test]
+    fn drop_table_drops_its_indexes_and_rollback_restores_them() {
+        let mut database = default_database();
+        let _ = run_sql(&mut database, "CREATE INDEX idx_id ON users (id);");
+        assert_eq!(
+            database.get_index_table_name("idx_id"),
+            Some("users".to_string())
+        );
+
+        let results = run_sql(&mut database, "BEGIN; DROP TABLE users;");
+        assert!(results.iter().all(|result| result.is_ok()));
+        assert!(database.get_index_table_name("idx_id").is_none());
+        assert!(run_sql(&mut database, "DROP INDEX idx_id;")[0].is_err());
+
+        let _ = run_sql(&mut database, "ROLLBACK;");
+        assert_eq!(
+            database.get_index_table_name("idx_id"),
+            Some("users".to_string())
+        );
+        let index = database.get_table("users").unwrap().get_index("idx_id");
+        assert_eq!(index.unwrap().len(), 4);
+
+        let statement = DropTableStatement {
+            table_name: "users".to_string(),
+            existence_check: None,
+        };
+        assert!(drop_table(&mut database, statement, false).is_ok());
+        assert!(database.get_index_table_name("idx_id").is_none());
+        assert!(run_sql(&mut database, "DROP INDEX idx_id;")[0].is_err());
+    }
 }
@@ -0,0 +1,437 @@
+use std::collections::{HashMap, HashSet};
+
+use crate::db::table::core::{
+    row::{DistinctRow, Row},
+    table::Table,
+    value::Value,
+};
+use crate::db::table::operations::helpers::common::{evaluate_column, evaluate_columns};
+use crate::interpreter::ast::{
+    CaseExpression, FunctionCall, FunctionName, SelectableColumn, SelectableStackElement,
+};
+
+// Whether the column calls an aggregate function, including in the arguments of another function
+// or in a CASE expression.
+pub fn has_aggregate(column: &SelectableColumn) -> bool {
+    column
+        .selectables
+        .iter()
+        .any(|selectable| match selectable {
+            SelectableStackElement::Function(func) => {
+                func.is_aggregate() || func.arguments.iter().any(has_aggregate)
+            }
+            SelectableStackElement::Case(case) => {
+                case.branches
+                    .iter()
+                    .any(|(condition, result)| has_aggregate(condition) || has_aggregate(result))
+                    || case.else_result.as_ref().is_some_and(has_aggregate)
+            }
+            _ => false,
+        })
+}
+
+// Evaluates the selected columns over a group of rows, producing a single row.
+// Aggregate functions are computed over the whole group. As in SQLite, other column
+// references are read from the row holding the minimum or maximum when the only aggregate
+// is a MIN or MAX, and otherwise from the last row of the group (or are NULL if the group
+// is empty). Computed columns and their aliases are used as in `get_columns`. The rows are
+// already read with `read_row`.
+pub fn get_aggregate_columns(
+    table: &Table,
+    rows: &[&Row],
+    selected_columns: &Vec<SelectableColumn>,
+    computed_columns: Option<&Row>,
+    aliases_to_indexes: Option<&HashMap<String, usize>>,
+) -> Result<Row, String> {
+    let mut resolved_columns = vec![];
+    let mut aggregates = vec![];
+    for column in selected_columns {
+        resolved_columns.push(resolve_aggregates(table, rows, column, &mut aggregates)?);
+    }
+
+    let empty_row = Row(vec![Value::Null; table.width()?]);
+    let min_max_row = match aggregates.as_slice() {
+        [func] if [FunctionName::Min, FunctionName::Max].contains(&func.name) => {
+            get_min_max_row(table, rows, func)?
+        }
+        _ => None,
+    };
+    let row = min_max_row
+        .or_else(|| rows.last().copied())
+        .unwrap_or(&empty_row);
+    evaluate_columns(
+        table,
+        row,
+        &resolved_columns,
+        computed_columns,
+        aliases_to_indexes,
+    )
+}
+
+// Replaces the aggregate function calls of a column, including those nested in the arguments of
+// other functions and in CASE expressions, with their value over the rows.
+fn resolve_aggregates<'a>(
+    table: &Table,
+    rows: &[&Row],
+    column: &'a SelectableColumn,
+    aggregates: &mut Vec<&'a FunctionCall>,
+) -> Result<SelectableColumn, String> {
+    let mut selectables = vec![];
+    for selectable in &column.selectables {
+        match selectable {
+            SelectableStackElement::Function(func) if func.is_aggregate() => {
+                selectables.push(SelectableStackElement::Value(compute_aggregate(
+                    table, rows, func,
+                )?));
+                aggregates.push(func);
+            }
+            SelectableStackElement::Function(func) => {
+                let mut arguments = vec![];
+                for argument in &func.arguments {
+                    arguments.push(resolve_aggregates(table, rows, argument, aggregates)?);
+                }
+                selectables.push(SelectableStackElement::Function(FunctionCall {
+                    arguments,
+                    ..func.clone()
+                }));
+            }
+            SelectableStackElement::Case(case) => {
+                let mut branches = vec![];
+                for (condition, result) in &case.branches {
+                    branches.push((
+                        resolve_aggregates(table, rows, condition, aggregates)?,
+                        resolve_aggregates(table, rows, result, aggregates)?,
+                    ));
+                }
+                let else_result = match &case.else_result {
+                    Some(else_result) => {
+                        Some(resolve_aggregates(table, rows, else_result, aggregates)?)
+                    }
+                    None => None,
+                };
+                selectables.push(SelectableStackElement::Case(CaseExpression {
+                    branches,
+                    else_result,
+                }));
+            }
+            _ => selectables.push(selectable.clone()),
+        }
+    }
+    Ok(SelectableColumn {
+        selectables,
+        column_name: column.column_name.clone(),
+    })
+}
+
+// Returns the first row holding the value computed by a MIN or MAX, or None if every value is NULL.
+fn get_min_max_row<'a>(
+    table: &Table,
+    rows: &[&'a Row],
+    func: &FunctionCall,
+) -> Result<Option<&'a Row>, String> {
+    let Some(argument) = func.arguments.first() else {
+        return Ok(None);
+    };
+    let mut best: Option<(Value, &Row)> = None;
+    for row in rows {
+        let value = evaluate_column(table, row, argument, None, None)?;
+        if value.is_null() {
+            continue;
+        }
+        let is_better = match &best {
+            None => true,
+            Some((best_value, _)) if func.name == FunctionName::Min => value < *best_value,
+            Some((best_value, _)) => value > *best_value,
+        };
+        if is_better {
+            best = Some((value, row));
+        }
+    }
+    Ok(best.map(|(_, row)| row))
+}
+
+fn compute_aggregate(table: &Table, rows: &[&Row], func: &FunctionCall) -> Result<Value, String> {
+    let max_arguments = match func.name {
+        FunctionName::GroupConcat => 2,
+        _ => 1,
+    };
+    let argument = match func.arguments.first() {
+        Some(argument) if func.arguments.len() <= max_arguments => argument,
+        _ => {
+            return Err(format!(
+                "Wrong number of arguments to function {:?}",
+                func.name
+            ));
+        }
+    };
+
+    if argument.selectables.first() == Some(&SelectableStackElement::All) {
+        if func.name != FunctionName::Count || func.distinct {
+            return Err(format!("Invalid use of * in function {:?}", func.name));
+        }
+        return Ok(Value::Integer(rows.len() as i64));
+    }
+
+    // NULLs are ignored by every aggregate, and DISTINCT keeps the first occurrence of each value.
+    let mut values = vec![];
+    let mut seen = HashSet::new();
+    for row in rows {
+        let value = evaluate_column(table, row, argument, None, None)?;
+        if value.is_null() {
+            continue;
+        }
+        if func.distinct && !seen.insert(DistinctRow(Row(vec![value.clone()]))) {
+            continue;
+        }
+        values.push(value);
+    }
+
+    let result = match func.name {
+        FunctionName::Count => Value::Integer(values.len() as i64),
+        FunctionName::Sum => sum(&values)?,
+        FunctionName::Avg => {
+            if values.is_empty() {
+                Value::Null
+            } else {
+                let total: f64 = values.iter().map(|v| v.cast_to_real().unwrap_or(0.0)).sum();
+                Value::Real(total / values.len() as f64)
+            }
+        }
+        FunctionName::Min => values
+            .into_iter()
+            .reduce(|min, value| if value < min { value } else { min })
+            .unwrap_or(Value::Null),
+        FunctionName::Max => values
+            .into_iter()
+            .reduce(|max, value| if value > max { value } else { max })
+            .unwrap_or(Value::Null),
+        FunctionName::GroupConcat => {
+            if values.is_empty() {
+                Value::Null
+            } else {
+                let separator = match (func.arguments.get(1), rows.first()) {
+                    (Some(separator), Some(row)) => {
+                        evaluate_column(table, row, separator, None, None)?
+                            .cast_to_text()
+                            .unwrap_or_default()
+                    }
+                    _ => ",".to_string(),
+                };
+                Value::Text(
+                    values
+                        .iter()
+                        .map(|value| value.cast_to_text().unwrap_or_default())
+                        .collect::<Vec<String>>()
+                        .join(&separator),
+                )
+            }
+        }
+        _ => return Err(format!("Unsupported aggregate function: {:?}", func.name)),
+    };
+    Ok(result)
+}
+
+fn sum(values: &[Value]) -> Result<Value, String> {
+    if values.is_empty() {
+        return Ok(Value::Null);
+    }
+    if values
+        .iter()
+        .all(|value| matches!(value, Value::Integer(_)))
+    {
+        let mut total: i64 = 0;
+        for value in values {
+            if let Value::Integer(val) = value {
+                total = total
+                    .checked_add(*val)
+                    .ok_or_else(|| "Integer overflow in SUM".to_string())?;
+            }
+        }
+        return Ok(Value::Integer(total));
+    }
+    Ok(Value::Real(
+        values.iter().map(|v| v.cast_to_real().unwrap_or(0.0)).sum(),
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::table::test_utils::default_table;
+
+    fn function(
+        name: FunctionName,
+        arguments: Vec<SelectableColumn>,
+        distinct: bool,
+    ) -> FunctionCall {
+        FunctionCall {
+            name,
+            arguments,
+            distinct,
+        }
+    }
+
+    fn column(name: &str) -> SelectableColumn {
+        SelectableColumn {
+            selectables: vec![SelectableStackElement::Column(name.to_string())],
+            column_name: name.to_string(),
+        }
+    }
+
+    fn text(value: &str) -> SelectableColumn {
+        SelectableColumn {
+            selectables: vec![SelectableStackElement::Value(Value::Text(
+                value.to_string(),
+            ))],
+            column_name: format!("'{}'", value),
+        }
+    }
+
+    #[test]
+    fn aggregates_are_computed_over_all_rows() {
+        let table = default_table();
+        let rows = table.get_rows();
+        let count_all = SelectableColumn {
+            selectables: vec![SelectableStackElement::All],
+            column_name: "*".to_string(),
+        };
+        let cases = vec![
+            (
+                function(FunctionName::Count, vec![count_all], false),
+                Value::Integer(4),
+            ),
+            (
+                function(FunctionName::Count, vec![column("name")], false),
+                Value::Integer(3),
+            ),
+            (
+                function(FunctionName::Sum, vec![column("age")], false),
+                Value::Integer(130),
+            ),
+            (
+                function(FunctionName::Avg, vec![column("age")], false),
+                Value::Real(32.5),
+            ),
+            (
+                function(FunctionName::Min, vec![column("name")], false),
+                Value::Text("Jane".to_string()),
+            ),
+            (
+                function(FunctionName::Max, vec![column("money")], false),
+                Value::Real(4000.0),
+            ),
+        ];
+        for (func, expected) in cases {
+            let result = compute_aggregate(&table, &rows, &func);
+            assert!(result.is_ok(), "{:?}", result);
+            assert!(result.unwrap().exactly_equal(&expected), "{:?}", func.name);
+        }
+    }
+
+    #[test]
+    fn min_and_max_ignore_null_values() {
+        let table = default_table();
+        let rows = table.get_rows();
+        // NULL sorts before every other value, so it would be the minimum if it was not skipped.
+        let func = function(FunctionName::Min, vec![column("name")], false);
+        let result = compute_aggregate(&table, &rows, &func).unwrap();
+        assert_eq!(result, Value::Text("Jane".to_string()));
+        let func = function(FunctionName::Max, vec![column("name")], false);
+        let result = compute_aggregate(&table, &rows, &func).unwrap();
+        assert_eq!(result, Value::Text("John".to_string()));
+
+        // Only the row whose name is NULL.
+        let null_rows = vec![rows[3]];
+        for name in [FunctionName::Min, FunctionName::Max] {
+            let func = function(name, vec![column("name")], false);
+            let result = compute_aggregate(&table, &null_rows, &func).unwrap();
+            assert!(result.is_null());
+        }
+    }
+
+    #[test]
+    fn group_concat_with_distinct_removes_duplicate_values() {
+        let mut table = default_table();
+        table.push(Row(vec![
+            Value::Integer(5),
+            Value::Text("Jim".to_string()),
+            Value::Integer(40),
+            Value::Real(5000.0),
+        ]));
+        let rows = table.get_rows();
+
+        let func = function(FunctionName::GroupConcat, vec![column("name")], false);
+        let result = compute_aggregate(&table, &rows, &func).unwrap();
+        assert_eq!(result, Value::Text("John,Jane,Jim,Jim".to_string()));
+
+        let func = function(
+            FunctionName::GroupConcat,
+            vec![column("name"), text("; ")],
+            true,
+        );
+        let result = compute_aggregate(&table, &rows, &func).unwrap();
+        assert_eq!(result, Value::Text("John; Jane; Jim".to_string()));
+    }
+
+    #[test]
+    fn get_aggregate_columns_evaluates_expressions_around_aggregates() {
+        let table = default_table();
+        let rows = table.get_rows();
+        let columns = vec![SelectableColumn {
+            selectables: vec![
+                SelectableStackElement::Function(function(
+                    FunctionName::Count,
+                    vec![column("id")],
+                    false,
+                )),
+                SelectableStackElement::Value(Value::Integer(1)),
+                SelectableStackElement::MathOperator(crate::interpreter::ast::MathOperator::Add),
+            ],
+            column_name: "COUNT(id) + 1".to_string(),
+        }];
+        let result = get_aggregate_columns(&table, &rows, &columns, None, None).unwrap();
+        assert_eq!(result, Row(vec![Value::Integer(5)]));
+
+        let result = get_aggregate_columns(&table, &[], &columns, None, None).unwrap();
+        assert_eq!(result, Row(vec![Value::Integer(1)]));
+    }
+
+    #[test]
+    fn bare_columns_come_from_the_min_or_max_row() {
+        let table = default_table();
+        let rows = table.get_rows();
+        let aggregate = |name: FunctionName| SelectableColumn {
+            selectables: vec![SelectableStackElement::Function(function(
+                name,
+                vec![column("money")],
+                false,
+            ))],
+            column_name: "aggregate".to_string(),
+        };
+
+        // With a single MIN or MAX, bare columns are read from the row holding that value.
+        let columns = vec![aggregate(FunctionName::Min), column("name")];
+        let result = get_aggregate_columns(&table, &rows, &columns, None, None).unwrap();
+        assert_eq!(
+            result,
+            Row(vec![Value::Real(1000.0), Value::Text("John".to_string())])
+        );
+        let reordered = vec![rows[3], rows[1], rows[2], rows[0]];
+        let columns = vec![column("name"), aggregate(FunctionName::Max)];
+        let result = get_aggregate_columns(&table, &reordered, &columns, None, None).unwrap();
+        assert!(result[0].is_null());
+        assert_eq!(result[1], Value::Real(4000.0));
+
+        // Any other aggregate reads them from the last row.
+        let columns = vec![aggregate(FunctionName::Sum), column("name")];
+        let result = get_aggregate_columns(&table, &reordered, &columns, None, None).unwrap();
+        assert_eq!(result[1], Value::Text("John".to_string()));
+        let columns = vec![
+            aggregate(FunctionName::Min),
+            aggregate(FunctionName::Max),
+            column("id"),
+        ];
+        let result = get_aggregate_columns(&table, &reordered, &columns, None, None).unwrap();
+        assert_eq!(result[2], Value::Integer(1));
+    }
+}
@@ -1,9 +1,21 @@
+use std::borrow::Cow;
+use std::cmp::Ordering;
 use std::collections::HashMap;
 use std::collections::HashSet;
 
-use crate::db::table::core::{row::Row, table::Table, value::DataType, value::Value};
+use crate::db::table::core::{
+    row::{DistinctRow, Row},
+    table::Table,
+    value::DataType,
+    value::Value,
+};
 use crate::db::table::operations::helpers::datetime_functions::build_julian_day;
 use crate::db::table::operations::helpers::order_by_clause::apply_order_by_from_precomputed;
+use crate::db::table::operations::helpers::query_planner::{
+    get_candidate_positions, get_operand_start, plan_where,
+};
+use crate::db::table::operations::helpers::scalar_functions::evaluate_scalar_function;
+use crate::db::table::operations::helpers::string_functions::{glob_match, like_match};
 use crate::interpreter::ast::{
     FunctionName, LimitClause, LogicalOperator, MathOperator, Operator, OrderByClause,
     SelectableColumn, SelectableStackElement,
@@ -29,12 +41,203 @@ pub fn validate_and_clone_row(table: &Table, row: &Row) -> Result<Row, String> {
     return Ok(row_values);
 }
 
+// Reals are compared exactly, as in SQLite, unless the table was given an epsilon within which
+// they are considered equal.
+fn numbers_equal(table: &Table, a: &Value, b: &Value, val1: f64, val2: f64) -> bool {
+    match table.real_equality_epsilon {
+        Some(epsilon) if matches!(a, Value::Real(_)) || matches!(b, Value::Real(_)) => {
+            (val1 - val2).abs() <= epsilon
+        }
+        _ => val1 == val2,
+    }
+}
+
+// `=` between two values, numbers being compared by value whatever their type.
+fn values_equal(table: &Table, a: &Value, b: &Value) -> bool {
+    match (a.numeric_to_f64(), b.numeric_to_f64()) {
+        (Some(val1), Some(val2)) => numbers_equal(table, a, b, val1, val2),
+        _ => a == b,
+    }
+}
+
+// Pops the operand and bounds of BETWEEN, which is `value >= low AND value <= high` with the
+// bounds included, so it is NULL when a comparison is unknown and the other one isn't false.
+fn evaluate_between(values: &mut Row, negated: bool) -> Result<Value, String> {
+    let (Some(high), Some(low), Some(value)) = (values.pop(), values.pop(), values.pop()) else {
+        return Err("Not enough values for BETWEEN".to_string());
+    };
+    let compare = |a: &Value, b: &Value| {
+        if a.is_null() || b.is_null() {
+            return None;
+        }
+        match (a.numeric_to_f64(), b.numeric_to_f64()) {
+            (Some(val1), Some(val2)) => val1.partial_cmp(&val2),
+            _ => a.partial_cmp(b),
+        }
+    };
+    let above_low = compare(&value, &low).map(|ordering| ordering != Ordering::Less);
+    let below_high = compare(&value, &high).map(|ordering| ordering != Ordering::Greater);
+    Ok(match (above_low, below_high) {
+        (Some(false), _) | (_, Some(false)) => Value::Integer(negated as i64),
+        (Some(true), Some(true)) => Value::Integer(!negated as i64),
+        _ => Value::Null,
+    })
+}
+
+// Pops the text, pattern and escape character of `text LIKE pattern ESCAPE character`.
+fn evaluate_like_escape(values: &mut Row) -> Result<Value, String> {
+    let (Some(escape), Some(pattern), Some(text)) = (values.pop(), values.pop(), values.pop())
+    else {
+        return Err("Not enough values for LIKE".to_string());
+    };
+    if text.is_null() || pattern.is_null() || escape.is_null() {
+        return Ok(Value::Null);
+    }
+    let escape = match &escape {
+        Value::Text(escape) if escape.chars().count() == 1 => escape.chars().next(),
+        _ => return Err("ESCAPE expression must be a single character".to_string()),
+    };
+    Ok(match (text, pattern) {
+        (Value::Text(text), Value::Text(pattern)) => {
+            Value::Integer(like_match(&pattern, &text, escape) as i64)
+        }
+        _ => Value::Integer(0),
+    })
+}
+
+// Pops the left operand of IN, a single value or a row value as wide as the rows of the list,
+// and checks whether one of the rows equals it. As SQLite does for a column, the type of each
+// value of the operand is applied to the values it is compared with, so `id IN ('1')` matches 1
+// and `name IN (1)` matches '1'.
+// Returns NULL rather than 0 when no row matches but a NULL was compared, since the NULL might
+// have been equal, so `NOT IN` excludes the row too. An empty list never matches.
+fn evaluate_in(
+    table: &Table,
+    values: &mut Row,
+    list: Option<&Vec<Vec<Value>>>,
+) -> Result<Value, String> {
+    let list = list.ok_or_else(|| "IN requires a list of values".to_string())?;
+    let width = list.first().map_or(1, |row| row.len());
+    let Some(start) = values.len().checked_sub(width) else {
+        return Err("Not enough values to compare with operator".to_string());
+    };
+    let operand = values.split_off(start);
+    let mut unknown = false;
+    for row in list.iter().filter(|row| row.len() == width) {
+        let mut has_null = false;
+        let mut differs = false;
+        for (left, right) in operand.iter().zip(row.iter()) {
+            if left.is_null() || right.is_null() {
+                has_null = true;
+                continue;
+            }
+            let coerced = match (left, right) {
+                (Value::Text(_), Value::Integer(_) | Value::Real(_))
+                | (Value::Integer(_) | Value::Real(_), Value::Text(_)) => {
+                    right.try_coerce_to(left.get_type()).ok()
+                }
+                _ => None,
+            };
+            if !values_equal(table, left, coerced.as_ref().unwrap_or(right)) {
+                differs = true;
+                break;
+            }
+        }
+        if !differs && !has_null {
+            return Ok(Value::Integer(1));
+        }
+        unknown |= !differs;
+    }
+    Ok(if unknown {
+        Value::Null
+    } else {
+        Value::Integer(0)
+    })
+}
+
+// Maps where the right operand of each AND and OR of an RPN expression starts to the position of
+// the operator and the value of the left operand deciding its result: false for AND, true for OR.
+fn get_short_circuits(selectables: &[SelectableStackElement]) -> HashMap<usize, (usize, bool)> {
+    let mut short_circuits = HashMap::new();
+    for (i, selectable) in selectables.iter().enumerate() {
+        let deciding = match selectable {
+            SelectableStackElement::LogicalOperator(LogicalOperator::And) => false,
+            SelectableStackElement::LogicalOperator(LogicalOperator::Or) => true,
+            _ => continue,
+        };
+        if let Some(start) = get_operand_start(&selectables[..i]) {
+            short_circuits.insert(start, (i, deciding));
+        }
+    }
+    short_circuits
+}
+
+#[cfg(test)]
+thread_local! {
+    // Number of functions evaluated by `evaluate_column`, lets tests check operands are skipped.
+    static EVALUATED_FUNCTIONS: std::cell::Cell<usize> = const { std::cell::Cell::new(0) };
+}
+
+// Reads an operand of NOT, AND or OR as a truth value, NULL being unknown.
+fn truth_value(value: &Value) -> Option<Option<bool>> {
+    match value {
+        Value::Integer(val) => Some(Some(*val != 0)),
+        Value::Null => Some(None),
+        _ => None,
+    }
+}
+
+// Whether a WHERE condition holds for a row. An unknown (NULL) condition does not.
+pub fn is_true(value: Value) -> Result<bool, String> {
+    match truth_value(&value) {
+        Some(truth) => Ok(truth.unwrap_or(false)),
+        None => Err("WHERE condition did not return a boolean".to_string()),
+    }
+}
+
+fn truth_to_value(truth: Option<bool>) -> Value {
+    match truth {
+        Some(truth) => Value::Integer(truth as i64),
+        None => Value::Null,
+    }
+}
+
+// Evaluates the RETURNING columns of an INSERT, UPDATE or DELETE for the rows at the given
+// positions. * expands to every column of the table, as in a SELECT.
+pub fn get_returning_rows(
+    table: &Table,
+    positions: &[usize],
+    columns: &Vec<SelectableColumn>,
+) -> Result<Vec<Row>, String> {
+    positions
+        .iter()
+        .map(|position| get_columns(table, &table[*position], columns, None, None))
+        .collect()
+}
+
 pub fn get_columns(
     table: &Table,
     row: &Row,
     selected_columns: &Vec<SelectableColumn>,
     computed_columns: Option<&Row>,
     aliases_to_indexes: Option<&HashMap<String, usize>>,
+) -> Result<Row, String> {
+    evaluate_columns(
+        table,
+        read_row(table, row)?.as_ref(),
+        selected_columns,
+        computed_columns,
+        aliases_to_indexes,
+    )
+}
+
+// Evaluates the columns against a row already read with `read_row`.
+pub fn evaluate_columns(
+    table: &Table,
+    row: &Row,
+    selected_columns: &Vec<SelectableColumn>,
+    computed_columns: Option<&Row>,
+    aliases_to_indexes: Option<&HashMap<String, usize>>,
 ) -> Result<Row, String> {
     let mut columns = vec![];
     for col in selected_columns {
@@ -48,7 +251,7 @@ pub fn get_columns(
                 columns.push(val.clone());
             }
         } else {
-            columns.push(get_column(
+            columns.push(evaluate_column(
                 table,
                 row,
                 col,
@@ -60,12 +263,82 @@ pub fn get_columns(
     return Ok(Row(columns));
 }
 
+// The row as it is read, with the values of its generated columns computed in column order so a
+// generated column can refer to the ones before it. Scans read each row once and evaluate their
+// expressions on the result, the row is borrowed as is when the table has no generated columns.
+pub fn read_row<'a>(table: &Table, row: &'a Row) -> Result<Cow<'a, Row>, String> {
+    if !table.has_generated_columns() {
+        return Ok(Cow::Borrowed(row));
+    }
+    let mut generated_row = row.clone();
+    for (i, column) in table.get_columns()?.iter().enumerate() {
+        if let Some(expression) = column.generated_expression() {
+            generated_row[i] = evaluate_column(table, &generated_row, expression, None, None)?;
+        }
+    }
+    Ok(Cow::Owned(generated_row))
+}
+
+// Evaluates the arguments of a scalar function against the current row.
+fn get_arguments(
+    table: &Table,
+    row: &Row,
+    arguments: &Vec<SelectableColumn>,
+    computed_columns: Option<&Row>,
+    aliases_to_indexes: Option<&HashMap<String, usize>>,
+) -> Result<Vec<Value>, String> {
+    let mut values = vec![];
+    for argument in arguments {
+        values.push(evaluate_column(
+            table,
+            row,
+            argument,
+            computed_columns,
+            aliases_to_indexes,
+        )?);
+    }
+    Ok(values)
+}
+
+// The columns of joined tables are named `table.column`, an unqualified name refers to the only
+// one of them with that column name.
+fn find_joined_column<'a>(
+    column_values: &HashMap<&String, &'a Value>,
+    name: &str,
+) -> Result<Option<&'a Value>, String> {
+    let suffix = format!(".{}", name);
+    let mut matches = column_values
+        .iter()
+        .filter(|(column, _)| column.ends_with(&suffix));
+    match (matches.next(), matches.next()) {
+        (Some(_), Some(_)) => Err(format!("Ambiguous column name: {}", name)),
+        (found, _) => Ok(found.map(|(_, value)| *value)),
+    }
+}
+
 pub fn get_column(
     table: &Table,
     row: &Row,
     selected_column: &SelectableColumn,
     computed_columns: Option<&Row>,
     aliases_to_indexes: Option<&HashMap<String, usize>>,
+) -> Result<Value, String> {
+    evaluate_column(
+        table,
+        read_row(table, row)?.as_ref(),
+        selected_column,
+        computed_columns,
+        aliases_to_indexes,
+    )
+}
+
+// Evaluates an expression against a row already read with `read_row`.
+pub fn evaluate_column(
+    table: &Table,
+    row: &Row,
+    selected_column: &SelectableColumn,
+    computed_columns: Option<&Row>,
+    aliases_to_indexes: Option<&HashMap<String, usize>>,
 ) -> Result<Value, String> {
     // Does NOT handle SelectableStackElement::All, since only returns one Value
     let mut row_values: Row = Row(vec![]);
@@ -84,8 +357,25 @@ pub fn get_column(
     }
 
     let column_values = column_values;
+    // Lists of IN are kept apart from the values until their operator is reached.
+    let mut value_lists = vec![];
 
-    for selectable in &selected_column.selectables {
+    let selectables = &selected_column.selectables;
+    let short_circuits = get_short_circuits(selectables);
+    let mut i = 0;
+    while i < selectables.len() {
+        // The left operand of an AND or OR is complete when its right operand starts, if it
+        // decides the result the right operand is skipped.
+        if let Some((operator_index, deciding)) = short_circuits.get(&i)
+            && row_values.last().and_then(truth_value) == Some(Some(*deciding))
+        {
+            row_values.pop();
+            row_values.push(truth_to_value(Some(*deciding)));
+            i = operator_index + 1;
+            continue;
+        }
+        let selectable = &selectables[i];
+        i += 1;
         match selectable {
             SelectableStackElement::All => {
                 for val in row.iter() {
@@ -105,6 +395,8 @@ pub fn get_column(
                     }
                 } else if let Some(val) = column_values.get(value) {
                     row_values.push((*val).clone());
+                } else if let Some(val) = find_joined_column(&column_values, value)? {
+                    row_values.push(val.clone());
                 } else {
                     return Err(format!("Invalid column name: {}", value));
                 }
@@ -112,12 +404,21 @@ pub fn get_column(
             SelectableStackElement::Value(value) => {
                 row_values.push(value.clone());
             }
-            SelectableStackElement::ValueList(_) => {
-                // TODO: handle ValueList
+            SelectableStackElement::ValueList(list) => {
+                value_lists.push(list);
+            }
+
+            SelectableStackElement::Exists(_)
+            | SelectableStackElement::InSubquery(_)
+            | SelectableStackElement::ScalarSubquery(_) => {
+                // Subqueries are resolved into values before the rows are evaluated
+                return Err("Subqueries are not supported in this statement".to_string());
             }
 
             // THIS IS SPECIFIC TO SCALAR FUNCTIONS i.e. (date, time)
             SelectableStackElement::Function(func) => {
+                #[cfg(test)]
+                EVALUATED_FUNCTIONS.with(|count| count.set(count.get() + 1));
                 let args = &func.arguments;
                 let res = match func.name {
                     FunctionName::DateTime => Value::Text(build_julian_day(args)?.as_datetime()),
@@ -125,25 +426,122 @@ pub fn get_column(
                     FunctionName::Time => Value::Text(build_julian_day(args)?.as_time()),
                     FunctionName::JulianDay => Value::Real(build_julian_day(args)?.value()),
                     FunctionName::UnixEpoch => Value::Real(build_julian_day(args)?.as_unix_epoch()),
+                    FunctionName::Abs
+                    | FunctionName::Round
+                    | FunctionName::Random
+                    | FunctionName::Quote
+                    | FunctionName::Length
+                    | FunctionName::Trim
+                    | FunctionName::Ltrim
+                    | FunctionName::Rtrim
+                    | FunctionName::Substr
+                    | FunctionName::Upper
+                    | FunctionName::Lower
+                    | FunctionName::Coalesce
+                    | FunctionName::IfNull
+                    | FunctionName::JsonExtract => evaluate_scalar_function(
+                        &func.name,
+                        &get_arguments(table, row, args, computed_columns, aliases_to_indexes)?,
+                    )?,
+                    FunctionName::Min | FunctionName::Max if !func.is_aggregate() => {
+                        evaluate_scalar_function(
+                            &func.name,
+                            &get_arguments(table, row, args, computed_columns, aliases_to_indexes)?,
+                        )?
+                    }
+                    FunctionName::RowNumber => {
+                        // The numbers are substituted by the SELECT producing the rows
+                        return Err(
+                            "row_number() is only supported in the columns of a SELECT".to_string()
+                        );
+                    }
+                    FunctionName::LastInsertRowid
+                    | FunctionName::Changes
+                    | FunctionName::TotalChanges => {
+                        // Resolved by the SELECT along with its subqueries
+                        let name = match func.name {
+                            FunctionName::LastInsertRowid => "last_insert_rowid",
+                            FunctionName::Changes => "changes",
+                            _ => "total_changes",
+                        };
+                        return Err(format!("{}() is not supported in this statement", name));
+                    }
                     _ => return Err(format!("Unsupported function: {:?}", func.name)),
                 };
                 row_values.push(res);
             }
+            SelectableStackElement::Case(case) => {
+                let mut result = case.else_result.as_ref();
+                for (condition, branch_result) in case.branches.iter() {
+                    let condition = evaluate_column(
+                        table,
+                        row,
+                        condition,
+                        computed_columns,
+                        aliases_to_indexes,
+                    )?;
+                    if is_true(condition)? {
+                        result = Some(branch_result);
+                        break;
+                    }
+                }
+                row_values.push(match result {
+                    Some(result) => {
+                        evaluate_column(table, row, result, computed_columns, aliases_to_indexes)?
+                    }
+                    None => Value::Null,
+                });
+            }
+            SelectableStackElement::Cast(data_type) => {
+                let value = pop_one_and_operate(
+                    |a| Ok(a.cast_to(data_type)),
+                    &mut row_values,
+                    Some("Not enough values for CAST".to_string()),
+                )?;
+                row_values.push(value);
+            }
+            SelectableStackElement::Operator(Operator::In) => {
+                let result = evaluate_in(table, &mut row_values, value_lists.pop())?;
+                row_values.push(result);
+            }
+            SelectableStackElement::Operator(Operator::LikeEscape) => {
+                let result = evaluate_like_escape(&mut row_values)?;
+                row_values.push(result);
+            }
+            SelectableStackElement::Operator(op @ (Operator::Between | Operator::NotBetween)) => {
+                let result = evaluate_between(&mut row_values, *op == Operator::NotBetween)?;
+                row_values.push(result);
+            }
             SelectableStackElement::Operator(op) => {
+                // Comparing NULL with anything is unknown, unlike IS and IS NOT.
+                let compares = matches!(
+                    op,
+                    Operator::Equals
+                        | Operator::NotEquals
+                        | Operator::LessThan
+                        | Operator::GreaterThan
+                        | Operator::LessEquals
+                        | Operator::GreaterEquals
+                        | Operator::Glob
+                        | Operator::Like
+                );
+                if compares
+                    && row_values.len() >= 2
+                    && row_values.iter().rev().take(2).any(Value::is_null)
+                {
+                    row_values.pop();
+                    row_values.pop();
+                    row_values.push(Value::Null);
+                    continue;
+                }
                 let res = match op {
                     Operator::Equals => pop_two_and_operate(
-                        |a, b| match (a.numeric_to_f64(), b.numeric_to_f64()) {
-                            (Some(val1), Some(val2)) => Ok(val1 == val2),
-                            _ => Ok(a == b),
-                        },
+                        |a, b| Ok(values_equal(table, &a, &b)),
                         &mut row_values,
                         None,
                     )?,
                     Operator::NotEquals => pop_two_and_operate(
-                        |a, b| match (a.numeric_to_f64(), b.numeric_to_f64()) {
-                            (Some(val1), Some(val2)) => Ok(val1 != val2),
-                            _ => Ok(a != b),
-                        },
+                        |a, b| Ok(!values_equal(table, &a, &b)),
                         &mut row_values,
                         None,
                     )?,
@@ -183,7 +581,7 @@ pub fn get_column(
                         |a, b| match (a, b) {
                             (Value::Null, Value::Null) => Ok(true),
                             (Value::Null, _) | (_, Value::Null) => Ok(false),
-                            (first, second) => Ok(first == second),
+                            (first, second) => Ok(values_equal(table, &first, &second)),
                         },
                         &mut row_values,
                         None,
@@ -192,57 +590,83 @@ pub fn get_column(
                         |a, b| match (a, b) {
                             (Value::Null, Value::Null) => Ok(false),
                             (Value::Null, _) | (_, Value::Null) => Ok(true),
-                            (first, second) => Ok(first != second),
+                            (first, second) => Ok(!values_equal(table, &first, &second)),
+                        },
+                        &mut row_values,
+                        None,
+                    )?,
+                    Operator::Glob => pop_two_and_operate(
+                        |a, b| match (a.cast_to_text(), b.cast_to_text()) {
+                            (Some(text), Some(pattern)) => Ok(glob_match(&pattern, &text)),
+                            _ => Ok(false),
                         },
                         &mut row_values,
                         None,
                     )?,
-                    // TODO: In, NotIn
+                    Operator::Like => pop_two_and_operate(
+                        |a, b| match (a, b) {
+                            (Value::Text(text), Value::Text(pattern)) => {
+                                Ok(like_match(&pattern, &text, None))
+                            }
+                            _ => Ok(false),
+                        },
+                        &mut row_values,
+                        None,
+                    )?,
+                    // TODO: NotIn
                     _ => false,
                 };
                 // TODO: add Bool type
                 row_values.push(Value::Integer(if res { 1 } else { 0 }));
             }
             SelectableStackElement::LogicalOperator(op) => {
+                // NULL is unknown: NOT keeps it unknown, AND is false and OR is true as soon as
+                // one side decides the result on its own.
                 let res = match op {
                     // TODO: add Bool as Value
                     LogicalOperator::Not => pop_one_and_operate(
-                        |a| {
-                            if let Value::Integer(val) = a {
-                                Ok(val == 0)
-                            } else {
-                                Err("Unexpected type for NOT".to_string())
-                            }
+                        |a| match truth_value(&a) {
+                            Some(truth) => Ok(truth.map(|truth| !truth)),
+                            None => Err("Unexpected type for NOT".to_string()),
                         },
                         &mut row_values,
                         None,
                     )?,
                     LogicalOperator::And => pop_two_and_operate(
-                        |a, b| {
-                            if let (Value::Integer(val1), Value::Integer(val2)) = (a, b) {
-                                Ok(val1 != 0 && val2 != 0)
-                            } else {
-                                Err("Unexpected type(s) for AND".to_string())
+                        |a, b| match (truth_value(&a), truth_value(&b)) {
+                            (Some(Some(false)), Some(_)) | (Some(_), Some(Some(false))) => {
+                                Ok(Some(false))
                             }
+                            (Some(Some(true)), Some(Some(true))) => Ok(Some(true)),
+                            (Some(_), Some(_)) => Ok(None),
+                            _ => Err("Unexpected type(s) for AND".to_string()),
                         },
                         &mut row_values,
                         None,
                     )?,
                     LogicalOperator::Or => pop_two_and_operate(
-                        |a, b| {
-                            if let (Value::Integer(val1), Value::Integer(val2)) = (a, b) {
-                                Ok(val1 != 0 || val2 != 0)
-                            } else {
-                                Err("Unexpected type(s) for OR".to_string())
+                        |a, b| match (truth_value(&a), truth_value(&b)) {
+                            (Some(Some(true)), Some(_)) | (Some(_), Some(Some(true))) => {
+                                Ok(Some(true))
                             }
+                            (Some(Some(false)), Some(Some(false))) => Ok(Some(false)),
+                            (Some(_), Some(_)) => Ok(None),
+                            _ => Err("Unexpected type(s) for OR".to_string()),
                         },
                         &mut row_values,
                         None,
                     )?,
                 };
-                row_values.push(Value::Integer(if res { 1 } else { 0 }));
+                row_values.push(truth_to_value(res));
             }
             SelectableStackElement::MathOperator(op) => {
+                // Any operation with a NULL operand results in NULL.
+                let operands = row_values.len().saturating_sub(2);
+                if row_values.len() >= 2 && row_values[operands..].iter().any(|val| val.is_null()) {
+                    row_values.truncate(operands);
+                    row_values.push(Value::Null);
+                    continue;
+                }
                 let res = match op {
                     MathOperator::Add => pop_two_and_operate(
                         |a, b| {
@@ -324,6 +748,15 @@ pub fn get_column(
                         &mut row_values,
                         None,
                     )?,
+                    // Operands are joined as text.
+                    MathOperator::Concat => pop_two_and_operate(
+                        |a, b| match (a.cast_to_text(), b.cast_to_text()) {
+                            (Some(a_s), Some(b_s)) => Ok(Value::Text(a_s + &b_s)),
+                            _ => Err("Unexpected type(s) for CONCAT".to_string()),
+                        },
+                        &mut row_values,
+                        None,
+                    )?,
                 };
                 row_values.push(res);
             }
@@ -338,7 +771,14 @@ pub fn get_column(
     return Ok(row_values[0].clone());
 }
 
+#[cfg(test)]
+thread_local! {
+    // Number of rows visited by an UPDATE, a DELETE or a SELECT.
+    pub static ROWS_SCANNED: std::cell::Cell<usize> = const { std::cell::Cell::new(0) };
+}
+
 // Used for UPDATE and DELETE. Not get_row_indicies_matching_clauses used for INSERT, since it possibly contains DISTINCT, in which case we need the actual evaluated SELECT values, not just the indices
+// Equality predicates on indexed columns only visit the rows found in the index.
 pub fn get_row_indicies_matching_clauses(
     table: &Table,
     where_clause: &Option<SelectableColumn>,
@@ -348,47 +788,54 @@ pub fn get_row_indicies_matching_clauses(
     let mut indices = vec![];
     let mut order_by_columns_precomputed = vec![];
     let (limit, offset) = limit_clause.as_ref().map_or((-1, 0), |stmt| {
-        (stmt.limit as i64, stmt.offset.map_or(0, |val| val))
+        (
+            i64::try_from(stmt.limit).unwrap_or(i64::MAX),
+            stmt.offset.map_or(0, |val| val),
+        )
     });
 
-    for (i, row) in table
-        .iter()
-        .skip(if order_by_clause.is_none() { offset } else { 0 })
-        .enumerate()
-    {
+    // Without ORDER BY, the offset skips the first matching rows.
+    let mut to_skip = if order_by_clause.is_none() { offset } else { 0 };
+    for position in get_candidate_positions(table, &plan_where(table, where_clause)) {
         if limit != -1 && indices.len() as i64 >= limit && order_by_clause.is_none() {
             break;
-        } else if let Some(stmt) = where_clause {
-            if let Value::Integer(val) = get_column(table, row, stmt, None, None)? {
-                if val == 0 {
-                    continue;
-                }
-            } else {
-                return Err("WHERE condition did not return a boolean".to_string());
+        }
+        let row = read_row(table, &table[position])?;
+        if let Some(stmt) = where_clause {
+            #[cfg(test)]
+            ROWS_SCANNED.with(|count| count.set(count.get() + 1));
+            if !is_true(evaluate_column(table, &row, stmt, None, None)?)? {
+                continue;
             }
         }
+        if to_skip > 0 {
+            to_skip -= 1;
+            continue;
+        }
 
-        // Fix: When skipping rows without ORDER BY, enumerate() resets index to 0
-        // We need to add the offset back to get the actual table index
-        let actual_index = i + if order_by_clause.is_none() { offset } else { 0 };
-        indices.push(actual_index);
+        indices.push(position);
         if let Some(stmt) = order_by_clause {
             // UPDATE and DELETE only, so not reading from any alias table
-            order_by_columns_precomputed.push(get_columns(table, row, &stmt.columns, None, None)?);
+            order_by_columns_precomputed.push(evaluate_columns(
+                table,
+                &row,
+                &stmt.columns,
+                None,
+                None,
+            )?);
         }
     }
 
     if let Some(stmt) = order_by_clause {
-        apply_order_by_from_precomputed(&mut indices, order_by_columns_precomputed, 0, stmt);
+        apply_order_by_from_precomputed(&mut indices, order_by_columns_precomputed, 0, stmt)?;
         if limit != -1 || offset != 0 {
             // If offset exceeds the result size, return empty set (SQLite-compatible behavior)
             if offset >= indices.len() {
                 indices = vec![];
             } else {
-                let end = if (limit == -1) || (offset + limit as usize > indices.len()) {
-                    indices.len()
-                } else {
-                    offset + limit as usize
+                let end = match offset.checked_add(limit as usize) {
+                    Some(end) if limit != -1 => end.min(indices.len()),
+                    _ => indices.len(),
                 };
                 indices = indices[offset..end].to_vec();
             }
@@ -398,10 +845,12 @@ pub fn get_row_indicies_matching_clauses(
     Ok(indices)
 }
 
+// Keeps the first occurrence of each row, in the order the rows are given.
 pub fn remove_duplicate_rows(rows: Vec<Row>) -> Vec<Row> {
-    let set = rows.into_iter().collect::<HashSet<Row>>();
-    let result = set.into_iter().collect::<Vec<Row>>();
-    return result;
+    let mut seen = HashSet::new();
+    rows.into_iter()
+        .filter(|row| seen.insert(DistinctRow(row.clone())))
+        .collect()
 }
 
 fn pop_one_and_operate<F, R>(f: F, values: &mut Row, err: Option<String>) -> Result<R, String>
@@ -427,3 +876,65 @@ where
         return Err(err.unwrap_or(format!("Not enough values to compare with operator")));
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::table::test_utils::default_table;
+    use crate::interpreter::ast::{SelectStatementStackElement, SqlStatement};
+    use crate::interpreter::parse_sql;
+
+    fn where_clause(condition: &str) -> SelectableColumn {
+        let sql = format!("SELECT * FROM users WHERE {};", condition);
+        let statement = parse_sql(&sql).remove(0).unwrap();
+        let SqlStatement::Select(stack) = statement.sql_statement else {
+            panic!("Expected a SELECT statement");
+        };
+        let SelectStatementStackElement::SelectStatement(select) = &stack.elements[0] else {
+            panic!("Expected a SELECT statement");
+        };
+        select.where_clause.clone().unwrap()
+    }
+
+    // Evaluates the condition against the first row, whose id is 1, and returns its value with
+    // the number of functions that were evaluated.
+    fn evaluate_counting_functions(condition: &str) -> (Value, usize) {
+        let table = default_table();
+        let condition = where_clause(condition);
+        EVALUATED_FUNCTIONS.with(|count| count.set(0));
+        let value = get_column(&table, &table[0], &condition, None, None).unwrap();
+        (value, EVALUATED_FUNCTIONS.with(|count| count.get()))
+    }
+
+    #[test]
+    fn and_and_or_skip_the_right_operand_when_the_left_one_decides() {
+        let cases = vec![
+            ("0 AND ABS(id) = 1", Value::Integer(0), 0),
+            ("1 OR ABS(id) = 1", Value::Integer(1), 0),
+            (
+                "id = 2 AND (ABS(id) = 1 OR ABS(id) = 2)",
+                Value::Integer(0),
+                0,
+            ),
+            (
+                "id = 1 OR ABS(id) = 1 AND ABS(id) = 2",
+                Value::Integer(1),
+                0,
+            ),
+            (
+                "ABS(id) = 2 AND ABS(id) = 1 OR ABS(id) = 1",
+                Value::Integer(1),
+                2,
+            ),
+            ("1 AND ABS(id) = 1", Value::Integer(1), 1),
+            ("0 OR ABS(id) = 2", Value::Integer(0), 1),
+            ("NULL AND ABS(id) = 2", Value::Integer(0), 1),
+            ("NULL OR ABS(id) = 2", Value::Null, 1),
+        ];
+        for (condition, expected, evaluated) in cases {
+            let (value, count) = evaluate_counting_functions(condition);
+            assert!(value.exactly_equal(&expected), "{}: {:?}", condition, value);
+            assert_eq!(count, evaluated, "{}", condition);
+        }
+    }
+}
@@ -0,0 +1,138 @@
+use crate::db::json::JsonValue;
+use crate::db::table::core::value::Value;
+use crate::interpreter::ast::FunctionName;
+
+// Evaluates a scalar JSON function over already evaluated arguments.
+pub fn evaluate_json_function(name: &FunctionName, arguments: &[Value]) -> Result<Value, String> {
+    match (name, arguments) {
+        (FunctionName::JsonExtract, [json, path]) => json_extract(json, path),
+        (FunctionName::JsonExtract, _) => {
+            Err(format!("Wrong number of arguments to function {:?}", name))
+        }
+        _ => Err(format!("Unsupported function: {:?}", name)),
+    }
+}
+
+enum PathStep {
+    Key(String),
+    Index(usize),
+}
+
+// Returns the value found at a path like `$.a.b[0]` in a JSON text, or NULL if the text is not
+// valid JSON or nothing is found at the path. Arrays and objects are returned as minified JSON.
+fn json_extract(json: &Value, path: &Value) -> Result<Value, String> {
+    let steps = parse_path(&path.cast_to_text().unwrap_or_default())?;
+    let Ok(document) = JsonValue::parse(&json.cast_to_text().unwrap_or_default()) else {
+        return Ok(Value::Null);
+    };
+    let mut current = &document;
+    for step in steps.iter() {
+        let next = match (step, current) {
+            (PathStep::Key(key), _) => current.get(key),
+            (PathStep::Index(index), JsonValue::Array(values)) => values.get(*index),
+            _ => None,
+        };
+        match next {
+            Some(next) => current = next,
+            None => return Ok(Value::Null),
+        }
+    }
+    Ok(match current {
+        JsonValue::Null => Value::Null,
+        JsonValue::Integer(val) => Value::Integer(*val),
+        JsonValue::Real(val) => Value::Real(*val),
+        JsonValue::String(val) => Value::Text(val.clone()),
+        JsonValue::Array(_) | JsonValue::Object(_) => Value::Text(current.to_string()),
+    })
+}
+
+// Splits a path into its steps. It must start with `$`, followed by any number of `.key` and
+// `[index]` steps. Keys with dots, brackets or spaces are quoted, as in `$."a b"`.
+fn parse_path(path: &str) -> Result<Vec<PathStep>, String> {
+    let error = || format!("JSON path error near '{}'", path);
+    let mut rest = path.strip_prefix('$').ok_or_else(error)?;
+    let mut steps = vec![];
+    while !rest.is_empty() {
+        if let Some(quoted) = rest.strip_prefix(".\"") {
+            let end = quoted.find('"').ok_or_else(error)?;
+            steps.push(PathStep::Key(quoted[..end].to_string()));
+            rest = &quoted[end + 1..];
+        } else if let Some(after) = rest.strip_prefix('.') {
+            let end = after.find(['.', '[']).unwrap_or(after.len());
+            if end == 0 {
+                return Err(error());
+            }
+            steps.push(PathStep::Key(after[..end].to_string()));
+            rest = &after[end..];
+        } else if let Some(after) = rest.strip_prefix('[') {
+            let end = after.find(']').ok_or_else(error)?;
+            let index = after[..end].trim().parse::<usize>().map_err(|_| error())?;
+            steps.push(PathStep::Index(index));
+            rest = &after[end + 1..];
+        } else {
+            return Err(error());
+        }
+    }
+    Ok(steps)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn extract(json: &str, path: &str) -> Result<Value, String> {
+        evaluate_json_function(
+            &FunctionName::JsonExtract,
+            &[Value::Text(json.to_string()), Value::Text(path.to_string())],
+        )
+    }
+
+    #[test]
+    fn json_extract_follows_object_keys_and_array_indices() {
+        let json = r#"{"user": {"name": "Jo", "tags": ["a", "b"], "age": 30}, "score": 1.5}"#;
+        assert_eq!(
+            extract(json, "$.user.name"),
+            Ok(Value::Text("Jo".to_string()))
+        );
+        assert_eq!(
+            extract(json, "$.user.tags[1]"),
+            Ok(Value::Text("b".to_string()))
+        );
+        assert_eq!(extract(json, "$.user.age"), Ok(Value::Integer(30)));
+        assert_eq!(extract(json, "$.score"), Ok(Value::Real(1.5)));
+        assert_eq!(
+            extract(json, "$.user.tags"),
+            Ok(Value::Text("[\"a\",\"b\"]".to_string()))
+        );
+        assert_eq!(extract("[10, [20, 30]]", "$[1][0]"), Ok(Value::Integer(20)));
+        assert_eq!(extract("true", "$"), Ok(Value::Integer(1)));
+    }
+
+    #[test]
+    fn json_extract_reads_quoted_keys() {
+        let json = r#"{"a b": 1, "c.d": {"e": [2, 3]}, "": 4}"#;
+        assert_eq!(extract(json, r#"$."a b""#), Ok(Value::Integer(1)));
+        assert_eq!(extract(json, r#"$."c.d".e[1]"#), Ok(Value::Integer(3)));
+        assert_eq!(extract(json, r#"$."""#), Ok(Value::Integer(4)));
+        assert!(extract(json, r#"$."a b"#).is_err());
+    }
+
+    #[test]
+    fn json_extract_returns_null_for_missing_paths_and_invalid_json() {
+        let json = r#"{"a": [1, 2], "b": null}"#;
+        assert!(extract(json, "$.missing").unwrap().is_null());
+        assert!(extract(json, "$.a[2]").unwrap().is_null());
+        assert!(extract(json, "$.a.b").unwrap().is_null());
+        assert!(extract(json, "$[0]").unwrap().is_null());
+        assert!(extract(json, "$.b").unwrap().is_null());
+        assert!(extract("{not json", "$.a").unwrap().is_null());
+
+        assert!(extract(json, "a").is_err());
+        assert!(extract(json, "$.").is_err());
+        assert!(extract(json, "$[x]").is_err());
+        assert!(
+            evaluate_json_function(&FunctionName::JsonExtract, &[Value::Text(json.to_string())])
+                .is_err()
+        );
+    }
+}
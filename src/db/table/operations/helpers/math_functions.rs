@@ -0,0 +1,122 @@
+use crate::db::table::core::value::Value;
+use crate::interpreter::ast::FunctionName;
+use std::cell::Cell;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+thread_local! {
+    // State of the generator behind RANDOM(), seeded from the clock on first use unless
+    // `seed_random` was called on this thread.
+    static RANDOM_STATE: Cell<Option<u64>> = const { Cell::new(None) };
+}
+
+// Evaluates a scalar math function over already evaluated, non-NULL arguments. Integers stay
+// integers unless the function has to produce a fractional result.
+pub fn evaluate_math_function(name: &FunctionName, arguments: &[Value]) -> Result<Value, String> {
+    match (name, arguments) {
+        (FunctionName::Abs, [value]) => abs(value),
+        (FunctionName::Round, [value]) => round(value, &Value::Integer(0)),
+        (FunctionName::Round, [value, precision]) => round(value, precision),
+        (FunctionName::Random, []) => Ok(Value::Integer(random())),
+        (FunctionName::Abs | FunctionName::Round | FunctionName::Random, _) => {
+            Err(format!("Wrong number of arguments to function {:?}", name))
+        }
+        _ => Err(format!("Unsupported function: {:?}", name)),
+    }
+}
+
+// Makes RANDOM() return the same sequence of numbers every time it is given the same seed, so
+// results depending on it, like `ORDER BY RANDOM()`, can be reproduced.
+pub fn seed_random(seed: u64) {
+    RANDOM_STATE.with(|state| state.set(Some(seed)));
+}
+
+// A pseudo-random integer anywhere in the range of i64, as SQLite returns. Uses SplitMix64, which
+// is fast and good enough for shuffling rows, but not for anything that needs to be unpredictable.
+fn random() -> i64 {
+    RANDOM_STATE.with(|state| {
+        let seed = state.get().unwrap_or_else(|| {
+            SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map_or(0, |duration| duration.as_nanos() as u64)
+        });
+        let next = seed.wrapping_add(0x9E3779B97F4A7C15);
+        state.set(Some(next));
+        let mut value = next;
+        value = (value ^ (value >> 30)).wrapping_mul(0xBF58476D1CE4E5B9);
+        value = (value ^ (value >> 27)).wrapping_mul(0x94D049BB133111EB);
+        (value ^ (value >> 31)) as i64
+    })
+}
+
+fn abs(value: &Value) -> Result<Value, String> {
+    match value {
+        Value::Integer(val) => val
+            .checked_abs()
+            .map(Value::Integer)
+            .ok_or_else(|| "Integer overflow in ABS".to_string()),
+        _ => Ok(Value::Real(value.cast_to_real().unwrap_or(0.0).abs())),
+    }
+}
+
+fn round(value: &Value, precision: &Value) -> Result<Value, String> {
+    // Negative precisions round to a whole number, like SQLite.
+    let digits = precision.cast_to_int().unwrap_or(0).clamp(0, 30) as i32;
+    match value {
+        Value::Integer(val) => Ok(Value::Integer(*val)),
+        _ => {
+            let val = value.cast_to_real().unwrap_or(0.0);
+            let factor = 10f64.powi(digits);
+            let rounded = (val * factor).round() / factor;
+            // Scaling very large values overflows, and those have no fractional part to round anyway.
+            if rounded.is_finite() {
+                Ok(Value::Real(rounded))
+            } else {
+                Ok(Value::Real(val.round()))
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn round_preserves_the_numeric_type() {
+        let cases = vec![
+            (
+                vec![Value::Real(3.14159), Value::Integer(2)],
+                Value::Real(3.14),
+            ),
+            (vec![Value::Real(2.5)], Value::Real(3.0)),
+            (vec![Value::Real(-2.5)], Value::Real(-3.0)),
+            (vec![Value::Integer(7)], Value::Integer(7)),
+            (
+                vec![Value::Integer(7), Value::Integer(2)],
+                Value::Integer(7),
+            ),
+            (
+                vec![Value::Real(1234.5), Value::Integer(-2)],
+                Value::Real(1235.0),
+            ),
+        ];
+        for (arguments, expected) in cases {
+            let result = evaluate_math_function(&FunctionName::Round, &arguments).unwrap();
+            assert!(result.exactly_equal(&expected), "{:?}", arguments);
+        }
+        assert!(evaluate_math_function(&FunctionName::Round, &[]).is_err());
+    }
+
+    #[test]
+    fn abs_preserves_the_numeric_type() {
+        let cases = vec![
+            (Value::Integer(-4), Value::Integer(4)),
+            (Value::Real(-4.5), Value::Real(4.5)),
+        ];
+        for (argument, expected) in cases {
+            let result = evaluate_math_function(&FunctionName::Abs, &[argument]).unwrap();
+            assert!(result.exactly_equal(&expected));
+        }
+        assert!(evaluate_math_function(&FunctionName::Abs, &[Value::Integer(i64::MIN)]).is_err());
+    }
+}
@@ -1,3 +1,10 @@
+pub mod aggregate_functions;
 pub mod common;
 pub mod datetime_functions;
+pub mod json_functions;
+pub mod math_functions;
 pub mod order_by_clause;
+pub mod qualified_columns;
+pub mod query_planner;
+pub mod scalar_functions;
+pub mod string_functions;
@@ -8,7 +8,23 @@ pub fn apply_order_by_from_precomputed<T: Clone>(
     precomputed: Vec<Row>,
     default: T,
     order_by_clause: &OrderByClause,
-) -> () {
+) -> Result<(), String> {
+    // The keys of each row are compared term by term, so every row needs one per direction.
+    if order_by_clause.directions.len() != order_by_clause.columns.len() {
+        return Err(format!(
+            "ORDER BY has {} terms but {} directions",
+            order_by_clause.columns.len(),
+            order_by_clause.directions.len()
+        ));
+    }
+    if precomputed.len() != to_order.len()
+        || precomputed
+            .iter()
+            .any(|row| row.len() < order_by_clause.directions.len())
+    {
+        return Err("ORDER BY keys don't match the rows being ordered".to_string());
+    }
+    // sort_by is stable, so rows with equal keys keep their relative order.
     let mut sorted_indices = (0..to_order.len()).collect::<Vec<usize>>();
     sorted_indices
         .sort_by(|a, b| perform_comparisons(&precomputed[*a], &precomputed[*b], order_by_clause));
@@ -19,11 +35,17 @@ pub fn apply_order_by_from_precomputed<T: Clone>(
         .collect();
     to_order.clear();
     to_order.extend(sorted_vec);
+    Ok(())
 }
 
 fn perform_comparisons(row1: &Row, row2: &Row, order_by_clause: &OrderByClause) -> Ordering {
     for (i, direction) in order_by_clause.directions.iter().enumerate() {
-        let ordering = row1[i].partial_cmp(&row2[i]).unwrap_or(Ordering::Equal);
+        // NULL < NULL holds for values, but two NULL keys must compare equal to keep the order stable.
+        let ordering = if row1[i].is_null() && row2[i].is_null() {
+            Ordering::Equal
+        } else {
+            row1[i].partial_cmp(&row2[i]).unwrap_or(Ordering::Equal)
+        };
         if ordering != Ordering::Equal {
             return if *direction == OrderByDirection::Desc {
                 ordering.reverse()
@@ -64,7 +86,8 @@ mod tests {
             directions: vec![OrderByDirection::Asc],
         };
 
-        apply_order_by_from_precomputed(&mut to_order, precomputed, "default", &order_by_clause);
+        apply_order_by_from_precomputed(&mut to_order, precomputed, "default", &order_by_clause)
+            .unwrap();
 
         assert_eq!(to_order, vec!["first", "second", "third", "fourth"]);
     }
@@ -96,11 +119,85 @@ mod tests {
             directions: vec![OrderByDirection::Desc, OrderByDirection::Asc],
         };
 
-        apply_order_by_from_precomputed(&mut to_order, precomputed, "default", &order_by_clause);
+        apply_order_by_from_precomputed(&mut to_order, precomputed, "default", &order_by_clause)
+            .unwrap();
 
         assert_eq!(
             to_order,
             vec!["first", "second", "third", "fourth", "fifth", "sixth"]
         );
     }
+
+    #[test]
+    fn apply_order_by_from_precomputed_keeps_the_order_of_equal_keys() {
+        let mut to_order = vec!["a", "b", "c", "d", "e", "f", "g"];
+
+        let precomputed = vec![
+            Row(vec![Value::Integer(2)]),
+            Row(vec![Value::Null]),
+            Row(vec![Value::Integer(1)]),
+            Row(vec![Value::Integer(2)]),
+            Row(vec![Value::Null]),
+            Row(vec![Value::Real(1.0)]),
+            Row(vec![Value::Integer(2)]),
+        ];
+
+        let mut order_by_clause = OrderByClause {
+            columns: vec![SelectableColumn {
+                selectables: vec![SelectableStackElement::Column("age".to_string())],
+                column_name: "age".to_string(),
+            }],
+            directions: vec![OrderByDirection::Asc],
+        };
+
+        let mut ascending = to_order.clone();
+        apply_order_by_from_precomputed(
+            &mut ascending,
+            precomputed.clone(),
+            "default",
+            &order_by_clause,
+        );
+        assert_eq!(ascending, vec!["b", "e", "c", "f", "a", "d", "g"]);
+
+        order_by_clause.directions = vec![OrderByDirection::Desc];
+        apply_order_by_from_precomputed(&mut to_order, precomputed, "default", &order_by_clause)
+            .unwrap();
+        assert_eq!(to_order, vec!["a", "d", "g", "c", "f", "b", "e"]);
+    }
+
+    #[test]
+    fn apply_order_by_from_precomputed_rejects_mismatched_terms() {
+        let column = SelectableColumn {
+            selectables: vec![SelectableStackElement::Column("age".to_string())],
+            column_name: "age".to_string(),
+        };
+        let order_by_clause = OrderByClause {
+            columns: vec![column.clone()],
+            directions: vec![OrderByDirection::Asc, OrderByDirection::Desc],
+        };
+        let mut to_order = vec!["b", "a"];
+        let precomputed = vec![
+            Row(vec![Value::Integer(2), Value::Integer(0)]),
+            Row(vec![Value::Integer(1), Value::Integer(0)]),
+        ];
+        let result =
+            apply_order_by_from_precomputed(&mut to_order, precomputed, "", &order_by_clause);
+        assert_eq!(
+            result,
+            Err("ORDER BY has 1 terms but 2 directions".to_string())
+        );
+        assert_eq!(to_order, vec!["b", "a"]);
+
+        let order_by_clause = OrderByClause {
+            columns: vec![column.clone(), column],
+            directions: vec![OrderByDirection::Asc, OrderByDirection::Desc],
+        };
+        let precomputed = vec![Row(vec![Value::Integer(2)]), Row(vec![Value::Integer(1)])];
+        let result =
+            apply_order_by_from_precomputed(&mut to_order, precomputed, "", &order_by_clause);
+        assert!(result.is_err());
+        let result = apply_order_by_from_precomputed(&mut to_order, vec![], "", &order_by_clause);
+        assert!(result.is_err());
+        assert_eq!(to_order, vec!["b", "a"]);
+    }
 }
@@ -0,0 +1,151 @@
+use crate::db::table::core::table::Table;
+use crate::interpreter::ast::{
+    OrderByClause, SelectStatement, SelectableColumn, SelectableStackElement, TableAliases,
+};
+
+// The qualifier the columns of a statement reading a single table are written with, e.g. `u` in
+// `u.id`: its alias if it was given one, otherwise its name.
+pub fn table_qualifiers(table_name: &str, table_aliases: &TableAliases) -> Vec<String> {
+    let aliases = table_aliases
+        .iter()
+        .filter(|(_, name)| *name == table_name)
+        .map(|(alias, _)| alias.clone())
+        .collect::<Vec<String>>();
+    if aliases.is_empty() {
+        vec![table_name.to_string()]
+    } else {
+        aliases
+    }
+}
+
+// The columns of a single table are stored by their name only, so their qualifier is dropped.
+// Columns qualified with another table are kept and are reported as invalid.
+pub fn strip_qualifiers(column: &mut SelectableColumn, qualifiers: &[String]) {
+    for selectable in column.selectables.iter_mut() {
+        match selectable {
+            SelectableStackElement::Column(name) => {
+                if let Some((qualifier, column_name)) = name.split_once('.')
+                    && qualifiers.iter().any(|other| other == qualifier)
+                {
+                    *name = column_name.to_string();
+                }
+            }
+            SelectableStackElement::Function(func) => {
+                for argument in func.arguments.iter_mut() {
+                    strip_qualifiers(argument, qualifiers);
+                }
+            }
+            SelectableStackElement::Case(case) => {
+                for (condition, result) in case.branches.iter_mut() {
+                    strip_qualifiers(condition, qualifiers);
+                    strip_qualifiers(result, qualifiers);
+                }
+                if let Some(else_result) = &mut case.else_result {
+                    strip_qualifiers(else_result, qualifiers);
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+// Replaces each `table.*` of the result columns by the columns of that table. Without
+// qualifiers the table is joined and its columns are already qualified, see
+// `join::join_tables`.
+pub fn expand_qualified_all_columns(
+    table: &Table,
+    columns: &mut Vec<SelectableColumn>,
+    qualifiers: Option<&[String]>,
+) -> Result<(), String> {
+    let mut expanded = vec![];
+    for column in columns.drain(..) {
+        let [SelectableStackElement::Column(name)] = column.selectables.as_slice() else {
+            expanded.push(column);
+            continue;
+        };
+        let Some(qualifier) = name.strip_suffix(".*") else {
+            expanded.push(column);
+            continue;
+        };
+        match qualifiers {
+            Some(qualifiers) if qualifiers.iter().any(|other| other == qualifier) => {
+                expanded.push(SelectableColumn {
+                    selectables: vec![SelectableStackElement::All],
+                    column_name: "*".to_string(),
+                });
+            }
+            Some(_) => return Err(format!("No such table: {}", qualifier)),
+            None => {
+                let prefix = format!("{}.", qualifier);
+                let table_columns = table
+                    .get_column_names()?
+                    .into_iter()
+                    .filter_map(|name| {
+                        let column_name = name.strip_prefix(&prefix)?.to_string();
+                        Some(SelectableColumn {
+                            selectables: vec![SelectableStackElement::Column(name.clone())],
+                            column_name,
+                        })
+                    })
+                    .collect::<Vec<SelectableColumn>>();
+                if table_columns.is_empty() {
+                    return Err(format!("No such table: {}", qualifier));
+                }
+                expanded.extend(table_columns);
+            }
+        }
+    }
+    *columns = expanded;
+    Ok(())
+}
+
+// Resolves the qualified columns of a SELECT reading `table`, see `strip_qualifiers` and
+// `expand_qualified_all_columns`.
+pub fn resolve_select_qualifiers(
+    table: &Table,
+    statement: &mut SelectStatement,
+) -> Result<(), String> {
+    if statement.table_name.is_empty() || !statement.joins.is_empty() {
+        return expand_qualified_all_columns(table, &mut statement.columns, None);
+    }
+    let qualifiers = table_qualifiers(&statement.table_name, &statement.table_aliases);
+    expand_qualified_all_columns(table, &mut statement.columns, Some(&qualifiers))?;
+    let group_by_columns = statement
+        .group_by_clause
+        .iter_mut()
+        .flat_map(|clause| clause.columns.iter_mut());
+    for column in statement
+        .columns
+        .iter_mut()
+        .chain(statement.where_clause.iter_mut())
+        .chain(group_by_columns)
+        .chain(order_by_columns(&mut statement.order_by_clause))
+    {
+        strip_qualifiers(column, &qualifiers);
+    }
+    Ok(())
+}
+
+// Drops the qualifiers of the clauses of an UPDATE or DELETE, which read a single table.
+pub fn strip_clause_qualifiers(
+    qualifiers: &[String],
+    where_clause: &mut Option<SelectableColumn>,
+    order_by_clause: &mut Option<OrderByClause>,
+    returning: &mut Option<Vec<SelectableColumn>>,
+) {
+    for column in where_clause
+        .iter_mut()
+        .chain(order_by_columns(order_by_clause))
+        .chain(returning.iter_mut().flatten())
+    {
+        strip_qualifiers(column, qualifiers);
+    }
+}
+
+fn order_by_columns(
+    order_by_clause: &mut Option<OrderByClause>,
+) -> impl Iterator<Item = &mut SelectableColumn> {
+    order_by_clause
+        .iter_mut()
+        .flat_map(|clause| clause.columns.iter_mut())
+}
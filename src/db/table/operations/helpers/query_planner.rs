@@ -0,0 +1,259 @@
+use crate::db::table::core::{row::Row, table::Table, value::Value};
+use crate::interpreter::ast::{
+    LogicalOperator, Operator, SelectableColumn, SelectableStackElement,
+};
+
+// An index is only used if a lookup is expected to return at most this fraction of the rows,
+// otherwise scanning the table is about as cheap and avoids the lookup.
+const MAX_INDEX_SELECTIVITY: f64 = 0.25;
+
+#[derive(Debug, PartialEq)]
+pub enum ScanPlan {
+    FullScan,
+    IndexLookup { index_name: String, key: Row },
+}
+
+// Chooses how to find the rows matching a WHERE clause. Only equality predicates on every column
+// of an index, combined with AND, can be answered by an index.
+pub fn plan_where(table: &Table, where_clause: &Option<SelectableColumn>) -> ScanPlan {
+    let Some(where_clause) = where_clause else {
+        return ScanPlan::FullScan;
+    };
+    let equalities = get_conjuncts(&where_clause.selectables)
+        .into_iter()
+        .filter_map(get_equality)
+        // An index only finds exactly equal keys, which misses numbers within the epsilon.
+        .filter(|(_, value)| {
+            table.real_equality_epsilon.is_none() || value.numeric_to_f64().is_none()
+        })
+        .collect::<Vec<(&String, &Value)>>();
+    if equalities.is_empty() {
+        return ScanPlan::FullScan;
+    }
+
+    let mut best_plan = ScanPlan::FullScan;
+    let mut best_selectivity = MAX_INDEX_SELECTIVITY;
+    for index in table.indexes.iter() {
+        let key = index
+            .columns
+            .iter()
+            .map(|column| {
+                equalities
+                    .iter()
+                    .find(|(name, _)| *name == column)
+                    .map(|(_, value)| (*value).clone())
+            })
+            .collect::<Option<Vec<Value>>>();
+        let (Some(key), Some(selectivity)) = (key, table.index_selectivity(&index.name)) else {
+            continue;
+        };
+        if selectivity <= best_selectivity {
+            best_selectivity = selectivity;
+            best_plan = ScanPlan::IndexLookup {
+                index_name: index.name.clone(),
+                key: Row(key),
+            };
+        }
+    }
+    best_plan
+}
+
+// Returns the positions of the rows that need to be evaluated against the WHERE clause, in table order.
+pub fn get_candidate_positions(table: &Table, plan: &ScanPlan) -> Vec<usize> {
+    match plan {
+        ScanPlan::FullScan => (0..table.len()).collect(),
+        ScanPlan::IndexLookup { index_name, key } => match table.get_index(index_name) {
+            Some(index) => {
+                let mut positions = index.lookup(key);
+                positions.sort();
+                positions
+            }
+            None => (0..table.len()).collect(),
+        },
+    }
+}
+
+// Describes a plan the way SQLite's EXPLAIN QUERY PLAN does, e.g. `SCAN users` or
+// `SEARCH users USING INDEX idx_id (id=?)`.
+pub fn describe_plan(table_name: &str, table: &Table, plan: &ScanPlan) -> String {
+    match plan {
+        ScanPlan::FullScan => format!("SCAN {}", table_name),
+        ScanPlan::IndexLookup { index_name, .. } => {
+            let columns = table
+                .get_index(index_name)
+                .map(|index| {
+                    index
+                        .columns
+                        .iter()
+                        .map(|column| format!("{}=?", column))
+                        .collect::<Vec<String>>()
+                        .join(" AND ")
+                })
+                .unwrap_or_default();
+            format!(
+                "SEARCH {} USING INDEX {} ({})",
+                table_name, index_name, columns
+            )
+        }
+    }
+}
+
+// Splits an RPN expression into the operands of its top-level ANDs.
+fn get_conjuncts(selectables: &[SelectableStackElement]) -> Vec<&[SelectableStackElement]> {
+    let Some(last) = selectables.last() else {
+        return vec![];
+    };
+    if *last != SelectableStackElement::LogicalOperator(LogicalOperator::And) {
+        return vec![selectables];
+    }
+    let operands = &selectables[..selectables.len() - 1];
+    let Some(split) = get_operand_start(operands) else {
+        return vec![selectables];
+    };
+    let mut conjuncts = get_conjuncts(&operands[..split]);
+    conjuncts.extend(get_conjuncts(&operands[split..]));
+    conjuncts
+}
+
+// Returns where the operand ending at the last element of the RPN expression starts.
+pub fn get_operand_start(selectables: &[SelectableStackElement]) -> Option<usize> {
+    let mut needed = 1;
+    for (i, selectable) in selectables.iter().enumerate().rev() {
+        needed -= 1;
+        needed += match selectable {
+            SelectableStackElement::Operator(
+                Operator::Between | Operator::NotBetween | Operator::LikeEscape,
+            ) => 3,
+            SelectableStackElement::Operator(_)
+            | SelectableStackElement::MathOperator(_)
+            | SelectableStackElement::LogicalOperator(LogicalOperator::And)
+            | SelectableStackElement::LogicalOperator(LogicalOperator::Or) => 2,
+            SelectableStackElement::LogicalOperator(LogicalOperator::Not)
+            | SelectableStackElement::Cast(_) => 1,
+            // The left operand of IN is as wide as the rows of its list.
+            SelectableStackElement::ValueList(rows) => rows.first().map_or(0, |row| row.len() - 1),
+            _ => 0,
+        };
+        if needed == 0 {
+            return Some(i);
+        }
+    }
+    None
+}
+
+// Matches `column = value` and `value = column`.
+fn get_equality(selectables: &[SelectableStackElement]) -> Option<(&String, &Value)> {
+    match selectables {
+        [
+            SelectableStackElement::Column(column),
+            SelectableStackElement::Value(value),
+            SelectableStackElement::Operator(Operator::Equals),
+        ]
+        | [
+            SelectableStackElement::Value(value),
+            SelectableStackElement::Column(column),
+            SelectableStackElement::Operator(Operator::Equals),
+        ] if !value.is_null() => Some((column, value)),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::table::core::index::Index;
+    use crate::db::table::test_utils::default_table;
+
+    fn equals(column: &str, value: Value) -> Vec<SelectableStackElement> {
+        vec![
+            SelectableStackElement::Column(column.to_string()),
+            SelectableStackElement::Value(value),
+            SelectableStackElement::Operator(Operator::Equals),
+        ]
+    }
+
+    fn where_clause(selectables: Vec<SelectableStackElement>) -> Option<SelectableColumn> {
+        Some(SelectableColumn {
+            selectables,
+            column_name: "".to_string(),
+        })
+    }
+
+    #[test]
+    fn planner_uses_index_for_selective_equality() {
+        let mut table = default_table();
+        for id in 5..=20 {
+            table.push(Row(vec![
+                Value::Integer(id),
+                Value::Text("Jim".to_string()),
+                Value::Integer(40),
+                Value::Real(1.0),
+            ]));
+        }
+        table
+            .create_index(Index::new("idx_id".to_string(), vec!["id".to_string()]))
+            .unwrap();
+        table
+            .create_index(Index::new("idx_name".to_string(), vec!["name".to_string()]))
+            .unwrap();
+        assert_eq!(table.row_count(), 20);
+        assert_eq!(table.index_selectivity("idx_id"), Some(0.05));
+        assert_eq!(table.index_selectivity("missing"), None);
+
+        // WHERE name = 'Jim' AND id = 7
+        let mut selectables = equals("name", Value::Text("Jim".to_string()));
+        selectables.extend(equals("id", Value::Integer(7)));
+        selectables.push(SelectableStackElement::LogicalOperator(
+            LogicalOperator::And,
+        ));
+        let plan = plan_where(&table, &where_clause(selectables));
+        assert_eq!(
+            plan,
+            ScanPlan::IndexLookup {
+                index_name: "idx_id".to_string(),
+                key: Row(vec![Value::Integer(7)]),
+            }
+        );
+        assert_eq!(get_candidate_positions(&table, &plan), vec![6]);
+    }
+
+    #[test]
+    fn planner_scans_when_no_index_is_selective() {
+        let mut table = default_table();
+        for _ in 0..10 {
+            table.push(Row(vec![
+                Value::Integer(1),
+                Value::Text("Jim".to_string()),
+                Value::Integer(40),
+                Value::Real(1.0),
+            ]));
+        }
+        table
+            .create_index(Index::new("idx_name".to_string(), vec!["name".to_string()]))
+            .unwrap();
+
+        // Most rows share the same name, so the index barely narrows the scan.
+        let plan = plan_where(
+            &table,
+            &where_clause(equals("name", Value::Text("John".to_string()))),
+        );
+        assert_eq!(plan, ScanPlan::FullScan);
+
+        // WHERE id = 2 OR id = 3 can't use an index.
+        table
+            .create_index(Index::new("idx_id".to_string(), vec!["id".to_string()]))
+            .unwrap();
+        let mut selectables = equals("id", Value::Integer(2));
+        selectables.extend(equals("id", Value::Integer(3)));
+        selectables.push(SelectableStackElement::LogicalOperator(LogicalOperator::Or));
+        assert_eq!(
+            plan_where(&table, &where_clause(selectables)),
+            ScanPlan::FullScan
+        );
+        assert_eq!(plan_where(&table, &None), ScanPlan::FullScan);
+        assert_eq!(
+            get_candidate_positions(&table, &ScanPlan::FullScan).len(),
+            table.len()
+        );
+    }
+}
@@ -0,0 +1,165 @@
+use crate::db::table::core::value::Value;
+use crate::db::table::operations::helpers::json_functions::evaluate_json_function;
+use crate::db::table::operations::helpers::math_functions::evaluate_math_function;
+use crate::db::table::operations::helpers::string_functions::evaluate_string_function;
+use crate::interpreter::ast::FunctionName;
+
+// Evaluates a scalar function over already evaluated arguments. Functions that propagate NULL
+// return it as soon as one of their arguments is NULL, without being evaluated.
+pub fn evaluate_scalar_function(name: &FunctionName, arguments: &[Value]) -> Result<Value, String> {
+    if name.propagates_null() && arguments.iter().any(Value::is_null) {
+        return Ok(Value::Null);
+    }
+    match name {
+        FunctionName::Abs | FunctionName::Round | FunctionName::Random => {
+            evaluate_math_function(name, arguments)
+        }
+        FunctionName::Quote
+        | FunctionName::Length
+        | FunctionName::Trim
+        | FunctionName::Ltrim
+        | FunctionName::Rtrim
+        | FunctionName::Substr
+        | FunctionName::Upper
+        | FunctionName::Lower => evaluate_string_function(name, arguments),
+        FunctionName::Coalesce | FunctionName::IfNull => coalesce(name, arguments),
+        FunctionName::Min | FunctionName::Max => min_max(name, arguments),
+        FunctionName::JsonExtract => evaluate_json_function(name, arguments),
+        _ => Err(format!("Unsupported function: {:?}", name)),
+    }
+}
+
+// Multi-argument MIN and MAX, which unlike their aggregate forms return NULL if any argument is
+// NULL. Values compare as in ORDER BY: numbers before texts before blobs. Texts are compared with
+// the BINARY collation, byte by byte, so uppercase letters sort before lowercase ones.
+fn min_max(name: &FunctionName, arguments: &[Value]) -> Result<Value, String> {
+    if arguments.len() < 2 {
+        return Err(format!("Wrong number of arguments to function {:?}", name));
+    }
+    if arguments.iter().any(Value::is_null) {
+        return Ok(Value::Null);
+    }
+    let mut result = &arguments[0];
+    for argument in &arguments[1..] {
+        let replaces = match name {
+            FunctionName::Min => argument < result,
+            _ => argument > result,
+        };
+        if replaces {
+            result = argument;
+        }
+    }
+    Ok(result.clone())
+}
+
+// Returns the first argument that is not NULL, or NULL if they all are.
+fn coalesce(name: &FunctionName, arguments: &[Value]) -> Result<Value, String> {
+    let valid_count = match name {
+        FunctionName::IfNull => arguments.len() == 2,
+        _ => arguments.len() >= 2,
+    };
+    if !valid_count {
+        return Err(format!("Wrong number of arguments to function {:?}", name));
+    }
+    Ok(arguments
+        .iter()
+        .find(|argument| !argument.is_null())
+        .cloned()
+        .unwrap_or(Value::Null))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn null_arguments_propagate_through_scalar_functions() {
+        let cases = vec![
+            (FunctionName::Abs, vec![Value::Null]),
+            (FunctionName::Round, vec![Value::Null]),
+            (FunctionName::Round, vec![Value::Real(1.5), Value::Null]),
+            (FunctionName::Length, vec![Value::Null]),
+            (FunctionName::Trim, vec![Value::Null]),
+            (FunctionName::Upper, vec![Value::Null]),
+            (
+                FunctionName::Ltrim,
+                vec![Value::Text("x".to_string()), Value::Null],
+            ),
+        ];
+        for (name, arguments) in cases {
+            let result = evaluate_scalar_function(&name, &arguments);
+            assert!(result.unwrap().is_null(), "{:?}", name);
+        }
+        assert_eq!(
+            evaluate_scalar_function(&FunctionName::Quote, &[Value::Null]),
+            Ok(Value::Text("NULL".to_string()))
+        );
+    }
+
+    #[test]
+    fn coalesce_and_ifnull_skip_null_arguments() {
+        let result = evaluate_scalar_function(
+            &FunctionName::Coalesce,
+            &[
+                Value::Null,
+                Value::Null,
+                Value::Integer(3),
+                Value::Integer(4),
+            ],
+        );
+        assert_eq!(result, Ok(Value::Integer(3)));
+        let result = evaluate_scalar_function(&FunctionName::Coalesce, &[Value::Null, Value::Null]);
+        assert!(result.unwrap().is_null());
+        let result = evaluate_scalar_function(
+            &FunctionName::IfNull,
+            &[Value::Null, Value::Text("none".to_string())],
+        );
+        assert_eq!(result, Ok(Value::Text("none".to_string())));
+
+        assert!(evaluate_scalar_function(&FunctionName::Coalesce, &[Value::Null]).is_err());
+        assert!(
+            evaluate_scalar_function(
+                &FunctionName::IfNull,
+                &[Value::Null, Value::Null, Value::Null]
+            )
+            .is_err()
+        );
+    }
+
+    #[test]
+    fn min_and_max_with_several_arguments_compare_them() {
+        let text = |value: &str| Value::Text(value.to_string());
+        let cases = vec![
+            (
+                FunctionName::Max,
+                vec![text("apple"), text("Banana")],
+                text("apple"),
+            ),
+            (
+                FunctionName::Min,
+                vec![text("apple"), text("Banana")],
+                text("Banana"),
+            ),
+            (
+                FunctionName::Max,
+                vec![Value::Integer(3), Value::Real(3.5), Value::Integer(-1)],
+                Value::Real(3.5),
+            ),
+            (
+                FunctionName::Min,
+                vec![text("1"), Value::Integer(2), Value::Blob(vec![0])],
+                Value::Integer(2),
+            ),
+            (
+                FunctionName::Max,
+                vec![Value::Integer(1), Value::Null],
+                Value::Null,
+            ),
+        ];
+        for (name, arguments, expected) in cases {
+            let result = evaluate_scalar_function(&name, &arguments).unwrap();
+            assert!(result.exactly_equal(&expected), "{:?}", result);
+        }
+        assert!(evaluate_scalar_function(&FunctionName::Max, &[Value::Integer(1)]).is_err());
+    }
+}
@@ -0,0 +1,458 @@
+use crate::db::json::encode_hex;
+use crate::db::table::core::value::Value;
+use crate::interpreter::ast::FunctionName;
+
+// Evaluates a scalar string function over already evaluated arguments.
+pub fn evaluate_string_function(name: &FunctionName, arguments: &[Value]) -> Result<Value, String> {
+    match (name, arguments) {
+        (FunctionName::Quote, [value]) => Ok(Value::Text(quote(value))),
+        (FunctionName::Length, [value]) => Ok(length(value)),
+        (FunctionName::Trim | FunctionName::Ltrim | FunctionName::Rtrim, [value]) => {
+            Ok(trim(name, value, " "))
+        }
+        (FunctionName::Trim | FunctionName::Ltrim | FunctionName::Rtrim, [value, characters]) => {
+            Ok(trim(
+                name,
+                value,
+                &characters.cast_to_text().unwrap_or_default(),
+            ))
+        }
+        (FunctionName::Substr, [value, start]) => Ok(substr(value, start, None)),
+        (FunctionName::Substr, [value, start, length]) => Ok(substr(value, start, Some(length))),
+        (FunctionName::Upper | FunctionName::Lower, [value]) => Ok(change_case(name, value)),
+        (
+            FunctionName::Quote
+            | FunctionName::Length
+            | FunctionName::Trim
+            | FunctionName::Ltrim
+            | FunctionName::Rtrim
+            | FunctionName::Substr
+            | FunctionName::Upper
+            | FunctionName::Lower,
+            _,
+        ) => Err(format!("Wrong number of arguments to function {:?}", name)),
+        _ => Err(format!("Unsupported function: {:?}", name)),
+    }
+}
+
+// Number of characters of a text, or of bytes of a blob. Numbers are measured as text.
+fn length(value: &Value) -> Value {
+    match value {
+        Value::Blob(val) => Value::Integer(val.len() as i64),
+        _ => Value::Integer(value.cast_to_text().unwrap_or_default().chars().count() as i64),
+    }
+}
+
+// Removes any of the given characters from both ends of a text, or only its start for LTRIM
+// and its end for RTRIM. Without a set of characters, SQLite only removes spaces.
+fn trim(name: &FunctionName, value: &Value, characters: &str) -> Value {
+    let text = value.cast_to_text().unwrap_or_default();
+    let is_trimmed = |c: char| characters.contains(c);
+    let trimmed = match name {
+        FunctionName::Ltrim => text.trim_start_matches(is_trimmed),
+        FunctionName::Rtrim => text.trim_end_matches(is_trimmed),
+        _ => text.trim_matches(is_trimmed),
+    };
+    Value::Text(trimmed.to_string())
+}
+
+// Converts a text to upper or lower case with the Unicode case mappings, unlike SQLite which only
+// converts ASCII letters. A character may map to several, so the length can change: 'ß' becomes
+// 'SS'. Other values are converted to text first.
+fn change_case(name: &FunctionName, value: &Value) -> Value {
+    let text = value.cast_to_text().unwrap_or_default();
+    Value::Text(match name {
+        FunctionName::Upper => text.to_uppercase(),
+        _ => text.to_lowercase(),
+    })
+}
+
+// Part of a text, counted in characters, or of a blob, counted in bytes, following SQLite. The
+// first character is at 1, and a negative start counts from the end. A negative length takes the
+// characters before the start instead of after it. Without a length, the rest of the value is
+// returned. Parts outside of the value are left out, possibly leaving an empty result.
+fn substr(value: &Value, start: &Value, length: Option<&Value>) -> Value {
+    let start = start.cast_to_int().unwrap_or(0);
+    let length = length.map(|length| length.cast_to_int().unwrap_or(0));
+    match value {
+        Value::Blob(bytes) => Value::Blob(bytes[substr_range(bytes.len(), start, length)].to_vec()),
+        _ => {
+            let chars = value
+                .cast_to_text()
+                .unwrap_or_default()
+                .chars()
+                .collect::<Vec<char>>();
+            Value::Text(
+                chars[substr_range(chars.len(), start, length)]
+                    .iter()
+                    .collect(),
+            )
+        }
+    }
+}
+
+// Range of the part of a value of the given length selected by SUBSTR.
+fn substr_range(len: usize, start: i64, length: Option<i64>) -> std::ops::Range<usize> {
+    let len = len as i64;
+    let mut count = length.map_or(i64::MAX, |length| length.saturating_abs());
+    let mut first = start;
+    if first < 0 {
+        first = first.saturating_add(len);
+        if first < 0 {
+            count = count.saturating_add(first).max(0);
+            first = 0;
+        }
+    } else if first > 0 {
+        first -= 1;
+    } else if count > 0 {
+        // Position 0 is just before the first character, so it takes one character less.
+        count -= 1;
+    }
+    if length.is_some_and(|length| length < 0) {
+        first -= count;
+        if first < 0 {
+            count = count.saturating_add(first).max(0);
+            first = 0;
+        }
+    }
+    let first = first.min(len);
+    let end = first.saturating_add(count).min(len);
+    first as usize..end as usize
+}
+
+// Matches a text against a case sensitive GLOB pattern. `*` matches any sequence of characters,
+// `?` a single character, and `[...]` a character from a class, such as `[a-z]` or `[^J]`.
+pub fn glob_match(pattern: &str, text: &str) -> bool {
+    let pattern = pattern.chars().collect::<Vec<char>>();
+    let text = text.chars().collect::<Vec<char>>();
+    let (mut p, mut t) = (0, 0);
+    // Where to resume after the last `*` when the rest of the pattern fails to match.
+    let mut backtrack: Option<(usize, usize)> = None;
+    while t < text.len() {
+        let matched_len = match pattern.get(p) {
+            Some('*') => {
+                backtrack = Some((p + 1, t));
+                p += 1;
+                continue;
+            }
+            Some('?') => Some(1),
+            Some('[') => match_class(&pattern[p..], text[t]),
+            Some(c) => (*c == text[t]).then_some(1),
+            None => None,
+        };
+        match (matched_len, backtrack) {
+            (Some(len), _) => {
+                p += len;
+                t += 1;
+            }
+            (None, Some((star_p, star_t))) => {
+                backtrack = Some((star_p, star_t + 1));
+                p = star_p;
+                t = star_t + 1;
+            }
+            (None, None) => return false,
+        }
+    }
+    pattern[p..].iter().all(|c| *c == '*')
+}
+
+// Matches a text against a case sensitive LIKE pattern. `%` matches any sequence of characters and
+// `_` a single character, every other character only matches itself. The character following the
+// `escape` character, if any, is matched literally even when it is a wildcard.
+pub fn like_match(pattern: &str, text: &str, escape: Option<char>) -> bool {
+    let pattern = pattern.chars().collect::<Vec<char>>();
+    let text = text.chars().collect::<Vec<char>>();
+    let (mut p, mut t) = (0, 0);
+    // Where to resume after the last `%` when the rest of the pattern fails to match.
+    let mut backtrack: Option<(usize, usize)> = None;
+    while t < text.len() {
+        let escaped = escape.is_some() && pattern.get(p) == escape.as_ref();
+        let (c, len) = match escaped {
+            true => (pattern.get(p + 1), 2),
+            false => (pattern.get(p), 1),
+        };
+        match c {
+            Some('%') if !escaped => {
+                backtrack = Some((p + 1, t));
+                p += 1;
+            }
+            Some(c) if (*c == '_' && !escaped) || *c == text[t] => {
+                p += len;
+                t += 1;
+            }
+            _ => match backtrack {
+                Some((star_p, star_t)) => {
+                    backtrack = Some((star_p, star_t + 1));
+                    p = star_p;
+                    t = star_t + 1;
+                }
+                None => return false,
+            },
+        }
+    }
+    pattern[p..].iter().all(|c| *c == '%')
+}
+
+// Matches a character against the class starting the pattern, returning the length of the class
+// if it matches. A `]` right after the opening bracket is part of the class, and an unterminated
+// class never matches.
+fn match_class(class: &[char], c: char) -> Option<usize> {
+    let mut i = 1;
+    let negated = class.get(i) == Some(&'^');
+    if negated {
+        i += 1;
+    }
+    let first = i;
+    let mut matched = false;
+    loop {
+        let start = *class.get(i)?;
+        if start == ']' && i > first {
+            break;
+        }
+        match (class.get(i + 1), class.get(i + 2)) {
+            (Some('-'), Some(end)) if *end != ']' => {
+                matched |= start <= c && c <= *end;
+                i += 3;
+            }
+            _ => {
+                matched |= start == c;
+                i += 1;
+            }
+        }
+    }
+    (matched != negated).then_some(i + 1)
+}
+
+// Renders a value as an SQL literal that evaluates back to the same value.
+pub fn quote(value: &Value) -> String {
+    match value {
+        Value::Null => "NULL".to_string(),
+        Value::Integer(val) => val.to_string(),
+        // NaN can't be written as a literal, it is stored as NULL like SQLite does.
+        Value::Real(val) if val.is_nan() => "NULL".to_string(),
+        Value::Real(val) if val.is_infinite() => {
+            if *val > 0.0 {
+                "9.0e+999".to_string()
+            } else {
+                "-9.0e+999".to_string()
+            }
+        }
+        // The debug representation keeps a fractional part, so the literal is read back as a real.
+        Value::Real(val) => format!("{:?}", val),
+        Value::Text(val) => format!("'{}'", val.replace('\'', "''")),
+        Value::Blob(val) => format!("X'{}'", encode_hex(val).to_uppercase()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn quote_renders_every_value_as_a_literal() {
+        let cases = vec![
+            (Value::Null, "NULL"),
+            (Value::Integer(-42), "-42"),
+            (Value::Real(1000.0), "1000.0"),
+            (Value::Real(-2.5), "-2.5"),
+            (Value::Real(f64::NAN), "NULL"),
+            (Value::Real(f64::INFINITY), "9.0e+999"),
+            (Value::Text("John".to_string()), "'John'"),
+            (
+                Value::Text("it's 'quoted'".to_string()),
+                "'it''s ''quoted'''",
+            ),
+            (Value::Text("".to_string()), "''"),
+            (Value::Blob(vec![0x00, 0xab, 0x10]), "X'00AB10'"),
+            (Value::Blob(vec![]), "X''"),
+        ];
+        for (value, expected) in cases {
+            assert_eq!(quote(&value), expected);
+        }
+    }
+
+    #[test]
+    fn length_counts_characters_or_bytes() {
+        let cases = vec![
+            (Value::Text("héllo".to_string()), 5),
+            (Value::Blob(vec![0x00, 0xff]), 2),
+            (Value::Integer(-120), 4),
+            (Value::Real(2.5), 3),
+        ];
+        for (value, expected) in cases {
+            let result = evaluate_string_function(&FunctionName::Length, &[value]);
+            assert_eq!(result, Ok(Value::Integer(expected)));
+        }
+    }
+
+    #[test]
+    fn glob_matches_wildcards_and_character_classes() {
+        let cases = vec![
+            ("[A-C]*", "Bob", true),
+            ("[A-C]*", "Dave", false),
+            ("[^J]*", "Jim", false),
+            ("[^J]*", "Anna", true),
+            ("[^J]*", "", false),
+            ("*", "", true),
+            ("J?m", "Jim", true),
+            ("J?m", "Jm", false),
+            ("*a*b", "xaxxab", true),
+            ("*a*b", "xaxxa", false),
+            ("[]x]", "]", true),
+            ("[a-]", "-", true),
+            ("[ab", "a", false),
+            ("jim", "Jim", false),
+        ];
+        for (pattern, text, expected) in cases {
+            assert_eq!(
+                glob_match(pattern, text),
+                expected,
+                "{} GLOB {}",
+                text,
+                pattern
+            );
+        }
+    }
+
+    #[test]
+    fn like_matches_percent_and_underscore_wildcards() {
+        let cases = vec![
+            ("J%", "Jim", true),
+            ("J%", "jim", false),
+            ("%im", "Jim", true),
+            ("%i%", "Jim", true),
+            ("%i%", "Bob", false),
+            ("_im", "Jim", true),
+            ("_im", "Tim!", false),
+            ("%", "", true),
+            ("_", "", false),
+            ("%a%b", "xaxxab", true),
+            ("%a%b", "xaxxa", false),
+            ("a.c", "abc", false),
+            ("[a]*", "[a]*", true),
+        ];
+        for (pattern, text, expected) in cases {
+            assert_eq!(
+                like_match(pattern, text, None),
+                expected,
+                "{} LIKE {}",
+                text,
+                pattern
+            );
+        }
+    }
+
+    #[test]
+    fn like_matches_escaped_wildcards_literally() {
+        let cases = vec![
+            ("100\\%", "100%", true),
+            ("100\\%", "1000", false),
+            ("a\\_c", "a_c", true),
+            ("a\\_c", "abc", false),
+            ("%\\%%", "50% off", true),
+            ("%\\%%", "50 off", false),
+            ("a\\\\b", "a\\b", true),
+            ("a\\b", "ab", true),
+            ("ab\\", "ab", false),
+        ];
+        for (pattern, text, expected) in cases {
+            assert_eq!(
+                like_match(pattern, text, Some('\\')),
+                expected,
+                "{} LIKE {} ESCAPE '\\'",
+                text,
+                pattern
+            );
+        }
+    }
+
+    #[test]
+    fn quote_function_returns_text() {
+        let result = evaluate_string_function(&FunctionName::Quote, &[Value::Integer(1)]);
+        assert_eq!(result, Ok(Value::Text("1".to_string())));
+        assert!(evaluate_string_function(&FunctionName::Quote, &[]).is_err());
+    }
+
+    #[test]
+    fn trim_removes_characters_from_the_ends_of_a_text() {
+        let text = |value: &str| Value::Text(value.to_string());
+        let cases = vec![
+            (FunctionName::Trim, vec![text("xxhixx"), text("x")], "hi"),
+            (FunctionName::Ltrim, vec![text("xxhixx"), text("x")], "hixx"),
+            (FunctionName::Rtrim, vec![text("xxhixx"), text("x")], "xxhi"),
+            (
+                FunctionName::Trim,
+                vec![text("  hi there \t")],
+                "hi there \t",
+            ),
+            (FunctionName::Ltrim, vec![text("  hi  ")], "hi  "),
+            (FunctionName::Rtrim, vec![text("  hi  ")], "  hi"),
+            (FunctionName::Trim, vec![text("-+-hi+-"), text("+-")], "hi"),
+            (FunctionName::Trim, vec![text("hi"), text("")], "hi"),
+            (FunctionName::Trim, vec![text("xxx"), text("x")], ""),
+            (
+                FunctionName::Trim,
+                vec![Value::Integer(1200), Value::Integer(0)],
+                "12",
+            ),
+        ];
+        for (name, arguments, expected) in cases {
+            let result = evaluate_string_function(&name, &arguments);
+            assert_eq!(result, Ok(text(expected)), "{:?}({:?})", name, arguments);
+        }
+        assert!(evaluate_string_function(&FunctionName::Trim, &[]).is_err());
+    }
+
+    #[test]
+    fn substr_handles_negative_and_out_of_range_positions() {
+        let text = |value: &str| Value::Text(value.to_string());
+        let int = Value::Integer;
+        let cases = vec![
+            // A negative length takes the characters before `start`, not including it, so this
+            // is 'ab' as in SQLite rather than 'bc'.
+            (vec![text("abcdef"), int(3), int(-2)], "ab"),
+            (vec![text("abc"), int(10)], ""),
+            (vec![text("abcdef"), int(2), int(3)], "bcd"),
+            (vec![text("abcdef"), int(3)], "cdef"),
+            (vec![text("abcdef"), int(-2)], "ef"),
+            (vec![text("abcdef"), int(-3), int(2)], "de"),
+            (vec![text("abcdef"), int(-10), int(6)], "ab"),
+            (vec![text("abcdef"), int(0), int(2)], "a"),
+            (vec![text("abcdef"), int(0)], "abcdef"),
+            (vec![text("abcdef"), int(1), int(-3)], ""),
+            (vec![text("abcdef"), int(5), int(-10)], "abcd"),
+            (vec![text("abcdef"), int(4), int(100)], "def"),
+            (vec![text("abc"), int(2), int(0)], ""),
+            (vec![text("héllo"), int(2), int(2)], "él"),
+            (vec![int(12345), int(2), int(3)], "234"),
+        ];
+        for (arguments, expected) in cases {
+            let result = evaluate_string_function(&FunctionName::Substr, &arguments);
+            assert_eq!(result, Ok(text(expected)), "SUBSTR{:?}", arguments);
+        }
+        let result = evaluate_string_function(
+            &FunctionName::Substr,
+            &[Value::Blob(vec![1, 2, 3, 4]), int(-3), int(2)],
+        );
+        assert_eq!(result, Ok(Value::Blob(vec![2, 3])));
+        assert!(evaluate_string_function(&FunctionName::Substr, &[text("abc")]).is_err());
+    }
+
+    #[test]
+    fn upper_and_lower_use_unicode_case_mappings() {
+        let text = |value: &str| Value::Text(value.to_string());
+        let cases = vec![
+            (FunctionName::Upper, text("straße"), "STRASSE"),
+            (FunctionName::Upper, text("élan café"), "ÉLAN CAFÉ"),
+            (FunctionName::Lower, text("ÀÉÎÕÜ Ÿ"), "àéîõü ÿ"),
+            (FunctionName::Lower, text("ΣΟΦΟΣ"), "σοφος"),
+            (FunctionName::Upper, text("abc123"), "ABC123"),
+            (FunctionName::Lower, Value::Real(1e20), "1e20"),
+        ];
+        for (name, value, expected) in cases {
+            let result = evaluate_string_function(&name, &[value]);
+            assert_eq!(result, Ok(text(expected)), "{:?}", name);
+        }
+        assert!(evaluate_string_function(&FunctionName::Upper, &[]).is_err());
+    }
+}
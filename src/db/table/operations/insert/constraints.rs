@@ -0,0 +1,317 @@
+use crate::db::table::core::column::{ConflictAction, ConstraintType};
+use crate::db::table::core::index::Index;
+use crate::db::table::core::{row::Row, table::Table};
+use std::collections::HashSet;
+
+struct UniqueKey {
+    column_indices: Vec<usize>,
+    description: String,
+    conflict_action: ConflictAction,
+    // Positions of both the existing rows and the rows pending insertion,
+    // pending rows are numbered after the existing ones.
+    positions: Index,
+}
+
+struct NotNullColumn {
+    column_index: usize,
+    description: String,
+    conflict_action: ConflictAction,
+}
+
+// Inserts the rows while enforcing the column constraints of the table. Each violated constraint
+// is resolved with its declared `ON CONFLICT` action, defaulting to ABORT. FAIL and ROLLBACK are
+// treated like ABORT: the statement errors and none of its rows are inserted.
+// Returns the positions of the replaced and inserted rows.
+pub fn insert_rows(
+    table: &mut Table,
+    rows: Vec<Row>,
+    is_transaction: bool,
+) -> Result<Vec<usize>, String> {
+    let table_name = table.name()?.clone();
+    let mut unique_keys = vec![];
+    let mut not_null_columns = vec![];
+    for (column_index, column) in table.get_columns()?.iter().enumerate() {
+        for constraint in column.constraints.iter() {
+            let conflict_action = constraint
+                .conflict_action
+                .clone()
+                .unwrap_or(ConflictAction::Abort);
+            let description = format!("{}.{}", table_name, column.name);
+            match constraint.constraint_type {
+                // Deferred keys are checked by `check_changed_rows` when the transaction commits,
+                // see `commit::check_transaction_constraints`.
+                ConstraintType::PrimaryKey | ConstraintType::Unique
+                    if constraint.deferred && is_transaction => {}
+                ConstraintType::PrimaryKey | ConstraintType::Unique => {
+                    unique_keys.push(UniqueKey {
+                        column_indices: vec![column_index],
+                        description,
+                        conflict_action,
+                        positions: Index::new(String::new(), vec![]),
+                    });
+                }
+                ConstraintType::NotNull => {
+                    not_null_columns.push(NotNullColumn {
+                        column_index,
+                        description,
+                        conflict_action,
+                    });
+                }
+                // Checked against the parent table by `check_foreign_keys` before inserting.
+                ConstraintType::ForeignKey { .. }
+                | ConstraintType::AutoIncrement
+                | ConstraintType::Generated(_)
+                | ConstraintType::Default(_) => {}
+            }
+        }
+    }
+
+    for table_constraint in table.table_constraints.iter() {
+        unique_keys.push(UniqueKey {
+            column_indices: get_key_column_indices(table, &table_constraint.columns)?,
+            description: describe_key(&table_name, &table_constraint.columns),
+            conflict_action: table_constraint
+                .conflict_action
+                .clone()
+                .unwrap_or(ConflictAction::Abort),
+            positions: Index::new(String::new(), vec![]),
+        });
+    }
+
+    for index in table.indexes.iter().filter(|index| index.unique) {
+        unique_keys.push(UniqueKey {
+            column_indices: get_column_indices(table, index)?,
+            description: format!("index {}", index.name),
+            conflict_action: ConflictAction::Abort,
+            positions: Index::new(String::new(), vec![]),
+        });
+    }
+
+    if unique_keys.is_empty() && not_null_columns.is_empty() {
+        return Ok(push_rows(table, rows));
+    }
+
+    let existing_length = table.len();
+    for unique_key in unique_keys.iter_mut() {
+        for (position, row) in table.iter().enumerate() {
+            if let Some(key) = Index::key(row, &unique_key.column_indices) {
+                unique_key.positions.insert(key, position);
+            }
+        }
+    }
+
+    let mut pending_rows: Vec<Row> = vec![];
+    let mut replaced_rows: Vec<(usize, Row)> = vec![];
+    'rows: for row in rows {
+        for not_null_column in not_null_columns.iter() {
+            if row[not_null_column.column_index].is_null() {
+                match not_null_column.conflict_action {
+                    ConflictAction::Ignore => continue 'rows,
+                    _ => {
+                        return Err(format!(
+                            "NOT NULL constraint failed: {}",
+                            not_null_column.description
+                        ));
+                    }
+                }
+            }
+        }
+
+        let mut conflicting_positions: Vec<usize> = vec![];
+        for unique_key in unique_keys.iter() {
+            let Some(key) = Index::key(&row, &unique_key.column_indices) else {
+                continue;
+            };
+            let Some(position) = unique_key.positions.lookup(&key).first().cloned() else {
+                continue;
+            };
+            match unique_key.conflict_action {
+                ConflictAction::Ignore => continue 'rows,
+                ConflictAction::Replace => {
+                    if !conflicting_positions.contains(&position) {
+                        conflicting_positions.push(position);
+                    }
+                }
+                _ => {
+                    return Err(format!(
+                        "UNIQUE constraint failed: {}",
+                        unique_key.description
+                    ));
+                }
+            }
+        }
+
+        // The new row takes the place of the row it conflicts with.
+        let position = match conflicting_positions.as_slice() {
+            [] => existing_length + pending_rows.len(),
+            [position] => *position,
+            _ => {
+                return Err(
+                    "ON CONFLICT REPLACE of a row conflicting with several rows is not supported"
+                        .to_string(),
+                );
+            }
+        };
+        for unique_key in unique_keys.iter_mut() {
+            let previous_row = if position >= existing_length {
+                pending_rows.get(position - existing_length)
+            } else {
+                replaced_rows
+                    .iter()
+                    .rev()
+                    .find(|(replaced_position, _)| *replaced_position == position)
+                    .map(|(_, row)| row)
+                    .or_else(|| Some(&table[position]))
+            };
+            if let Some(previous_row) = previous_row
+                && let Some(key) = Index::key(previous_row, &unique_key.column_indices)
+            {
+                unique_key.positions.remove(&key, position);
+            }
+            if let Some(key) = Index::key(&row, &unique_key.column_indices) {
+                unique_key.positions.insert(key, position);
+            }
+        }
+        if position >= existing_length + pending_rows.len() {
+            pending_rows.push(row);
+        } else if position >= existing_length {
+            pending_rows[position - existing_length] = row;
+        } else {
+            replaced_rows.push((position, row));
+        }
+    }
+
+    let mut row_indicies = vec![];
+    for (position, row) in replaced_rows {
+        if is_transaction && !row_indicies.contains(&position) {
+            table.get_row_stacks_mut()[position].append_clone();
+        }
+        table[position] = row;
+        if !row_indicies.contains(&position) {
+            row_indicies.push(position);
+        }
+    }
+    if !row_indicies.is_empty() {
+        table.rebuild_indexes()?;
+    }
+    row_indicies.extend(push_rows(table, pending_rows));
+    Ok(row_indicies)
+}
+
+// Checks the constraints of the table on the rows at the given positions once they hold the given
+// values, before an UPDATE stores them or when a transaction commits. Only conflicts involving
+// these rows are reported, the other rows were checked when they were stored. Deferred keys are
+// skipped unless `check_deferred`, inside a transaction they wait for COMMIT.
+pub fn check_changed_rows(
+    table: &Table,
+    changed_rows: &[(usize, Row)],
+    check_deferred: bool,
+) -> Result<(), String> {
+    let table_name = table.name()?;
+    // The columns of each key, with the description used in its error.
+    let mut keys: Vec<(Vec<usize>, String)> = vec![];
+    for (column_index, column) in table.get_columns()?.iter().enumerate() {
+        for constraint in column.constraints.iter() {
+            let description = format!("{}.{}", table_name, column.name);
+            match constraint.constraint_type {
+                ConstraintType::PrimaryKey | ConstraintType::Unique
+                    if constraint.deferred && !check_deferred => {}
+                ConstraintType::PrimaryKey | ConstraintType::Unique => {
+                    keys.push((vec![column_index], description));
+                }
+                ConstraintType::NotNull => {
+                    if changed_rows
+                        .iter()
+                        .any(|(_, row)| row[column_index].is_null())
+                    {
+                        return Err(format!("NOT NULL constraint failed: {}", description));
+                    }
+                }
+                ConstraintType::ForeignKey { .. }
+                | ConstraintType::AutoIncrement
+                | ConstraintType::Generated(_)
+                | ConstraintType::Default(_) => {}
+            }
+        }
+    }
+    for table_constraint in table.table_constraints.iter() {
+        keys.push((
+            get_key_column_indices(table, &table_constraint.columns)?,
+            describe_key(table_name, &table_constraint.columns),
+        ));
+    }
+    for index in table.indexes.iter().filter(|index| index.unique) {
+        keys.push((
+            get_column_indices(table, index)?,
+            format!("index {}", index.name),
+        ));
+    }
+
+    let changed_positions = changed_rows
+        .iter()
+        .map(|(position, _)| *position)
+        .collect::<HashSet<usize>>();
+    for (column_indices, description) in keys {
+        let mut changed_keys = Index::new(String::new(), vec![]);
+        for (position, row) in changed_rows {
+            let Some(key) = Index::key(row, &column_indices) else {
+                continue;
+            };
+            let conflicting = !changed_keys.lookup(&key).is_empty()
+                || get_positions_with_key(table, &column_indices, &key)
+                    .iter()
+                    .any(|other| !changed_positions.contains(other));
+            if conflicting {
+                return Err(format!("UNIQUE constraint failed: {}", description));
+            }
+            changed_keys.insert(key, *position);
+        }
+    }
+    Ok(())
+}
+
+// Positions of the rows of the table with the given key, through an index on the same columns if
+// there is one.
+fn get_positions_with_key(table: &Table, column_indices: &[usize], key: &Row) -> Vec<usize> {
+    let index = table.indexes.iter().find(|index| {
+        get_column_indices(table, index).is_ok_and(|indices| indices == column_indices)
+    });
+    match index {
+        Some(index) => index.lookup(key),
+        None => table
+            .iter()
+            .enumerate()
+            .filter(|(_, row)| Index::key(row, column_indices).as_ref() == Some(key))
+            .map(|(position, _)| position)
+            .collect(),
+    }
+}
+
+fn get_column_indices(table: &Table, index: &Index) -> Result<Vec<usize>, String> {
+    get_key_column_indices(table, &index.columns)
+}
+
+fn get_key_column_indices(table: &Table, columns: &[String]) -> Result<Vec<usize>, String> {
+    columns
+        .iter()
+        .map(|column| table.get_index_of_column(column))
+        .collect()
+}
+
+// Names the columns of a key the way SQLite does in constraint errors, e.g. `t.a, t.b`.
+fn describe_key(table_name: &str, columns: &[String]) -> String {
+    columns
+        .iter()
+        .map(|column| format!("{}.{}", table_name, column))
+        .collect::<Vec<String>>()
+        .join(", ")
+}
+
+fn push_rows(table: &mut Table, rows: Vec<Row>) -> Vec<usize> {
+    let mut row_indicies: Vec<usize> = vec![];
+    for row in rows {
+        table.push(row);
+        row_indicies.push(table.len() - 1);
+    }
+    row_indicies
+}
@@ -0,0 +1,69 @@
+use crate::db::database::Database;
+use crate::db::table::core::column::ConstraintType;
+use crate::interpreter::ast::InsertIntoStatement;
+
+// Checks that every non-NULL value inserted into a foreign key column matches a row of the parent
+// table, before any row is inserted. Parent rows inserted by the same statement are not seen.
+pub fn check_foreign_keys(
+    database: &Database,
+    statement: &InsertIntoStatement,
+) -> Result<(), String> {
+    let table = database.get_table(&statement.table_name)?;
+    for (column_index, column) in table.get_columns()?.iter().enumerate() {
+        for constraint in column.constraints.iter() {
+            let ConstraintType::ForeignKey {
+                table_name: parent_name,
+                column: parent_column,
+            } = &constraint.constraint_type
+            else {
+                continue;
+            };
+            // Columns left out of the statement are NULL, which never violates a foreign key.
+            let value_index = match &statement.columns {
+                Some(columns) => match columns.iter().position(|name| *name == column.name) {
+                    Some(value_index) => value_index,
+                    None => continue,
+                },
+                None => column_index,
+            };
+
+            let parent = database.get_table(parent_name)?;
+            let parent_column = match parent_column {
+                Some(parent_column) => Some(parent_column.clone()),
+                None => parent
+                    .get_columns()?
+                    .iter()
+                    .find(|column| {
+                        column.constraints.iter().any(|constraint| {
+                            constraint.constraint_type == ConstraintType::PrimaryKey
+                        })
+                    })
+                    .map(|column| column.name.clone()),
+            };
+            let parent_index = match parent_column {
+                Some(parent_column) if parent.has_column(&parent_column)? => {
+                    parent.get_index_of_column(&parent_column)?
+                }
+                _ => {
+                    return Err(format!(
+                        "Foreign key mismatch: `{}` referencing `{}`",
+                        statement.table_name, parent_name
+                    ));
+                }
+            };
+
+            for values in statement.values.iter() {
+                let Some(value) = values.get(value_index) else {
+                    continue;
+                };
+                if !value.is_null() && !parent.iter().any(|row| row[parent_index] == *value) {
+                    return Err(format!(
+                        "FOREIGN KEY constraint failed: {}.{}",
+                        statement.table_name, column.name
+                    ));
+                }
+            }
+        }
+    }
+    Ok(())
+}
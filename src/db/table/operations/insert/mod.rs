@@ -4,8 +4,20 @@ use crate::db::table::core::{row::Row, table::Table, value::Value};
 use crate::db::table::operations::helpers::common::validate_and_clone_row;
 use crate::interpreter::ast::InsertIntoStatement;
 
-pub fn insert(table: &mut Table, statement: InsertIntoStatement) -> Result<Vec<usize>, String> {
+pub mod constraints;
+pub mod foreign_keys;
+
+pub fn insert(
+    table: &mut Table,
+    statement: InsertIntoStatement,
+    is_transaction: bool,
+) -> Result<Vec<usize>, String> {
     // Validate columns
+    let generated_columns = table
+        .get_columns()?
+        .iter()
+        .map(|column| column.generated_expression().is_some())
+        .collect::<Vec<bool>>();
     if let Some(columns) = &statement.columns {
         for column in columns {
             if table
@@ -16,6 +28,30 @@ pub fn insert(table: &mut Table, statement: InsertIntoStatement) -> Result<Vec<u
             {
                 return Err(format!("Column '{}' does not exist in table", column));
             }
+            if generated_columns[table.get_index_of_column(column)?] {
+                return Err(format!("Cannot INSERT into generated column `{}`", column));
+            }
+        }
+    }
+
+    // Every tuple must supply exactly one value per named column, or per stored table column
+    // when none are named. Checked before building any row so a bad tuple leaves the table
+    // untouched.
+    let expected_len = match &statement.columns {
+        Some(columns) => columns.len(),
+        None => generated_columns
+            .iter()
+            .filter(|generated| !**generated)
+            .count(),
+    };
+    for values in statement.values.iter() {
+        if values.len() != expected_len {
+            return Err(format!(
+                "{} values for {} column{}",
+                values.len(),
+                expected_len,
+                if expected_len == 1 { "" } else { "s" }
+            ));
         }
     }
 
@@ -40,26 +76,86 @@ pub fn insert(table: &mut Table, statement: InsertIntoStatement) -> Result<Vec<u
                     let value = queue.pop_front().unwrap();
                     row.push(value);
                 } else {
-                    row.push(Value::Null);
+                    // Columns left out of the statement take their default value, NULL for
+                    // generated columns and columns without a DEFAULT.
+                    row.push(table_column.default_value());
                 }
             }
             rows.push(row);
         }
     } else {
-        // Inserts entire row in the order provided in the statement
+        // Inserts entire row in the order provided in the statement, generated columns are
+        // left NULL.
         for row in statement.values {
+            let mut values = row.into_iter();
+            let row = generated_columns
+                .iter()
+                .map(|generated| match generated {
+                    true => Value::Null,
+                    false => values.next().unwrap_or(Value::Null),
+                })
+                .collect();
             let row_values = validate_and_clone_row(table, &Row(row))?;
             rows.push(row_values);
         }
     }
 
+    let sequence = assign_autoincrement_values(table, &mut rows)?;
+    assign_rowid_alias_values(table, &mut rows)?;
+
     // Insert rows
-    let mut row_indicies: Vec<usize> = vec![];
-    for row in rows {
-        table.push(row);
-        row_indicies.push(table.len() - 1);
+    let rows_inserted = constraints::insert_rows(table, rows, is_transaction)?;
+    table.autoincrement_sequence = sequence;
+    return Ok(rows_inserted);
+}
+
+// Gives the rows without a value for the AUTOINCREMENT column, if the table has one, the next
+// values above any the column held. Returns the largest value of the column once the rows are in.
+fn assign_autoincrement_values(table: &Table, rows: &mut [Row]) -> Result<i64, String> {
+    let Some(column_index) = table.autoincrement_column()? else {
+        return Ok(table.autoincrement_sequence);
+    };
+    let mut sequence = table.autoincrement_sequence;
+    for row in rows.iter_mut() {
+        match row[column_index] {
+            Value::Null => {
+                sequence = sequence
+                    .checked_add(1)
+                    .ok_or_else(|| "database or disk is full".to_string())?;
+                row[column_index] = Value::Integer(sequence);
+            }
+            Value::Integer(value) => sequence = sequence.max(value),
+            _ => {}
+        }
+    }
+    Ok(sequence)
+}
+
+// Gives the rows without a value for the INTEGER PRIMARY KEY column, if it is an alias of the
+// rowid, one more than the largest value of the column.
+fn assign_rowid_alias_values(table: &Table, rows: &mut [Row]) -> Result<(), String> {
+    let Some(column_index) = table.rowid_alias_column()? else {
+        return Ok(());
+    };
+    let mut largest = table
+        .get_rows()
+        .into_iter()
+        .chain(rows.iter())
+        .filter_map(|row| match row[column_index] {
+            Value::Integer(value) => Some(value),
+            _ => None,
+        })
+        .max()
+        .unwrap_or(0);
+    for row in rows.iter_mut() {
+        if row[column_index].is_null() {
+            largest = largest
+                .checked_add(1)
+                .ok_or_else(|| "database or disk is full".to_string())?;
+            row[column_index] = Value::Integer(largest);
+        }
     }
-    return Ok(row_indicies);
+    Ok(())
 }
 
 #[cfg(test)]
@@ -67,7 +163,7 @@ mod tests {
     use super::*;
     use crate::db::table::{
         core::{
-            column::ColumnDefinition,
+            column::{ColumnConstraint, ColumnDefinition, ConflictAction, ConstraintType},
             table::Table,
             value::{DataType, Value},
         },
@@ -114,8 +210,10 @@ mod tests {
                 Value::Integer(25),
                 Value::Real(1000.0),
             ]],
+            last_insert_rowids: vec![],
+            returning: None,
         };
-        assert!(insert(&mut table, statement).is_ok());
+        assert!(insert(&mut table, statement, false).is_ok());
         let expected = vec![Row(vec![
             Value::Integer(1),
             Value::Text("John".to_string()),
@@ -149,8 +247,10 @@ mod tests {
                 vec![Value::Integer(3), Value::Text("John".to_string())],
                 vec![Value::Integer(4), Value::Text("Jane".to_string())],
             ],
+            last_insert_rowids: vec![],
+            returning: None,
         };
-        let result = insert(&mut table, statement);
+        let result = insert(&mut table, statement, false);
         assert!(result.is_ok());
         let row_indicies = result.unwrap();
         assert_eq!(row_indicies, vec![2, 3]);
@@ -182,4 +282,142 @@ mod tests {
         ];
         assert_table_rows_eq(expected, table.get_rows_clone());
     }
+
+    #[test]
+    fn insert_with_wrong_number_of_values_errors_before_inserting() {
+        let mut table = default_table();
+        let statement = InsertIntoStatement {
+            table_name: "users".to_string(),
+            columns: Some(vec!["id".to_string()]),
+            values: vec![
+                vec![Value::Integer(1)],
+                vec![Value::Integer(2), Value::Text("Jane".to_string())],
+            ],
+            last_insert_rowids: vec![],
+            returning: None,
+        };
+        let result = insert(&mut table, statement, false);
+        assert_eq!(result, Err("2 values for 1 column".to_string()));
+        assert_eq!(table.len(), 0);
+
+        let statement = InsertIntoStatement {
+            table_name: "users".to_string(),
+            columns: Some(vec!["id".to_string(), "name".to_string()]),
+            values: vec![vec![Value::Integer(1)]],
+            last_insert_rowids: vec![],
+            returning: None,
+        };
+        let result = insert(&mut table, statement, false);
+        assert_eq!(result, Err("1 values for 2 columns".to_string()));
+
+        let statement = InsertIntoStatement {
+            table_name: "users".to_string(),
+            columns: None,
+            values: vec![vec![Value::Integer(1), Value::Text("John".to_string())]],
+            last_insert_rowids: vec![],
+            returning: None,
+        };
+        let result = insert(&mut table, statement, false);
+        assert_eq!(result, Err("2 values for 4 columns".to_string()));
+        assert_eq!(table.len(), 0);
+    }
+
+    fn constrained_table(conflict_action: Option<ConflictAction>) -> Table {
+        Table::new(
+            "users".to_string(),
+            vec![
+                ColumnDefinition {
+                    name: "id".to_string(),
+                    data_type: DataType::Integer,
+                    constraints: vec![ColumnConstraint {
+                        constraint_type: ConstraintType::NotNull,
+                        conflict_action: None,
+                        deferred: false,
+                    }],
+                },
+                ColumnDefinition {
+                    name: "email".to_string(),
+                    data_type: DataType::Text,
+                    constraints: vec![ColumnConstraint {
+                        constraint_type: ConstraintType::Unique,
+                        conflict_action: conflict_action,
+                        deferred: false,
+                    }],
+                },
+            ],
+        )
+    }
+
+    fn insert_emails(table: &mut Table, rows: Vec<(i64, &str)>) -> Result<Vec<usize>, String> {
+        let statement = InsertIntoStatement {
+            table_name: "users".to_string(),
+            columns: None,
+            values: rows
+                .into_iter()
+                .map(|(id, email)| vec![Value::Integer(id), Value::Text(email.to_string())])
+                .collect(),
+            last_insert_rowids: vec![],
+            returning: None,
+        };
+        insert(table, statement, false)
+    }
+
+    #[test]
+    fn insert_with_unique_conflict_aborts_whole_statement() {
+        let mut table = constrained_table(None);
+        assert!(insert_emails(&mut table, vec![(1, "a@b.com")]).is_ok());
+        let result = insert_emails(&mut table, vec![(2, "c@d.com"), (3, "a@b.com")]);
+        assert_eq!(
+            result,
+            Err("UNIQUE constraint failed: users.email".to_string())
+        );
+        assert_eq!(table.len(), 1);
+    }
+
+    #[test]
+    fn insert_with_unique_conflict_ignore_skips_duplicates() {
+        let mut table = constrained_table(Some(ConflictAction::Ignore));
+        let result = insert_emails(
+            &mut table,
+            vec![(1, "a@b.com"), (2, "a@b.com"), (3, "c@d.com")],
+        );
+        assert_eq!(result, Ok(vec![0, 1]));
+        let expected = vec![
+            Row(vec![Value::Integer(1), Value::Text("a@b.com".to_string())]),
+            Row(vec![Value::Integer(3), Value::Text("c@d.com".to_string())]),
+        ];
+        assert_table_rows_eq(expected, table.get_rows_clone());
+    }
+
+    #[test]
+    fn insert_with_unique_conflict_replace_overwrites_existing_row() {
+        let mut table = constrained_table(Some(ConflictAction::Replace));
+        assert!(insert_emails(&mut table, vec![(1, "a@b.com"), (2, "c@d.com")]).is_ok());
+        let result = insert_emails(&mut table, vec![(3, "a@b.com"), (4, "e@f.com")]);
+        assert_eq!(result, Ok(vec![0, 2]));
+        let expected = vec![
+            Row(vec![Value::Integer(3), Value::Text("a@b.com".to_string())]),
+            Row(vec![Value::Integer(2), Value::Text("c@d.com".to_string())]),
+            Row(vec![Value::Integer(4), Value::Text("e@f.com".to_string())]),
+        ];
+        assert_table_rows_eq(expected, table.get_rows_clone());
+    }
+
+    #[test]
+    fn insert_with_null_in_not_null_column_errors() {
+        let mut table = constrained_table(None);
+        let statement = InsertIntoStatement {
+            table_name: "users".to_string(),
+            columns: Some(vec!["email".to_string()]),
+            values: vec![vec![Value::Text("a@b.com".to_string())]],
+            last_insert_rowids: vec![],
+            returning: None,
+        };
+        let result = insert(&mut table, statement, false);
+        assert_eq!(
+            result,
+            Err("NOT NULL constraint failed: users.id".to_string())
+        );
+        assert_eq!(table.len(), 0);
+    }
 }
@@ -1,6 +1,8 @@
 pub mod alter_table;
+pub mod create_index;
 pub mod create_table;
 pub mod delete;
+pub mod drop_index;
 pub mod drop_table;
 pub mod helpers;
 pub mod insert;
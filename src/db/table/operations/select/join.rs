@@ -0,0 +1,113 @@
+use crate::db::database::Database;
+use crate::db::table::core::{column::ColumnDefinition, row::Row, table::Table, value::Value};
+use crate::db::table::operations::helpers::common::{get_column, is_true, read_row};
+use crate::interpreter::ast::{JoinType, SelectStatement, SelectableColumn};
+
+// Builds the table a SELECT with joins reads from. Its columns are those of every table of the
+// FROM clause, named `table.column` after the alias of the table if it has one, and its rows are
+// the combinations of their rows.
+pub fn join_tables(database: &Database, statement: &SelectStatement) -> Result<Table, String> {
+    let first = database.get_table_with_aliases(&statement.table_name, &statement.table_aliases)?;
+    // The alias of the first table is the one not given to a joined table.
+    let first_qualifier = statement
+        .table_aliases
+        .0
+        .iter()
+        .find(|(alias, name)| {
+            **name == statement.table_name
+                && !statement
+                    .joins
+                    .iter()
+                    .any(|join| join.table_alias == **alias)
+        })
+        .map_or(&statement.table_name, |(alias, _)| alias);
+
+    let mut columns = vec![];
+    push_qualified_columns(&mut columns, first_qualifier, first)?;
+    let mut rows = get_rows(first)?;
+    for join in statement.joins.iter() {
+        let table = database.get_table(&join.table_name)?;
+        let qualifier = if join.table_alias.is_empty() {
+            &join.table_name
+        } else {
+            &join.table_alias
+        };
+        push_qualified_columns(&mut columns, qualifier, table)?;
+        // The ON condition is evaluated against the columns joined so far.
+        let mut condition_table = Table::new(first.name()?.clone(), columns.clone());
+        condition_table.real_equality_epsilon = database.real_equality_epsilon;
+        let table_rows = get_rows(table)?;
+        let padding = match join.join_type {
+            JoinType::Cross | JoinType::Inner => None,
+            JoinType::Left => Some(table.get_columns()?.len()),
+        };
+        rows = get_matching_rows(
+            &condition_table,
+            &rows,
+            &table_rows,
+            &join.on_clause,
+            padding,
+        )?;
+    }
+
+    let mut joined = Table::new(first.name()?.clone(), columns);
+    joined.real_equality_epsilon = database.real_equality_epsilon;
+    joined.set_rows(rows);
+    Ok(joined)
+}
+
+// Every combination of a row of the left side with a row of the right side matching the condition.
+// With a padding width, a left row matching no right row is kept once, followed by that many NULLs.
+fn get_matching_rows(
+    condition_table: &Table,
+    left_rows: &[Row],
+    right_rows: &[Row],
+    on_clause: &Option<SelectableColumn>,
+    padding: Option<usize>,
+) -> Result<Vec<Row>, String> {
+    let mut rows = vec![];
+    for left in left_rows {
+        let mut matched = false;
+        for right in right_rows {
+            let row = Row(left.iter().chain(right.iter()).cloned().collect());
+            if let Some(condition) = on_clause
+                && !is_true(get_column(condition_table, &row, condition, None, None)?)?
+            {
+                continue;
+            }
+            matched = true;
+            rows.push(row);
+        }
+        if let Some(width) = padding
+            && !matched
+        {
+            let nulls = std::iter::repeat_n(Value::Null, width);
+            rows.push(Row(left.iter().cloned().chain(nulls).collect()));
+        }
+    }
+    Ok(rows)
+}
+
+// The rows of a table as they are read, generated columns are computed before the tables are
+// combined since the joined table doesn't know their expressions.
+fn get_rows(table: &Table) -> Result<Vec<Row>, String> {
+    table
+        .iter()
+        .map(|row| read_row(table, row).map(|row| row.into_owned()))
+        .collect()
+}
+
+fn push_qualified_columns(
+    columns: &mut Vec<ColumnDefinition>,
+    qualifier: &str,
+    table: &Table,
+) -> Result<(), String> {
+    for column in table.get_columns()? {
+        columns.push(ColumnDefinition {
+            name: format!("{}.{}", qualifier, column.name),
+            data_type: column.data_type.clone(),
+            constraints: vec![],
+        });
+    }
+    Ok(())
+}
@@ -1,12 +1,18 @@
+pub mod join;
 pub mod select_statement;
 pub mod set_operator_evaluator;
+pub mod subquery;
+use crate::db::table::core::value::Value;
 use crate::db::table::operations::helpers::order_by_clause::apply_order_by_from_precomputed;
+use crate::db::table::operations::helpers::qualified_columns::resolve_select_qualifiers;
+use crate::db::table::operations::helpers::query_planner::describe_plan;
 use crate::db::{
-    database::Database,
+    database::{Database, MASTER_TABLE_NAME},
     table::core::{row::Row, table::Table},
 };
 use crate::interpreter::ast::{
-    SelectStatementStack, SelectStatementStackElement, SelectableColumn, SetOperator,
+    SelectStatement, SelectStatementStack, SelectStatementStackElement, SelectableColumn,
+    SelectableStackElement, SetOperator,
 };
 
 pub fn select_statement_stack(
@@ -14,49 +20,54 @@ pub fn select_statement_stack(
     statement: SelectStatementStack,
 ) -> Result<Vec<Row>, String> {
     let mut evaluator = set_operator_evaluator::SetOperatorEvaluator::new();
-    let mut column_names: Option<Vec<String>> = None;
+    // The result columns of the first SELECT, which name the columns of the whole result.
+    let mut result_columns: Option<Vec<SelectableColumn>> = None;
 
-    // TODO: so ugly and also just false. Needed in some sort of way for now. See later TODO about dealing with 2+ tables
-    let mut first_table = None;
+    // A SELECT without a FROM clause is evaluated once, against a single row without columns.
+    let mut no_table = Table::new(String::new(), vec![]);
+    no_table.real_equality_epsilon = database.real_equality_epsilon;
+    no_table.push(Row(vec![]));
 
     for element in statement.elements {
         match element {
-            SelectStatementStackElement::SelectStatement(select_statement) => {
-                let table = database.get_table_with_aliases(
-                    &select_statement.table_name,
-                    &select_statement.table_aliases,
-                )?;
-                let expanded_column_names =
-                    expand_all_column_names(table, &select_statement.columns)?;
-                match &column_names {
-                    Some(column_names) => {
-                        if expanded_column_names.len() != column_names.len() {
-                            return Err(format!(
-                                "Columns mismatch between SELECT statements in Union"
-                            ));
-                        } else if expanded_column_names
-                            .iter()
-                            .zip(column_names)
-                            .filter(|&(a, b)| a != b)
-                            .count()
-                            != 0
-                        {
+            SelectStatementStackElement::SelectStatement(mut select_statement) => {
+                subquery::resolve_subqueries(database, &mut select_statement)?;
+                let joined;
+                let mut master;
+                let table = if select_statement.table_name.is_empty() {
+                    &no_table
+                } else if !select_statement.joins.is_empty() {
+                    joined = join::join_tables(database, &select_statement)?;
+                    &joined
+                } else if is_master_table(database, &select_statement) {
+                    master = database.master_table()?;
+                    master.real_equality_epsilon = database.real_equality_epsilon;
+                    &master
+                } else {
+                    database.get_table_with_aliases(
+                        &select_statement.table_name,
+                        &select_statement.table_aliases,
+                    )?
+                };
+                resolve_select_qualifiers(table, &mut select_statement)?;
+                let expanded_columns = expand_all_columns(&[table], &select_statement.columns)?;
+                // As in SQLite, only the number of columns has to match, the names of the
+                // first SELECT are kept.
+                match &result_columns {
+                    Some(result_columns) => {
+                        if expanded_columns.len() != result_columns.len() {
                             return Err(format!(
                                 "Columns mismatch between SELECT statements in Union"
                             ));
                         }
                     }
                     None => {
-                        column_names = Some(expanded_column_names);
+                        result_columns = Some(expanded_columns);
                     }
                 }
 
                 let rows = select_statement::select_statement(table, &select_statement)?;
                 evaluator.push(rows);
-
-                if first_table.is_none() {
-                    first_table = Some(table);
-                }
             }
             SelectStatementStackElement::SetOperator(set_operator) => match set_operator {
                 SetOperator::UnionAll => {
@@ -76,43 +87,24 @@ pub fn select_statement_stack(
     }
     let mut result = evaluator.result()?;
     if let Some(order_by_clause) = statement.order_by_clause {
-        if let Some(_) = first_table {
-            // TODO: this is just plain false when working with 2+ tables
-            // When using ORDER BY at the end of set operations on SELECTs, the ordering columns are guaranteed (?) to be present in the selected columns
-            // TODO: this ^ is not quite accurate
-            let mut result_indices = vec![];
-            for order_by_column in &order_by_clause.columns {
-                result_indices.push(
-                    column_names
-                        .as_ref()
-                        .ok_or_else(|| "No column names found".to_string())?
-                        .iter()
-                        .position(|column_name| column_name == &order_by_column.column_name)
-                        .ok_or_else(|| {
-                            "Ordering column name not found in selected columns".to_string()
-                        })?,
-                );
-            }
-
-            let precomputed = result
-                .iter()
-                .map(|row| {
-                    let mut order_columns = vec![];
-                    result_indices.iter().for_each(|i| {
-                        order_columns.push(row[*i].clone());
-                    });
-                    Row(order_columns)
-                })
-                .collect::<Vec<Row>>();
-            apply_order_by_from_precomputed(
-                &mut result,
-                precomputed,
-                Row(vec![]),
-                &order_by_clause,
-            );
-        } else {
-            unreachable!();
+        let result_columns =
+            result_columns.ok_or_else(|| "ORDER BY without a SELECT statement".to_string())?;
+        let mut result_indices = vec![];
+        for order_by_column in &order_by_clause.columns {
+            result_indices.push(get_result_column_index(&result_columns, order_by_column)?);
         }
+
+        let precomputed = result
+            .iter()
+            .map(|row| {
+                let mut order_columns = vec![];
+                result_indices.iter().for_each(|i| {
+                    order_columns.push(row[*i].clone());
+                });
+                Row(order_columns)
+            })
+            .collect::<Vec<Row>>();
+        apply_order_by_from_precomputed(&mut result, precomputed, Row(vec![]), &order_by_clause)?;
     }
 
     // TODO: if LIMIT without ORDER BY, apply LIMIT at the beginning / after the WHERE
@@ -122,36 +114,118 @@ pub fn select_statement_stack(
         if offset >= result.len() {
             result = vec![];
         } else {
-            let end = if limit_clause.limit + offset > result.len() {
-                result.len()
-            } else {
-                limit_clause.limit + offset
-            };
+            let end = limit_clause
+                .limit
+                .checked_add(offset)
+                .map_or(result.len(), |end| end.min(result.len()));
             result = result[offset..end].to_vec();
         }
     }
     Ok(result)
 }
 
+// Describes how each SELECT of the statement finds its rows, one line per SELECT in the order
+// they are written.
+pub fn explain_query_plan(
+    database: &Database,
+    statement: &SelectStatementStack,
+) -> Result<Vec<Row>, String> {
+    let mut lines = vec![];
+    for element in statement.elements.iter() {
+        let SelectStatementStackElement::SelectStatement(select_statement) = element else {
+            continue;
+        };
+        let line = if select_statement.table_name.is_empty() {
+            "SCAN CONSTANT ROW".to_string()
+        } else {
+            let table = database.get_table_with_aliases(
+                &select_statement.table_name,
+                &select_statement.table_aliases,
+            )?;
+            let plan = select_statement::get_scan_plan(table, select_statement);
+            describe_plan(&select_statement.table_name, table, &plan)
+        };
+        lines.push(Row(vec![Value::Text(line)]));
+        for join in select_statement.joins.iter() {
+            lines.push(Row(vec![Value::Text(format!("SCAN {}", join.table_name))]));
+        }
+    }
+    Ok(lines)
+}
+
+// An ORDER BY term after set operations refers to a result column either by its name, which is
+// the alias when one was given, or by repeating the expression of the column.
+fn get_result_column_index(
+    result_columns: &[SelectableColumn],
+    order_by_column: &SelectableColumn,
+) -> Result<usize, String> {
+    result_columns
+        .iter()
+        .position(|column| column.column_name == order_by_column.column_name)
+        .or_else(|| {
+            result_columns
+                .iter()
+                .position(|column| column.selectables == order_by_column.selectables)
+        })
+        .ok_or_else(|| {
+            format!(
+                "ORDER BY term `{}` does not match any column in the result set",
+                order_by_column.column_name
+            )
+        })
+}
+
+// Whether the statement reads the table describing the schema, which is not stored with the others.
+fn is_master_table(database: &Database, statement: &SelectStatement) -> bool {
+    statement.table_name.eq_ignore_ascii_case(MASTER_TABLE_NAME)
+        && !database.has_table(&statement.table_name)
+}
+
 // TODO: add this logic in evaluation too
-fn expand_all_column_names(
-    table: &Table,
+// Expands `*` into the columns of every table in the FROM clause, in declaration order. Column
+// names shared by several tables are qualified with their table name. The columns of a joined
+// table are already qualified, see `join::join_tables`.
+fn expand_all_columns(
+    tables: &[&Table],
     columns: &Vec<SelectableColumn>,
-) -> Result<Vec<String>, String> {
+) -> Result<Vec<SelectableColumn>, String> {
+    let mut all_table_columns = vec![];
+    for table in tables {
+        for name in table.get_column_names()? {
+            let (table_name, column) = match name.split_once('.') {
+                Some((qualifier, column)) => (qualifier, column),
+                None => (table.name()?.as_str(), name.as_str()),
+            };
+            all_table_columns.push((table_name, column, name));
+        }
+    }
+
     let mut new = vec![];
     for column in columns {
-        if *column.column_name == "*".to_string() {
-            for name in table.get_column_names()? {
-                if !new.contains(name) {
-                    new.push(name.clone());
-                }
+        if column.column_name == "*" {
+            for (table_name, name, full_name) in all_table_columns.iter() {
+                let is_ambiguous = all_table_columns
+                    .iter()
+                    .filter(|(_, other, _)| other == name)
+                    .count()
+                    > 1;
+                let column_name = if is_ambiguous {
+                    format!("{}.{}", table_name, name)
+                } else {
+                    name.to_string()
+                };
+                new.push(SelectableColumn {
+                    selectables: vec![SelectableStackElement::Column(full_name.to_string())],
+                    column_name,
+                });
             }
         } else {
-            new.push(column.column_name.clone());
+            new.push(column.clone());
         }
     }
     Ok(new)
 }
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -171,12 +245,14 @@ mod tests {
                 SelectStatement {
                     table_name: "users".to_string(),
                     table_aliases: TableAliases(HashMap::new()),
+                    joins: vec![],
                     mode: SelectMode::All,
                     columns: vec![SelectableColumn {
                         selectables: vec![SelectableStackElement::All],
                         column_name: "*".to_string(),
                     }],
                     where_clause: None,
+                    group_by_clause: None,
                     order_by_clause: None,
                     limit_clause: None,
                 },
@@ -223,6 +299,7 @@ mod tests {
                 SelectStatementStackElement::SelectStatement(SelectStatement {
                     table_name: "users".to_string(),
                     table_aliases: TableAliases(HashMap::new()),
+                    joins: vec![],
                     mode: SelectMode::All,
                     columns: vec![SelectableColumn {
                         selectables: vec![SelectableStackElement::All],
@@ -236,18 +313,21 @@ mod tests {
                         ],
                         column_name: "id = 1".to_string(),
                     }),
+                    group_by_clause: None,
                     order_by_clause: None,
                     limit_clause: None,
                 }),
                 SelectStatementStackElement::SelectStatement(SelectStatement {
                     table_name: "users".to_string(),
                     table_aliases: TableAliases(HashMap::new()),
+                    joins: vec![],
                     mode: SelectMode::All,
                     columns: vec![SelectableColumn {
                         selectables: vec![SelectableStackElement::All],
                         column_name: "*".to_string(),
                     }],
                     where_clause: None,
+                    group_by_clause: None,
                     order_by_clause: None,
                     limit_clause: None,
                 }),
@@ -275,18 +355,21 @@ mod tests {
                 SelectStatementStackElement::SelectStatement(SelectStatement {
                     table_name: "users".to_string(),
                     table_aliases: TableAliases(HashMap::new()),
+                    joins: vec![],
                     mode: SelectMode::All,
                     columns: vec![SelectableColumn {
                         selectables: vec![SelectableStackElement::All],
                         column_name: "*".to_string(),
                     }],
                     where_clause: None,
+                    group_by_clause: None,
                     order_by_clause: None,
                     limit_clause: None,
                 }),
                 SelectStatementStackElement::SelectStatement(SelectStatement {
                     table_name: "users".to_string(),
                     table_aliases: TableAliases(HashMap::new()),
+                    joins: vec![],
                     mode: SelectMode::All,
                     columns: vec![SelectableColumn {
                         selectables: vec![SelectableStackElement::All],
@@ -304,6 +387,7 @@ mod tests {
                         ],
                         column_name: "id = 1 OR id = 2".to_string(),
                     }),
+                    group_by_clause: None,
                     order_by_clause: None,
                     limit_clause: None,
                 }),
@@ -311,6 +395,7 @@ mod tests {
                 SelectStatementStackElement::SelectStatement(SelectStatement {
                     table_name: "users".to_string(),
                     table_aliases: TableAliases(HashMap::new()),
+                    joins: vec![],
                     mode: SelectMode::All,
                     columns: vec![SelectableColumn {
                         selectables: vec![SelectableStackElement::All],
@@ -324,6 +409,7 @@ mod tests {
                         ],
                         column_name: "id = 1".to_string(),
                     }),
+                    group_by_clause: None,
                     order_by_clause: None,
                     limit_clause: None,
                 }),
@@ -342,4 +428,78 @@ mod tests {
         ])];
         assert_table_rows_eq(expected, result.unwrap());
     }
+
+    #[test]
+    fn expand_all_columns_lists_every_table_and_qualifies_collisions() {
+        use crate::db::table::core::column::ColumnDefinition;
+        use crate::db::table::core::value::DataType;
+        use crate::db::table::test_utils::default_table;
+
+        let users = default_table();
+        let orders = Table::new(
+            "orders".to_string(),
+            vec![
+                ColumnDefinition {
+                    name: "id".to_string(),
+                    data_type: DataType::Integer,
+                    constraints: vec![],
+                },
+                ColumnDefinition {
+                    name: "user_id".to_string(),
+                    data_type: DataType::Integer,
+                    constraints: vec![],
+                },
+                ColumnDefinition {
+                    name: "money".to_string(),
+                    data_type: DataType::Real,
+                    constraints: vec![],
+                },
+            ],
+        );
+        let columns = vec![
+            SelectableColumn {
+                selectables: vec![SelectableStackElement::Column("name".to_string())],
+                column_name: "name".to_string(),
+            },
+            SelectableColumn {
+                selectables: vec![SelectableStackElement::All],
+                column_name: "*".to_string(),
+            },
+        ];
+        let column_names = |tables: &[&Table]| {
+            expand_all_columns(tables, &columns).map(|columns| {
+                columns
+                    .into_iter()
+                    .map(|column| column.column_name)
+                    .collect::<Vec<String>>()
+            })
+        };
+        let result = column_names(&[&users, &orders]);
+        assert_eq!(
+            result,
+            Ok(vec![
+                "name".to_string(),
+                "users.id".to_string(),
+                "name".to_string(),
+                "age".to_string(),
+                "users.money".to_string(),
+                "orders.id".to_string(),
+                "user_id".to_string(),
+                "orders.money".to_string(),
+            ])
+        );
+
+        // A single table never needs qualified names.
+        let result = column_names(&[&users]);
+        assert_eq!(
+            result,
+            Ok(vec![
+                "name".to_string(),
+                "id".to_string(),
+                "name".to_string(),
+                "age".to_string(),
+                "money".to_string(),
+            ])
+        );
+    }
 }
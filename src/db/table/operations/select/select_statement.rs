@@ -1,15 +1,52 @@
-use crate::db::table::core::{row::Row, table::Table, value::Value};
-use crate::db::table::operations::helpers::common::{get_column, get_columns};
+use crate::db::table::core::{
+    row::{DistinctRow, Row},
+    table::Table,
+    value::Value,
+};
+use crate::db::table::operations::helpers::aggregate_functions::{
+    get_aggregate_columns, has_aggregate,
+};
+#[cfg(test)]
+use crate::db::table::operations::helpers::common::ROWS_SCANNED;
+use crate::db::table::operations::helpers::common::{
+    evaluate_column, evaluate_columns, get_columns, is_true, read_row,
+};
 use crate::db::table::operations::helpers::order_by_clause::apply_order_by_from_precomputed;
-use crate::interpreter::ast::{SelectMode, SelectStatement};
-use std::collections::{HashMap, HashSet};
+use crate::db::table::operations::helpers::query_planner::{
+    ScanPlan, get_candidate_positions, plan_where,
+};
+use crate::interpreter::ast::{
+    FunctionName, GroupByClause, OrderByClause, OrderByDirection, SelectMode, SelectStatement,
+    SelectableColumn, SelectableStackElement,
+};
+use std::borrow::Cow;
+use std::collections::{HashMap, HashSet, hash_map::Entry};
 
 pub fn select_statement(table: &Table, statement: &SelectStatement) -> Result<Vec<Row>, String> {
-    let mut rows = vec![];
     let (limit, offset) = statement.limit_clause.as_ref().map_or((-1, 0), |stmt| {
-        (stmt.limit as i64, stmt.offset.map_or(0, |val| val))
+        (
+            i64::try_from(stmt.limit).unwrap_or(i64::MAX),
+            stmt.offset.map_or(0, |val| val),
+        )
     });
 
+    // The columns calling row_number() are evaluated with the number the row would get if kept,
+    // and evaluated again in output order if ORDER BY moves the rows around.
+    let numbered = statement.columns.iter().any(has_row_number);
+    check_row_number_use(statement, numbered)?;
+
+    if let Some(group_by_clause) = &statement.group_by_clause {
+        let rows = select_grouped(table, statement, group_by_clause)?;
+        return Ok(apply_limit(rows, limit, offset));
+    }
+
+    if statement.columns.iter().any(has_aggregate) {
+        let rows = vec![select_aggregate(table, statement)?];
+        return Ok(apply_limit(rows, limit, offset));
+    }
+
+    let mut rows = vec![];
+
     let mut order_by_columns_precomputed = vec![];
 
     let mut distinct_map = match statement.mode {
@@ -17,76 +54,353 @@ pub fn select_statement(table: &Table, statement: &SelectStatement) -> Result<Ve
         SelectMode::Distinct => Some(HashSet::new()),
     };
 
-    let alias_to_computed_index = statement
-        .columns
-        .iter()
-        .enumerate()
-        .map(|(i, column)| (column.column_name.clone(), i))
-        .collect::<HashMap<String, usize>>();
-
-    for row in table.iter() {
-        let columns = get_columns(table, row, &statement.columns, None, None)?;
-        if let Some(stmt) = &statement.where_clause {
-            if let Value::Integer(val) = get_column(
+    let alias_to_computed_index = get_alias_to_computed_index(statement);
+    let mut positions = vec![];
+
+    // Without ORDER BY the rows are returned in scan order, so the scan can stop as soon as the
+    // rows covered by LIMIT and OFFSET are collected. With DISTINCT only distinct rows count.
+    let rows_needed = match &statement.order_by_clause {
+        None if limit >= 0 => Some(offset.saturating_add(limit as usize)),
+        _ => None,
+    };
+
+    for position in get_candidate_positions(table, &get_scan_plan(table, statement)) {
+        if rows_needed.is_some_and(|rows_needed| rows.len() >= rows_needed) {
+            break;
+        }
+        #[cfg(test)]
+        ROWS_SCANNED.with(|count| count.set(count.get() + 1));
+        let row = read_row(table, &table[position])?;
+        let columns = if numbered {
+            let number = (rows.len() + 1).saturating_sub(offset);
+            let numbered_columns = number_columns(&statement.columns, number);
+            evaluate_columns(table, &row, &numbered_columns, None, None)?
+        } else {
+            evaluate_columns(table, &row, &statement.columns, None, None)?
+        };
+        if let Some(stmt) = &statement.where_clause
+            && !is_true(evaluate_column(
                 table,
-                row,
+                &row,
                 stmt,
                 Some(&columns),
                 Some(&alias_to_computed_index),
-            )? {
-                if val == 0 {
-                    continue;
-                }
-            } else {
-                return Err("WHERE condition did not return a boolean".to_string());
-            }
+            )?)?
+        {
+            continue;
         }
 
         if let Some(map) = &mut distinct_map {
-            if map.insert(columns.clone()) {
+            if map.insert(DistinctRow(columns.clone())) {
                 if let Some(stmt) = &statement.order_by_clause {
-                    order_by_columns_precomputed.push(get_columns(
+                    order_by_columns_precomputed.push(evaluate_columns(
                         table,
-                        row,
+                        &row,
                         &stmt.columns,
                         Some(&columns),
                         Some(&alias_to_computed_index),
                     )?);
                 }
                 rows.push(columns);
+                positions.push(position);
             }
         } else {
             if let Some(stmt) = &statement.order_by_clause {
-                order_by_columns_precomputed.push(get_columns(
+                order_by_columns_precomputed.push(evaluate_columns(
                     table,
-                    row,
+                    &row,
                     &stmt.columns,
                     Some(&columns),
                     Some(&alias_to_computed_index),
                 )?);
             }
             rows.push(columns);
+            positions.push(position);
+        }
+    }
+
+    if let Some(stmt) = &statement.order_by_clause {
+        if numbered {
+            apply_order_by_from_precomputed(&mut positions, order_by_columns_precomputed, 0, stmt)?;
+            rows = positions
+                .iter()
+                .enumerate()
+                .map(|(i, position)| {
+                    let number = (i + 1).saturating_sub(offset);
+                    let numbered_columns = number_columns(&statement.columns, number);
+                    get_columns(table, &table[*position], &numbered_columns, None, None)
+                })
+                .collect::<Result<_, _>>()?;
+        } else {
+            apply_order_by_from_precomputed(
+                &mut rows,
+                order_by_columns_precomputed,
+                Row(vec![]),
+                stmt,
+            )?;
+        }
+    }
+
+    Ok(apply_limit(rows, limit, offset))
+}
+
+fn has_row_number(column: &SelectableColumn) -> bool {
+    column
+        .selectables
+        .iter()
+        .any(|selectable| match selectable {
+            SelectableStackElement::Function(func) => {
+                func.name == FunctionName::RowNumber || func.arguments.iter().any(has_row_number)
+            }
+            SelectableStackElement::Case(case) => {
+                case.branches
+                    .iter()
+                    .any(|(condition, result)| has_row_number(condition) || has_row_number(result))
+                    || case.else_result.as_ref().is_some_and(has_row_number)
+            }
+            _ => false,
+        })
+}
+
+// The rows are numbered once their order and the rows skipped by OFFSET are known, so the numbers
+// can't order them. Neither are groups numbered.
+fn check_row_number_use(statement: &SelectStatement, numbered: bool) -> Result<(), String> {
+    if numbered
+        && (statement.group_by_clause.is_some() || statement.columns.iter().any(has_aggregate))
+    {
+        return Err("row_number() can't be used with GROUP BY or aggregate functions".to_string());
+    }
+    let numbered_names = statement
+        .columns
+        .iter()
+        .filter(|column| has_row_number(column))
+        .map(|column| &column.column_name)
+        .collect::<Vec<&String>>();
+    let orders_by_number = |column: &SelectableColumn| {
+        has_row_number(column)
+            || column.selectables.iter().any(|selectable| {
+                matches!(selectable, SelectableStackElement::Column(name) if numbered_names.contains(&name))
+            })
+    };
+    if let Some(order_by_clause) = &statement.order_by_clause
+        && order_by_clause.columns.iter().any(orders_by_number)
+    {
+        return Err(
+            "ORDER BY can't refer to row_number(), which numbers the rows in output order"
+                .to_string(),
+        );
+    }
+    Ok(())
+}
+
+// Returns the columns with every call to row_number() replaced by the number of the row.
+fn number_columns(columns: &[SelectableColumn], number: usize) -> Vec<SelectableColumn> {
+    let mut columns = columns.to_vec();
+    for column in columns.iter_mut() {
+        number_column(column, number);
+    }
+    columns
+}
+
+fn number_column(column: &mut SelectableColumn, number: usize) {
+    for selectable in column.selectables.iter_mut() {
+        match selectable {
+            SelectableStackElement::Function(func) if func.name == FunctionName::RowNumber => {
+                *selectable = SelectableStackElement::Value(Value::Integer(number as i64));
+            }
+            SelectableStackElement::Function(func) => {
+                for argument in func.arguments.iter_mut() {
+                    number_column(argument, number);
+                }
+            }
+            SelectableStackElement::Case(case) => {
+                for (condition, result) in case.branches.iter_mut() {
+                    number_column(condition, number);
+                    number_column(result, number);
+                }
+                if let Some(else_result) = &mut case.else_result {
+                    number_column(else_result, number);
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+// When several columns share an alias, references to it resolve to the first of them,
+// as in SQLite.
+fn get_alias_to_computed_index(statement: &SelectStatement) -> HashMap<String, usize> {
+    let mut alias_to_computed_index = HashMap::new();
+    for (i, column) in statement.columns.iter().enumerate() {
+        alias_to_computed_index
+            .entry(column.column_name.clone())
+            .or_insert(i);
+    }
+    alias_to_computed_index
+}
+
+// Partitions the rows matching the WHERE clause by the values of the GROUP BY terms and produces
+// one row per group, with aggregates computed over the group. Without ORDER BY the groups are
+// returned in the order of their keys, as SQLite does. An empty table has no groups.
+fn select_grouped(
+    table: &Table,
+    statement: &SelectStatement,
+    group_by_clause: &GroupByClause,
+) -> Result<Vec<Row>, String> {
+    let rows = read_matching_rows(table, statement)?;
+    let mut keys = vec![];
+    let mut groups: Vec<Vec<&Row>> = vec![];
+    let mut group_indexes: HashMap<DistinctRow, usize> = HashMap::new();
+    for row in rows.iter().map(|row| row.as_ref()) {
+        let key = evaluate_columns(table, row, &group_by_clause.columns, None, None)?;
+        match group_indexes.entry(DistinctRow(key.clone())) {
+            Entry::Occupied(entry) => groups[*entry.get()].push(row),
+            Entry::Vacant(entry) => {
+                entry.insert(groups.len());
+                keys.push(key);
+                groups.push(vec![row]);
+            }
         }
     }
+    let by_key = OrderByClause {
+        columns: group_by_clause.columns.clone(),
+        directions: vec![OrderByDirection::Asc; group_by_clause.columns.len()],
+    };
+    apply_order_by_from_precomputed(&mut groups, keys, vec![], &by_key)?;
 
+    let alias_to_computed_index = get_alias_to_computed_index(statement);
+    let mut distinct_rows = HashSet::new();
+    let mut rows = vec![];
+    let mut order_by_columns_precomputed = vec![];
+    for group in groups.iter() {
+        let columns = get_aggregate_columns(table, group, &statement.columns, None, None)?;
+        if statement.mode == SelectMode::Distinct
+            && !distinct_rows.insert(DistinctRow(columns.clone()))
+        {
+            continue;
+        }
+        if let Some(stmt) = &statement.order_by_clause {
+            order_by_columns_precomputed.push(get_aggregate_columns(
+                table,
+                group,
+                &stmt.columns,
+                Some(&columns),
+                Some(&alias_to_computed_index),
+            )?);
+        }
+        rows.push(columns);
+    }
     if let Some(stmt) = &statement.order_by_clause {
-        apply_order_by_from_precomputed(&mut rows, order_by_columns_precomputed, Row(vec![]), stmt);
+        apply_order_by_from_precomputed(
+            &mut rows,
+            order_by_columns_precomputed,
+            Row(vec![]),
+            stmt,
+        )?;
+    }
+    Ok(rows)
+}
+
+// Without GROUP BY, every row matching the WHERE clause belongs to a single group.
+fn select_aggregate(table: &Table, statement: &SelectStatement) -> Result<Row, String> {
+    if let Some(row) = select_min_max_from_index(table, statement)? {
+        return Ok(row);
+    }
+    let rows = read_matching_rows(table, statement)?;
+    let group = rows.iter().map(|row| row.as_ref()).collect::<Vec<&Row>>();
+    get_aggregate_columns(table, &group, &statement.columns, None, None)
+}
+
+// The rows matching the WHERE clause, as they are read.
+fn read_matching_rows<'a>(
+    table: &'a Table,
+    statement: &SelectStatement,
+) -> Result<Vec<Cow<'a, Row>>, String> {
+    let mut rows = vec![];
+    for position in get_candidate_positions(table, &get_scan_plan(table, statement)) {
+        #[cfg(test)]
+        ROWS_SCANNED.with(|count| count.set(count.get() + 1));
+        let row = read_row(table, &table[position])?;
+        if let Some(stmt) = &statement.where_clause
+            && !is_true(evaluate_column(table, &row, stmt, None, None)?)?
+        {
+            continue;
+        }
+        rows.push(row);
     }
+    Ok(rows)
+}
 
+// A lone MIN(column) or MAX(column) over the whole table is read from the first or last key of
+// an index on that column. Rows with a NULL column are not indexed, just as MIN and MAX skip them.
+// Returns None when the query can't be answered this way.
+fn select_min_max_from_index(
+    table: &Table,
+    statement: &SelectStatement,
+) -> Result<Option<Row>, String> {
+    if statement.where_clause.is_some() {
+        return Ok(None);
+    }
+    let [column] = statement.columns.as_slice() else {
+        return Ok(None);
+    };
+    let [SelectableStackElement::Function(func)] = column.selectables.as_slice() else {
+        return Ok(None);
+    };
+    let [argument] = func.arguments.as_slice() else {
+        return Ok(None);
+    };
+    let [SelectableStackElement::Column(column_name)] = argument.selectables.as_slice() else {
+        return Ok(None);
+    };
+    // Only single column indexes hold every row where the column is not NULL.
+    let Some(index) = table
+        .indexes
+        .iter()
+        .find(|index| index.columns == [column_name.clone()])
+    else {
+        return Ok(None);
+    };
+    let positions = match func.name {
+        FunctionName::Min => index.min_positions(),
+        FunctionName::Max => index.max_positions(),
+        _ => return Ok(None),
+    };
+    let column_index = table.get_index_of_column(column_name)?;
+    let value = positions
+        .and_then(|positions| positions.iter().min())
+        .map_or(Value::Null, |position| {
+            table[*position][column_index].clone()
+        });
+    Ok(Some(Row(vec![value])))
+}
+
+pub fn get_scan_plan(table: &Table, statement: &SelectStatement) -> ScanPlan {
+    // The WHERE clause may refer to a selected column aliased with the name of a table column,
+    // in which case it does not match the column the index was built on.
+    let shadows_column = statement.columns.iter().any(|column| {
+        column.selectables != [SelectableStackElement::Column(column.column_name.clone())]
+            && table.has_column(&column.column_name).unwrap_or(true)
+    });
+    if shadows_column {
+        return ScanPlan::FullScan;
+    }
+    plan_where(table, &statement.where_clause)
+}
+
+fn apply_limit(mut rows: Vec<Row>, limit: i64, offset: usize) -> Vec<Row> {
     if limit != -1 || offset != 0 {
         if offset >= rows.len() {
             rows = vec![];
         } else {
-            let end = if (limit == -1) || (offset + limit as usize > rows.len()) {
-                rows.len()
-            } else {
-                offset + limit as usize
+            let end = match offset.checked_add(limit as usize) {
+                Some(end) if limit != -1 => end.min(rows.len()),
+                _ => rows.len(),
             };
             rows = rows[offset..end].to_vec();
         }
     }
-
-    Ok(rows)
+    rows
 }
 
 #[cfg(test)]
@@ -111,12 +425,14 @@ mod tests {
         let statement = SelectStatement {
             table_name: "users".to_string(),
             table_aliases: TableAliases(HashMap::new()),
+            joins: vec![],
             mode: SelectMode::All,
             columns: vec![SelectableColumn {
                 selectables: vec![SelectableStackElement::All],
                 column_name: "*".to_string(),
             }],
             where_clause: None,
+            group_by_clause: None,
             order_by_clause: None,
             limit_clause: None,
         };
@@ -157,6 +473,7 @@ mod tests {
         let statement = SelectStatement {
             table_name: "users".to_string(),
             table_aliases: TableAliases(HashMap::new()),
+            joins: vec![],
             mode: SelectMode::All,
             columns: vec![
                 SelectableColumn {
@@ -169,6 +486,7 @@ mod tests {
                 },
             ],
             where_clause: None,
+            group_by_clause: None,
             order_by_clause: None,
             limit_clause: None,
         };
@@ -189,6 +507,7 @@ mod tests {
         let statement = SelectStatement {
             table_name: "users".to_string(),
             table_aliases: TableAliases(HashMap::new()),
+            joins: vec![],
             mode: SelectMode::All,
             columns: vec![SelectableColumn {
                 selectables: vec![SelectableStackElement::All],
@@ -202,6 +521,7 @@ mod tests {
                 ],
                 column_name: "name = 'John'".to_string(),
             }),
+            group_by_clause: None,
             order_by_clause: None,
             limit_clause: None,
         };
@@ -216,12 +536,50 @@ mod tests {
         assert_table_rows_eq(expected, result.unwrap());
     }
 
+    #[test]
+    fn select_with_where_clause_comparing_two_columns() {
+        let table = default_table();
+        // SELECT id FROM users WHERE age * 100 > money;
+        let statement = SelectStatement {
+            table_name: "users".to_string(),
+            table_aliases: TableAliases(HashMap::new()),
+            joins: vec![],
+            mode: SelectMode::All,
+            columns: vec![SelectableColumn {
+                selectables: vec![SelectableStackElement::Column("id".to_string())],
+                column_name: "id".to_string(),
+            }],
+            where_clause: Some(SelectableColumn {
+                selectables: vec![
+                    SelectableStackElement::Column("age".to_string()),
+                    SelectableStackElement::Value(Value::Integer(100)),
+                    SelectableStackElement::MathOperator(MathOperator::Multiply),
+                    SelectableStackElement::Column("money".to_string()),
+                    SelectableStackElement::Operator(Operator::GreaterThan),
+                ],
+                column_name: "age * 100 > money".to_string(),
+            }),
+            group_by_clause: None,
+            order_by_clause: None,
+            limit_clause: None,
+        };
+        let result = select_statement(&table, &statement).unwrap();
+        let expected = vec![
+            Row(vec![Value::Integer(1)]),
+            Row(vec![Value::Integer(2)]),
+            Row(vec![Value::Integer(3)]),
+        ];
+        assert_eq!(result.len(), expected.len());
+        assert_table_rows_eq(expected, result);
+    }
+
     #[test]
     fn select_with_where_clause_using_column_not_included_in_selected_columns() {
         let table = default_table();
         let statement = SelectStatement {
             table_name: "users".to_string(),
             table_aliases: TableAliases(HashMap::new()),
+            joins: vec![],
             mode: SelectMode::All,
             columns: vec![
                 SelectableColumn {
@@ -241,6 +599,7 @@ mod tests {
                 ],
                 column_name: "money = 1000.0".to_string(),
             }),
+            group_by_clause: None,
             order_by_clause: None,
             limit_clause: None,
         };
@@ -259,12 +618,14 @@ mod tests {
         let statement = SelectStatement {
             table_name: "users".to_string(),
             table_aliases: TableAliases(HashMap::new()),
+            joins: vec![],
             mode: SelectMode::All,
             columns: vec![SelectableColumn {
                 selectables: vec![SelectableStackElement::All],
                 column_name: "*".to_string(),
             }],
             where_clause: None,
+            group_by_clause: None,
             order_by_clause: None,
             limit_clause: Some(LimitClause {
                 limit: 1,
@@ -288,6 +649,7 @@ mod tests {
         let statement = SelectStatement {
             table_name: "users".to_string(),
             table_aliases: TableAliases(HashMap::new()),
+            joins: vec![],
             mode: SelectMode::All,
             columns: vec![SelectableColumn {
                 selectables: vec![SelectableStackElement::All],
@@ -301,6 +663,7 @@ mod tests {
                 ],
                 column_name: "column_not_included = 'John'".to_string(),
             }),
+            group_by_clause: None,
             order_by_clause: None,
             limit_clause: None,
         };
@@ -318,12 +681,14 @@ mod tests {
         let statement = SelectStatement {
             table_name: "users".to_string(),
             table_aliases: TableAliases(HashMap::new()),
+            joins: vec![],
             mode: SelectMode::All,
             columns: vec![SelectableColumn {
                 selectables: vec![SelectableStackElement::All],
                 column_name: "*".to_string(),
             }],
             where_clause: None,
+            group_by_clause: None,
             order_by_clause: Some(OrderByClause {
                 columns: vec![SelectableColumn {
                     selectables: vec![SelectableStackElement::Column("money".to_string())],
@@ -390,12 +755,14 @@ mod tests {
         let statement = SelectStatement {
             table_name: "users".to_string(),
             table_aliases: TableAliases(HashMap::new()),
+            joins: vec![],
             mode: SelectMode::Distinct,
             columns: vec![SelectableColumn {
                 selectables: vec![SelectableStackElement::Column("name".to_string())],
                 column_name: "name".to_string(),
             }],
             where_clause: None,
+            group_by_clause: None,
             order_by_clause: None,
             limit_clause: None,
         };
@@ -409,12 +776,60 @@ mod tests {
         assert_table_rows_eq_unordered(expected, result.unwrap());
     }
 
+    #[test]
+    fn select_distinct_and_order_by_treat_nan_and_negative_zero_consistently() {
+        let mut table = Table::new(
+            "values".to_string(),
+            vec![ColumnDefinition {
+                name: "value".to_string(),
+                data_type: DataType::Real,
+                constraints: vec![],
+            }],
+        );
+        table.set_rows(vec![
+            Row(vec![Value::Real(1.5)]),
+            Row(vec![Value::Real(f64::NAN)]),
+            Row(vec![Value::Real(-0.0)]),
+            Row(vec![Value::Null]),
+            Row(vec![Value::Real(f64::NAN)]),
+            Row(vec![Value::Real(0.0)]),
+        ]);
+        let statement = SelectStatement {
+            table_name: "values".to_string(),
+            table_aliases: TableAliases(HashMap::new()),
+            joins: vec![],
+            mode: SelectMode::Distinct,
+            columns: vec![SelectableColumn {
+                selectables: vec![SelectableStackElement::Column("value".to_string())],
+                column_name: "value".to_string(),
+            }],
+            where_clause: None,
+            group_by_clause: None,
+            order_by_clause: Some(OrderByClause {
+                columns: vec![SelectableColumn {
+                    selectables: vec![SelectableStackElement::Column("value".to_string())],
+                    column_name: "value".to_string(),
+                }],
+                directions: vec![OrderByDirection::Asc],
+            }),
+            limit_clause: None,
+        };
+        let result = select_statement(&table, &statement).unwrap();
+        // NaN sorts after NULL and before every other number.
+        assert_eq!(result.len(), 4);
+        assert!(result[0][0].is_null());
+        assert!(matches!(result[1][0], Value::Real(val) if val.is_nan()));
+        assert!(result[2][0].exactly_equal(&Value::Real(-0.0)));
+        assert!(result[3][0].exactly_equal(&Value::Real(1.5)));
+    }
+
     #[test]
     fn select_with_math_and_logic_operations_is_generated_correctly() {
         let table = default_table();
         let statement = SelectStatement {
             table_name: "users".to_string(),
             table_aliases: TableAliases(HashMap::new()),
+            joins: vec![],
             mode: SelectMode::All,
             columns: vec![
                 SelectableColumn {
@@ -457,6 +872,7 @@ mod tests {
                 },
             ],
             where_clause: None,
+            group_by_clause: None,
             order_by_clause: Some(OrderByClause {
                 columns: vec![SelectableColumn {
                     selectables: vec![
@@ -516,6 +932,7 @@ mod tests {
         let statement = SelectStatement {
             table_name: "users".to_string(),
             table_aliases: TableAliases(HashMap::new()),
+            joins: vec![],
             mode: SelectMode::All,
             columns: vec![
                 SelectableColumn {
@@ -552,6 +969,7 @@ mod tests {
                 },
             ],
             where_clause: None,
+            group_by_clause: None,
             order_by_clause: None,
             limit_clause: None,
         };
@@ -595,6 +1013,7 @@ mod tests {
         let statement = SelectStatement {
             table_name: "users".to_string(),
             table_aliases: TableAliases(HashMap::new()),
+            joins: vec![],
             mode: SelectMode::All,
             columns: vec![SelectableColumn {
                 selectables: vec![
@@ -612,6 +1031,7 @@ mod tests {
                 ],
                 column_name: "some_alias > 80".to_string(),
             }),
+            group_by_clause: None,
             order_by_clause: Some(OrderByClause {
                 columns: vec![SelectableColumn {
                     selectables: vec![SelectableStackElement::Column("some_alias".to_string())],
@@ -639,6 +1059,7 @@ mod tests {
         let statement = SelectStatement {
             table_name: "users".to_string(),
             table_aliases: TableAliases(HashMap::new()),
+            joins: vec![],
             mode: SelectMode::All,
             columns: vec![SelectableColumn {
                 selectables: vec![
@@ -656,6 +1077,7 @@ mod tests {
                 ],
                 column_name: "nonexisting_alias > 80".to_string(),
             }),
+            group_by_clause: None,
             order_by_clause: None,
             limit_clause: None,
         };
@@ -671,6 +1093,7 @@ mod tests {
         let statement = SelectStatement {
             table_name: "users".to_string(),
             table_aliases: TableAliases(HashMap::new()),
+            joins: vec![],
             mode: SelectMode::All,
             columns: vec![
                 SelectableColumn {
@@ -691,6 +1114,7 @@ mod tests {
                 },
             ],
             where_clause: None,
+            group_by_clause: None,
             order_by_clause: None,
             limit_clause: None,
         };
@@ -699,4 +1123,129 @@ mod tests {
         assert!(result.is_err());
         assert!(result.err().unwrap() == "Invalid column name: some_alias");
     }
+
+    #[test]
+    fn min_and_max_are_read_from_an_index_without_scanning() {
+        use crate::db::table::core::index::Index;
+        use crate::db::table::operations::helpers::common::ROWS_SCANNED;
+        use crate::interpreter::ast::{FunctionCall, FunctionName};
+
+        let mut table = default_table();
+        table
+            .create_index(Index::new(
+                "idx_money".to_string(),
+                vec!["money".to_string()],
+            ))
+            .unwrap();
+        table
+            .create_index(Index::new("idx_name".to_string(), vec!["name".to_string()]))
+            .unwrap();
+        let aggregate = |name: FunctionName, column: &str| SelectStatement {
+            table_name: "users".to_string(),
+            table_aliases: TableAliases(HashMap::new()),
+            joins: vec![],
+            mode: SelectMode::All,
+            columns: vec![SelectableColumn {
+                selectables: vec![SelectableStackElement::Function(FunctionCall {
+                    name,
+                    arguments: vec![SelectableColumn {
+                        selectables: vec![SelectableStackElement::Column(column.to_string())],
+                        column_name: column.to_string(),
+                    }],
+                    distinct: false,
+                })],
+                column_name: "aggregate".to_string(),
+            }],
+            where_clause: None,
+            group_by_clause: None,
+            order_by_clause: None,
+            limit_clause: None,
+        };
+
+        let cases = vec![
+            (
+                aggregate(FunctionName::Max, "money"),
+                Value::Real(4000.0),
+                0,
+            ),
+            (
+                aggregate(FunctionName::Min, "money"),
+                Value::Real(1000.0),
+                0,
+            ),
+            // The NULL name is not indexed.
+            (
+                aggregate(FunctionName::Min, "name"),
+                Value::Text("Jane".to_string()),
+                0,
+            ),
+            // Without an index on age, the table is scanned.
+            (aggregate(FunctionName::Min, "age"), Value::Integer(25), 4),
+            (
+                aggregate(FunctionName::Sum, "money"),
+                Value::Real(10000.0),
+                4,
+            ),
+        ];
+        for (statement, expected, rows_scanned) in cases {
+            ROWS_SCANNED.with(|count| count.set(0));
+            let result = select_statement(&table, &statement).unwrap();
+            assert!(result[0][0].exactly_equal(&expected), "{:?}", result);
+            assert_eq!(ROWS_SCANNED.with(|count| count.get()), rows_scanned);
+        }
+
+        table.set_rows(vec![]);
+        let result = select_statement(&table, &aggregate(FunctionName::Max, "money")).unwrap();
+        assert!(result[0][0].is_null());
+    }
+
+    #[test]
+    fn distinct_with_limit_stops_after_enough_distinct_rows() {
+        use crate::db::table::operations::helpers::common::ROWS_SCANNED;
+
+        let mut table = default_table();
+        table.set_rows(
+            [30, 30, 30, 40, 40, 50, 60]
+                .into_iter()
+                .map(|age| {
+                    Row(vec![
+                        Value::Integer(1),
+                        Value::Text("John".to_string()),
+                        Value::Integer(age),
+                        Value::Real(1.0),
+                    ])
+                })
+                .collect(),
+        );
+        let statement = |limit: usize, offset: Option<usize>| SelectStatement {
+            table_name: "users".to_string(),
+            table_aliases: TableAliases(HashMap::new()),
+            joins: vec![],
+            mode: SelectMode::Distinct,
+            columns: vec![SelectableColumn {
+                selectables: vec![SelectableStackElement::Column("age".to_string())],
+                column_name: "age".to_string(),
+            }],
+            where_clause: None,
+            group_by_clause: None,
+            order_by_clause: None,
+            limit_clause: Some(LimitClause { limit, offset }),
+        };
+
+        let cases = vec![
+            (statement(2, None), vec![30, 40], 4),
+            (statement(2, Some(1)), vec![40, 50], 6),
+            (statement(10, None), vec![30, 40, 50, 60], 7),
+        ];
+        for (statement, expected, rows_scanned) in cases {
+            ROWS_SCANNED.with(|count| count.set(0));
+            let result = select_statement(&table, &statement).unwrap();
+            let expected = expected
+                .into_iter()
+                .map(|age| Row(vec![Value::Integer(age)]))
+                .collect::<Vec<Row>>();
+            assert_table_rows_eq(expected, result);
+            assert_eq!(ROWS_SCANNED.with(|count| count.get()), rows_scanned);
+        }
+    }
 }
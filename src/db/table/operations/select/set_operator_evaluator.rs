@@ -1,6 +1,6 @@
 use std::collections::HashSet;
 
-use crate::db::table::core::row::Row;
+use crate::db::table::core::row::{DistinctRow, Row};
 use crate::db::table::operations::helpers::common::remove_duplicate_rows;
 
 pub struct SetOperatorEvaluator {
@@ -47,31 +47,25 @@ impl SetOperatorEvaluator {
     }
 
     pub fn intersect(&mut self) -> Result<(), String> {
-        let second = self.pop()?.into_iter().collect::<HashSet<Row>>();
+        let second = self
+            .pop()?
+            .into_iter()
+            .map(DistinctRow)
+            .collect::<HashSet<DistinctRow>>();
         let mut first = self.pop()?;
-        let mut index: usize = 0;
-        while index < first.len() {
-            if second.contains(&first[index]) {
-                index += 1;
-            } else {
-                first.swap_remove(index);
-            }
-        }
+        first.retain(|row| second.contains(&DistinctRow(row.clone())));
         self.push(first);
         Ok(())
     }
 
     pub fn except(&mut self) -> Result<(), String> {
-        let second = self.pop()?.into_iter().collect::<HashSet<Row>>();
+        let second = self
+            .pop()?
+            .into_iter()
+            .map(DistinctRow)
+            .collect::<HashSet<DistinctRow>>();
         let mut first = self.pop()?;
-        let mut index: usize = 0;
-        while index < first.len() {
-            if second.contains(&first[index]) {
-                first.swap_remove(index);
-            } else {
-                index += 1;
-            }
-        }
+        first.retain(|row| !second.contains(&DistinctRow(row.clone())));
         self.push(first);
         Ok(())
     }
@@ -220,4 +214,31 @@ mod test {
         ];
         assert_table_rows_eq_unordered(expected, result.unwrap());
     }
+
+    #[test]
+    fn set_operators_treat_null_values_as_equal() {
+        // SQLite considers NULLs equal to each other in set operations, unlike in comparisons.
+        let null_row = || Row(vec![Value::Null, Value::Text("Jim".to_string())]);
+        let mut evaluator = SetOperatorEvaluator::new();
+        evaluator.push(vec![null_row(), Row(vec![Value::Integer(1), Value::Null])]);
+        evaluator.push(vec![null_row()]);
+        assert!(evaluator.intersect().is_ok());
+        let result = evaluator.result().unwrap();
+        assert_eq!(result.len(), 1);
+        assert_table_rows_eq_unordered(vec![null_row()], result);
+
+        let mut evaluator = SetOperatorEvaluator::new();
+        evaluator.push(vec![null_row(), Row(vec![Value::Integer(1), Value::Null])]);
+        evaluator.push(vec![null_row()]);
+        assert!(evaluator.except().is_ok());
+        let result = evaluator.result().unwrap();
+        assert_eq!(result.len(), 1);
+        assert_table_rows_eq_unordered(vec![Row(vec![Value::Integer(1), Value::Null])], result);
+
+        let mut evaluator = SetOperatorEvaluator::new();
+        evaluator.push(vec![null_row(), Row(vec![Value::Real(1.0), Value::Null])]);
+        evaluator.push(vec![null_row(), Row(vec![Value::Integer(1), Value::Null])]);
+        assert!(evaluator.union().is_ok());
+        assert_eq!(evaluator.result().unwrap().len(), 2);
+    }
 }
@@ -0,0 +1,97 @@
+use crate::db::database::Database;
+use crate::db::table::core::value::Value;
+use crate::db::table::operations::select::select_statement_stack;
+use crate::interpreter::ast::{
+    FunctionName, SelectStatement, SelectableColumn, SelectableStackElement,
+};
+
+// Subqueries cannot reference the columns of the outer query, so each of them is evaluated once
+// and replaced by its result before the rows of the outer query are evaluated. So is
+// last_insert_rowid(), which depends on the database rather than the rows.
+pub fn resolve_subqueries(
+    database: &Database,
+    statement: &mut SelectStatement,
+) -> Result<(), String> {
+    for column in statement.columns.iter_mut() {
+        resolve_column(database, column)?;
+    }
+    if let Some(where_clause) = &mut statement.where_clause {
+        resolve_column(database, where_clause)?;
+    }
+    if let Some(order_by_clause) = &mut statement.order_by_clause {
+        for column in order_by_clause.columns.iter_mut() {
+            resolve_column(database, column)?;
+        }
+    }
+    Ok(())
+}
+
+fn resolve_column(database: &Database, column: &mut SelectableColumn) -> Result<(), String> {
+    for selectable in column.selectables.iter_mut() {
+        match selectable {
+            SelectableStackElement::Exists(subquery) => {
+                let rows = select_statement_stack(database, (**subquery).clone())?;
+                *selectable = SelectableStackElement::Value(Value::Integer(if rows.is_empty() {
+                    0
+                } else {
+                    1
+                }));
+            }
+            SelectableStackElement::InSubquery(subquery) => {
+                let rows = select_statement_stack(database, (**subquery).clone())?;
+                if rows.iter().any(|row| row.len() != 1) {
+                    return Err("IN subquery must return a single column".to_string());
+                }
+                *selectable =
+                    SelectableStackElement::ValueList(rows.into_iter().map(|row| row.0).collect());
+            }
+            SelectableStackElement::ScalarSubquery(subquery) => {
+                let rows = select_statement_stack(database, (**subquery).clone())?;
+                if let Some(row) = rows.first()
+                    && row.len() != 1
+                {
+                    return Err(format!(
+                        "sub-select returns {} columns - expected 1",
+                        row.len()
+                    ));
+                }
+                let value = rows
+                    .into_iter()
+                    .next()
+                    .and_then(|row| row.0.into_iter().next())
+                    .unwrap_or(Value::Null);
+                *selectable = SelectableStackElement::Value(value);
+            }
+            SelectableStackElement::Function(func)
+                if func.name == FunctionName::LastInsertRowid =>
+            {
+                *selectable =
+                    SelectableStackElement::Value(Value::Integer(database.last_insert_rowid));
+            }
+            SelectableStackElement::Function(func) if func.name == FunctionName::Changes => {
+                *selectable =
+                    SelectableStackElement::Value(Value::Integer(database.changes as i64));
+            }
+            SelectableStackElement::Function(func) if func.name == FunctionName::TotalChanges => {
+                *selectable =
+                    SelectableStackElement::Value(Value::Integer(database.total_changes as i64));
+            }
+            SelectableStackElement::Function(func) => {
+                for argument in func.arguments.iter_mut() {
+                    resolve_column(database, argument)?;
+                }
+            }
+            SelectableStackElement::Case(case) => {
+                for (condition, result) in case.branches.iter_mut() {
+                    resolve_column(database, condition)?;
+                    resolve_column(database, result)?;
+                }
+                if let Some(else_result) = &mut case.else_result {
+                    resolve_column(database, else_result)?;
+                }
+            }
+            _ => {}
+        }
+    }
+    Ok(())
+}
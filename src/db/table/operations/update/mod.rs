@@ -1,5 +1,9 @@
-use crate::db::table::core::{table::Table, value::DataType};
+use crate::db::table::core::{
+    table::Table,
+    value::{DataType, Value},
+};
 use crate::db::table::operations::helpers::common::get_row_indicies_matching_clauses;
+use crate::db::table::operations::insert::constraints::check_changed_rows;
 use crate::interpreter::ast::{ColumnValue, UpdateStatement};
 
 pub fn update(
@@ -19,6 +23,7 @@ pub fn update(
         statement.update_values,
         is_transaction,
     )?;
+    table.rebuild_indexes()?;
     Ok(row_indicies)
 }
 
@@ -28,10 +33,20 @@ fn update_rows_from_indicies(
     update_values: Vec<ColumnValue>,
     is_transaction: bool,
 ) -> Result<(), String> {
-    for row_index in row_indicies {
-        if is_transaction {
-            table.get_row_stacks_mut()[*row_index].append_clone();
+    for update_value in &update_values {
+        if table.get_columns()?.iter().any(|column| {
+            column.name == update_value.column && column.generated_expression().is_some()
+        }) {
+            return Err(format!(
+                "Cannot UPDATE generated column `{}`",
+                update_value.column
+            ));
         }
+    }
+    // The updated rows are computed and checked first so a failing update leaves the table untouched.
+    let mut updated_rows = vec![];
+    for row_index in row_indicies {
+        let mut row = table[*row_index].clone();
         for update_value in &update_values {
             let column_index = table.get_index_of_column(&update_value.column)?;
             if table.get_columns()?[column_index].data_type != update_value.value.get_type()
@@ -43,9 +58,26 @@ fn update_rows_from_indicies(
                     update_value.value.get_type()
                 ));
             }
-            table[*row_index][column_index] = update_value.value.clone();
+            row[column_index] = update_value.value.clone();
+        }
+        updated_rows.push((*row_index, row));
+    }
+    check_changed_rows(table, &updated_rows, !is_transaction)?;
+
+    // A value written into the AUTOINCREMENT column is never given to an inserted row either.
+    if let Some(column_index) = table.autoincrement_column()? {
+        for (_, row) in &updated_rows {
+            if let Value::Integer(value) = row[column_index] {
+                table.autoincrement_sequence = table.autoincrement_sequence.max(value);
+            }
         }
     }
+    for (row_index, row) in updated_rows {
+        if is_transaction {
+            table.get_row_stacks_mut()[row_index].append_clone();
+        }
+        table[row_index] = row;
+    }
     Ok(())
 }
 
@@ -76,6 +108,7 @@ mod tests {
             where_clause: None,
             order_by_clause: None,
             limit_clause: None,
+            returning: None,
         };
         let result = update(&mut table, statement, false);
         assert!(result.is_ok());
@@ -181,6 +214,7 @@ mod tests {
                 limit: 1,
                 offset: Some(2),
             }),
+            returning: None,
         };
         let result = update(&mut table, statement, false);
         assert!(result.is_ok());
@@ -257,6 +291,7 @@ mod tests {
             }),
             order_by_clause: None,
             limit_clause: None,
+            returning: None,
         };
         let result = update(&mut table, statement, false);
         assert!(result.is_ok());
@@ -312,6 +347,7 @@ mod tests {
             where_clause: None,
             order_by_clause: None,
             limit_clause: None,
+            returning: None,
         };
         let result = update(&mut table, statement, false);
         assert!(result.is_ok());
@@ -333,6 +369,7 @@ mod tests {
             where_clause: None,
             order_by_clause: None,
             limit_clause: None,
+            returning: None,
         };
         let result = update(&mut table, statement, false);
         assert!(result.is_err());
@@ -355,6 +392,7 @@ mod tests {
             where_clause: None,
             order_by_clause: None,
             limit_clause: None,
+            returning: None,
         };
         let result = update(&mut table, statement, false);
         assert!(result.is_err());
@@ -377,6 +415,7 @@ mod tests {
             where_clause: None,
             order_by_clause: None,
             limit_clause: None,
+            returning: None,
         };
         let result = update(&mut table, statement, false);
         assert!(result.is_ok());
@@ -423,6 +462,7 @@ mod tests {
             where_clause: None,
             order_by_clause: None,
             limit_clause: None,
+            returning: None,
         };
         let result = update(&mut table, statement, true);
         assert!(result.is_ok());
@@ -488,6 +528,7 @@ mod tests {
             }),
             order_by_clause: None,
             limit_clause: None,
+            returning: None,
         };
         let original_row = table.get_rows_clone()[0].clone();
         let result = update(&mut table, statement, true);
@@ -508,4 +549,101 @@ mod tests {
             "After popping once from stack, should get back original row. This fails if stack was corrupted with multiple clones."
         );
     }
+
+    #[test]
+    fn update_with_indexed_equality_only_scans_matching_rows() {
+        use crate::db::table::core::index::Index;
+        use crate::db::table::operations::helpers::common::ROWS_SCANNED;
+
+        let mut table = default_table();
+        for id in 5..=20 {
+            table.push(Row(vec![
+                Value::Integer(id),
+                Value::Text("Jim".to_string()),
+                Value::Integer(40),
+                Value::Real(1.0),
+            ]));
+        }
+        table
+            .create_index(Index::new("idx_id".to_string(), vec!["id".to_string()]))
+            .unwrap();
+        let statement = UpdateStatement {
+            table_name: "users".to_string(),
+            table_aliases: TableAliases(HashMap::new()),
+            update_values: vec![ColumnValue {
+                column: "name".to_string(),
+                value: Value::Text("Jill".to_string()),
+            }],
+            where_clause: Some(SelectableColumn {
+                selectables: vec![
+                    SelectableStackElement::Column("id".to_string()),
+                    SelectableStackElement::Value(Value::Integer(7)),
+                    SelectableStackElement::Operator(Operator::Equals),
+                ],
+                column_name: "id = 7".to_string(),
+            }),
+            order_by_clause: None,
+            limit_clause: None,
+            returning: None,
+        };
+        ROWS_SCANNED.with(|count| count.set(0));
+        let result = update(&mut table, statement, false);
+        assert_eq!(result, Ok(vec![6]));
+        assert_eq!(ROWS_SCANNED.with(|count| count.get()), 1);
+        let renamed = table
+            .iter()
+            .filter(|row| row[1] == Value::Text("Jill".to_string()))
+            .collect::<Vec<&Row>>();
+        assert_eq!(renamed.len(), 1);
+        assert_eq!(renamed[0][0], Value::Integer(7));
+    }
+
+    #[test]
+    fn update_with_order_by_and_limit_changes_only_the_first_ordered_rows() {
+        let mut table = default_table();
+        let statement = UpdateStatement {
+            table_name: "users".to_string(),
+            table_aliases: TableAliases(HashMap::new()),
+            update_values: vec![ColumnValue {
+                column: "name".to_string(),
+                value: Value::Text("x".to_string()),
+            }],
+            where_clause: None,
+            order_by_clause: Some(OrderByClause {
+                columns: vec![SelectableColumn {
+                    selectables: vec![SelectableStackElement::Column("id".to_string())],
+                    column_name: "id".to_string(),
+                }],
+                directions: vec![OrderByDirection::Desc],
+            }),
+            limit_clause: Some(LimitClause {
+                limit: 2,
+                offset: None,
+            }),
+            returning: None,
+        };
+        // The rows are returned in the order of the ORDER BY clause.
+        assert_eq!(update(&mut table, statement, true), Ok(vec![3, 2]));
+        let names = table
+            .get_rows_clone()
+            .into_iter()
+            .map(|row| row[1].clone())
+            .collect::<Vec<Value>>();
+        assert_eq!(
+            names,
+            vec![
+                Value::Text("John".to_string()),
+                Value::Text("Jane".to_string()),
+                Value::Text("x".to_string()),
+                Value::Text("x".to_string()),
+            ]
+        );
+        // Each updated row keeps a single copy of its previous version for a rollback.
+        let depths = table
+            .get_row_stacks_clone()
+            .iter()
+            .map(|row_stack| row_stack.stack.len())
+            .collect::<Vec<usize>>();
+        assert_eq!(depths, vec![1, 1, 2, 2]);
+    }
 }
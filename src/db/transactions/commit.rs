@@ -1,16 +1,71 @@
 use crate::db::database::Database;
+use crate::db::table::operations::insert::constraints::check_changed_rows;
 use crate::db::transactions::TransactionEntry;
+use crate::db::transactions::rollback::rollback_statement;
+use crate::interpreter::ast::RollbackStatement;
 
 pub fn commit_transaction(database: &mut Database) -> Result<(), String> {
+    if !database.transaction.in_transaction() {
+        return Err("cannot commit - no transaction is active".to_string());
+    }
+    // Constraints are validated before anything is committed. If one fails the whole
+    // transaction is rolled back, so COMMIT either applies every change or none of them.
+    if let Err(error) = check_transaction_constraints(database) {
+        rollback_statement(
+            database,
+            &RollbackStatement {
+                savepoint_name: None,
+            },
+        )?;
+        return Err(error);
+    }
+
     let transaction_log = database.transaction.commit_transaction()?;
+    let mut table_names: Vec<&String> = vec![];
     for transaction_entry in transaction_log.get_entries()?.iter() {
         match transaction_entry {
             TransactionEntry::Statement(statement) => {
                 let table = database.get_table_mut(&statement.table_name)?;
                 table.commit_transaction(&statement.affected_rows)?;
+                if !table_names.contains(&&statement.table_name) {
+                    table_names.push(&statement.table_name);
+                }
             }
             TransactionEntry::Savepoint(_) => {}
         }
     }
+    let restore_insertion_order = database.restore_insertion_order_on_commit;
+    for table_name in table_names {
+        if database.has_table(table_name) {
+            database
+                .get_table_mut(table_name)?
+                .commit_rows(restore_insertion_order)?;
+        }
+    }
+    Ok(())
+}
+
+// Checks the constraints on the rows changed during the transaction, in every table it changed that
+// still exists.
+fn check_transaction_constraints(database: &Database) -> Result<(), String> {
+    let mut table_names: Vec<&String> = vec![];
+    for transaction_entry in database.transaction.get_entries()?.iter() {
+        if let TransactionEntry::Statement(statement) = transaction_entry
+            && !table_names.contains(&&statement.table_name)
+        {
+            table_names.push(&statement.table_name);
+        }
+    }
+    for table_name in table_names {
+        if database.has_table(table_name) {
+            let table = database.get_table(table_name)?;
+            let changed_rows = table
+                .changed_positions()
+                .into_iter()
+                .map(|position| (position, table[position].clone()))
+                .collect::<Vec<_>>();
+            check_changed_rows(table, &changed_rows, true)?;
+        }
+    }
     Ok(())
 }
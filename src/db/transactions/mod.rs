@@ -45,6 +45,7 @@ impl TransactionLog {
             SqlStatement::DeleteStatement(statement) => statement.table_name.clone(),
             SqlStatement::DropTable(statement) => statement.table_name.clone(),
             SqlStatement::AlterTable(statement) => statement.table_name.clone(),
+            SqlStatement::CreateIndex(statement) => statement.table_name.clone(),
             SqlStatement::Savepoint(statement) => {
                 self.append_savepoint(Savepoint {
                     name: statement.savepoint_name.clone(),
@@ -53,6 +54,19 @@ impl TransactionLog {
             }
             _ => return Err("Invalid transaction entry".to_string()),
         };
+        self.append_table_entry(sql_statement, table_name, affected_rows)
+    }
+
+    // For statements whose affected table is only known once they have executed, e.g. DROP INDEX.
+    pub fn append_table_entry(
+        &mut self,
+        sql_statement: SqlStatement,
+        table_name: String,
+        affected_rows: Vec<usize>,
+    ) -> Result<(), String> {
+        if !self.in_transaction() {
+            return Ok(());
+        }
         self.get_entries_mut()?
             .push(TransactionEntry::Statement(StatementEntry {
                 statement: sql_statement,
@@ -92,6 +106,9 @@ impl TransactionLog {
     }
 
     pub fn commit_transaction(&mut self) -> Result<TransactionLog, String> {
+        if !self.in_transaction() {
+            return Err("cannot commit - no transaction is active".to_string());
+        }
         let transaction_log = TransactionLog {
             entries: self.entries.take(),
         };
@@ -1,4 +1,5 @@
 use crate::db::database::Database;
+use crate::db::table::operations::delete;
 use crate::db::transactions::{StatementEntry, TransactionEntry};
 use crate::interpreter::ast::{AlterTableAction, RollbackStatement, SqlStatement};
 
@@ -7,7 +8,7 @@ pub fn rollback_statement(
     statement: &RollbackStatement,
 ) -> Result<(), String> {
     if !database.transaction.in_transaction() {
-        return Err("No transaction is currently active".to_string());
+        return Err("cannot rollback - no transaction is active".to_string());
     }
 
     if let Some(savepoint_name) = &statement.savepoint_name {
@@ -15,7 +16,8 @@ pub fn rollback_statement(
         if !database.transaction.savepoint_exists(savepoint_name)? {
             return Err(format!("Savepoint `{}` does not exist", savepoint_name));
         }
-        // Rollback to savepoint - keep transaction active
+        // Rollback to savepoint - keep transaction active, and the savepoint too so it can be
+        // rolled back to again. The savepoints after it are dropped.
         let mut current_entry = database.transaction.pop_entry()?;
         while current_entry.is_some() {
             match current_entry.unwrap() {
@@ -24,6 +26,7 @@ pub fn rollback_statement(
                 }
                 TransactionEntry::Savepoint(savepoint_statement) => {
                     if savepoint_statement.name == *savepoint_name {
+                        database.transaction.append_savepoint(savepoint_statement)?;
                         break;
                     }
                 }
@@ -39,7 +42,8 @@ pub fn rollback_statement(
                     TransactionEntry::Statement(statement) => {
                         // TODO: Some matching needs to be here for table based operations.
                         // CURRENTLY SUPPORTED STATEMENTS ARE:
-                        // - ALTER TABLE RENAME COLUMN, ALTER TABLE ADD COLUMN, ALTER TABLE DROP COLUMN, ALTER TABLE RENAME TABLE
+                        // - ALTER TABLE RENAME COLUMN, ALTER TABLE ADD COLUMN, ALTER TABLE DROP COLUMN, ALTER TABLE RENAME TABLE,
+                        //   ALTER TABLE ALTER COLUMN SET NOT NULL
                         // - CREATE TABLE, DROP TABLE
                         // - INSERT INTO, UPDATE, DELETE
                         rollback_transaction_entry(database, &statement)?;
@@ -58,7 +62,7 @@ pub fn rollback_transaction_entry(
 ) -> Result<(), String> {
     match &statement_entry.statement {
         SqlStatement::AlterTable(alter_table) => match alter_table.action {
-            AlterTableAction::RenameColumn { .. } => {
+            AlterTableAction::RenameColumn { .. } | AlterTableAction::SetNotNull { .. } => {
                 let table = database.get_table_mut(&statement_entry.table_name)?;
                 table.rollback_columns();
             }
@@ -93,22 +97,45 @@ pub fn rollback_transaction_entry(
                 table_versions.pop();
             }
         }
+        SqlStatement::CreateIndex(statement) => {
+            let table = database.get_table_mut(&statement_entry.table_name)?;
+            table.drop_index(&statement.index_name, false)?;
+        }
+        SqlStatement::DropIndex(_) => {
+            let table = database.get_table_mut(&statement_entry.table_name)?;
+            table.rollback_drop_index()?;
+        }
         SqlStatement::InsertInto(_) => {
             let table = database.get_table_mut(&statement_entry.table_name)?;
-            for _ in &statement_entry.affected_rows {
-                table.get_row_stacks_mut().pop(); // We can pop all the rows off because they always get pushed to the end
+            // Rows replaced through ON CONFLICT REPLACE have their previous version on the stack,
+            // the others are new rows which are removed where they were inserted, before any row
+            // hidden by a DELETE.
+            let mut inserted_positions = vec![];
+            for index in &statement_entry.affected_rows {
+                let row_stack = &mut table.get_row_stacks_mut()[*index];
+                if row_stack.stack.len() > 1 {
+                    row_stack.stack.pop();
+                } else {
+                    inserted_positions.push(*index);
+                }
             }
-            table.set_length(table.len() - statement_entry.affected_rows.len());
+            inserted_positions.sort();
+            for position in inserted_positions.iter().rev() {
+                table.get_row_stacks_mut().remove(*position);
+            }
+            table.set_length(table.len() - inserted_positions.len());
+            table.rebuild_indexes()?;
         }
         SqlStatement::UpdateStatement(_) => {
             let table = database.get_table_mut(&statement_entry.table_name)?;
             for index in &statement_entry.affected_rows {
                 table.get_row_stacks_mut()[*index].stack.pop();
             }
+            table.rebuild_indexes()?;
         }
         SqlStatement::DeleteStatement(_) => {
             let table = database.get_table_mut(&statement_entry.table_name)?;
-            table.set_length(table.len() + statement_entry.affected_rows.len());
+            delete::undo_swap_remove(table, &statement_entry.affected_rows);
         }
         _ => return Err("UNSUPPORTED".to_string()),
     }
@@ -131,7 +158,10 @@ mod tests {
         };
         let result = rollback_statement(&mut database, &statement);
         assert!(result.is_err());
-        assert_eq!(result.unwrap_err(), "No transaction is currently active");
+        assert_eq!(
+            result.unwrap_err(),
+            "cannot rollback - no transaction is active"
+        );
     }
 
     #[test]
@@ -163,6 +193,8 @@ mod tests {
                 Value::Integer(50),
                 Value::Real(5000.0),
             ]],
+            last_insert_rowids: vec![],
+            returning: None,
         });
         database
             .transaction
@@ -176,6 +208,8 @@ mod tests {
         let result = rollback_statement(&mut database, &rollback_stmt);
         assert!(result.is_ok());
         assert!(database.transaction.in_transaction());
+        // The savepoint stays, only the insert was undone.
+        assert_eq!(database.transaction.get_entries().unwrap().len(), 1);
         assert_eq!(database.get_table("users").unwrap().len(), 4);
         assert_table_rows_eq_unordered(
             database.get_table("users").unwrap().get_rows_clone(),
@@ -210,6 +244,8 @@ mod tests {
                 Value::Integer(50),
                 Value::Real(5000.0),
             ]],
+            last_insert_rowids: vec![],
+            returning: None,
         });
         let statement_entry = StatementEntry {
             statement: insert_statement,
@@ -292,6 +328,8 @@ mod tests {
                 Value::Integer(28),
                 Value::Real(5000.0),
             ]],
+            last_insert_rowids: vec![],
+            returning: None,
         });
         database
             .transaction
@@ -315,6 +353,8 @@ mod tests {
                 Value::Integer(5),
                 Value::Real(150.0),
             ]],
+            last_insert_rowids: vec![],
+            returning: None,
         });
         database
             .transaction
@@ -1,5 +1,6 @@
-use crate::db::table::core::column::ColumnDefinition;
+use crate::db::table::core::column::{ColumnConstraint, ColumnDefinition, ConstraintType};
 use crate::interpreter::{
+    ast::create_statement::{check_default_value, default_clause},
     ast::helpers::common::get_table_name,
     ast::helpers::token::{expect_token_type, token_to_data_type},
     ast::{AlterTableAction, AlterTableStatement, SqlStatement, parser::Parser},
@@ -60,13 +61,22 @@ fn get_action(parser: &mut Parser) -> Result<AlterTableAction, String> {
             let name = parser.current_token()?.value.to_string();
             parser.advance()?;
             let data_type = token_to_data_type(parser)?;
-            Ok(AlterTableAction::AddColumn {
-                column_def: ColumnDefinition {
-                    name,
-                    data_type,
-                    constraints: vec![],
-                },
-            })
+            let mut constraints = vec![];
+            if parser.peek_is(1, TokenTypes::Default) {
+                parser.advance()?;
+                constraints.push(ColumnConstraint {
+                    constraint_type: ConstraintType::Default(default_clause(parser)?),
+                    conflict_action: None,
+                    deferred: false,
+                });
+            }
+            let column_def = ColumnDefinition {
+                name,
+                data_type,
+                constraints,
+            };
+            check_default_value(&column_def)?;
+            Ok(AlterTableAction::AddColumn { column_def })
         }
         TokenTypes::Drop => {
             parser.advance()?;
@@ -76,6 +86,20 @@ fn get_action(parser: &mut Parser) -> Result<AlterTableAction, String> {
             let column_name = parser.current_token()?.value.to_string();
             Ok(AlterTableAction::DropColumn { column_name })
         }
+        TokenTypes::Alter => {
+            parser.advance()?;
+            expect_token_type(parser, TokenTypes::Column)?;
+            parser.advance()?;
+            expect_token_type(parser, TokenTypes::Identifier)?;
+            let column_name = parser.current_token()?.value.to_string();
+            parser.advance()?;
+            expect_token_type(parser, TokenTypes::Set)?;
+            parser.advance()?;
+            expect_token_type(parser, TokenTypes::Not)?;
+            parser.advance()?;
+            expect_token_type(parser, TokenTypes::Null)?;
+            Ok(AlterTableAction::SetNotNull { column_name })
+        }
         _ => return Err(parser.format_error()),
     };
 }
@@ -193,4 +217,32 @@ mod tests {
         });
         assert_eq!(expected, statement);
     }
+
+    #[test]
+    fn alter_table_set_not_null_is_generated_correctly() {
+        // ALTER TABLE users ALTER COLUMN name SET NOT NULL;
+        let tokens = vec![
+            token(TokenTypes::Alter, "ALTER"),
+            token(TokenTypes::Table, "TABLE"),
+            token(TokenTypes::Identifier, "users"),
+            token(TokenTypes::Alter, "ALTER"),
+            token(TokenTypes::Column, "COLUMN"),
+            token(TokenTypes::Identifier, "name"),
+            token(TokenTypes::Set, "SET"),
+            token(TokenTypes::Not, "NOT"),
+            token(TokenTypes::Null, "NULL"),
+            token(TokenTypes::SemiColon, ";"),
+        ];
+        let mut parser = Parser::new(tokens);
+        let result = build(&mut parser);
+        assert!(result.is_ok());
+        let statement = result.unwrap();
+        let expected = SqlStatement::AlterTable(AlterTableStatement {
+            table_name: "users".to_string(),
+            action: AlterTableAction::SetNotNull {
+                column_name: "name".to_string(),
+            },
+        });
+        assert_eq!(expected, statement);
+    }
 }
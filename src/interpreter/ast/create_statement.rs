@@ -1,10 +1,13 @@
-use crate::db::table::core::column::ColumnDefinition;
+use crate::db::table::core::column::{
+    ColumnConstraint, ColumnDefinition, ConflictAction, ConstraintType, TableConstraint,
+};
+use crate::db::table::core::value::{DataType, Value};
 use crate::interpreter::{
     ast::{
-        CreateTableStatement, ExistenceCheck,
-        SqlStatement::{self, CreateTable},
-        helpers::common::{exists_clause, get_table_name},
-        helpers::token::{expect_token_type, token_to_data_type},
+        CreateIndexStatement, CreateTableStatement, ExistenceCheck,
+        SqlStatement::{self, CreateIndex, CreateTable},
+        helpers::common::{exists_clause, get_selectables, get_table_name},
+        helpers::token::{expect_token_type, token_to_data_type, token_to_value},
         parser::Parser,
     },
     tokenizer::token::TokenTypes,
@@ -19,12 +22,21 @@ pub fn build(parser: &mut Parser) -> Result<SqlStatement, String> {
         TokenTypes::Table => {
             statement = table_statement(parser);
         }
+        TokenTypes::Index => {
+            statement = index_statement(parser, false);
+        }
+        TokenTypes::Unique => {
+            parser.advance()?;
+            expect_token_type(parser, TokenTypes::Index)?;
+            statement = index_statement(parser, true);
+        }
         _ => return Err(parser.format_error()),
     }
 
+    let statement = statement?;
     // Ensure SemiColon
     expect_token_type(parser, TokenTypes::SemiColon)?;
-    return statement;
+    return Ok(statement);
 }
 
 fn table_statement(parser: &mut Parser) -> Result<SqlStatement, String> {
@@ -36,56 +48,435 @@ fn table_statement(parser: &mut Parser) -> Result<SqlStatement, String> {
         return Err("Table aliases in CREATE TABLE statement not allowed".to_string());
     }
 
-    let column_definitions = column_definitions(parser)?;
+    let (column_definitions, table_constraints) = column_definitions(parser)?;
+    let without_rowid = without_rowid_clause(parser)?;
+    let primary_keys = column_definitions
+        .iter()
+        .flat_map(|column| column.constraints.iter())
+        .map(|constraint| &constraint.constraint_type)
+        .chain(
+            table_constraints
+                .iter()
+                .map(|constraint| &constraint.constraint_type),
+        )
+        .filter(|constraint_type| **constraint_type == ConstraintType::PrimaryKey)
+        .count();
+    if primary_keys > 1 {
+        return Err(format!(
+            "Table {} has more than one primary key",
+            table_name
+        ));
+    }
+    if without_rowid && primary_keys == 0 {
+        return Err(format!("PRIMARY KEY missing on table {}", table_name));
+    }
+    if column_definitions.iter().any(|column| {
+        column.data_type != DataType::Integer
+            && column
+                .constraints
+                .iter()
+                .any(|constraint| constraint.constraint_type == ConstraintType::AutoIncrement)
+    }) {
+        return Err("AUTOINCREMENT is only allowed on an INTEGER PRIMARY KEY".to_string());
+    }
+    for column in column_definitions.iter() {
+        check_default_value(column)?;
+    }
     return Ok(CreateTable(CreateTableStatement {
         table_name,
         existence_check,
         columns: column_definitions,
+        table_constraints,
+        without_rowid,
+    }));
+}
+
+pub fn check_default_value(column: &ColumnDefinition) -> Result<(), String> {
+    let default_type = column.default_value().get_type();
+    if default_type != DataType::Null && default_type != column.data_type {
+        return Err(format!(
+            "Default value of column {} is not of type {:?}",
+            column.name, column.data_type
+        ));
+    }
+    Ok(())
+}
+
+// Parses the optional `WITHOUT ROWID` table option.
+fn without_rowid_clause(parser: &mut Parser) -> Result<bool, String> {
+    if parser.current_token()?.token_type != TokenTypes::Without {
+        return Ok(false);
+    }
+    parser.advance()?;
+    let token = parser.current_token()?;
+    if token.token_type != TokenTypes::Identifier || !token.value.eq_ignore_ascii_case("ROWID") {
+        return Err(parser.format_error());
+    }
+    parser.advance()?;
+    Ok(true)
+}
+
+fn index_statement(parser: &mut Parser, unique: bool) -> Result<SqlStatement, String> {
+    parser.advance()?;
+    let existence_check = exists_clause(parser, ExistenceCheck::IfNotExists)?;
+
+    expect_token_type(parser, TokenTypes::Identifier)?;
+    let index_name = parser.current_token()?.value.to_string();
+    parser.advance()?;
+
+    expect_token_type(parser, TokenTypes::On)?;
+    parser.advance()?;
+    let (table_name, table_alias) = get_table_name(parser)?;
+    if !table_alias.is_empty() {
+        return Err("Table aliases in CREATE INDEX statement not allowed".to_string());
+    }
+
+    expect_token_type(parser, TokenTypes::LeftParen)?;
+    parser.advance()?;
+    let mut columns = vec![];
+    loop {
+        expect_token_type(parser, TokenTypes::Identifier)?;
+        columns.push(parser.current_token()?.value.to_string());
+        parser.advance()?;
+
+        let token = parser.current_token()?;
+        match token.token_type {
+            TokenTypes::Comma => parser.advance()?,
+            TokenTypes::RightParen => {
+                parser.advance()?;
+                break;
+            }
+            _ => return Err(parser.format_error()),
+        }
+    }
+
+    return Ok(CreateIndex(CreateIndexStatement {
+        index_name,
+        table_name,
+        existence_check,
+        columns,
+        unique,
     }));
 }
 
-fn column_definitions(parser: &mut Parser) -> Result<Vec<ColumnDefinition>, String> {
+fn column_definitions(
+    parser: &mut Parser,
+) -> Result<(Vec<ColumnDefinition>, Vec<TableConstraint>), String> {
     let mut columns: Vec<ColumnDefinition> = vec![];
+    let mut table_constraints = vec![];
 
     expect_token_type(parser, TokenTypes::LeftParen)?;
     parser.advance()?;
 
     loop {
         let token = parser.current_token()?;
-        expect_token_type(parser, TokenTypes::Identifier)?;
-        let column_name = token.value.to_string();
-        parser.advance()?;
+        if token.token_type == TokenTypes::Foreign {
+            table_foreign_key(parser, &mut columns)?;
+        } else if [TokenTypes::Primary, TokenTypes::Unique].contains(&token.token_type) {
+            if let Some(table_constraint) = table_key(parser, &mut columns)? {
+                table_constraints.push(table_constraint);
+            }
+        } else {
+            expect_token_type(parser, TokenTypes::Identifier)?;
+            let column_name = token.value.to_string();
+            parser.advance()?;
 
-        // Grab the column data type
-        let column_data_type = token_to_data_type(parser)?;
-        parser.advance()?;
+            // Grab the column data type
+            let column_data_type = token_to_data_type(parser)?;
+            parser.advance()?;
 
-        // TODO: Modifiers and Constraints
+            let constraints = column_constraints(parser)?;
+            columns.push(ColumnDefinition {
+                name: column_name,
+                data_type: column_data_type,
+                constraints,
+            });
+        }
 
         // Ensure we have a comma or right paren
         let token = parser.current_token()?;
         match token.token_type {
-            TokenTypes::Comma => {
-                columns.push(ColumnDefinition {
-                    name: column_name,
-                    data_type: column_data_type,
-                    constraints: vec![], // TODO,
-                });
+            TokenTypes::Comma => parser.advance()?,
+            TokenTypes::RightParen => {
                 parser.advance()?;
+                break;
             }
+            _ => return Err(parser.format_error()),
+        }
+    }
+    return Ok((columns, table_constraints));
+}
+
+// Parses a table constraint `PRIMARY KEY (columns)` or `UNIQUE (columns)`. A constraint on a
+// single column is added to the constraints of that column, one on several columns is returned.
+fn table_key(
+    parser: &mut Parser,
+    columns: &mut [ColumnDefinition],
+) -> Result<Option<TableConstraint>, String> {
+    let constraint_type = match parser.current_token()?.token_type {
+        TokenTypes::Primary => {
+            parser.advance()?;
+            expect_token_type(parser, TokenTypes::Key)?;
+            ConstraintType::PrimaryKey
+        }
+        _ => ConstraintType::Unique,
+    };
+    parser.advance()?;
+    expect_token_type(parser, TokenTypes::LeftParen)?;
+    parser.advance()?;
+    let mut key_columns = vec![];
+    loop {
+        expect_token_type(parser, TokenTypes::Identifier)?;
+        let column_name = parser.current_token()?.value.to_string();
+        if !columns.iter().any(|column| column.name == column_name) {
+            return Err(format!(
+                "Unknown column `{}` in key definition",
+                column_name
+            ));
+        }
+        if key_columns.contains(&column_name) {
+            return Err(format!("Column `{}` is repeated in key", column_name));
+        }
+        key_columns.push(column_name);
+        parser.advance()?;
+
+        match parser.current_token()?.token_type {
+            TokenTypes::Comma => parser.advance()?,
             TokenTypes::RightParen => {
-                columns.push(ColumnDefinition {
-                    name: column_name,
-                    data_type: column_data_type,
-                    constraints: vec![], // TODO,
-                });
                 parser.advance()?;
                 break;
             }
             _ => return Err(parser.format_error()),
         }
     }
-    return Ok(columns);
+    let conflict_action = conflict_clause(parser)?;
+
+    if let [column_name] = key_columns.as_slice() {
+        let column = columns
+            .iter_mut()
+            .find(|column| column.name == *column_name)
+            .ok_or_else(|| format!("Unknown column `{}`", column_name))?;
+        column.constraints.push(ColumnConstraint {
+            constraint_type,
+            conflict_action,
+            deferred: false,
+        });
+        return Ok(None);
+    }
+    Ok(Some(TableConstraint {
+        constraint_type,
+        columns: key_columns,
+        conflict_action,
+    }))
+}
+
+// Parses a table constraint `FOREIGN KEY (column) REFERENCES table(column)` and adds it to the
+// constraints of the column it is declared on, which must already be defined.
+fn table_foreign_key(parser: &mut Parser, columns: &mut [ColumnDefinition]) -> Result<(), String> {
+    parser.advance()?;
+    expect_token_type(parser, TokenTypes::Key)?;
+    parser.advance()?;
+    expect_token_type(parser, TokenTypes::LeftParen)?;
+    parser.advance()?;
+    expect_token_type(parser, TokenTypes::Identifier)?;
+    let column_name = parser.current_token()?.value.to_string();
+    parser.advance()?;
+    if parser.current_token()?.token_type == TokenTypes::Comma {
+        return Err("Foreign keys on several columns are not supported".to_string());
+    }
+    expect_token_type(parser, TokenTypes::RightParen)?;
+    parser.advance()?;
+
+    let constraint_type = references_clause(parser)?;
+    let deferred = deferrable_clause(parser)?;
+    let column = columns
+        .iter_mut()
+        .find(|column| column.name == column_name)
+        .ok_or_else(|| format!("Unknown column `{}` in foreign key definition", column_name))?;
+    column.constraints.push(ColumnConstraint {
+        constraint_type,
+        conflict_action: None,
+        deferred,
+    });
+    Ok(())
+}
+
+// Parses `REFERENCES table [(column)]`.
+fn references_clause(parser: &mut Parser) -> Result<ConstraintType, String> {
+    expect_token_type(parser, TokenTypes::References)?;
+    parser.advance()?;
+    expect_token_type(parser, TokenTypes::Identifier)?;
+    let table_name = parser.current_token()?.value.to_string();
+    parser.advance()?;
+
+    let mut column = None;
+    if parser.current_token()?.token_type == TokenTypes::LeftParen {
+        parser.advance()?;
+        expect_token_type(parser, TokenTypes::Identifier)?;
+        column = Some(parser.current_token()?.value.to_string());
+        parser.advance()?;
+        expect_token_type(parser, TokenTypes::RightParen)?;
+        parser.advance()?;
+    }
+    if parser.current_token()?.token_type == TokenTypes::On
+        && (parser.peek_is(1, TokenTypes::Delete) || parser.peek_is(1, TokenTypes::Update))
+    {
+        return Err(
+            "ON DELETE and ON UPDATE actions of foreign keys are not supported".to_string(),
+        );
+    }
+    Ok(ConstraintType::ForeignKey { table_name, column })
+}
+
+// Parses `DEFAULT value`, leaving the parser on the value.
+pub fn default_clause(parser: &mut Parser) -> Result<Value, String> {
+    parser.advance()?;
+    if parser.current_token()?.token_type == TokenTypes::LeftParen {
+        return Err("Only literal DEFAULT values are supported".to_string());
+    }
+    token_to_value(parser)
+}
+
+fn column_constraints(parser: &mut Parser) -> Result<Vec<ColumnConstraint>, String> {
+    let mut constraints: Vec<ColumnConstraint> = vec![];
+    loop {
+        let token = parser.current_token()?;
+        let constraint_type = match token.token_type {
+            TokenTypes::Primary => {
+                parser.advance()?;
+                expect_token_type(parser, TokenTypes::Key)?;
+                ConstraintType::PrimaryKey
+            }
+            TokenTypes::Not => {
+                parser.advance()?;
+                expect_token_type(parser, TokenTypes::Null)?;
+                ConstraintType::NotNull
+            }
+            TokenTypes::Unique => ConstraintType::Unique,
+            TokenTypes::AutoIncrement => {
+                let follows_primary_key = constraints.last().is_some_and(|constraint| {
+                    constraint.constraint_type == ConstraintType::PrimaryKey
+                });
+                if !follows_primary_key {
+                    return Err(parser.format_error());
+                }
+                ConstraintType::AutoIncrement
+            }
+            TokenTypes::As => {
+                constraints.push(ColumnConstraint {
+                    constraint_type: generated_clause(parser)?,
+                    conflict_action: None,
+                    deferred: false,
+                });
+                continue;
+            }
+            TokenTypes::Identifier if token.value.eq_ignore_ascii_case("GENERATED") => {
+                parser.advance()?;
+                let token = parser.current_token()?;
+                if token.token_type != TokenTypes::Identifier
+                    || !token.value.eq_ignore_ascii_case("ALWAYS")
+                {
+                    return Err(parser.format_error());
+                }
+                parser.advance()?;
+                expect_token_type(parser, TokenTypes::As)?;
+                constraints.push(ColumnConstraint {
+                    constraint_type: generated_clause(parser)?,
+                    conflict_action: None,
+                    deferred: false,
+                });
+                continue;
+            }
+            TokenTypes::Default => ConstraintType::Default(default_clause(parser)?),
+            TokenTypes::References => {
+                let constraint_type = references_clause(parser)?;
+                let deferred = deferrable_clause(parser)?;
+                constraints.push(ColumnConstraint {
+                    constraint_type,
+                    conflict_action: None,
+                    deferred,
+                });
+                continue;
+            }
+            _ => break,
+        };
+        parser.advance()?;
+        let conflict_action = conflict_clause(parser)?;
+        let deferred = deferrable_clause(parser)?;
+        constraints.push(ColumnConstraint {
+            constraint_type,
+            conflict_action,
+            deferred,
+        });
+    }
+    return Ok(constraints);
+}
+
+// Parses `AS (expression) [VIRTUAL]`, the end of a generated column definition.
+fn generated_clause(parser: &mut Parser) -> Result<ConstraintType, String> {
+    parser.advance()?;
+    expect_token_type(parser, TokenTypes::LeftParen)?;
+    parser.advance()?;
+    let mut expression = get_selectables(parser, false, false, &mut None)?;
+    if expression.len() != 1 {
+        return Err(parser.format_error());
+    }
+    expect_token_type(parser, TokenTypes::RightParen)?;
+    parser.advance()?;
+
+    let token = parser.current_token()?;
+    if token.token_type == TokenTypes::Identifier {
+        if token.value.eq_ignore_ascii_case("STORED") {
+            return Err("STORED generated columns are not supported".to_string());
+        }
+        if token.value.eq_ignore_ascii_case("VIRTUAL") {
+            parser.advance()?;
+        }
+    }
+    Ok(ConstraintType::Generated(expression.remove(0)))
+}
+
+// Parses an optional `ON CONFLICT <action>` clause attached to a constraint.
+fn conflict_clause(parser: &mut Parser) -> Result<Option<ConflictAction>, String> {
+    if parser.current_token()?.token_type != TokenTypes::On {
+        return Ok(None);
+    }
+    parser.advance()?;
+    expect_token_type(parser, TokenTypes::Conflict)?;
+    parser.advance()?;
+
+    let token = parser.current_token()?;
+    let conflict_action = match token.token_type {
+        TokenTypes::Rollback => ConflictAction::Rollback,
+        TokenTypes::Abort => ConflictAction::Abort,
+        TokenTypes::Fail => ConflictAction::Fail,
+        TokenTypes::Ignore => ConflictAction::Ignore,
+        TokenTypes::Replace => ConflictAction::Replace,
+        _ => return Err(parser.format_error()),
+    };
+    parser.advance()?;
+    return Ok(Some(conflict_action));
+}
+
+// Parses an optional `DEFERRABLE [INITIALLY DEFERRED | INITIALLY IMMEDIATE]` clause.
+// Returns whether the constraint is deferred, constraints are initially immediate by default.
+fn deferrable_clause(parser: &mut Parser) -> Result<bool, String> {
+    if parser.current_token()?.token_type != TokenTypes::Deferrable {
+        return Ok(false);
+    }
+    parser.advance()?;
+    if parser.current_token()?.token_type != TokenTypes::Initially {
+        return Ok(false);
+    }
+    parser.advance()?;
+
+    let deferred = match parser.current_token()?.token_type {
+        TokenTypes::Deferred => true,
+        TokenTypes::Immediate => false,
+        _ => return Err(parser.format_error()),
+    };
+    parser.advance()?;
+    return Ok(deferred);
 }
 
 #[cfg(test)]
@@ -94,6 +485,7 @@ mod tests {
     use crate::db::table::core::value::DataType;
     use crate::interpreter::ast::ExistenceCheck;
     use crate::interpreter::ast::test_utils::token;
+    use crate::interpreter::tokenizer::tokenize;
 
     #[test]
     fn create_table_generates_proper_statement() {
@@ -129,6 +521,134 @@ mod tests {
                     constraints: vec![],
                 },
             ],
+            table_constraints: vec![],
+            without_rowid: false,
+        });
+        assert_eq!(result.unwrap(), expected);
+    }
+
+    #[test]
+    fn create_table_parses_column_constraints_with_conflict_actions() {
+        // CREATE TABLE users (id INTEGER PRIMARY KEY ON CONFLICT REPLACE, email TEXT NOT NULL UNIQUE ON CONFLICT IGNORE);
+        let tokens = vec![
+            token(TokenTypes::Create, "CREATE"),
+            token(TokenTypes::Table, "TABLE"),
+            token(TokenTypes::Identifier, "users"),
+            token(TokenTypes::LeftParen, "("),
+            token(TokenTypes::Identifier, "id"),
+            token(TokenTypes::Integer, "INTEGER"),
+            token(TokenTypes::Primary, "PRIMARY"),
+            token(TokenTypes::Key, "KEY"),
+            token(TokenTypes::On, "ON"),
+            token(TokenTypes::Conflict, "CONFLICT"),
+            token(TokenTypes::Replace, "REPLACE"),
+            token(TokenTypes::Comma, ","),
+            token(TokenTypes::Identifier, "email"),
+            token(TokenTypes::Text, "TEXT"),
+            token(TokenTypes::Not, "NOT"),
+            token(TokenTypes::Null, "NULL"),
+            token(TokenTypes::Unique, "UNIQUE"),
+            token(TokenTypes::On, "ON"),
+            token(TokenTypes::Conflict, "CONFLICT"),
+            token(TokenTypes::Ignore, "IGNORE"),
+            token(TokenTypes::RightParen, ")"),
+            token(TokenTypes::SemiColon, ";"),
+            token(TokenTypes::EOF, ""),
+        ];
+        let mut parser = Parser::new(tokens);
+        let result = build(&mut parser);
+        let expected = SqlStatement::CreateTable(CreateTableStatement {
+            table_name: "users".to_string(),
+            existence_check: None,
+            columns: vec![
+                ColumnDefinition {
+                    name: "id".to_string(),
+                    data_type: DataType::Integer,
+                    constraints: vec![ColumnConstraint {
+                        constraint_type: ConstraintType::PrimaryKey,
+                        conflict_action: Some(ConflictAction::Replace),
+                        deferred: false,
+                    }],
+                },
+                ColumnDefinition {
+                    name: "email".to_string(),
+                    data_type: DataType::Text,
+                    constraints: vec![
+                        ColumnConstraint {
+                            constraint_type: ConstraintType::NotNull,
+                            conflict_action: None,
+                            deferred: false,
+                        },
+                        ColumnConstraint {
+                            constraint_type: ConstraintType::Unique,
+                            conflict_action: Some(ConflictAction::Ignore),
+                            deferred: false,
+                        },
+                    ],
+                },
+            ],
+            table_constraints: vec![],
+            without_rowid: false,
+        });
+        assert_eq!(result.unwrap(), expected);
+    }
+
+    #[test]
+    fn create_index_generates_proper_statement() {
+        // CREATE INDEX IF NOT EXISTS idx_users_name ON users (name, age);
+        let tokens = vec![
+            token(TokenTypes::Create, "CREATE"),
+            token(TokenTypes::Index, "INDEX"),
+            token(TokenTypes::If, "IF"),
+            token(TokenTypes::Not, "NOT"),
+            token(TokenTypes::Exists, "EXISTS"),
+            token(TokenTypes::Identifier, "idx_users_name"),
+            token(TokenTypes::On, "ON"),
+            token(TokenTypes::Identifier, "users"),
+            token(TokenTypes::LeftParen, "("),
+            token(TokenTypes::Identifier, "name"),
+            token(TokenTypes::Comma, ","),
+            token(TokenTypes::Identifier, "age"),
+            token(TokenTypes::RightParen, ")"),
+            token(TokenTypes::SemiColon, ";"),
+            token(TokenTypes::EOF, ""),
+        ];
+        let mut parser = Parser::new(tokens);
+        let result = build(&mut parser);
+        let expected = SqlStatement::CreateIndex(CreateIndexStatement {
+            index_name: "idx_users_name".to_string(),
+            table_name: "users".to_string(),
+            existence_check: Some(ExistenceCheck::IfNotExists),
+            columns: vec!["name".to_string(), "age".to_string()],
+            unique: false,
+        });
+        assert_eq!(result.unwrap(), expected);
+    }
+
+    #[test]
+    fn create_unique_index_statement_is_generated_correctly() {
+        // CREATE UNIQUE INDEX idx_users_email ON users (email);
+        let tokens = vec![
+            token(TokenTypes::Create, "CREATE"),
+            token(TokenTypes::Unique, "UNIQUE"),
+            token(TokenTypes::Index, "INDEX"),
+            token(TokenTypes::Identifier, "idx_users_email"),
+            token(TokenTypes::On, "ON"),
+            token(TokenTypes::Identifier, "users"),
+            token(TokenTypes::LeftParen, "("),
+            token(TokenTypes::Identifier, "email"),
+            token(TokenTypes::RightParen, ")"),
+            token(TokenTypes::SemiColon, ";"),
+            token(TokenTypes::EOF, ""),
+        ];
+        let mut parser = Parser::new(tokens);
+        let result = build(&mut parser);
+        let expected = SqlStatement::CreateIndex(CreateIndexStatement {
+            index_name: "idx_users_email".to_string(),
+            table_name: "users".to_string(),
+            existence_check: None,
+            columns: vec!["email".to_string()],
+            unique: true,
         });
         assert_eq!(result.unwrap(), expected);
     }
@@ -228,7 +748,185 @@ mod tests {
                 data_type: DataType::Integer,
                 constraints: vec![],
             }],
+            table_constraints: vec![],
+            without_rowid: false,
         });
         assert_eq!(expected, result.unwrap());
     }
+
+    #[test]
+    fn create_table_without_rowid_requires_a_primary_key() {
+        // CREATE TABLE users (id INTEGER PRIMARY KEY) WITHOUT ROWID;
+        let tokens = vec![
+            token(TokenTypes::Create, "CREATE"),
+            token(TokenTypes::Table, "TABLE"),
+            token(TokenTypes::Identifier, "users"),
+            token(TokenTypes::LeftParen, "("),
+            token(TokenTypes::Identifier, "id"),
+            token(TokenTypes::Integer, "INTEGER"),
+            token(TokenTypes::Primary, "PRIMARY"),
+            token(TokenTypes::Key, "KEY"),
+            token(TokenTypes::RightParen, ")"),
+            token(TokenTypes::Without, "WITHOUT"),
+            token(TokenTypes::Identifier, "ROWID"),
+            token(TokenTypes::SemiColon, ";"),
+            token(TokenTypes::EOF, ""),
+        ];
+        let mut parser = Parser::new(tokens);
+        let result = build(&mut parser);
+        let expected = SqlStatement::CreateTable(CreateTableStatement {
+            table_name: "users".to_string(),
+            existence_check: None,
+            columns: vec![ColumnDefinition {
+                name: "id".to_string(),
+                data_type: DataType::Integer,
+                constraints: vec![ColumnConstraint {
+                    constraint_type: ConstraintType::PrimaryKey,
+                    conflict_action: None,
+                    deferred: false,
+                }],
+            }],
+            table_constraints: vec![],
+            without_rowid: true,
+        });
+        assert_eq!(expected, result.unwrap());
+
+        // CREATE TABLE users (id INTEGER) WITHOUT ROWID;
+        let tokens = vec![
+            token(TokenTypes::Create, "CREATE"),
+            token(TokenTypes::Table, "TABLE"),
+            token(TokenTypes::Identifier, "users"),
+            token(TokenTypes::LeftParen, "("),
+            token(TokenTypes::Identifier, "id"),
+            token(TokenTypes::Integer, "INTEGER"),
+            token(TokenTypes::RightParen, ")"),
+            token(TokenTypes::Without, "WITHOUT"),
+            token(TokenTypes::Identifier, "ROWID"),
+            token(TokenTypes::SemiColon, ";"),
+            token(TokenTypes::EOF, ""),
+        ];
+        let mut parser = Parser::new(tokens);
+        assert_eq!(
+            build(&mut parser),
+            Err("PRIMARY KEY missing on table users".to_string())
+        );
+    }
+
+    #[test]
+    fn create_table_parses_foreign_keys() {
+        let sql = "CREATE TABLE orders (id INTEGER, user_id INTEGER REFERENCES users(id), \
+            item_id INTEGER, FOREIGN KEY (item_id) REFERENCES items DEFERRABLE INITIALLY DEFERRED);";
+        let mut parser = Parser::new(tokenize(sql));
+        let Ok(SqlStatement::CreateTable(statement)) = build(&mut parser) else {
+            panic!("Expected a CREATE TABLE statement");
+        };
+        let constraints = statement
+            .columns
+            .iter()
+            .map(|column| column.constraints.clone())
+            .collect::<Vec<Vec<ColumnConstraint>>>();
+        assert_eq!(
+            constraints,
+            vec![
+                vec![],
+                vec![ColumnConstraint {
+                    constraint_type: ConstraintType::ForeignKey {
+                        table_name: "users".to_string(),
+                        column: Some("id".to_string()),
+                    },
+                    conflict_action: None,
+                    deferred: false,
+                }],
+                vec![ColumnConstraint {
+                    constraint_type: ConstraintType::ForeignKey {
+                        table_name: "items".to_string(),
+                        column: None,
+                    },
+                    conflict_action: None,
+                    deferred: true,
+                }],
+            ]
+        );
+
+        let errors = vec![
+            "CREATE TABLE orders (id INTEGER, FOREIGN KEY (user_id) REFERENCES users(id));",
+            "CREATE TABLE orders (a INTEGER, b INTEGER, FOREIGN KEY (a, b) REFERENCES users);",
+            "CREATE TABLE orders (user_id INTEGER REFERENCES users(id) ON DELETE CASCADE);",
+        ];
+        for sql in errors {
+            let mut parser = Parser::new(tokenize(sql));
+            assert!(build(&mut parser).is_err(), "{}", sql);
+        }
+    }
+
+    #[test]
+    fn create_table_parses_table_level_keys() {
+        let sql = "CREATE TABLE grades (student INTEGER, course INTEGER, grade INTEGER, \
+            PRIMARY KEY (student, course), UNIQUE (grade) ON CONFLICT IGNORE);";
+        let mut parser = Parser::new(tokenize(sql));
+        let Ok(SqlStatement::CreateTable(statement)) = build(&mut parser) else {
+            panic!("Expected a CREATE TABLE statement");
+        };
+        assert_eq!(
+            statement.table_constraints,
+            vec![TableConstraint {
+                constraint_type: ConstraintType::PrimaryKey,
+                columns: vec!["student".to_string(), "course".to_string()],
+                conflict_action: None,
+            }]
+        );
+        assert_eq!(
+            statement.columns[2].constraints,
+            vec![ColumnConstraint {
+                constraint_type: ConstraintType::Unique,
+                conflict_action: Some(ConflictAction::Ignore),
+                deferred: false,
+            }]
+        );
+
+        let errors = vec![
+            "CREATE TABLE t (a INTEGER, PRIMARY KEY (a, b));",
+            "CREATE TABLE t (a INTEGER, b INTEGER, UNIQUE (a, a));",
+            "CREATE TABLE t (a INTEGER PRIMARY KEY, b INTEGER, PRIMARY KEY (a, b));",
+            "CREATE TABLE t (a INTEGER, b INTEGER, PRIMARY KEY ());",
+        ];
+        for sql in errors {
+            let mut parser = Parser::new(tokenize(sql));
+            assert!(build(&mut parser).is_err(), "{}", sql);
+        }
+    }
+
+    #[test]
+    fn create_table_parses_generated_columns() {
+        let sql = "CREATE TABLE items (price INTEGER, quantity INTEGER, \
+            total INTEGER GENERATED ALWAYS AS (price * quantity) VIRTUAL, half REAL AS (total / 2));";
+        let mut parser = Parser::new(tokenize(sql));
+        let Ok(SqlStatement::CreateTable(statement)) = build(&mut parser) else {
+            panic!("Expected a CREATE TABLE statement");
+        };
+        let expressions = statement
+            .columns
+            .iter()
+            .map(|column| column.generated_expression().map(|e| e.column_name.clone()))
+            .collect::<Vec<Option<String>>>();
+        assert_eq!(
+            expressions,
+            vec![
+                None,
+                None,
+                Some("price * quantity".to_string()),
+                Some("total / 2".to_string()),
+            ]
+        );
+
+        let errors = vec![
+            "CREATE TABLE t (a INTEGER, b INTEGER AS (a * 2) STORED);",
+            "CREATE TABLE t (a INTEGER, b INTEGER GENERATED AS (a * 2));",
+            "CREATE TABLE t (a INTEGER, b INTEGER AS a * 2);",
+        ];
+        for sql in errors {
+            let mut parser = Parser::new(tokenize(sql));
+            assert!(build(&mut parser).is_err(), "{}", sql);
+        }
+    }
 }
@@ -3,7 +3,8 @@ use crate::interpreter::{
         DeleteStatement, SqlStatement, TableAliases,
         helpers::{
             common::get_table_name, limit_clause::get_limit, order_by_clause::get_order_by,
-            token::expect_token_type, where_clause::get_where_clause,
+            returning_clause::get_returning, token::expect_token_type,
+            where_clause::get_where_clause,
         },
         parser::Parser,
     },
@@ -23,6 +24,7 @@ pub fn build(parser: &mut Parser) -> Result<SqlStatement, String> {
     let where_clause = get_where_clause(parser)?;
     let order_by_clause = get_order_by(parser)?;
     let limit_clause = get_limit(parser)?;
+    let returning = get_returning(parser)?;
 
     return Ok(SqlStatement::DeleteStatement(DeleteStatement {
         table_name: table_name,
@@ -30,6 +32,7 @@ pub fn build(parser: &mut Parser) -> Result<SqlStatement, String> {
         where_clause: where_clause,
         order_by_clause: order_by_clause,
         limit_clause: limit_clause,
+        returning,
     }));
 }
 
@@ -64,6 +67,7 @@ mod tests {
             where_clause: None,
             order_by_clause: None,
             limit_clause: None,
+            returning: None,
         });
         assert_eq!(expected, statement);
     }
@@ -115,7 +119,46 @@ mod tests {
                 limit: 10,
                 offset: Some(5),
             }),
+            returning: None,
         });
         assert_eq!(expected, statement);
     }
+
+    #[test]
+    fn delete_statement_with_returning_is_generated_correctly() {
+        // DELETE FROM users WHERE id = 1 RETURNING *;
+        let tokens = vec![
+            token(TokenTypes::Delete, "DELETE"),
+            token(TokenTypes::From, "FROM"),
+            token(TokenTypes::Identifier, "users"),
+            token(TokenTypes::Where, "WHERE"),
+            token(TokenTypes::Identifier, "id"),
+            token(TokenTypes::Equals, "="),
+            token(TokenTypes::IntLiteral, "1"),
+            token(TokenTypes::Returning, "RETURNING"),
+            token(TokenTypes::Asterisk, "*"),
+            token(TokenTypes::SemiColon, ";"),
+        ];
+        let mut parser = Parser::new(tokens);
+        let result = build(&mut parser);
+        let expected = SqlStatement::DeleteStatement(DeleteStatement {
+            table_name: "users".to_string(),
+            table_aliases: TableAliases(HashMap::new()),
+            where_clause: Some(SelectableColumn {
+                selectables: vec![
+                    SelectableStackElement::Column("id".to_string()),
+                    SelectableStackElement::Value(Value::Integer(1)),
+                    SelectableStackElement::Operator(Operator::Equals),
+                ],
+                column_name: "id = 1".to_string(),
+            }),
+            order_by_clause: None,
+            limit_clause: None,
+            returning: Some(vec![SelectableColumn {
+                selectables: vec![SelectableStackElement::All],
+                column_name: "*".to_string(),
+            }]),
+        });
+        assert_eq!(result, Ok(expected));
+    }
 }
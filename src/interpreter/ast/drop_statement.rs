@@ -1,6 +1,6 @@
 use crate::interpreter::{
     ast::{
-        DropTableStatement, ExistenceCheck, SqlStatement,
+        DropIndexStatement, DropTableStatement, ExistenceCheck, SqlStatement,
         helpers::common::{exists_clause, get_table_name},
         helpers::token::expect_token_type,
         parser::Parser,
@@ -10,7 +10,15 @@ use crate::interpreter::{
 
 pub fn build(parser: &mut Parser) -> Result<SqlStatement, String> {
     parser.advance()?;
-    expect_token_type(parser, TokenTypes::Table)?;
+    let token = parser.current_token()?;
+    match token.token_type {
+        TokenTypes::Table => table_statement(parser),
+        TokenTypes::Index => index_statement(parser),
+        _ => Err(parser.format_error()),
+    }
+}
+
+fn table_statement(parser: &mut Parser) -> Result<SqlStatement, String> {
     parser.advance()?;
 
     let existence_check = exists_clause(parser, ExistenceCheck::IfExists)?;
@@ -24,6 +32,19 @@ pub fn build(parser: &mut Parser) -> Result<SqlStatement, String> {
     }));
 }
 
+fn index_statement(parser: &mut Parser) -> Result<SqlStatement, String> {
+    parser.advance()?;
+
+    let existence_check = exists_clause(parser, ExistenceCheck::IfExists)?;
+    expect_token_type(parser, TokenTypes::Identifier)?;
+    let index_name = parser.current_token()?.value.to_string();
+    parser.advance()?;
+    return Ok(SqlStatement::DropIndex(DropIndexStatement {
+        index_name: index_name,
+        existence_check: existence_check,
+    }));
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -70,4 +91,26 @@ mod tests {
         });
         assert_eq!(expected, statement);
     }
+
+    #[test]
+    fn drop_index_statement_with_if_exists_clause_is_generated_correctly() {
+        // DROP INDEX IF EXISTS idx_users_name;
+        let tokens = vec![
+            token(TokenTypes::Drop, "DROP"),
+            token(TokenTypes::Index, "INDEX"),
+            token(TokenTypes::If, "IF"),
+            token(TokenTypes::Exists, "EXISTS"),
+            token(TokenTypes::Identifier, "idx_users_name"),
+            token(TokenTypes::SemiColon, ";"),
+        ];
+        let mut parser = Parser::new(tokens);
+        let result = build(&mut parser);
+        assert!(result.is_ok());
+        let statement = result.unwrap();
+        let expected = SqlStatement::DropIndex(DropIndexStatement {
+            index_name: "idx_users_name".to_string(),
+            existence_check: Some(ExistenceCheck::IfExists),
+        });
+        assert_eq!(expected, statement);
+    }
 }
@@ -0,0 +1,47 @@
+use crate::interpreter::ast::{SqlStatement, parser::Parser, select_statement_stack};
+use crate::interpreter::tokenizer::token::TokenTypes;
+
+// Only `EXPLAIN QUERY PLAN <select>` is supported. QUERY and PLAN are not keywords, so they are
+// read as identifiers and can still be used as names elsewhere.
+pub fn build(parser: &mut Parser) -> Result<SqlStatement, String> {
+    parser.advance()?;
+    for word in ["QUERY", "PLAN"] {
+        let token = parser.current_token()?;
+        if token.token_type != TokenTypes::Identifier || !token.value.eq_ignore_ascii_case(word) {
+            return Err(format!(
+                "{} (only EXPLAIN QUERY PLAN is supported)",
+                parser.format_error()
+            ));
+        }
+        parser.advance()?;
+    }
+    match select_statement_stack::build(parser)? {
+        SqlStatement::Select(statement) => Ok(SqlStatement::ExplainQueryPlan(statement)),
+        _ => Err(parser.format_error()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::interpreter::tokenizer::tokenize;
+
+    #[test]
+    fn explain_query_plan_wraps_a_select_statement() {
+        let select = "SELECT * FROM users WHERE id = 1;";
+        let sql = format!("EXPLAIN query PLAN {}", select);
+        let mut parser = Parser::new(tokenize(&sql));
+        let result = build(&mut parser);
+        let expected = select_statement_stack::build(&mut Parser::new(tokenize(select)));
+        match (result, expected) {
+            (Ok(SqlStatement::ExplainQueryPlan(result)), Ok(SqlStatement::Select(expected))) => {
+                assert_eq!(result, expected)
+            }
+            (result, _) => panic!("Unexpected result: {:?}", result),
+        }
+
+        let sql = format!("EXPLAIN {}", select);
+        let mut parser = Parser::new(tokenize(&sql));
+        assert!(build(&mut parser).is_err());
+    }
+}
@@ -0,0 +1,21 @@
+use crate::interpreter::ast::helpers::common::get_selectables;
+use crate::interpreter::ast::{GroupByClause, parser::Parser};
+use crate::interpreter::tokenizer::token::TokenTypes;
+
+use crate::interpreter::ast::helpers::token::expect_token_type;
+
+pub fn get_group_by(parser: &mut Parser) -> Result<Option<GroupByClause>, String> {
+    if expect_token_type(parser, TokenTypes::Group).is_err() {
+        return Ok(None);
+    }
+    parser.advance()?;
+
+    expect_token_type(parser, TokenTypes::By)?;
+    parser.advance()?;
+
+    let columns = get_selectables(parser, true, false, &mut None)?;
+    if columns.is_empty() {
+        return Err(parser.format_error());
+    }
+    Ok(Some(GroupByClause { columns }))
+}
@@ -10,14 +10,15 @@ pub fn get_limit(parser: &mut Parser) -> Result<Option<LimitClause>, String> {
     parser.advance()?;
 
     expect_token_type(parser, TokenTypes::IntLiteral)?;
-    let limit = match token_to_value(parser)? {
-        Value::Integer(v) => {
+    let limit = match token_to_value(parser) {
+        Ok(Value::Integer(v)) => {
             if v < 0 {
                 return Err("Invalid LIMIT value: must be positive.".to_string());
             }
             v as usize
         }
-        _ => return Err("Invalid LIMIT value: must be integer.".to_string()),
+        Ok(_) => return Err("Invalid LIMIT value: must be integer.".to_string()),
+        Err(error) => get_large_bound(parser).ok_or(error)?,
     };
     parser.advance()?;
 
@@ -31,14 +32,15 @@ pub fn get_limit(parser: &mut Parser) -> Result<Option<LimitClause>, String> {
     parser.advance()?;
 
     expect_token_type(parser, TokenTypes::IntLiteral)?;
-    let offset = match token_to_value(parser)? {
-        Value::Integer(v) => {
+    let offset = match token_to_value(parser) {
+        Ok(Value::Integer(v)) => {
             if v < 0 {
                 return Err(parser.format_error());
             }
             v as usize
         }
-        _ => return Err(parser.format_error()),
+        Ok(_) => return Err(parser.format_error()),
+        Err(error) => get_large_bound(parser).ok_or(error)?,
     };
     parser.advance()?;
 
@@ -48,6 +50,11 @@ pub fn get_limit(parser: &mut Parser) -> Result<Option<LimitClause>, String> {
     }));
 }
 
+// Integer literals too large for an INTEGER are still accepted as a bound, up to usize::MAX.
+fn get_large_bound(parser: &Parser) -> Option<usize> {
+    parser.current_token().ok()?.value.parse::<usize>().ok()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
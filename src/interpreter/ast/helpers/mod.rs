@@ -1,6 +1,8 @@
 pub mod common;
+pub mod group_by_clause;
 pub mod limit_clause;
 pub mod order_by_clause;
+pub mod returning_clause;
 pub mod select_statement;
 pub mod selectables;
 pub mod token;
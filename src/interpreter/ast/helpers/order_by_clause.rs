@@ -1,5 +1,7 @@
 use crate::interpreter::ast::helpers::common::get_selectables;
-use crate::interpreter::ast::{OrderByClause, parser::Parser};
+use crate::interpreter::ast::{
+    FunctionName, OrderByClause, SelectableStackElement, parser::Parser,
+};
 use crate::interpreter::tokenizer::token::TokenTypes;
 
 use crate::interpreter::ast::helpers::token::expect_token_type;
@@ -14,7 +16,34 @@ pub fn get_order_by(parser: &mut Parser) -> Result<Option<OrderByClause>, String
     parser.advance()?;
 
     let mut directions = vec![];
-    let columns = get_selectables(parser, true, false, &mut Some(&mut directions))?;
+    let mut columns = get_selectables(parser, true, false, &mut Some(&mut directions))?;
+
+    // A term repeating an earlier one can't break a tie the earlier one left, so it is dropped.
+    // RANDOM() is the exception, as it evaluates to a new value every time.
+    if columns.len() == directions.len() {
+        let mut index = 0;
+        while index < columns.len() {
+            let is_random = columns[index]
+                .selectables
+                .iter()
+                .any(|selectable| match selectable {
+                    SelectableStackElement::Function(function) => {
+                        function.name == FunctionName::Random
+                    }
+                    _ => false,
+                });
+            if !is_random
+                && columns[..index]
+                    .iter()
+                    .any(|column| column.selectables == columns[index].selectables)
+            {
+                columns.remove(index);
+                directions.remove(index);
+            } else {
+                index += 1;
+            }
+        }
+    }
 
     return Ok(Some(OrderByClause {
         columns: columns,
@@ -26,7 +55,10 @@ pub fn get_order_by(parser: &mut Parser) -> Result<Option<OrderByClause>, String
 mod tests {
     use super::*;
     use crate::interpreter::ast::test_utils::token;
-    use crate::interpreter::ast::{OrderByDirection, SelectableColumn, SelectableStackElement};
+    use crate::interpreter::ast::{
+        MathOperator, OrderByDirection, SelectableColumn, SelectableStackElement,
+    };
+    use crate::interpreter::tokenizer::tokenize;
 
     #[test]
     fn order_by_clause_is_generated_correctly() {
@@ -101,4 +133,62 @@ mod tests {
         });
         assert_eq!(expected, order_by_clause);
     }
+
+    #[test]
+    fn order_by_clause_with_parenthesized_expression_is_generated_correctly() {
+        // ORDER BY (age + money) DESC) ...
+        let tokens = vec![
+            token(TokenTypes::Order, "ORDER"),
+            token(TokenTypes::By, "BY"),
+            token(TokenTypes::LeftParen, "("),
+            token(TokenTypes::Identifier, "age"),
+            token(TokenTypes::Plus, "+"),
+            token(TokenTypes::Identifier, "money"),
+            token(TokenTypes::RightParen, ")"),
+            token(TokenTypes::Desc, "DESC"),
+            token(TokenTypes::RightParen, ")"),
+        ];
+        let mut parser = Parser::new(tokens);
+        let result = get_order_by(&mut parser);
+        assert!(result.is_ok(), "{:?}", result);
+        let expected = Some(OrderByClause {
+            columns: vec![SelectableColumn {
+                selectables: vec![
+                    SelectableStackElement::Column("age".to_string()),
+                    SelectableStackElement::Column("money".to_string()),
+                    SelectableStackElement::MathOperator(MathOperator::Add),
+                ],
+                column_name: "(age + money)".to_string(),
+            }],
+            directions: vec![OrderByDirection::Desc],
+        });
+        assert_eq!(expected, result.unwrap());
+        assert_eq!(
+            parser.current_token().unwrap().token_type,
+            TokenTypes::RightParen
+        );
+    }
+
+    #[test]
+    fn order_by_clause_drops_repeated_terms() {
+        let mut parser = Parser::new(tokenize(
+            "ORDER BY age DESC, name, age ASC, RANDOM(), RANDOM();",
+        ));
+        let order_by_clause = get_order_by(&mut parser).unwrap().unwrap();
+        let names = order_by_clause
+            .columns
+            .iter()
+            .map(|column| column.column_name.as_str())
+            .collect::<Vec<&str>>();
+        assert_eq!(names, vec!["age", "name", "RANDOM()", "RANDOM()"]);
+        assert_eq!(
+            order_by_clause.directions,
+            vec![
+                OrderByDirection::Desc,
+                OrderByDirection::Asc,
+                OrderByDirection::Asc,
+                OrderByDirection::Asc
+            ]
+        );
+    }
 }
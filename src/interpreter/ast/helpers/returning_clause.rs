@@ -0,0 +1,60 @@
+use crate::interpreter::ast::helpers::common::get_selectables;
+use crate::interpreter::ast::{SelectableColumn, parser::Parser};
+use crate::interpreter::tokenizer::token::TokenTypes;
+
+use crate::interpreter::ast::helpers::token::expect_token_type;
+
+// RETURNING takes the same columns as a SELECT, including * and aliases.
+pub fn get_returning(parser: &mut Parser) -> Result<Option<Vec<SelectableColumn>>, String> {
+    if expect_token_type(parser, TokenTypes::Returning).is_err() {
+        return Ok(None);
+    }
+    parser.advance()?;
+
+    let columns = get_selectables(parser, true, true, &mut None)?;
+    if columns.is_empty() {
+        return Err(parser.format_error());
+    }
+    Ok(Some(columns))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::interpreter::ast::SelectableStackElement;
+    use crate::interpreter::ast::test_utils::token;
+
+    #[test]
+    fn returning_clause_is_generated_correctly() {
+        // RETURNING *, id AS n;
+        let tokens = vec![
+            token(TokenTypes::Returning, "RETURNING"),
+            token(TokenTypes::Asterisk, "*"),
+            token(TokenTypes::Comma, ","),
+            token(TokenTypes::Identifier, "id"),
+            token(TokenTypes::As, "AS"),
+            token(TokenTypes::Identifier, "n"),
+            token(TokenTypes::SemiColon, ";"),
+        ];
+        let mut parser = Parser::new(tokens);
+        let result = get_returning(&mut parser);
+        let expected = vec![
+            SelectableColumn {
+                selectables: vec![SelectableStackElement::All],
+                column_name: "*".to_string(),
+            },
+            SelectableColumn {
+                selectables: vec![SelectableStackElement::Column("id".to_string())],
+                column_name: "n".to_string(),
+            },
+        ];
+        assert_eq!(result, Ok(Some(expected)));
+
+        // RETURNING;
+        let tokens = vec![
+            token(TokenTypes::Returning, "RETURNING"),
+            token(TokenTypes::SemiColon, ";"),
+        ];
+        assert!(get_returning(&mut Parser::new(tokens)).is_err());
+    }
+}
@@ -1,11 +1,12 @@
 use crate::interpreter::{
     ast::{
-        SelectMode, SelectStatement, SelectableColumn, TableAliases,
+        JoinClause, JoinType, SelectMode, SelectStatement, SelectableColumn,
+        SelectableStackElement, TableAliases,
         helpers::{
-            common::{get_selectables, get_table_name},
+            common::{expect_token_type, get_selectables, get_table_name},
+            group_by_clause::get_group_by,
             limit_clause::get_limit,
             order_by_clause::get_order_by,
-            token::expect_token_type,
             where_clause::get_where_clause,
         },
         parser::Parser,
@@ -24,28 +25,122 @@ pub fn get_statement(parser: &mut Parser) -> Result<SelectStatement, String> {
         _ => SelectMode::All,
     };
     let columns = get_columns_and_names(parser)?;
-    expect_token_type(parser, TokenTypes::From)?; // TODO: this is not true, you can do SELECT 1;
-    parser.advance()?;
-    let (table_name, table_alias) = get_table_name(parser)?;
+    if columns.is_empty() {
+        return Err(parser.format_error());
+    }
+    // Without a FROM clause the table name is left empty, e.g. SELECT 1;
+    let mut table_name = String::new();
     let mut aliases = HashMap::new();
-    if table_alias != "" {
-        aliases.insert(table_alias, table_name.clone());
+    let mut joins = vec![];
+    if parser.current_token()?.token_type == TokenTypes::From {
+        parser.advance()?;
+        let (name, table_alias) = get_from_table_name(parser)?;
+        if !table_alias.is_empty() {
+            aliases.insert(table_alias, name.clone());
+        }
+        table_name = name;
+        joins = get_joins(parser, &mut aliases)?;
+    } else if columns
+        .iter()
+        .any(|column| column.selectables.first() == Some(&SelectableStackElement::All))
+    {
+        // SELECT * needs a table to select from
+        return Err(parser.format_error());
     }
     let where_clause = get_where_clause(parser)?;
+    let group_by_clause = get_group_by(parser)?;
     let order_by_clause = get_order_by(parser)?;
     let limit_clause = get_limit(parser)?;
 
     return Ok(SelectStatement {
         table_name: table_name,
         table_aliases: TableAliases(aliases),
+        joins,
         mode: mode,
         columns: columns,
         where_clause: where_clause,
+        group_by_clause,
         order_by_clause: order_by_clause,
         limit_clause: limit_clause,
     });
 }
 
+// Tables of the FROM clause may be aliased without AS, e.g. `FROM users u`.
+fn get_from_table_name(parser: &mut Parser) -> Result<(String, String), String> {
+    let (table_name, mut table_alias) = get_table_name(parser)?;
+    let token = parser.current_token()?;
+    if table_alias.is_empty() && token.token_type == TokenTypes::Identifier {
+        table_alias = token.value.to_string();
+        parser.advance()?;
+    }
+    Ok((table_name, table_alias))
+}
+
+// Parses the tables following the first one of the FROM clause. A comma between two tables is
+// the same as CROSS JOIN, and JOIN without a type is an INNER JOIN.
+fn get_joins(
+    parser: &mut Parser,
+    aliases: &mut HashMap<String, String>,
+) -> Result<Vec<JoinClause>, String> {
+    let mut joins = vec![];
+    loop {
+        let join_type = match parser.current_token()?.token_type {
+            TokenTypes::Comma => {
+                parser.advance()?;
+                JoinType::Cross
+            }
+            TokenTypes::Cross => {
+                parser.advance()?;
+                expect_token_type(parser, TokenTypes::Join)?;
+                parser.advance()?;
+                JoinType::Cross
+            }
+            TokenTypes::Inner => {
+                parser.advance()?;
+                expect_token_type(parser, TokenTypes::Join)?;
+                parser.advance()?;
+                JoinType::Inner
+            }
+            TokenTypes::Join => {
+                parser.advance()?;
+                JoinType::Inner
+            }
+            TokenTypes::Left => {
+                parser.advance()?;
+                if parser.current_token()?.token_type == TokenTypes::Outer {
+                    parser.advance()?;
+                }
+                expect_token_type(parser, TokenTypes::Join)?;
+                parser.advance()?;
+                JoinType::Left
+            }
+            _ => break,
+        };
+        let (table_name, table_alias) = get_from_table_name(parser)?;
+        if !table_alias.is_empty() {
+            aliases.insert(table_alias.clone(), table_name.clone());
+        }
+        let on_clause = match parser.current_token()?.token_type {
+            TokenTypes::On => {
+                parser.advance()?;
+                let mut condition = get_selectables(parser, false, false, &mut None)?;
+                if condition.len() != 1 {
+                    return Err("Invalid ON condition".to_string());
+                }
+                condition.pop()
+            }
+            _ => None,
+        };
+        joins.push(JoinClause {
+            join_type,
+            table_name,
+            table_alias,
+            on_clause,
+        });
+    }
+    Ok(joins)
+}
+
 fn get_columns_and_names(parser: &mut Parser) -> Result<Vec<SelectableColumn>, String> {
     Ok(get_selectables(parser, true, true, &mut None)?)
 }
@@ -58,7 +153,7 @@ mod tests {
     use crate::interpreter::ast::{
         FunctionCall, FunctionName, LimitClause, LogicalOperator, MathOperator, Operator,
     };
-    use crate::interpreter::ast::{OrderByClause, OrderByDirection, SelectableStackElement};
+    use crate::interpreter::ast::{GroupByClause, OrderByClause, OrderByDirection};
 
     #[test]
     fn select_statement_with_all_tokens_is_generated_correctly() {
@@ -79,12 +174,14 @@ mod tests {
             SelectStatement {
                 table_name: "users".to_string(),
                 table_aliases: TableAliases(HashMap::new()),
+                joins: vec![],
                 mode: SelectMode::All,
                 columns: vec![SelectableColumn {
                     selectables: vec![SelectableStackElement::All],
                     column_name: "*".to_string(),
                 }],
                 where_clause: None,
+                group_by_clause: None,
                 order_by_clause: None,
                 limit_clause: None,
             }
@@ -110,12 +207,14 @@ mod tests {
             SelectStatement {
                 table_name: "guests".to_string(),
                 table_aliases: TableAliases(HashMap::new()),
+                joins: vec![],
                 mode: SelectMode::All,
                 columns: vec![SelectableColumn {
                     selectables: vec![SelectableStackElement::Column("id".to_string())],
                     column_name: "id".to_string(),
                 }],
                 where_clause: None,
+                group_by_clause: None,
                 order_by_clause: None,
                 limit_clause: None,
             }
@@ -143,6 +242,7 @@ mod tests {
             SelectStatement {
                 table_name: "users".to_string(),
                 table_aliases: TableAliases(HashMap::new()),
+                joins: vec![],
                 mode: SelectMode::All,
                 columns: vec![
                     SelectableColumn {
@@ -155,6 +255,7 @@ mod tests {
                     },
                 ],
                 where_clause: None,
+                group_by_clause: None,
                 order_by_clause: None,
                 limit_clause: None,
             }
@@ -195,6 +296,7 @@ mod tests {
         let expected = SelectStatement {
             table_name: "guests".to_string(),
             table_aliases: TableAliases(HashMap::new()),
+            joins: vec![],
             mode: SelectMode::All,
             columns: vec![SelectableColumn {
                 selectables: vec![SelectableStackElement::Column("id".to_string())],
@@ -208,6 +310,7 @@ mod tests {
                 ],
                 column_name: "id = 1".to_string(),
             }),
+            group_by_clause: None,
             order_by_clause: Some(OrderByClause {
                 columns: vec![
                     SelectableColumn {
@@ -257,12 +360,14 @@ mod tests {
             SelectStatement {
                 table_name: "guests".to_string(),
                 table_aliases: TableAliases(HashMap::new()),
+                joins: vec![],
                 mode: SelectMode::Distinct,
                 columns: vec![SelectableColumn {
                     selectables: vec![SelectableStackElement::Column("id".to_string())],
                     column_name: "id".to_string(),
                 }],
                 where_clause: None,
+                group_by_clause: None,
                 order_by_clause: None,
                 limit_clause: None,
             }
@@ -328,6 +433,7 @@ mod tests {
         let expected = SelectStatement {
             table_name: "people".to_string(),
             table_aliases: TableAliases(HashMap::new()),
+            joins: vec![],
             mode: SelectMode::Distinct,
             columns: vec![
                 SelectableColumn {
@@ -356,7 +462,7 @@ mod tests {
                         SelectableStackElement::MathOperator(MathOperator::Subtract),
                         SelectableStackElement::MathOperator(MathOperator::Multiply),
                     ],
-                    column_name: "2 * ( ( age - ( id % age - id / money ) ) )".to_string(),
+                    column_name: "2 * ((age - (id % age - id / money)))".to_string(),
                 },
                 SelectableColumn {
                     selectables: vec![
@@ -377,6 +483,7 @@ mod tests {
                 },
             ],
             where_clause: None,
+            group_by_clause: None,
             order_by_clause: Some(OrderByClause {
                 columns: vec![SelectableColumn {
                     selectables: vec![
@@ -414,6 +521,7 @@ mod tests {
         let expected = SelectStatement {
             table_name: "users".to_string(),
             table_aliases: TableAliases(HashMap::new()),
+            joins: vec![],
             mode: SelectMode::All,
             columns: vec![
                 SelectableColumn {
@@ -426,6 +534,7 @@ mod tests {
                 },
             ],
             where_clause: None,
+            group_by_clause: None,
             order_by_clause: None,
             limit_clause: Some(LimitClause {
                 limit: 5,
@@ -456,6 +565,7 @@ mod tests {
         let expected = SelectStatement {
             table_name: "users".to_string(),
             table_aliases: TableAliases(HashMap::new()),
+            joins: vec![],
             mode: SelectMode::All,
             columns: vec![SelectableColumn {
                 selectables: vec![SelectableStackElement::Column("name".to_string())],
@@ -469,6 +579,7 @@ mod tests {
                 ],
                 column_name: "name = 'John'".to_string(),
             }),
+            group_by_clause: None,
             order_by_clause: None,
             limit_clause: None,
         };
@@ -495,6 +606,7 @@ mod tests {
         let expected = SelectStatement {
             table_name: "users".to_string(),
             table_aliases: TableAliases(HashMap::new()),
+            joins: vec![],
             mode: SelectMode::Distinct,
             columns: vec![
                 SelectableColumn {
@@ -507,6 +619,7 @@ mod tests {
                 },
             ],
             where_clause: None,
+            group_by_clause: None,
             order_by_clause: None,
             limit_clause: None,
         };
@@ -536,6 +649,7 @@ mod tests {
         let expected = SelectStatement {
             table_name: "users".to_string(),
             table_aliases: TableAliases(HashMap::new()),
+            joins: vec![],
             mode: SelectMode::All,
             columns: vec![
                 SelectableColumn {
@@ -548,6 +662,7 @@ mod tests {
                 },
             ],
             where_clause: None,
+            group_by_clause: None,
             order_by_clause: Some(OrderByClause {
                 columns: vec![SelectableColumn {
                     selectables: vec![SelectableStackElement::Column("name".to_string())],
@@ -560,6 +675,69 @@ mod tests {
         assert_eq!(expected, statement);
     }
 
+    #[test]
+    fn select_statement_with_joined_tables_is_generated_correctly() {
+        // SELECT u.id FROM users AS u, orders CROSS JOIN items;
+        let tokens = vec![
+            token(TokenTypes::Select, "SELECT"),
+            token(TokenTypes::Identifier, "u"),
+            token(TokenTypes::Dot, "."),
+            token(TokenTypes::Identifier, "id"),
+            token(TokenTypes::From, "FROM"),
+            token(TokenTypes::Identifier, "users"),
+            token(TokenTypes::As, "AS"),
+            token(TokenTypes::Identifier, "u"),
+            token(TokenTypes::Comma, ","),
+            token(TokenTypes::Identifier, "orders"),
+            token(TokenTypes::Cross, "CROSS"),
+            token(TokenTypes::Join, "JOIN"),
+            token(TokenTypes::Identifier, "items"),
+            token(TokenTypes::SemiColon, ";"),
+        ];
+        let mut parser = Parser::new(tokens);
+        let statement = get_statement(&mut parser).unwrap();
+        let expected = SelectStatement {
+            table_name: "users".to_string(),
+            table_aliases: TableAliases(HashMap::from([("u".to_string(), "users".to_string())])),
+            joins: vec![
+                JoinClause {
+                    join_type: JoinType::Cross,
+                    table_name: "orders".to_string(),
+                    table_alias: "".to_string(),
+                    on_clause: None,
+                },
+                JoinClause {
+                    join_type: JoinType::Cross,
+                    table_name: "items".to_string(),
+                    table_alias: "".to_string(),
+                    on_clause: None,
+                },
+            ],
+            mode: SelectMode::All,
+            columns: vec![SelectableColumn {
+                selectables: vec![SelectableStackElement::Column("u.id".to_string())],
+                column_name: "u.id".to_string(),
+            }],
+            where_clause: None,
+            group_by_clause: None,
+            order_by_clause: None,
+            limit_clause: None,
+        };
+        assert_eq!(expected, statement);
+
+        // CROSS must be followed by JOIN
+        let tokens = vec![
+            token(TokenTypes::Select, "SELECT"),
+            token(TokenTypes::Asterisk, "*"),
+            token(TokenTypes::From, "FROM"),
+            token(TokenTypes::Identifier, "users"),
+            token(TokenTypes::Cross, "CROSS"),
+            token(TokenTypes::Identifier, "orders"),
+            token(TokenTypes::SemiColon, ";"),
+        ];
+        assert!(get_statement(&mut Parser::new(tokens)).is_err());
+    }
+
     #[test]
     fn select_statement_with_function_calls_is_generated_correctly() {
         // SELECT COUNT(*), SUM(salary), Date('now'), UnixEpoch('now', '-1 month'), FROM employees;
@@ -597,6 +775,7 @@ mod tests {
         let expected = SelectStatement {
             table_name: "employees".to_string(),
             table_aliases: TableAliases(HashMap::new()),
+            joins: vec![],
             mode: SelectMode::All,
             columns: vec![
                 SelectableColumn {
@@ -606,6 +785,7 @@ mod tests {
                             selectables: vec![SelectableStackElement::All],
                             column_name: "*".to_string(),
                         }],
+                        distinct: false,
                     })],
                     column_name: "COUNT(*)".to_string(),
                 },
@@ -616,6 +796,7 @@ mod tests {
                             selectables: vec![SelectableStackElement::Column("salary".to_string())],
                             column_name: "salary".to_string(),
                         }],
+                        distinct: false,
                     })],
                     column_name: "SUM(salary)".to_string(),
                 },
@@ -628,6 +809,7 @@ mod tests {
                             ))],
                             column_name: "'now'".to_string(),
                         }],
+                        distinct: false,
                     })],
                     column_name: "Date('now')".to_string(),
                 },
@@ -648,14 +830,227 @@ mod tests {
                                 column_name: "'-1 month'".to_string(),
                             },
                         ],
+                        distinct: false,
                     })],
                     column_name: "UnixEpoch('now', '-1 month')".to_string(),
                 },
             ],
             where_clause: None,
+            group_by_clause: None,
             order_by_clause: None,
             limit_clause: None,
         };
         assert_eq!(expected, statement);
     }
+
+    #[test]
+    fn select_statement_with_exists_subquery_and_no_from_is_generated_correctly() {
+        // SELECT EXISTS(SELECT 1 FROM users WHERE id = 1);
+        let tokens = vec![
+            token(TokenTypes::Select, "SELECT"),
+            token(TokenTypes::Exists, "EXISTS"),
+            token(TokenTypes::LeftParen, "("),
+            token(TokenTypes::Select, "SELECT"),
+            token(TokenTypes::IntLiteral, "1"),
+            token(TokenTypes::From, "FROM"),
+            token(TokenTypes::Identifier, "users"),
+            token(TokenTypes::Where, "WHERE"),
+            token(TokenTypes::Identifier, "id"),
+            token(TokenTypes::Equals, "="),
+            token(TokenTypes::IntLiteral, "1"),
+            token(TokenTypes::RightParen, ")"),
+            token(TokenTypes::SemiColon, ";"),
+        ];
+        let mut parser = Parser::new(tokens);
+        let result = get_statement(&mut parser);
+        assert!(result.is_ok(), "{:?}", result);
+        let statement = result.unwrap();
+        assert_eq!(statement.table_name, "");
+        assert_eq!(statement.columns.len(), 1);
+        assert_eq!(
+            statement.columns[0].column_name,
+            "EXISTS(SELECT 1 FROM users WHERE id = 1)"
+        );
+        let SelectableStackElement::Exists(subquery) = &statement.columns[0].selectables[0] else {
+            panic!("Expected an EXISTS subquery");
+        };
+        assert_eq!(subquery.elements.len(), 1);
+        let crate::interpreter::ast::SelectStatementStackElement::SelectStatement(inner) =
+            &subquery.elements[0]
+        else {
+            panic!("Expected a SELECT statement");
+        };
+        assert_eq!(inner.table_name, "users");
+        assert!(inner.where_clause.is_some());
+        assert_eq!(
+            parser.current_token().unwrap().token_type,
+            TokenTypes::SemiColon
+        );
+    }
+
+    #[test]
+    fn select_statement_with_scalar_subquery_in_where_is_generated_correctly() {
+        // SELECT id FROM users WHERE money > (SELECT AVG(money) FROM users);
+        let tokens = vec![
+            token(TokenTypes::Select, "SELECT"),
+            token(TokenTypes::Identifier, "id"),
+            token(TokenTypes::From, "FROM"),
+            token(TokenTypes::Identifier, "users"),
+            token(TokenTypes::Where, "WHERE"),
+            token(TokenTypes::Identifier, "money"),
+            token(TokenTypes::GreaterThan, ">"),
+            token(TokenTypes::LeftParen, "("),
+            token(TokenTypes::Select, "SELECT"),
+            token(TokenTypes::Avg, "AVG"),
+            token(TokenTypes::LeftParen, "("),
+            token(TokenTypes::Identifier, "money"),
+            token(TokenTypes::RightParen, ")"),
+            token(TokenTypes::From, "FROM"),
+            token(TokenTypes::Identifier, "users"),
+            token(TokenTypes::RightParen, ")"),
+            token(TokenTypes::SemiColon, ";"),
+        ];
+        let mut parser = Parser::new(tokens);
+        let statement = get_statement(&mut parser).unwrap();
+        let where_clause = statement.where_clause.unwrap();
+        let [
+            SelectableStackElement::Column(column),
+            SelectableStackElement::ScalarSubquery(subquery),
+            SelectableStackElement::Operator(Operator::GreaterThan),
+        ] = where_clause.selectables.as_slice()
+        else {
+            panic!("Unexpected WHERE clause: {:?}", where_clause);
+        };
+        assert_eq!(column, "money");
+        assert_eq!(subquery.elements.len(), 1);
+        assert_eq!(
+            parser.current_token().unwrap().token_type,
+            TokenTypes::SemiColon
+        );
+    }
+
+    #[test]
+    fn select_all_without_from_is_an_error() {
+        // SELECT *;
+        let tokens = vec![
+            token(TokenTypes::Select, "SELECT"),
+            token(TokenTypes::Asterisk, "*"),
+            token(TokenTypes::SemiColon, ";"),
+        ];
+        let mut parser = Parser::new(tokens);
+        assert_eq!(
+            get_statement(&mut parser),
+            Err("Error at line 1, column 0: Unexpected value: ;".to_string())
+        );
+    }
+
+    #[test]
+    fn select_statement_with_group_by_is_generated_correctly() {
+        // SELECT name FROM users GROUP BY name, age ORDER BY name;
+        let tokens = vec![
+            token(TokenTypes::Select, "SELECT"),
+            token(TokenTypes::Identifier, "name"),
+            token(TokenTypes::From, "FROM"),
+            token(TokenTypes::Identifier, "users"),
+            token(TokenTypes::Group, "GROUP"),
+            token(TokenTypes::By, "BY"),
+            token(TokenTypes::Identifier, "name"),
+            token(TokenTypes::Comma, ","),
+            token(TokenTypes::Identifier, "age"),
+            token(TokenTypes::Order, "ORDER"),
+            token(TokenTypes::By, "BY"),
+            token(TokenTypes::Identifier, "name"),
+            token(TokenTypes::SemiColon, ";"),
+        ];
+        let mut parser = Parser::new(tokens);
+        let statement = get_statement(&mut parser).unwrap();
+        let column = |name: &str| SelectableColumn {
+            selectables: vec![SelectableStackElement::Column(name.to_string())],
+            column_name: name.to_string(),
+        };
+        assert_eq!(
+            statement.group_by_clause,
+            Some(GroupByClause {
+                columns: vec![column("name"), column("age")],
+            })
+        );
+        assert_eq!(
+            statement.order_by_clause,
+            Some(OrderByClause {
+                columns: vec![column("name")],
+                directions: vec![OrderByDirection::Asc],
+            })
+        );
+
+        // GROUP must be followed by BY and at least one term
+        let tokens = vec![
+            token(TokenTypes::Select, "SELECT"),
+            token(TokenTypes::Identifier, "name"),
+            token(TokenTypes::From, "FROM"),
+            token(TokenTypes::Identifier, "users"),
+            token(TokenTypes::Group, "GROUP"),
+            token(TokenTypes::By, "BY"),
+            token(TokenTypes::SemiColon, ";"),
+        ];
+        assert!(get_statement(&mut Parser::new(tokens)).is_err());
+    }
+
+    #[test]
+    fn select_statement_with_inner_joins_is_generated_correctly() {
+        // SELECT * FROM users u INNER JOIN orders o ON u.id = o.user_id JOIN items;
+        let tokens = vec![
+            token(TokenTypes::Select, "SELECT"),
+            token(TokenTypes::Asterisk, "*"),
+            token(TokenTypes::From, "FROM"),
+            token(TokenTypes::Identifier, "users"),
+            token(TokenTypes::Identifier, "u"),
+            token(TokenTypes::Inner, "INNER"),
+            token(TokenTypes::Join, "JOIN"),
+            token(TokenTypes::Identifier, "orders"),
+            token(TokenTypes::Identifier, "o"),
+            token(TokenTypes::On, "ON"),
+            token(TokenTypes::Identifier, "u"),
+            token(TokenTypes::Dot, "."),
+            token(TokenTypes::Identifier, "id"),
+            token(TokenTypes::Equals, "="),
+            token(TokenTypes::Identifier, "o"),
+            token(TokenTypes::Dot, "."),
+            token(TokenTypes::Identifier, "user_id"),
+            token(TokenTypes::Join, "JOIN"),
+            token(TokenTypes::Identifier, "items"),
+            token(TokenTypes::SemiColon, ";"),
+        ];
+        let statement = get_statement(&mut Parser::new(tokens)).unwrap();
+        assert_eq!(
+            statement.table_aliases,
+            TableAliases(HashMap::from([
+                ("u".to_string(), "users".to_string()),
+                ("o".to_string(), "orders".to_string()),
+            ]))
+        );
+        assert_eq!(
+            statement.joins,
+            vec![
+                JoinClause {
+                    join_type: JoinType::Inner,
+                    table_name: "orders".to_string(),
+                    table_alias: "o".to_string(),
+                    on_clause: Some(SelectableColumn {
+                        selectables: vec![
+                            SelectableStackElement::Column("u.id".to_string()),
+                            SelectableStackElement::Column("o.user_id".to_string()),
+                            SelectableStackElement::Operator(Operator::Equals),
+                        ],
+                        column_name: "u.id = o.user_id".to_string(),
+                    }),
+                },
+                JoinClause {
+                    join_type: JoinType::Inner,
+                    table_name: "items".to_string(),
+                    table_alias: "".to_string(),
+                    on_clause: None,
+                },
+            ]
+        );
+    }
 }
@@ -1,7 +1,11 @@
+use crate::db::table::core::value::{DataType, Value};
 use crate::interpreter::{
     ast::{
-        FunctionCall, FunctionName, LogicalOperator, MathOperator, Operator, OrderByDirection,
-        SelectableColumn, SelectableStackElement, helpers::token::token_to_value, parser::Parser,
+        CaseExpression, FunctionCall, FunctionName, LogicalOperator, MathOperator, Operator,
+        OrderByDirection, SelectableColumn, SelectableStackElement,
+        helpers::token::{expect_token_type, token_to_data_type, token_to_value},
+        parser::Parser,
+        select_statement_stack::build_subquery,
     },
     tokenizer::token::TokenTypes,
 };
@@ -15,11 +19,30 @@ fn token_to_function_name(token_type: &TokenTypes) -> Option<FunctionName> {
         TokenTypes::Avg => Some(FunctionName::Avg),
         TokenTypes::Min => Some(FunctionName::Min),
         TokenTypes::Max => Some(FunctionName::Max),
+        TokenTypes::GroupConcat => Some(FunctionName::GroupConcat),
         TokenTypes::Date => Some(FunctionName::Date),
         TokenTypes::Time => Some(FunctionName::Time),
         TokenTypes::DateTime => Some(FunctionName::DateTime),
         TokenTypes::JulianDay => Some(FunctionName::JulianDay),
         TokenTypes::UnixEpoch => Some(FunctionName::UnixEpoch),
+        TokenTypes::Abs => Some(FunctionName::Abs),
+        TokenTypes::Round => Some(FunctionName::Round),
+        TokenTypes::Random => Some(FunctionName::Random),
+        TokenTypes::Quote => Some(FunctionName::Quote),
+        TokenTypes::Length => Some(FunctionName::Length),
+        TokenTypes::Trim => Some(FunctionName::Trim),
+        TokenTypes::Ltrim => Some(FunctionName::Ltrim),
+        TokenTypes::Rtrim => Some(FunctionName::Rtrim),
+        TokenTypes::Substr => Some(FunctionName::Substr),
+        TokenTypes::Upper => Some(FunctionName::Upper),
+        TokenTypes::Lower => Some(FunctionName::Lower),
+        TokenTypes::Coalesce => Some(FunctionName::Coalesce),
+        TokenTypes::IfNull => Some(FunctionName::IfNull),
+        TokenTypes::JsonExtract => Some(FunctionName::JsonExtract),
+        TokenTypes::RowNumber => Some(FunctionName::RowNumber),
+        TokenTypes::LastInsertRowid => Some(FunctionName::LastInsertRowid),
+        TokenTypes::Changes => Some(FunctionName::Changes),
+        TokenTypes::TotalChanges => Some(FunctionName::TotalChanges),
         _ => None,
     }
 }
@@ -41,6 +64,19 @@ fn parse_function_arguments(parser: &mut Parser) -> Result<Vec<SelectableColumn>
                 selectables: vec![SelectableStackElement::All],
                 column_name: "*".to_string(),
             },
+            // A column qualified with its table, e.g. `orders.amount`.
+            TokenTypes::Identifier
+                if parser.peek_is(1, TokenTypes::Dot)
+                    && parser.peek_is(2, TokenTypes::Identifier) =>
+            {
+                let column = format!("{}.{}", token.value, parser.peek(2).unwrap().value);
+                parser.advance()?;
+                parser.advance()?;
+                SelectableColumn {
+                    selectables: vec![SelectableStackElement::Column(column.clone())],
+                    column_name: column,
+                }
+            }
             TokenTypes::Identifier => SelectableColumn {
                 selectables: vec![SelectableStackElement::Column(token.value.to_string())],
                 column_name: token.value.to_string(),
@@ -56,6 +92,18 @@ fn parse_function_arguments(parser: &mut Parser) -> Result<Vec<SelectableColumn>
                 selectables: vec![SelectableStackElement::Value(token_to_value(parser)?)],
                 column_name: format!("'{}'", token.value),
             },
+            TokenTypes::Case => {
+                let start = parser.position();
+                let case = get_case_expression(parser)?;
+                SelectableColumn {
+                    selectables: vec![SelectableStackElement::Case(case)],
+                    column_name: format!(
+                        "{} {}",
+                        parser.get_text_since(start),
+                        parser.current_token()?.value
+                    ),
+                }
+            }
             _ => {
                 return Err(format!(
                     "Unexpected token in function arguments: {:?}",
@@ -101,9 +149,22 @@ pub fn get_selectables(
 ) -> Result<Vec<SelectableColumn>, String> {
     let mut all_columns: Vec<SelectableColumn> = vec![];
     let mut current_column: Vec<SelectableStackElement> = vec![];
+    // Result column names follow SQLite: the alias if there is one, the column name for a bare
+    // column, and otherwise the expression text with single spaces between tokens and none inside
+    // parentheses, e.g. `(a + b) * 2`.
     let mut current_name = "".to_string();
     let mut operators: Vec<ExtendedSelectableStackElement> = vec![];
     let mut depth = 0;
+    // Commas seen inside each open parenthesis, and the number of values of the parenthesized
+    // group that was just closed, which is the width of a row value such as `(a, b)`.
+    let mut group_commas: Vec<usize> = vec![];
+    let mut closed_group_width = 1;
+    // Depths of the parentheses of the CASTs being parsed, an AS at one of them is the one of the
+    // CAST rather than an alias.
+    let mut cast_depths: Vec<usize> = vec![];
+    // Depths at which a BETWEEN is waiting for its AND, which separates its bounds instead of
+    // being a logical AND.
+    let mut between_depths: Vec<usize> = vec![];
 
     let mut first = true;
     let mut expect_new_value = false; // Will be set after a valid ASC or DESC (if ORDER BY) or after a valid AS <identifier> (if SELECT) to ensure proper syntax
@@ -116,6 +177,7 @@ pub fn get_selectables(
         }
         let was_first = first;
         first = false;
+        let group_width = std::mem::replace(&mut closed_group_width, 1);
 
         let token = parser.current_token()?;
 
@@ -132,16 +194,32 @@ pub fn get_selectables(
             TokenTypes::From,
             TokenTypes::SemiColon,
             TokenTypes::Where,
+            TokenTypes::Join,
+            TokenTypes::Inner,
+            TokenTypes::Left,
+            TokenTypes::Cross,
+            TokenTypes::Group,
             TokenTypes::Order,
             TokenTypes::Limit,
+            TokenTypes::Returning,
             TokenTypes::Union,
             TokenTypes::Intersect,
             TokenTypes::Except,
+            TokenTypes::When,
+            TokenTypes::Then,
+            TokenTypes::Else,
+            TokenTypes::End,
             TokenTypes::EOF,
         ]
         .contains(&token.token_type)
+            || (token.token_type == TokenTypes::RightParen && depth == 0)
         {
+            if between_depths.last() == Some(&depth) {
+                return Err("Expected AND in BETWEEN".to_string());
+            }
             // Tokens needing special handling
+            // When dealing with set operators or subqueries, a SELECT statement may end with ) (so a WHERE
+            // or ORDER BY clause may too)
             // TODO: more tokens should be added here (e.g. Group for GROUP BY)
             // Default ordering is ASC
             if !expect_new_value && let Some(order_by_directions_vector) = order_by_directions {
@@ -150,9 +228,6 @@ pub fn get_selectables(
             break;
         } else if expect_new_value && token.token_type != TokenTypes::Comma {
             return Err("Unexpected token after ordering direction".to_string());
-        } else if token.token_type == TokenTypes::RightParen && depth == 0 {
-            // When deadling with set operators, a SELECT statement may end with ) (so a WHERE statement may too)
-            break;
         }
 
         if token.token_type == TokenTypes::Asterisk
@@ -160,9 +235,22 @@ pub fn get_selectables(
         {
             // * (All) is only allowed at certain places, otherwise it's * (Multiply)
             current_column.push(SelectableStackElement::All);
-            current_name += token.value;
+            current_name += &token.value;
             current_name += " ";
             continue;
+        } else if token.token_type == TokenTypes::Identifier
+            && parser.peek_is(1, TokenTypes::Dot)
+            && parser.peek_is(2, TokenTypes::Asterisk)
+            && (was_first || last_token_type == TokenTypes::Comma)
+        {
+            // All the columns of one table, e.g. `u.*`, expanded once the tables are known.
+            let column = format!("{}.*", token.value);
+            parser.advance()?;
+            parser.advance()?;
+            current_name += &column;
+            current_name += " ";
+            current_column.push(SelectableStackElement::Column(column));
+            continue;
         } else if token.token_type == TokenTypes::Comma {
             // Push all current operators on the stack inside the current parenthesis
             while !operators.is_empty() {
@@ -202,20 +290,40 @@ pub fn get_selectables(
                 current_column = vec![];
                 current_name = "".to_string();
             } else {
-                current_name += token.value;
-                current_name += " ";
+                trim_name_end(&mut current_name);
+                current_name += ", ";
+                if let Some(commas) = group_commas.last_mut() {
+                    *commas += 1;
+                }
             }
 
+            continue;
+        } else if token.token_type == TokenTypes::LeftParen && parser.peek_is(1, TokenTypes::Select)
+        {
+            parser.advance()?;
+            let start = parser.position();
+            let subquery = build_subquery(parser)?;
+            current_name += &format!("({}) ", parser.get_text_since(start));
+            expect_token_type(parser, TokenTypes::RightParen)?;
+            current_column.push(SelectableStackElement::ScalarSubquery(Box::new(subquery)));
             continue;
         } else if token.token_type == TokenTypes::LeftParen {
             operators.push(ExtendedSelectableStackElement::LeftParen);
-            current_name += token.value;
-            current_name += " ";
+            current_name += &token.value;
             depth += 1;
+            group_commas.push(0);
             continue;
         } else if token.token_type == TokenTypes::RightParen {
+            if cast_depths.last() == Some(&depth) {
+                return Err("Expected AS in CAST".to_string());
+            }
+            if between_depths.last() == Some(&depth) {
+                return Err("Expected AND in BETWEEN".to_string());
+            }
             depth -= 1;
-            current_name += token.value;
+            closed_group_width = group_commas.pop().unwrap_or(0) + 1;
+            trim_name_end(&mut current_name);
+            current_name += &token.value;
             current_name += " ";
             while let Some(operator) = operators.pop() {
                 match operator {
@@ -228,6 +336,38 @@ pub fn get_selectables(
                 }
             }
             continue;
+        } else if token.token_type == TokenTypes::As && cast_depths.last() == Some(&depth) {
+            if group_commas.last() != Some(&0) {
+                return Err("Unexpected token: COMMA".to_string());
+            }
+            // The expression of the CAST is complete, its conversion is applied when the
+            // parenthesis closes.
+            while let Some(ExtendedSelectableStackElement::SelectableStackElement(_)) =
+                operators.last()
+            {
+                if let Some(ExtendedSelectableStackElement::SelectableStackElement(operator)) =
+                    operators.pop()
+                {
+                    current_column.push(operator);
+                }
+            }
+            current_name += &token.value;
+            current_name += " ";
+            parser.advance()?;
+            let data_type = match token_to_data_type(parser)? {
+                DataType::Null => return Err(parser.format_error()),
+                data_type => data_type,
+            };
+            current_name += &parser.current_token()?.value;
+            if !parser.peek_is(1, TokenTypes::RightParen) {
+                parser.advance()?;
+                return Err(parser.format_error());
+            }
+            operators.push(ExtendedSelectableStackElement::SelectableStackElement(
+                SelectableStackElement::Cast(data_type),
+            ));
+            cast_depths.pop();
+            continue;
         } else if token.token_type == TokenTypes::As {
             if depth != 0 || !allow_aliases {
                 return Err("Unexpected token: AS".to_string());
@@ -252,8 +392,45 @@ pub fn get_selectables(
             }
         }
 
+        if token.token_type == TokenTypes::Cast {
+            current_name += &token.value;
+            parser.advance()?;
+            expect_token_type(parser, TokenTypes::LeftParen)?;
+            current_name += &parser.current_token()?.value;
+            operators.push(ExtendedSelectableStackElement::LeftParen);
+            depth += 1;
+            group_commas.push(0);
+            cast_depths.push(depth);
+            continue;
+        }
+
+        if token.token_type == TokenTypes::Case {
+            let start = parser.position();
+            let case = get_case_expression(parser)?;
+            current_name += &format!(
+                "{} {} ",
+                parser.get_text_since(start),
+                parser.current_token()?.value
+            );
+            current_column.push(SelectableStackElement::Case(case));
+            continue;
+        }
+
+        if token.token_type == TokenTypes::Exists {
+            let keyword = token.value.to_string();
+            parser.advance()?;
+            expect_token_type(parser, TokenTypes::LeftParen)?;
+            parser.advance()?;
+            let start = parser.position();
+            let subquery = build_subquery(parser)?;
+            current_name += &format!("{}({}) ", keyword, parser.get_text_since(start));
+            expect_token_type(parser, TokenTypes::RightParen)?;
+            current_column.push(SelectableStackElement::Exists(Box::new(subquery)));
+            continue;
+        }
+
         if let Some(func_name) = token_to_function_name(&token.token_type) {
-            current_name += token.value;
+            current_name += &token.value;
 
             parser.advance()?;
             let next_token = parser.current_token()?;
@@ -263,10 +440,22 @@ pub fn get_selectables(
                     next_token.token_type
                 ));
             }
-            current_name += next_token.value;
+            current_name += &next_token.value;
 
             parser.advance()?;
 
+            let distinct = parser.current_token()?.token_type == TokenTypes::Distinct;
+            if distinct {
+                if !func_name.is_aggregate() {
+                    return Err(format!(
+                        "DISTINCT is only allowed in aggregate functions, got: {:?}",
+                        func_name
+                    ));
+                }
+                current_name += "DISTINCT ";
+                parser.advance()?;
+            }
+
             let arguments = parse_function_arguments(parser)?;
 
             for (i, arg) in arguments.iter().enumerate() {
@@ -283,23 +472,86 @@ pub fn get_selectables(
                     close_paren.token_type
                 ));
             }
-            current_name += close_paren.value;
+            current_name += &close_paren.value;
             current_name += " ";
 
             let func_call = FunctionCall {
                 name: func_name,
                 arguments,
+                distinct,
             };
+            if distinct && !func_call.is_aggregate() {
+                return Err(format!(
+                    "DISTINCT is only allowed in aggregate functions, got: {:?}",
+                    func_call.name
+                ));
+            }
             current_column.push(SelectableStackElement::Function(func_call));
             continue;
         }
 
+        // A column qualified with its table, e.g. `users.id`.
+        if token.token_type == TokenTypes::Identifier
+            && parser.peek_is(1, TokenTypes::Dot)
+            && parser.peek_is(2, TokenTypes::Identifier)
+        {
+            let column = format!("{}.{}", token.value, parser.peek(2).unwrap().value);
+            parser.advance()?;
+            parser.advance()?;
+            current_name += &column;
+            current_name += " ";
+            current_column.push(SelectableStackElement::Column(column));
+            continue;
+        }
+
+        if token.token_type == TokenTypes::And && between_depths.last() == Some(&depth) {
+            // The lower bound is complete, the BETWEEN stays on the stack for the upper one.
+            while let Some(ExtendedSelectableStackElement::SelectableStackElement(operator)) =
+                operators.last()
+            {
+                if matches!(
+                    operator,
+                    SelectableStackElement::Operator(Operator::Between | Operator::NotBetween)
+                ) {
+                    break;
+                }
+                current_column.push(operator.clone());
+                operators.pop();
+            }
+            between_depths.pop();
+            current_name += &token.value;
+            current_name += " ";
+            continue;
+        }
+
+        if token.token_type == TokenTypes::Escape {
+            // The pattern is complete, the LIKE takes the escape character as a third operand.
+            loop {
+                match operators.pop() {
+                    Some(ExtendedSelectableStackElement::SelectableStackElement(
+                        SelectableStackElement::Operator(Operator::Like),
+                    )) => break,
+                    Some(ExtendedSelectableStackElement::SelectableStackElement(operator)) => {
+                        current_column.push(operator)
+                    }
+                    _ => return Err("ESCAPE must follow a LIKE pattern".to_string()),
+                }
+            }
+            operators.push(ExtendedSelectableStackElement::SelectableStackElement(
+                SelectableStackElement::Operator(Operator::LikeEscape),
+            ));
+            current_name += &token.value;
+            current_name += " ";
+            continue;
+        }
+
         match token.token_type {
             TokenTypes::StringLiteral => current_name.push_str(&format!("'{}'", token.value)),
-            _ => current_name += token.value,
+            _ => current_name += &token.value,
         };
         current_name += " ";
 
+        let mut in_operand = None;
         let operator = match token.token_type {
             TokenTypes::Equals => Some(SelectableStackElement::Operator(Operator::Equals)),
             TokenTypes::NotEquals => Some(SelectableStackElement::Operator(Operator::NotEquals)),
@@ -311,10 +563,41 @@ pub fn get_selectables(
             TokenTypes::GreaterEquals => {
                 Some(SelectableStackElement::Operator(Operator::GreaterEquals))
             }
-            TokenTypes::In => Some(SelectableStackElement::Operator(Operator::In)),
+            TokenTypes::In => {
+                in_operand = Some(
+                    if parser.peek_is(1, TokenTypes::LeftParen)
+                        && parser.peek_is(2, TokenTypes::Select)
+                    {
+                        get_in_subquery(parser, &mut current_name, group_width)?
+                    } else {
+                        SelectableStackElement::ValueList(get_in_list(
+                            parser,
+                            &mut current_name,
+                            group_width,
+                        )?)
+                    },
+                );
+                current_name += " ";
+                Some(SelectableStackElement::Operator(Operator::In))
+            }
             // TODO: handle NOT IN (not a token)
-            TokenTypes::Is => Some(SelectableStackElement::Operator(Operator::Is)),
-            // TODO: handle IS NOT (not a token)
+            TokenTypes::Is => Some(SelectableStackElement::Operator(get_is_operator(
+                parser,
+                &mut current_name,
+            )?)),
+            TokenTypes::Glob => Some(SelectableStackElement::Operator(Operator::Glob)),
+            TokenTypes::Like => Some(SelectableStackElement::Operator(Operator::Like)),
+            TokenTypes::Between => {
+                between_depths.push(depth);
+                Some(SelectableStackElement::Operator(Operator::Between))
+            }
+            TokenTypes::Not if parser.peek_is(1, TokenTypes::Between) => {
+                parser.advance()?;
+                current_name += &parser.current_token()?.value;
+                current_name += " ";
+                between_depths.push(depth);
+                Some(SelectableStackElement::Operator(Operator::NotBetween))
+            }
             // Logical operators
             TokenTypes::Not => Some(SelectableStackElement::LogicalOperator(
                 LogicalOperator::Not,
@@ -331,11 +614,15 @@ pub fn get_selectables(
             }
             TokenTypes::Divide => Some(SelectableStackElement::MathOperator(MathOperator::Divide)),
             TokenTypes::Modulo => Some(SelectableStackElement::MathOperator(MathOperator::Modulo)),
+            TokenTypes::Concat => Some(SelectableStackElement::MathOperator(MathOperator::Concat)),
             _ => None,
         };
 
         if let Some(value) = operator {
-            while operators.len() > 0 {
+            // A prefix operator has no left operand yet, so it must not pop pending operators
+            // (e.g. `NOT NOT a` is `NOT (NOT a)`).
+            let is_prefix = value == SelectableStackElement::LogicalOperator(LogicalOperator::Not);
+            while !is_prefix && operators.len() > 0 {
                 match operators.last() {
                     Some(last) => match last {
                         ExtendedSelectableStackElement::SelectableStackElement(inner) => {
@@ -359,10 +646,14 @@ pub fn get_selectables(
             operators.push(ExtendedSelectableStackElement::SelectableStackElement(
                 value,
             ));
+            if let Some(in_operand) = in_operand {
+                current_column.push(in_operand);
+            }
             continue;
         }
 
         // Tokens that are automatically added to output
+        let token = parser.current_token()?;
         let element = match token.token_type {
             // All
             TokenTypes::All => SelectableStackElement::All,
@@ -402,6 +693,190 @@ pub fn get_selectables(
     Ok(all_columns)
 }
 
+// Drops the space left after the last token of a column name.
+fn trim_name_end(current_name: &mut String) {
+    current_name.truncate(current_name.trim_end().len());
+}
+
+// Parses the list on the right of IN, leaving the parser on its closing parenthesis. The list
+// is either literal values, `(1, 2)`, or literal rows, `((1, 2), (3, 4))` or
+// `(VALUES (1, 2), (3, 4))`, whose width must match the row value on the left of IN.
+fn get_in_list(
+    parser: &mut Parser,
+    current_name: &mut String,
+    width: usize,
+) -> Result<Vec<Vec<Value>>, String> {
+    parser.advance()?;
+    expect_token_type(parser, TokenTypes::LeftParen)?;
+    let list = if parser.peek_is(1, TokenTypes::RightParen) {
+        parser.advance()?;
+        *current_name += "()";
+        vec![]
+    } else if parser.peek_is(1, TokenTypes::Values) || parser.peek_is(1, TokenTypes::LeftParen) {
+        parser.advance()?;
+        *current_name += "(";
+        if parser.current_token()?.token_type == TokenTypes::Values {
+            *current_name += "VALUES ";
+            parser.advance()?;
+        }
+        let mut rows = vec![];
+        loop {
+            rows.push(get_literal_row(parser, current_name)?);
+            parser.advance()?;
+            match parser.current_token()?.token_type {
+                TokenTypes::Comma => {
+                    *current_name += ", ";
+                    parser.advance()?;
+                }
+                TokenTypes::RightParen => break,
+                _ => return Err(parser.format_error()),
+            }
+        }
+        *current_name += ")";
+        rows
+    } else {
+        get_literal_row(parser, current_name)?
+            .into_iter()
+            .map(|value| vec![value])
+            .collect()
+    };
+
+    if list.is_empty() && width > 1 {
+        return Err("Row values can't be compared with an empty IN list".to_string());
+    }
+    if let Some(row) = list.iter().find(|row| row.len() != width) {
+        return Err(format!(
+            "IN expected rows of {} value(s), found a row of {}",
+            width,
+            row.len()
+        ));
+    }
+    Ok(list)
+}
+
+// Parses `CASE [operand] WHEN condition THEN result ... [ELSE result] END`, leaving the parser on
+// END. With an operand, each condition is the operand being equal to the value of its WHEN.
+fn get_case_expression(parser: &mut Parser) -> Result<CaseExpression, String> {
+    parser.advance()?;
+    let operand = match parser.current_token()?.token_type {
+        TokenTypes::When => None,
+        _ => Some(get_case_operand(parser)?),
+    };
+    expect_token_type(parser, TokenTypes::When)?;
+    let mut branches = vec![];
+    let mut else_result = None;
+    loop {
+        match parser.current_token()?.token_type {
+            TokenTypes::When => {
+                parser.advance()?;
+                let mut condition = get_case_operand(parser)?;
+                if let Some(operand) = &operand {
+                    let mut selectables = operand.selectables.clone();
+                    selectables.append(&mut condition.selectables);
+                    selectables.push(SelectableStackElement::Operator(Operator::Equals));
+                    condition.selectables = selectables;
+                }
+                expect_token_type(parser, TokenTypes::Then)?;
+                parser.advance()?;
+                branches.push((condition, get_case_operand(parser)?));
+            }
+            TokenTypes::Else if else_result.is_none() => {
+                parser.advance()?;
+                else_result = Some(get_case_operand(parser)?);
+                expect_token_type(parser, TokenTypes::End)?;
+            }
+            TokenTypes::End => break,
+            _ => return Err(parser.format_error()),
+        }
+    }
+    Ok(CaseExpression {
+        branches,
+        else_result,
+    })
+}
+
+fn get_case_operand(parser: &mut Parser) -> Result<SelectableColumn, String> {
+    let mut columns = get_selectables(parser, false, false, &mut None)?;
+    match (columns.pop(), columns.is_empty()) {
+        (Some(column), true) => Ok(column),
+        _ => Err(parser.format_error()),
+    }
+}
+
+// Parses `(SELECT ...)` after IN, leaving the parser on the closing parenthesis. The subquery is
+// resolved into a list of values before the rows are evaluated.
+fn get_in_subquery(
+    parser: &mut Parser,
+    current_name: &mut String,
+    width: usize,
+) -> Result<SelectableStackElement, String> {
+    if width > 1 {
+        return Err("Row values can't be compared with an IN subquery".to_string());
+    }
+    parser.advance()?;
+    parser.advance()?;
+    let start = parser.position();
+    let subquery = build_subquery(parser)?;
+    *current_name += &format!("({})", parser.get_text_since(start));
+    expect_token_type(parser, TokenTypes::RightParen)?;
+    Ok(SelectableStackElement::InSubquery(Box::new(subquery)))
+}
+
+// Parses `(value, ...)` made of literals, leaving the parser on the closing parenthesis.
+fn get_literal_row(parser: &mut Parser, current_name: &mut String) -> Result<Vec<Value>, String> {
+    expect_token_type(parser, TokenTypes::LeftParen)?;
+    *current_name += "(";
+    let mut values = vec![];
+    loop {
+        parser.advance()?;
+        let token = parser.current_token()?;
+        match token.token_type {
+            TokenTypes::StringLiteral => current_name.push_str(&format!("'{}'", token.value)),
+            _ => current_name.push_str(&token.value),
+        }
+        values.push(token_to_value(parser)?);
+        parser.advance()?;
+        match parser.current_token()?.token_type {
+            TokenTypes::Comma => *current_name += ", ",
+            TokenTypes::RightParen => {
+                *current_name += ")";
+                return Ok(values);
+            }
+            _ => return Err(parser.format_error()),
+        }
+    }
+}
+
+// Consumes the rest of `IS [NOT] [DISTINCT FROM]`, leaving the parser on its last word.
+// `IS DISTINCT FROM` and `IS NOT DISTINCT FROM` are the same NULL-safe comparisons as `IS NOT` and `IS`.
+fn get_is_operator(parser: &mut Parser, current_name: &mut String) -> Result<Operator, String> {
+    let not = parser.peek_is(1, TokenTypes::Not);
+    let distinct_position = if not { 2 } else { 1 };
+    let distinct = parser.peek_is(distinct_position, TokenTypes::Distinct);
+    // Every keyword but the FROM of DISTINCT FROM, which is checked below.
+    let keywords = if distinct {
+        distinct_position
+    } else {
+        not as usize
+    };
+    for _ in 0..keywords {
+        parser.advance()?;
+        *current_name += &parser.current_token()?.value;
+        *current_name += " ";
+    }
+    if distinct {
+        parser.advance()?;
+        expect_token_type(parser, TokenTypes::From)?;
+        *current_name += &parser.current_token()?.value;
+        *current_name += " ";
+    }
+    Ok(if not != distinct {
+        Operator::IsNot
+    } else {
+        Operator::Is
+    })
+}
+
 pub fn compare_precedence(
     first: &SelectableStackElement,
     second: &SelectableStackElement,
@@ -420,6 +895,7 @@ pub fn compare_precedence(
 fn get_precedence(operator: &SelectableStackElement) -> Result<i32, String> {
     let result = match operator {
         SelectableStackElement::Function(_) => 50,
+        SelectableStackElement::MathOperator(MathOperator::Concat) => 45,
         SelectableStackElement::MathOperator(MathOperator::Multiply) => 40,
         SelectableStackElement::MathOperator(MathOperator::Divide) => 40,
         SelectableStackElement::MathOperator(MathOperator::Modulo) => 40,
@@ -438,6 +914,11 @@ fn get_precedence(operator: &SelectableStackElement) -> Result<i32, String> {
         SelectableStackElement::Operator(Operator::IsNot) => 25,
         SelectableStackElement::Operator(Operator::In) => 25,
         SelectableStackElement::Operator(Operator::NotIn) => 25,
+        SelectableStackElement::Operator(Operator::Glob) => 25,
+        SelectableStackElement::Operator(Operator::Like) => 25,
+        SelectableStackElement::Operator(Operator::LikeEscape) => 25,
+        SelectableStackElement::Operator(Operator::Between) => 25,
+        SelectableStackElement::Operator(Operator::NotBetween) => 25,
 
         SelectableStackElement::LogicalOperator(LogicalOperator::Not) => 20,
         SelectableStackElement::LogicalOperator(LogicalOperator::And) => 15,
@@ -459,6 +940,194 @@ mod tests {
     };
     use crate::interpreter::tokenizer::token::TokenTypes;
 
+    fn parse_expression(tokens: Vec<(TokenTypes, &'static str)>) -> Vec<SelectableStackElement> {
+        let mut tokens = tokens
+            .into_iter()
+            .map(|(token_type, value)| token(token_type, value))
+            .collect::<Vec<_>>();
+        tokens.push(token(TokenTypes::SemiColon, ";"));
+        let mut parser = Parser::new(tokens);
+        let result = get_selectables(&mut parser, false, false, &mut None);
+        assert!(result.is_ok(), "Failed to parse: {:?}", result.err());
+        let mut columns = result.unwrap();
+        assert_eq!(columns.len(), 1);
+        columns.pop().unwrap().selectables
+    }
+
+    fn column(name: &str) -> SelectableStackElement {
+        SelectableStackElement::Column(name.to_string())
+    }
+
+    fn int(value: i64) -> SelectableStackElement {
+        SelectableStackElement::Value(Value::Integer(value))
+    }
+
+    #[test]
+    fn mixed_operators_follow_sqlite_precedence() {
+        // a + 1 > b AND c => ((a + 1) > b) AND c
+        let result = parse_expression(vec![
+            (TokenTypes::Identifier, "a"),
+            (TokenTypes::Plus, "+"),
+            (TokenTypes::IntLiteral, "1"),
+            (TokenTypes::GreaterThan, ">"),
+            (TokenTypes::Identifier, "b"),
+            (TokenTypes::And, "AND"),
+            (TokenTypes::Identifier, "c"),
+        ]);
+        let expected = vec![
+            column("a"),
+            int(1),
+            SelectableStackElement::MathOperator(MathOperator::Add),
+            column("b"),
+            SelectableStackElement::Operator(Operator::GreaterThan),
+            column("c"),
+            SelectableStackElement::LogicalOperator(LogicalOperator::And),
+        ];
+        assert_eq!(expected, result);
+
+        // a = 1 OR b = 2 AND c = 3 => (a = 1) OR ((b = 2) AND (c = 3))
+        let result = parse_expression(vec![
+            (TokenTypes::Identifier, "a"),
+            (TokenTypes::Equals, "="),
+            (TokenTypes::IntLiteral, "1"),
+            (TokenTypes::Or, "OR"),
+            (TokenTypes::Identifier, "b"),
+            (TokenTypes::Equals, "="),
+            (TokenTypes::IntLiteral, "2"),
+            (TokenTypes::And, "AND"),
+            (TokenTypes::Identifier, "c"),
+            (TokenTypes::Equals, "="),
+            (TokenTypes::IntLiteral, "3"),
+        ]);
+        let expected = vec![
+            column("a"),
+            int(1),
+            SelectableStackElement::Operator(Operator::Equals),
+            column("b"),
+            int(2),
+            SelectableStackElement::Operator(Operator::Equals),
+            column("c"),
+            int(3),
+            SelectableStackElement::Operator(Operator::Equals),
+            SelectableStackElement::LogicalOperator(LogicalOperator::And),
+            SelectableStackElement::LogicalOperator(LogicalOperator::Or),
+        ];
+        assert_eq!(expected, result);
+
+        // a * 2 + b * 3 >= c - 1 - a => ((a * 2) + (b * 3)) >= ((c - 1) - a)
+        let result = parse_expression(vec![
+            (TokenTypes::Identifier, "a"),
+            (TokenTypes::Asterisk, "*"),
+            (TokenTypes::IntLiteral, "2"),
+            (TokenTypes::Plus, "+"),
+            (TokenTypes::Identifier, "b"),
+            (TokenTypes::Asterisk, "*"),
+            (TokenTypes::IntLiteral, "3"),
+            (TokenTypes::GreaterEquals, ">="),
+            (TokenTypes::Identifier, "c"),
+            (TokenTypes::Minus, "-"),
+            (TokenTypes::IntLiteral, "1"),
+            (TokenTypes::Minus, "-"),
+            (TokenTypes::Identifier, "a"),
+        ]);
+        let expected = vec![
+            column("a"),
+            int(2),
+            SelectableStackElement::MathOperator(MathOperator::Multiply),
+            column("b"),
+            int(3),
+            SelectableStackElement::MathOperator(MathOperator::Multiply),
+            SelectableStackElement::MathOperator(MathOperator::Add),
+            column("c"),
+            int(1),
+            SelectableStackElement::MathOperator(MathOperator::Subtract),
+            column("a"),
+            SelectableStackElement::MathOperator(MathOperator::Subtract),
+            SelectableStackElement::Operator(Operator::GreaterEquals),
+        ];
+        assert_eq!(expected, result);
+    }
+
+    #[test]
+    fn is_distinct_from_parses_as_null_safe_comparison() {
+        // a IS DISTINCT FROM 1 OR b IS NOT DISTINCT FROM NULL
+        let result = parse_expression(vec![
+            (TokenTypes::Identifier, "a"),
+            (TokenTypes::Is, "IS"),
+            (TokenTypes::Distinct, "DISTINCT"),
+            (TokenTypes::From, "FROM"),
+            (TokenTypes::IntLiteral, "1"),
+            (TokenTypes::Or, "OR"),
+            (TokenTypes::Identifier, "b"),
+            (TokenTypes::Is, "IS"),
+            (TokenTypes::Not, "NOT"),
+            (TokenTypes::Distinct, "DISTINCT"),
+            (TokenTypes::From, "FROM"),
+            (TokenTypes::Null, "NULL"),
+        ]);
+        let expected = vec![
+            column("a"),
+            int(1),
+            SelectableStackElement::Operator(Operator::IsNot),
+            column("b"),
+            SelectableStackElement::Value(Value::Null),
+            SelectableStackElement::Operator(Operator::Is),
+            SelectableStackElement::LogicalOperator(LogicalOperator::Or),
+        ];
+        assert_eq!(expected, result);
+    }
+
+    #[test]
+    fn not_binds_looser_than_comparisons_and_tighter_than_and() {
+        // NOT a = 1 AND NOT NOT b => (NOT (a = 1)) AND (NOT (NOT b))
+        let result = parse_expression(vec![
+            (TokenTypes::Not, "NOT"),
+            (TokenTypes::Identifier, "a"),
+            (TokenTypes::Equals, "="),
+            (TokenTypes::IntLiteral, "1"),
+            (TokenTypes::And, "AND"),
+            (TokenTypes::Not, "NOT"),
+            (TokenTypes::Not, "NOT"),
+            (TokenTypes::Identifier, "b"),
+        ]);
+        let expected = vec![
+            column("a"),
+            int(1),
+            SelectableStackElement::Operator(Operator::Equals),
+            SelectableStackElement::LogicalOperator(LogicalOperator::Not),
+            column("b"),
+            SelectableStackElement::LogicalOperator(LogicalOperator::Not),
+            SelectableStackElement::LogicalOperator(LogicalOperator::Not),
+            SelectableStackElement::LogicalOperator(LogicalOperator::And),
+        ];
+        assert_eq!(expected, result);
+
+        // a < b = (c OR NOT d) => (a < b) = (c OR (NOT d))
+        let result = parse_expression(vec![
+            (TokenTypes::Identifier, "a"),
+            (TokenTypes::LessThan, "<"),
+            (TokenTypes::Identifier, "b"),
+            (TokenTypes::Equals, "="),
+            (TokenTypes::LeftParen, "("),
+            (TokenTypes::Identifier, "c"),
+            (TokenTypes::Or, "OR"),
+            (TokenTypes::Not, "NOT"),
+            (TokenTypes::Identifier, "d"),
+            (TokenTypes::RightParen, ")"),
+        ]);
+        let expected = vec![
+            column("a"),
+            column("b"),
+            SelectableStackElement::Operator(Operator::LessThan),
+            column("c"),
+            column("d"),
+            SelectableStackElement::LogicalOperator(LogicalOperator::Not),
+            SelectableStackElement::LogicalOperator(LogicalOperator::Or),
+            SelectableStackElement::Operator(Operator::Equals),
+        ];
+        assert_eq!(expected, result);
+    }
+
     #[test]
     fn precedence_handles_correctly() {
         let result = compare_precedence(
@@ -543,6 +1212,46 @@ mod tests {
         }
     }
 
+    #[test]
+    fn get_selectables_parses_distinct_in_aggregate_arguments() {
+        // ... GROUP_CONCAT(DISTINCT name, '; '), Date(DISTINCT 'now') FROM ...
+        let tokens = vec![
+            token(TokenTypes::GroupConcat, "GROUP_CONCAT"),
+            token(TokenTypes::LeftParen, "("),
+            token(TokenTypes::Distinct, "DISTINCT"),
+            token(TokenTypes::Identifier, "name"),
+            token(TokenTypes::Comma, ","),
+            token(TokenTypes::StringLiteral, "; "),
+            token(TokenTypes::RightParen, ")"),
+            token(TokenTypes::From, "FROM"),
+        ];
+        let mut parser = Parser::new(tokens);
+        let result = get_selectables(&mut parser, true, true, &mut None);
+        assert!(result.is_ok(), "Failed to parse: {:?}", result.err());
+        let columns = result.unwrap();
+        assert_eq!(columns.len(), 1);
+        assert_eq!(columns[0].column_name, "GROUP_CONCAT(DISTINCT name, '; ')");
+        match &columns[0].selectables[0] {
+            SelectableStackElement::Function(f) => {
+                assert_eq!(f.name, FunctionName::GroupConcat);
+                assert!(f.distinct);
+                assert_eq!(f.arguments.len(), 2);
+            }
+            _ => panic!("Expected GroupConcat function"),
+        }
+
+        let tokens = vec![
+            token(TokenTypes::Date, "Date"),
+            token(TokenTypes::LeftParen, "("),
+            token(TokenTypes::Distinct, "DISTINCT"),
+            token(TokenTypes::StringLiteral, "now"),
+            token(TokenTypes::RightParen, ")"),
+            token(TokenTypes::From, "FROM"),
+        ];
+        let mut parser = Parser::new(tokens);
+        assert!(get_selectables(&mut parser, true, true, &mut None).is_err());
+    }
+
     #[test]
     fn get_selectables_works_with_date_and_time_functions() {
         // ... Date('now', '-7 days'), Time('now', '-1 month', '+1 hour', '3 hours', '10 minutes', '1 second'), DateTime('now', 'modifier') FROM ...
@@ -628,4 +1337,232 @@ mod tests {
             _ => panic!("Expected DateTime function"),
         }
     }
+
+    #[test]
+    fn get_selectables_derives_column_names() {
+        let sql = "id, age + 1, (age + id) * 2, ABS(money), id AS n, 'it''s', \
+                   COUNT(DISTINCT name), age IN (1, 2), id IS NOT NULL, \
+                   (id, name) IN (VALUES (1, 'a')) AND id > 0 FROM users;";
+        let mut parser = Parser::new(crate::interpreter::tokenizer::tokenize(sql));
+        let names = get_selectables(&mut parser, true, true, &mut None)
+            .unwrap()
+            .into_iter()
+            .map(|column| column.column_name)
+            .collect::<Vec<String>>();
+        assert_eq!(
+            names,
+            vec![
+                "id",
+                "age + 1",
+                "(age + id) * 2",
+                "ABS(money)",
+                "n",
+                "'it''s'",
+                "COUNT(DISTINCT name)",
+                "age IN (1, 2)",
+                "id IS NOT NULL",
+                "(id, name) IN (VALUES (1, 'a')) AND id > 0",
+            ]
+        );
+    }
+
+    #[test]
+    fn get_selectables_parses_row_values_in_a_values_list() {
+        let sql = "(id, age) IN ((1, 2), (3, 4)) AND name IN () FROM users;";
+        let mut parser = Parser::new(crate::interpreter::tokenizer::tokenize(sql));
+        let columns = get_selectables(&mut parser, false, false, &mut None).unwrap();
+        assert_eq!(
+            columns[0].selectables,
+            vec![
+                SelectableStackElement::Column("id".to_string()),
+                SelectableStackElement::Column("age".to_string()),
+                SelectableStackElement::ValueList(vec![
+                    vec![Value::Integer(1), Value::Integer(2)],
+                    vec![Value::Integer(3), Value::Integer(4)],
+                ]),
+                SelectableStackElement::Operator(Operator::In),
+                SelectableStackElement::Column("name".to_string()),
+                SelectableStackElement::ValueList(vec![]),
+                SelectableStackElement::Operator(Operator::In),
+                SelectableStackElement::LogicalOperator(LogicalOperator::And),
+            ]
+        );
+
+        for sql in [
+            "(id, age) IN (1, 2) FROM users;",
+            "id IN (VALUES (1, 2)) FROM users;",
+            "(id, age) IN () FROM users;",
+        ] {
+            let mut parser = Parser::new(crate::interpreter::tokenizer::tokenize(sql));
+            assert!(get_selectables(&mut parser, false, false, &mut None).is_err());
+        }
+    }
+
+    #[test]
+    fn get_selectables_parses_all_columns_of_a_table() {
+        let sql = "u.*, id, o.* FROM users u;";
+        let mut parser = Parser::new(crate::interpreter::tokenizer::tokenize(sql));
+        let columns = get_selectables(&mut parser, true, true, &mut None).unwrap();
+        assert_eq!(
+            columns
+                .iter()
+                .map(|column| column.selectables.clone())
+                .collect::<Vec<_>>(),
+            vec![
+                vec![SelectableStackElement::Column("u.*".to_string())],
+                vec![SelectableStackElement::Column("id".to_string())],
+                vec![SelectableStackElement::Column("o.*".to_string())],
+            ]
+        );
+        assert_eq!(columns[0].column_name, "u.*");
+    }
+
+    #[test]
+    fn get_selectables_tells_cast_as_from_alias_as() {
+        let sql = "CAST(age + 1 AS TEXT) AS label, cast(id AS real) FROM users;";
+        let mut parser = Parser::new(crate::interpreter::tokenizer::tokenize(sql));
+        let columns = get_selectables(&mut parser, true, true, &mut None).unwrap();
+        assert_eq!(
+            columns,
+            vec![
+                SelectableColumn {
+                    selectables: vec![
+                        column("age"),
+                        int(1),
+                        SelectableStackElement::MathOperator(MathOperator::Add),
+                        SelectableStackElement::Cast(DataType::Text),
+                    ],
+                    column_name: "label".to_string(),
+                },
+                SelectableColumn {
+                    selectables: vec![column("id"), SelectableStackElement::Cast(DataType::Real)],
+                    column_name: "cast(id AS real)".to_string(),
+                },
+            ]
+        );
+
+        for sql in [
+            "CAST(id) FROM users;",
+            "CAST(id AS) FROM users;",
+            "CAST(id, age AS INTEGER) FROM users;",
+            "CAST(id AS INTEGER age) FROM users;",
+            "(id AS n) FROM users;",
+        ] {
+            let mut parser = Parser::new(crate::interpreter::tokenizer::tokenize(sql));
+            assert!(
+                get_selectables(&mut parser, true, true, &mut None).is_err(),
+                "{}",
+                sql
+            );
+        }
+    }
+
+    #[test]
+    fn get_selectables_parses_between_bounds() {
+        let sql = "age NOT BETWEEN 20 + 5 AND 30 AND id BETWEEN 1 AND 2, name FROM users;";
+        let mut parser = Parser::new(crate::interpreter::tokenizer::tokenize(sql));
+        let columns = get_selectables(&mut parser, true, false, &mut None).unwrap();
+        assert_eq!(
+            columns[0],
+            SelectableColumn {
+                selectables: vec![
+                    column("age"),
+                    int(20),
+                    int(5),
+                    SelectableStackElement::MathOperator(MathOperator::Add),
+                    int(30),
+                    SelectableStackElement::Operator(Operator::NotBetween),
+                    column("id"),
+                    int(1),
+                    int(2),
+                    SelectableStackElement::Operator(Operator::Between),
+                    SelectableStackElement::LogicalOperator(LogicalOperator::And),
+                ],
+                column_name: "age NOT BETWEEN 20 + 5 AND 30 AND id BETWEEN 1 AND 2".to_string(),
+            }
+        );
+        assert_eq!(columns[1].selectables, vec![column("name")]);
+
+        for sql in [
+            "age BETWEEN 1 FROM users;",
+            "(age BETWEEN 1) AND 2 FROM users;",
+        ] {
+            let mut parser = Parser::new(crate::interpreter::tokenizer::tokenize(sql));
+            assert!(
+                get_selectables(&mut parser, true, false, &mut None).is_err(),
+                "{}",
+                sql
+            );
+        }
+    }
+
+    #[test]
+    fn get_selectables_parses_case_expressions() {
+        let sql = "CASE WHEN age > 30 THEN 'old' ELSE 'young' END AS label, CASE id WHEN 1 THEN 2 END FROM users;";
+        let mut parser = Parser::new(crate::interpreter::tokenizer::tokenize(sql));
+        let columns = get_selectables(&mut parser, true, true, &mut None).unwrap();
+        let text = |value: &str| SelectableColumn {
+            selectables: vec![SelectableStackElement::Value(Value::Text(
+                value.to_string(),
+            ))],
+            column_name: format!("'{}'", value),
+        };
+        assert_eq!(
+            columns,
+            vec![
+                SelectableColumn {
+                    selectables: vec![SelectableStackElement::Case(CaseExpression {
+                        branches: vec![(
+                            SelectableColumn {
+                                selectables: vec![
+                                    column("age"),
+                                    int(30),
+                                    SelectableStackElement::Operator(Operator::GreaterThan),
+                                ],
+                                column_name: "age > 30".to_string(),
+                            },
+                            text("old"),
+                        )],
+                        else_result: Some(text("young")),
+                    })],
+                    column_name: "label".to_string(),
+                },
+                SelectableColumn {
+                    selectables: vec![SelectableStackElement::Case(CaseExpression {
+                        branches: vec![(
+                            SelectableColumn {
+                                selectables: vec![
+                                    column("id"),
+                                    int(1),
+                                    SelectableStackElement::Operator(Operator::Equals),
+                                ],
+                                column_name: "1".to_string(),
+                            },
+                            SelectableColumn {
+                                selectables: vec![int(2)],
+                                column_name: "2".to_string(),
+                            },
+                        )],
+                        else_result: None,
+                    })],
+                    column_name: "CASE id WHEN 1 THEN 2 END".to_string(),
+                },
+            ]
+        );
+
+        for sql in [
+            "CASE END FROM users;",
+            "CASE WHEN age THEN 1 FROM users;",
+            "CASE WHEN age 1 END FROM users;",
+            "CASE WHEN age THEN 1 ELSE 2 ELSE 3 END FROM users;",
+            "CASE WHEN age, id THEN 1 END FROM users;",
+        ] {
+            let mut parser = Parser::new(crate::interpreter::tokenizer::tokenize(sql));
+            assert!(
+                get_selectables(&mut parser, true, true, &mut None).is_err(),
+                "{}",
+                sql
+            );
+        }
+    }
 }
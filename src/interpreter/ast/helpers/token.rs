@@ -1,6 +1,7 @@
 use crate::db::table::core::{value::DataType, value::Value};
 use crate::interpreter::ast::helpers::common::hex_decode;
 use crate::interpreter::ast::parser::Parser;
+use crate::interpreter::quote_identifier;
 use crate::interpreter::tokenizer::scanner::Token;
 use crate::interpreter::tokenizer::token::TokenTypes;
 
@@ -31,9 +32,9 @@ pub fn token_to_value(parser: &Parser) -> Result<Value, String> {
                 .map_err(|_| parser.format_error())?;
             Ok(Value::Real(num))
         }
-        TokenTypes::StringLiteral => Ok(Value::Text(token.value.to_string())), // TODO: rename to StringLiteral
+        TokenTypes::StringLiteral => Ok(Value::Text(token.value.replace("''", "'"))), // TODO: rename to StringLiteral
         TokenTypes::HexLiteral => {
-            let bytes = hex_decode(token.value).map_err(|_| parser.format_error())?;
+            let bytes = hex_decode(&token.value).map_err(|_| parser.format_error())?;
             Ok(Value::Blob(bytes))
         }
         TokenTypes::Null => Ok(Value::Null),
@@ -57,6 +58,7 @@ pub fn token_to_string(token: &Token) -> String {
     match token.token_type {
         TokenTypes::StringLiteral => format!("'{}'", token.value),
         TokenTypes::HexLiteral => format!("X'{}'", token.value),
+        TokenTypes::Identifier => quote_identifier(&token.value) + " ",
         TokenTypes::EOF
         | TokenTypes::SemiColon
         | TokenTypes::LeftParen
@@ -101,4 +103,24 @@ mod tests {
         let result = format_statement_tokens(&tokens);
         assert_eq!("SELECT * FROM users;".to_string(), result);
     }
+
+    #[test]
+    fn format_statement_tokens_quotes_identifiers_that_need_it() {
+        let tokens = vec![
+            token(TokenTypes::Select, "SELECT"),
+            token(TokenTypes::Identifier, "order"),
+            token(TokenTypes::Comma, ","),
+            token(TokenTypes::Identifier, "first name"),
+            token(TokenTypes::Comma, ","),
+            token(TokenTypes::Identifier, "id"),
+            token(TokenTypes::From, "FROM"),
+            token(TokenTypes::Identifier, "users"),
+            token(TokenTypes::SemiColon, ";"),
+        ];
+        let result = format_statement_tokens(&tokens);
+        assert_eq!(
+            "SELECT \"order\", \"first name\", id FROM users;".to_string(),
+            result
+        );
+    }
 }
@@ -4,6 +4,7 @@ use crate::interpreter::{
         InsertIntoStatement,
         SqlStatement::{self, InsertInto},
         helpers::common::get_table_name,
+        helpers::returning_clause::get_returning,
         helpers::token::{expect_token_type, token_to_value},
         parser::Parser,
     },
@@ -41,16 +42,31 @@ fn into_statement(parser: &mut Parser) -> Result<SqlStatement, String> {
     let columns = match token.token_type {
         TokenTypes::LeftParen => Some(get_columns(parser)?),
         TokenTypes::Values => None,
+        TokenTypes::Default => {
+            // DEFAULT VALUES inserts a single row without naming any column, so every
+            // column gets its default value.
+            parser.advance()?;
+            expect_token_type(parser, TokenTypes::Values)?;
+            parser.advance()?;
+            return Ok(InsertInto(InsertIntoStatement {
+                table_name,
+                columns: Some(vec![]),
+                values: vec![vec![]],
+                last_insert_rowids: vec![],
+                returning: get_returning(parser)?,
+            }));
+        }
         _ => return Err(parser.format_error()),
     };
 
     let mut values = vec![];
+    let mut last_insert_rowids = vec![];
 
     let token = parser.current_token()?;
     if token.token_type == TokenTypes::Values {
         parser.advance()?;
         loop {
-            values.push(get_values(parser)?);
+            values.push(get_values(parser, values.len(), &mut last_insert_rowids)?);
             let token = parser.current_token()?;
             match token.token_type {
                 TokenTypes::Comma => {
@@ -65,6 +81,8 @@ fn into_statement(parser: &mut Parser) -> Result<SqlStatement, String> {
         table_name: table_name,
         columns: columns,
         values: values,
+        last_insert_rowids,
+        returning: get_returning(parser)?,
     };
     validate_insert_statement(&statement)?;
     return Ok(InsertInto(statement));
@@ -85,13 +103,28 @@ fn validate_insert_statement(statement: &InsertIntoStatement) -> Result<(), Stri
     return Ok(());
 }
 
-fn get_values(parser: &mut Parser) -> Result<Vec<Value>, String> {
+// Parses a parenthesized row of values. A `last_insert_rowid()` is left NULL and its position is
+// recorded so it can be filled in when the statement runs.
+fn get_values(
+    parser: &mut Parser,
+    row: usize,
+    last_insert_rowids: &mut Vec<(usize, usize)>,
+) -> Result<Vec<Value>, String> {
     // Check for LeftParen
     expect_token_type(parser, TokenTypes::LeftParen)?;
     parser.advance()?;
     let mut values: Vec<Value> = vec![];
     loop {
-        values.push(token_to_value(parser)?);
+        if parser.current_token()?.token_type == TokenTypes::LastInsertRowid {
+            parser.advance()?;
+            expect_token_type(parser, TokenTypes::LeftParen)?;
+            parser.advance()?;
+            expect_token_type(parser, TokenTypes::RightParen)?;
+            last_insert_rowids.push((row, values.len()));
+            values.push(Value::Null);
+        } else {
+            values.push(token_to_value(parser)?);
+        }
         parser.advance()?;
 
         let token = parser.current_token()?;
@@ -166,6 +199,8 @@ mod tests {
                 table_name: "users".to_string(),
                 columns: None,
                 values: vec![vec![Value::Integer(1), Value::Text("Alice".to_string()),]],
+                last_insert_rowids: vec![],
+                returning: None,
             })
         );
     }
@@ -204,6 +239,8 @@ mod tests {
                     vec![Value::Integer(1), Value::Text("Alice".to_string()),],
                     vec![Value::Integer(2), Value::Text("Bob".to_string()),]
                 ],
+                last_insert_rowids: vec![],
+                returning: None,
             })
         );
     }
@@ -248,6 +285,8 @@ mod tests {
                 Value::Blob(vec![0xAA, 0xB0, 0x00]),
                 Value::Null,
             ]],
+            last_insert_rowids: vec![],
+            returning: None,
         });
         assert_eq!(expected, statement);
     }
@@ -370,7 +409,76 @@ mod tests {
             table_name: "users".to_string(),
             columns: Some(vec!["id".to_string()]),
             values: vec![vec![Value::Integer(1)]],
+            last_insert_rowids: vec![],
+            returning: None,
         });
         assert_eq!(expected, statement);
     }
+
+    #[test]
+    fn default_values_insert_statement_is_generated_correctly() {
+        // INSERT INTO users DEFAULT VALUES;
+        let tokens = vec![
+            token(TokenTypes::Insert, "INSERT"),
+            token(TokenTypes::Into, "INTO"),
+            token(TokenTypes::Identifier, "users"),
+            token(TokenTypes::Default, "DEFAULT"),
+            token(TokenTypes::Values, "VALUES"),
+            token(TokenTypes::SemiColon, ";"),
+        ];
+        let mut parser = Parser::new(tokens);
+        let result = build(&mut parser);
+        assert_eq!(
+            result,
+            Ok(SqlStatement::InsertInto(InsertIntoStatement {
+                table_name: "users".to_string(),
+                columns: Some(vec![]),
+                values: vec![vec![]],
+                last_insert_rowids: vec![],
+                returning: None,
+            }))
+        );
+    }
+
+    #[test]
+    fn insert_statement_records_last_insert_rowid_calls() {
+        // INSERT INTO posts VALUES (1, last_insert_rowid()), (last_insert_rowid(), 2);
+        let tokens = vec![
+            token(TokenTypes::Insert, "INSERT"),
+            token(TokenTypes::Into, "INTO"),
+            token(TokenTypes::Identifier, "posts"),
+            token(TokenTypes::Values, "VALUES"),
+            token(TokenTypes::LeftParen, "("),
+            token(TokenTypes::IntLiteral, "1"),
+            token(TokenTypes::Comma, ","),
+            token(TokenTypes::LastInsertRowid, "last_insert_rowid"),
+            token(TokenTypes::LeftParen, "("),
+            token(TokenTypes::RightParen, ")"),
+            token(TokenTypes::RightParen, ")"),
+            token(TokenTypes::Comma, ","),
+            token(TokenTypes::LeftParen, "("),
+            token(TokenTypes::LastInsertRowid, "last_insert_rowid"),
+            token(TokenTypes::LeftParen, "("),
+            token(TokenTypes::RightParen, ")"),
+            token(TokenTypes::Comma, ","),
+            token(TokenTypes::IntLiteral, "2"),
+            token(TokenTypes::RightParen, ")"),
+            token(TokenTypes::SemiColon, ";"),
+        ];
+        let mut parser = Parser::new(tokens);
+        let result = build(&mut parser);
+        assert_eq!(
+            result,
+            Ok(SqlStatement::InsertInto(InsertIntoStatement {
+                table_name: "posts".to_string(),
+                columns: None,
+                values: vec![
+                    vec![Value::Integer(1), Value::Null],
+                    vec![Value::Null, Value::Integer(2)],
+                ],
+                last_insert_rowids: vec![(0, 1), (1, 0)],
+                returning: None,
+            }))
+        );
+    }
 }
@@ -1,6 +1,11 @@
-use crate::db::table::core::{column::ColumnDefinition, row::Row, value::Value};
+use crate::db::table::core::{
+    column::{ColumnDefinition, TableConstraint},
+    row::Row,
+    value::{DataType, Value},
+};
 use crate::interpreter::tokenizer::{scanner::Token, token::TokenTypes};
 
+use std::borrow::Cow;
 use std::collections::HashMap;
 use std::ops::{Deref, DerefMut};
 
@@ -8,9 +13,11 @@ mod alter_table_statement;
 mod create_statement;
 mod delete_statement;
 mod drop_statement;
+mod explain_statement;
 mod helpers;
 mod insert_statement;
 mod parser;
+mod pragma_statement;
 mod select_statement_stack;
 mod statement_builder;
 #[cfg(test)]
@@ -23,6 +30,9 @@ pub struct DatabaseSqlStatement {
     pub sql_statement: SqlStatement,
     pub line_num: usize,
     pub statement_text: String,
+    // Text of a `-- comment` following the statement on the line it ends on, if the tokens
+    // were produced with comments.
+    pub trailing_comment: Option<String>,
 }
 
 #[derive(Debug, PartialEq, Clone)]
@@ -33,12 +43,16 @@ pub enum SqlStatement {
     UpdateStatement(UpdateStatement),
     DeleteStatement(DeleteStatement),
     DropTable(DropTableStatement),
+    CreateIndex(CreateIndexStatement),
+    DropIndex(DropIndexStatement),
     AlterTable(AlterTableStatement),
     BeginTransaction(BeginStatement),
     Commit,
     Rollback(RollbackStatement),
     Savepoint(SavepointStatement),
     Release(ReleaseStatement),
+    ExplainQueryPlan(SelectStatementStack),
+    Pragma(PragmaStatement),
 }
 
 #[derive(Debug, PartialEq, Clone)]
@@ -46,6 +60,9 @@ pub struct CreateTableStatement {
     pub table_name: String,
     pub existence_check: Option<ExistenceCheck>,
     pub columns: Vec<ColumnDefinition>,
+    pub table_constraints: Vec<TableConstraint>,
+    // Set by a trailing `WITHOUT ROWID`, such tables must declare a PRIMARY KEY.
+    pub without_rowid: bool,
 }
 
 #[derive(Debug, PartialEq, Clone)]
@@ -54,6 +71,21 @@ pub struct DropTableStatement {
     pub existence_check: Option<ExistenceCheck>,
 }
 
+#[derive(Debug, PartialEq, Clone)]
+pub struct CreateIndexStatement {
+    pub index_name: String,
+    pub table_name: String,
+    pub existence_check: Option<ExistenceCheck>,
+    pub columns: Vec<String>,
+    pub unique: bool,
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct DropIndexStatement {
+    pub index_name: String,
+    pub existence_check: Option<ExistenceCheck>,
+}
+
 #[derive(Debug, PartialEq, Clone)]
 pub enum ExistenceCheck {
     // Eventually expand to temp tables
@@ -66,12 +98,18 @@ pub struct InsertIntoStatement {
     pub table_name: String,
     pub columns: Option<Vec<String>>,
     pub values: Vec<Vec<Value>>,
+    // The row and column in `values` of each `last_insert_rowid()`, which is only known once the
+    // statement runs. They hold NULL until then.
+    pub last_insert_rowids: Vec<(usize, usize)>,
+    pub returning: Option<Vec<SelectableColumn>>,
 }
 
 impl PartialEq for InsertIntoStatement {
     fn eq(&self, other: &Self) -> bool {
         self.table_name == other.table_name
             && self.columns == other.columns
+            && self.last_insert_rowids == other.last_insert_rowids
+            && self.returning == other.returning
             && self
                 .values
                 .iter()
@@ -117,13 +155,37 @@ impl SetOperator {
     }
 }
 
+#[derive(Debug, PartialEq, Clone)]
+pub enum JoinType {
+    // `a CROSS JOIN b` or `a, b`: every row of `a` with every row of `b`.
+    Cross,
+    // `a [INNER] JOIN b ON condition`: the rows of `a` with the rows of `b` matching the condition.
+    Inner,
+    // `a LEFT [OUTER] JOIN b ON condition`: as INNER, plus the rows of `a` matching no row of `b`,
+    // with NULLs for the columns of `b`.
+    Left,
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct JoinClause {
+    pub join_type: JoinType,
+    pub table_name: String,
+    // Empty if the table has no alias.
+    pub table_alias: String,
+    // Combinations of rows not matching the ON condition are left out, whatever the join type.
+    pub on_clause: Option<SelectableColumn>,
+}
+
 #[derive(Debug, PartialEq, Clone)]
 pub struct SelectStatement {
     pub table_name: String,
     pub table_aliases: TableAliases,
+    // Tables joined to `table_name`, in the order they appear in the FROM clause.
+    pub joins: Vec<JoinClause>,
     pub mode: SelectMode,
     pub columns: Vec<SelectableColumn>,
     pub where_clause: Option<SelectableColumn>,
+    pub group_by_clause: Option<GroupByClause>,
     pub order_by_clause: Option<OrderByClause>,
     pub limit_clause: Option<LimitClause>,
 }
@@ -135,6 +197,7 @@ pub struct DeleteStatement {
     pub where_clause: Option<SelectableColumn>,
     pub order_by_clause: Option<OrderByClause>,
     pub limit_clause: Option<LimitClause>,
+    pub returning: Option<Vec<SelectableColumn>>,
 }
 
 #[derive(Debug, PartialEq, Clone)]
@@ -145,6 +208,7 @@ pub struct UpdateStatement {
     pub where_clause: Option<SelectableColumn>,
     pub order_by_clause: Option<OrderByClause>,
     pub limit_clause: Option<LimitClause>,
+    pub returning: Option<Vec<SelectableColumn>>,
 }
 
 #[derive(Debug, PartialEq, Clone)]
@@ -168,6 +232,9 @@ pub enum AlterTableAction {
     DropColumn {
         column_name: String,
     },
+    SetNotNull {
+        column_name: String,
+    },
 }
 
 #[derive(Debug, PartialEq, Clone)]
@@ -192,6 +259,13 @@ pub struct ReleaseStatement {
     pub savepoint_name: String,
 }
 
+#[derive(Debug, PartialEq, Clone)]
+pub struct PragmaStatement {
+    pub name: String,
+    // The value assigned with `PRAGMA name = value`, None when the pragma is only read.
+    pub value: Option<String>,
+}
+
 #[derive(Debug, PartialEq, Clone)]
 #[repr(transparent)]
 pub struct TableAliases(pub HashMap<String, String>);
@@ -227,10 +301,30 @@ pub enum SelectMode {
     Distinct,
 }
 
+// CASE WHEN condition THEN result ... [ELSE result] END. The result of the first branch whose
+// condition holds is used, or the ELSE result, or NULL without one.
+#[derive(Debug, PartialEq, Clone)]
+pub struct CaseExpression {
+    pub branches: Vec<(SelectableColumn, SelectableColumn)>,
+    pub else_result: Option<SelectableColumn>,
+}
+
 #[derive(Debug, PartialEq, Clone)]
 pub struct FunctionCall {
     pub name: FunctionName,
     pub arguments: Vec<SelectableColumn>,
+    pub distinct: bool,
+}
+
+impl FunctionCall {
+    // MIN and MAX with more than one argument are scalar functions returning the smallest or
+    // largest of their arguments, as in SQLite.
+    pub fn is_aggregate(&self) -> bool {
+        match self.name {
+            FunctionName::Min | FunctionName::Max => self.arguments.len() <= 1,
+            _ => self.name.is_aggregate(),
+        }
+    }
 }
 
 #[derive(Debug, PartialEq, Clone)]
@@ -240,11 +334,36 @@ pub enum FunctionName {
     Avg,
     Min,
     Max,
+    GroupConcat,
     Date,
     Time,
     DateTime,
     JulianDay,
     UnixEpoch,
+    Abs,
+    Round,
+    Random,
+    Quote,
+    Length,
+    Trim,
+    Ltrim,
+    Rtrim,
+    Substr,
+    Upper,
+    Lower,
+    Coalesce,
+    IfNull,
+    JsonExtract,
+    // Numbers the rows of a SELECT from 1 in the order they are output, after ORDER BY and OFFSET.
+    // There is no OVER clause, the whole result is a single partition. ORDER BY can't refer to it
+    // and it can't be used with GROUP BY or aggregate functions.
+    RowNumber,
+    // The rowid of the last row inserted into the database, see `Database::last_insert_rowid`.
+    LastInsertRowid,
+    // The number of rows changed by the last INSERT, UPDATE or DELETE, and by all of them since the
+    // database was opened, see `Database::changes`.
+    Changes,
+    TotalChanges,
     // TODO: Support Strftime
     // TODO: Support TimeDiff
 }
@@ -256,12 +375,67 @@ impl FunctionName {
             | FunctionName::Sum
             | FunctionName::Avg
             | FunctionName::Min
-            | FunctionName::Max => true,
+            | FunctionName::Max
+            | FunctionName::GroupConcat => true,
             FunctionName::Date
             | FunctionName::Time
             | FunctionName::DateTime
             | FunctionName::JulianDay
-            | FunctionName::UnixEpoch => false,
+            | FunctionName::UnixEpoch
+            | FunctionName::Abs
+            | FunctionName::Round
+            | FunctionName::Random
+            | FunctionName::Quote
+            | FunctionName::Length
+            | FunctionName::Trim
+            | FunctionName::Ltrim
+            | FunctionName::Rtrim
+            | FunctionName::Substr
+            | FunctionName::Upper
+            | FunctionName::Lower
+            | FunctionName::Coalesce
+            | FunctionName::IfNull
+            | FunctionName::JsonExtract
+            | FunctionName::RowNumber
+            | FunctionName::LastInsertRowid
+            | FunctionName::Changes
+            | FunctionName::TotalChanges => false,
+        }
+    }
+
+    // Whether the function returns NULL as soon as one of its arguments is NULL. Functions
+    // that give NULL arguments a meaning of their own, like COALESCE or QUOTE, don't.
+    pub fn propagates_null(&self) -> bool {
+        match self {
+            FunctionName::Abs
+            | FunctionName::Round
+            | FunctionName::Length
+            | FunctionName::Trim
+            | FunctionName::Ltrim
+            | FunctionName::Rtrim
+            | FunctionName::Substr
+            | FunctionName::Upper
+            | FunctionName::Lower
+            | FunctionName::JsonExtract => true,
+            FunctionName::Count
+            | FunctionName::Sum
+            | FunctionName::Avg
+            | FunctionName::Min
+            | FunctionName::Max
+            | FunctionName::GroupConcat
+            | FunctionName::Date
+            | FunctionName::Time
+            | FunctionName::DateTime
+            | FunctionName::JulianDay
+            | FunctionName::UnixEpoch
+            | FunctionName::Random
+            | FunctionName::Quote
+            | FunctionName::Coalesce
+            | FunctionName::IfNull
+            | FunctionName::RowNumber
+            | FunctionName::LastInsertRowid
+            | FunctionName::Changes
+            | FunctionName::TotalChanges => false,
         }
     }
 }
@@ -273,6 +447,7 @@ pub enum MathOperator {
     Multiply,
     Divide,
     Modulo,
+    Concat,
 }
 
 #[derive(Debug, PartialEq, Clone)]
@@ -286,8 +461,19 @@ pub enum SelectableStackElement {
     All,
     Column(String),
     Value(Value),
-    ValueList(Vec<Value>), // TODO: add column as data type in Value
+    // Right operand of IN, each entry being a row of as many values as the left operand.
+    ValueList(Vec<Vec<Value>>),
     Function(FunctionCall),
+    // EXISTS (subquery), evaluates to 1 if the subquery returns any row.
+    Exists(Box<SelectStatementStack>),
+    // IN (subquery), replaced by the ValueList of the rows of the subquery before evaluation.
+    InSubquery(Box<SelectStatementStack>),
+    // (subquery) used as a value, replaced by the first value of its first row, or NULL if it
+    // returns no row, before evaluation.
+    ScalarSubquery(Box<SelectStatementStack>),
+    // CAST(expression AS type), converts the value of the expression before it.
+    Cast(DataType),
+    Case(CaseExpression),
     Operator(Operator),
     LogicalOperator(LogicalOperator),
     MathOperator(MathOperator),
@@ -301,11 +487,27 @@ impl PartialEq for SelectableStackElement {
             (SelectableStackElement::Value(a), SelectableStackElement::Value(b)) => {
                 a.exactly_equal(b)
             }
-            (SelectableStackElement::ValueList(a), SelectableStackElement::ValueList(b)) => a
-                .iter()
-                .zip(b.iter())
-                .all(|(first, second)| first.exactly_equal(second)),
+            (SelectableStackElement::ValueList(a), SelectableStackElement::ValueList(b)) => {
+                a.len() == b.len()
+                    && a.iter().zip(b.iter()).all(|(first, second)| {
+                        first.len() == second.len()
+                            && first
+                                .iter()
+                                .zip(second.iter())
+                                .all(|(first, second)| first.exactly_equal(second))
+                    })
+            }
             (SelectableStackElement::Function(a), SelectableStackElement::Function(b)) => a == b,
+            (SelectableStackElement::Exists(a), SelectableStackElement::Exists(b)) => a == b,
+            (SelectableStackElement::InSubquery(a), SelectableStackElement::InSubquery(b)) => {
+                a == b
+            }
+            (
+                SelectableStackElement::ScalarSubquery(a),
+                SelectableStackElement::ScalarSubquery(b),
+            ) => a == b,
+            (SelectableStackElement::Cast(a), SelectableStackElement::Cast(b)) => a == b,
+            (SelectableStackElement::Case(a), SelectableStackElement::Case(b)) => a == b,
             (SelectableStackElement::Operator(a), SelectableStackElement::Operator(b)) => a == b,
             (
                 SelectableStackElement::LogicalOperator(a),
@@ -331,6 +533,13 @@ pub enum Operator {
     NotIn,
     Is,
     IsNot,
+    Glob,
+    Like,
+    // `text LIKE pattern ESCAPE character`, which like BETWEEN has three operands.
+    LikeEscape,
+    // `value BETWEEN low AND high`.
+    Between,
+    NotBetween,
 }
 
 #[derive(Debug, PartialEq, Clone)]
@@ -373,57 +582,62 @@ pub struct OrderByClause {
     pub directions: Vec<OrderByDirection>,
 }
 
+#[derive(Debug, PartialEq, Clone)]
+pub struct GroupByClause {
+    pub columns: Vec<SelectableColumn>,
+}
+
 #[derive(Debug, PartialEq, Clone)]
 pub struct LimitClause {
     pub limit: usize,
     pub offset: Option<usize>,
 }
 
-pub trait StatementBuilder {
-    fn build_create(&self, parser: &mut parser::Parser) -> Result<SqlStatement, String>;
-    fn build_insert(&self, parser: &mut parser::Parser) -> Result<SqlStatement, String>;
-    fn build_select(&self, parser: &mut parser::Parser) -> Result<SqlStatement, String>;
-    fn build_update(&self, parser: &mut parser::Parser) -> Result<SqlStatement, String>;
-    fn build_delete(&self, parser: &mut parser::Parser) -> Result<SqlStatement, String>;
-    fn build_drop(&self, parser: &mut parser::Parser) -> Result<SqlStatement, String>;
-    fn build_alter(&self, parser: &mut parser::Parser) -> Result<SqlStatement, String>;
+#[derive(Debug, Default, Clone)]
+pub struct ParseOptions {
+    // Stop at the first error and report tokens left after a complete statement,
+    // instead of skipping ahead to the next semicolon.
+    pub strict: bool,
+    // Treat a statement keyword starting a line as the start of a new statement when the previous
+    // one is missing its semicolon. This is a best effort: a statement can't always be told apart
+    // from the continuation of the previous one, so keywords that can continue a statement (a
+    // SELECT after UNION or AS, a DROP in an ALTER TABLE...) are never taken as boundaries, and
+    // neither is anything inside parentheses.
+    pub lenient_splitting: bool,
 }
 
-pub struct DefaultStatementBuilder;
-
-impl StatementBuilder for DefaultStatementBuilder {
-    fn build_create(&self, parser: &mut parser::Parser) -> Result<SqlStatement, String> {
-        create_statement::build(parser)
-    }
-
-    fn build_insert(&self, parser: &mut parser::Parser) -> Result<SqlStatement, String> {
-        insert_statement::build(parser)
-    }
-
-    fn build_select(&self, parser: &mut parser::Parser) -> Result<SqlStatement, String> {
-        select_statement_stack::build(parser)
-    }
-
-    fn build_update(&self, parser: &mut parser::Parser) -> Result<SqlStatement, String> {
-        update_statement::build(parser)
-    }
-
-    fn build_delete(&self, parser: &mut parser::Parser) -> Result<SqlStatement, String> {
-        delete_statement::build(parser)
-    }
-
-    fn build_drop(&self, parser: &mut parser::Parser) -> Result<SqlStatement, String> {
-        drop_statement::build(parser)
-    }
-
-    fn build_alter(&self, parser: &mut parser::Parser) -> Result<SqlStatement, String> {
-        alter_table_statement::build(parser)
-    }
+pub fn generate(tokens: Vec<Token>) -> Vec<Result<DatabaseSqlStatement, String>> {
+    return generate_with_options(tokens, ParseOptions::default());
 }
 
-pub fn generate(tokens: Vec<Token>) -> Vec<Result<DatabaseSqlStatement, String>> {
+pub fn generate_with_options(
+    tokens: Vec<Token>,
+    options: ParseOptions,
+) -> Vec<Result<DatabaseSqlStatement, String>> {
     let mut results: Vec<Result<DatabaseSqlStatement, String>> = vec![];
-    let mut parser = parser::Parser::new(tokens);
+    // Comments are not parsed, the ones right after a semicolon on the same line are kept
+    // by the position of that semicolon.
+    let mut trailing_comments = HashMap::new();
+    let mut statement_tokens = vec![];
+    let tokens = if options.lenient_splitting {
+        insert_implicit_semicolons(tokens)
+    } else {
+        tokens
+    };
+    for token in tokens {
+        if token.token_type != TokenTypes::Comment {
+            statement_tokens.push(token);
+            continue;
+        }
+        if let Some(previous) = statement_tokens.last()
+            && previous.token_type == TokenTypes::SemiColon
+            && previous.line_num == token.line_num
+        {
+            trailing_comments.insert(statement_tokens.len() - 1, token.value.trim().to_string());
+        }
+    }
+    let mut parser = parser::Parser::new(statement_tokens);
+    parser.set_strict(options.strict);
     loop {
         let line_num = match parser.line_num() {
             Ok(line_num) => line_num,
@@ -437,6 +651,9 @@ pub fn generate(tokens: Vec<Token>) -> Vec<Result<DatabaseSqlStatement, String>>
             match next_statement {
                 Err(error) => {
                     results.push(Err(error));
+                    if parser.is_strict() {
+                        return results;
+                    }
                     // If we encountered a parsing error, skip until we find a semicolon or EOF
                     loop {
                         if let Ok(token) = parser.current_token() {
@@ -456,6 +673,14 @@ pub fn generate(tokens: Vec<Token>) -> Vec<Result<DatabaseSqlStatement, String>>
                     }
                 }
                 Ok(sql_statement) => {
+                    if parser.is_strict()
+                        && let Ok(token) = parser.current_token()
+                        && token.token_type != TokenTypes::SemiColon
+                    {
+                        results.push(Err(parser.format_trailing_token_error()));
+                        return results;
+                    }
+                    let trailing_comment = trailing_comments.remove(&parser.position());
                     let parser_advance_result = parser.advance_past_semicolon();
                     if parser_advance_result.is_err() {
                         results.push(Err(parser_advance_result.err().unwrap()));
@@ -465,6 +690,7 @@ pub fn generate(tokens: Vec<Token>) -> Vec<Result<DatabaseSqlStatement, String>>
                         sql_statement: sql_statement,
                         line_num: line_num,
                         statement_text: parser.get_sql_statement_text(),
+                        trailing_comment,
                     }));
                 }
             }
@@ -475,9 +701,103 @@ pub fn generate(tokens: Vec<Token>) -> Vec<Result<DatabaseSqlStatement, String>>
     return results;
 }
 
+// Adds the semicolon missing before a statement keyword that starts a line, see
+// `ParseOptions::lenient_splitting`. The semicolon is placed right after the previous token.
+fn insert_implicit_semicolons(tokens: Vec<Token>) -> Vec<Token> {
+    let mut result: Vec<Token> = vec![];
+    let mut depth = 0usize;
+    // The first token of the current statement and the last token that isn't a comment.
+    let mut first: Option<TokenTypes> = None;
+    let mut previous: Option<(TokenTypes, Cow<str>, usize, usize)> = None;
+    for token in tokens {
+        if token.token_type == TokenTypes::Comment {
+            result.push(token);
+            continue;
+        }
+        if let Some((previous_type, previous_value, line_num, col_num)) = previous
+            && depth == 0
+            && line_num < token.line_num
+            && starts_statement(&token.token_type)
+            && !continues_statement(first.as_ref(), &previous_type, &previous_value, &token)
+        {
+            result.push(Token {
+                token_type: TokenTypes::SemiColon,
+                value: ";".into(),
+                col_num: col_num + previous_value.len(),
+                line_num,
+            });
+            first = None;
+        }
+        match token.token_type {
+            TokenTypes::LeftParen => depth += 1,
+            TokenTypes::RightParen => depth = depth.saturating_sub(1),
+            TokenTypes::SemiColon => depth = 0,
+            _ => {}
+        }
+        first = match token.token_type {
+            TokenTypes::SemiColon => None,
+            _ => first.or(Some(token.token_type.clone())),
+        };
+        previous = Some((
+            token.token_type.clone(),
+            token.value.clone(),
+            token.line_num,
+            token.col_num,
+        ));
+        result.push(token);
+    }
+    result
+}
+
+fn starts_statement(token_type: &TokenTypes) -> bool {
+    matches!(
+        token_type,
+        TokenTypes::Create
+            | TokenTypes::Insert
+            | TokenTypes::Select
+            | TokenTypes::Update
+            | TokenTypes::Delete
+            | TokenTypes::Drop
+            | TokenTypes::Alter
+            | TokenTypes::Begin
+            | TokenTypes::Commit
+            | TokenTypes::Rollback
+            | TokenTypes::Savepoint
+            | TokenTypes::Release
+            | TokenTypes::Explain
+            | TokenTypes::Pragma
+    )
+}
+
+fn continues_statement(
+    first: Option<&TokenTypes>,
+    previous_type: &TokenTypes,
+    previous_value: &str,
+    token: &Token,
+) -> bool {
+    match (first, previous_type, &token.token_type) {
+        (_, TokenTypes::SemiColon, _) => true,
+        (
+            _,
+            TokenTypes::Union
+            | TokenTypes::Intersect
+            | TokenTypes::Except
+            | TokenTypes::All
+            | TokenTypes::As
+            | TokenTypes::Or,
+            _,
+        ) => true,
+        (Some(TokenTypes::Explain), _, TokenTypes::Select) => {
+            previous_value.eq_ignore_ascii_case("PLAN")
+        }
+        (Some(TokenTypes::Alter), _, TokenTypes::Drop) => true,
+        _ => false,
+    }
+}
+
 #[cfg(test)]
 mod tests {
-    use super::test_utils::token;
+    use super::test_utils::{token, token_with_location};
     use super::*;
 
     #[test]
@@ -502,6 +822,119 @@ mod tests {
         assert_eq!(expected, result);
     }
 
+    #[test]
+    fn ast_strict_mode_rejects_trailing_tokens() {
+        // SELECT * FROM users AS u garbage; DELETE FROM users garbage;
+        let select_tokens = vec![
+            token(TokenTypes::Select, "SELECT"),
+            token(TokenTypes::Asterisk, "*"),
+            token(TokenTypes::From, "FROM"),
+            token(TokenTypes::Identifier, "users"),
+            token(TokenTypes::As, "AS"),
+            token(TokenTypes::Identifier, "u"),
+            token_with_location(TokenTypes::Identifier, "garbage", 25, 1),
+            token(TokenTypes::SemiColon, ";"),
+            token(TokenTypes::EOF, ""),
+        ];
+        let strict = ParseOptions {
+            strict: true,
+            ..Default::default()
+        };
+        let result = generate_with_options(select_tokens, strict.clone());
+        let expected = vec![Err(
+            "Error at line 1, column 25: unexpected token 'garbage'".to_string(),
+        )];
+        assert_eq!(expected, result);
+
+        let delete_tokens = vec![
+            token(TokenTypes::Delete, "DELETE"),
+            token(TokenTypes::From, "FROM"),
+            token(TokenTypes::Identifier, "users"),
+            token_with_location(TokenTypes::Identifier, "garbage", 19, 2),
+            token(TokenTypes::SemiColon, ";"),
+            token(TokenTypes::Delete, "DELETE"),
+            token(TokenTypes::From, "FROM"),
+            token(TokenTypes::Identifier, "users"),
+            token(TokenTypes::SemiColon, ";"),
+            token(TokenTypes::EOF, ""),
+        ];
+        let result = generate_with_options(delete_tokens, strict);
+        let expected = vec![Err(
+            "Error at line 2, column 19: unexpected token 'garbage'".to_string(),
+        )];
+        assert_eq!(expected, result);
+    }
+
+    #[test]
+    fn ast_lenient_splitting_adds_missing_semicolons() {
+        // SELECT * FROM users
+        // DELETE FROM users
+        // SELECT id FROM users
+        // UNION
+        // SELECT id FROM users WHERE id IN (
+        // SELECT id FROM users);
+        let tokens = || {
+            vec![
+                token_with_location(TokenTypes::Select, "SELECT", 0, 1),
+                token_with_location(TokenTypes::Asterisk, "*", 7, 1),
+                token_with_location(TokenTypes::From, "FROM", 9, 1),
+                token_with_location(TokenTypes::Identifier, "users", 14, 1),
+                token_with_location(TokenTypes::Delete, "DELETE", 0, 2),
+                token_with_location(TokenTypes::From, "FROM", 7, 2),
+                token_with_location(TokenTypes::Identifier, "users", 12, 2),
+                token_with_location(TokenTypes::Select, "SELECT", 0, 3),
+                token_with_location(TokenTypes::Identifier, "id", 7, 3),
+                token_with_location(TokenTypes::From, "FROM", 10, 3),
+                token_with_location(TokenTypes::Identifier, "users", 15, 3),
+                token_with_location(TokenTypes::Union, "UNION", 0, 4),
+                token_with_location(TokenTypes::Select, "SELECT", 0, 5),
+                token_with_location(TokenTypes::Identifier, "id", 7, 5),
+                token_with_location(TokenTypes::From, "FROM", 10, 5),
+                token_with_location(TokenTypes::Identifier, "users", 15, 5),
+                token_with_location(TokenTypes::Where, "WHERE", 21, 5),
+                token_with_location(TokenTypes::Identifier, "id", 27, 5),
+                token_with_location(TokenTypes::In, "IN", 30, 5),
+                token_with_location(TokenTypes::LeftParen, "(", 33, 5),
+                token_with_location(TokenTypes::Select, "SELECT", 0, 6),
+                token_with_location(TokenTypes::Identifier, "id", 7, 6),
+                token_with_location(TokenTypes::From, "FROM", 10, 6),
+                token_with_location(TokenTypes::Identifier, "users", 15, 6),
+                token_with_location(TokenTypes::RightParen, ")", 20, 6),
+                token_with_location(TokenTypes::SemiColon, ";", 21, 6),
+                token_with_location(TokenTypes::EOF, "", 0, 0),
+            ]
+        };
+        let lenient = ParseOptions {
+            lenient_splitting: true,
+            ..Default::default()
+        };
+        let result = generate_with_options(tokens(), lenient);
+        assert_eq!(result.len(), 3);
+        let statements = result
+            .into_iter()
+            .map(|result| result.unwrap())
+            .collect::<Vec<_>>();
+        assert!(matches!(
+            statements[0].sql_statement,
+            SqlStatement::Select(_)
+        ));
+        assert!(matches!(
+            statements[1].sql_statement,
+            SqlStatement::DeleteStatement(_)
+        ));
+        match &statements[2].sql_statement {
+            SqlStatement::Select(stack) => assert_eq!(stack.elements.len(), 3),
+            _ => panic!("Expected a compound SELECT"),
+        }
+        assert_eq!(
+            statements.iter().map(|s| s.line_num).collect::<Vec<_>>(),
+            vec![1, 2, 3]
+        );
+
+        // Without the option the missing semicolons are errors.
+        assert!(generate(tokens()).iter().any(|result| result.is_err()));
+    }
+
     #[test]
     fn ast_handles_multiple_statements() {
         // SELECT * FROM users; INSERT INTO users VALUES (1, "Alice");
@@ -533,12 +966,14 @@ mod tests {
                         SelectStatement {
                             table_name: "users".to_string(),
                             table_aliases: TableAliases(HashMap::new()),
+                            joins: vec![],
                             mode: SelectMode::All,
                             columns: vec![SelectableColumn {
                                 selectables: vec![SelectableStackElement::All],
                                 column_name: "*".to_string(),
                             }],
                             where_clause: None,
+                            group_by_clause: None,
                             order_by_clause: None,
                             limit_clause: None,
                         },
@@ -548,15 +983,19 @@ mod tests {
                 }),
                 line_num: 1,
                 statement_text: "SELECT * FROM users;".to_string(),
+                trailing_comment: None,
             }),
             Ok(DatabaseSqlStatement {
                 sql_statement: SqlStatement::InsertInto(InsertIntoStatement {
                     table_name: "users".to_string(),
                     columns: None,
                     values: vec![vec![Value::Integer(1), Value::Text("Alice".to_string())]],
+                    last_insert_rowids: vec![],
+                    returning: None,
                 }),
                 line_num: 1,
                 statement_text: "INSERT INTO users VALUES (1, 'Alice');".to_string(),
+                trailing_comment: None,
             }),
         ];
         assert_eq!(expected, result);
@@ -566,7 +1005,6 @@ mod tests {
     fn ast_handles_invalid_statement_then_valid_statement() {
         let tokens = vec![
             token(TokenTypes::Select, "SELECT"),
-            token(TokenTypes::Identifier, "users"),
             token(TokenTypes::SemiColon, ";"),
             token(TokenTypes::Insert, "INSERT"),
             token(TokenTypes::Into, "INTO"),
@@ -590,9 +1028,12 @@ mod tests {
                     table_name: "users".to_string(),
                     columns: None,
                     values: vec![vec![Value::Integer(1), Value::Text("Alice".to_string())]],
+                    last_insert_rowids: vec![],
+                    returning: None,
                 }),
                 line_num: 1,
                 statement_text: "INSERT INTO users VALUES (1, 'Alice');".to_string(),
+                trailing_comment: None,
             }),
         ];
         assert_eq!(expected, result);
@@ -628,12 +1069,14 @@ mod tests {
                         SelectStatement {
                             table_name: "users".to_string(),
                             table_aliases: TableAliases(HashMap::new()),
+                            joins: vec![],
                             mode: SelectMode::All,
                             columns: vec![SelectableColumn {
                                 selectables: vec![SelectableStackElement::All],
                                 column_name: "*".to_string(),
                             }],
                             where_clause: None,
+                            group_by_clause: None,
                             order_by_clause: None,
                             limit_clause: None,
                         },
@@ -643,17 +1086,74 @@ mod tests {
                 }),
                 line_num: 1,
                 statement_text: "SELECT * FROM users;".to_string(),
+                trailing_comment: None,
             }),
             Ok(DatabaseSqlStatement {
                 sql_statement: SqlStatement::InsertInto(InsertIntoStatement {
                     table_name: "users".to_string(),
                     columns: None,
                     values: vec![vec![Value::Integer(1), Value::Text("Alice".to_string())]],
+                    last_insert_rowids: vec![],
+                    returning: None,
                 }),
                 line_num: 1,
                 statement_text: "INSERT INTO users VALUES (1, 'Alice');".to_string(),
+                trailing_comment: None,
             }),
         ];
         assert_eq!(expected, result);
     }
+
+    #[test]
+    fn ast_attaches_trailing_comments_to_statements() {
+        let sql = "SELECT * FROM users; -- list users\n\
+                   -- a comment on its own line\n\
+                   DELETE FROM users -- not after the semicolon\n;\n\
+                   DROP TABLE users;-- drop it  ";
+        let result = generate(crate::interpreter::tokenizer::tokenize_with_comments(sql));
+        let comments = result
+            .into_iter()
+            .map(|statement| statement.unwrap().trailing_comment)
+            .collect::<Vec<Option<String>>>();
+        assert_eq!(
+            comments,
+            vec![
+                Some("list users".to_string()),
+                None,
+                Some("drop it".to_string())
+            ]
+        );
+    }
+
+    #[test]
+    fn ast_statement_text_round_trips_blob_and_text_literals() {
+        let sql = "INSERT INTO files VALUES (X'00FF', x'ab', 'it''s', X'');";
+        let statement = generate(crate::interpreter::tokenizer::tokenize(sql))
+            .remove(0)
+            .unwrap();
+        assert_eq!(
+            statement.statement_text,
+            "INSERT INTO files VALUES (X'00FF', X'ab', 'it''s', X'');"
+        );
+        let SqlStatement::InsertInto(insert) = &statement.sql_statement else {
+            panic!("Expected an INSERT statement");
+        };
+        assert_eq!(
+            insert.values,
+            vec![vec![
+                Value::Blob(vec![0x00, 0xff]),
+                Value::Blob(vec![0xab]),
+                Value::Text("it's".to_string()),
+                Value::Blob(vec![]),
+            ]]
+        );
+
+        let reparsed = generate(crate::interpreter::tokenizer::tokenize(
+            &statement.statement_text,
+        ))
+        .remove(0)
+        .unwrap();
+        assert_eq!(reparsed.sql_statement, statement.sql_statement);
+        assert_eq!(reparsed.statement_text, statement.statement_text);
+    }
 }
@@ -13,6 +13,7 @@ pub struct Parser<'a> {
     start: usize,
     current: usize,
     builder: &'a dyn StatementBuilder,
+    strict: bool,
 }
 
 impl<'a> Parser<'a> {
@@ -22,9 +23,20 @@ impl<'a> Parser<'a> {
             start: 0,
             current: 0,
             builder: &DefaultStatementBuilder {},
+            strict: false,
         };
     }
 
+    // In strict mode tokens left over after a complete statement are reported as such
+    // instead of being treated like any other unexpected value.
+    pub fn set_strict(&mut self, strict: bool) {
+        self.strict = strict;
+    }
+
+    pub fn is_strict(&self) -> bool {
+        return self.strict;
+    }
+
     pub fn line_num(&self) -> Result<usize, String> {
         return Ok(self.current_token()?.line_num);
     }
@@ -37,16 +49,36 @@ impl<'a> Parser<'a> {
     }
 
     pub fn peek_token(&self) -> Result<&Token<'a>, String> {
-        if self.current + 1 >= self.tokens.len() {
-            return Err(self.format_error());
-        }
-        return Ok(&self.tokens[self.current + 1]);
+        return self.peek(1).ok_or_else(|| self.format_error());
+    }
+
+    // Returns the token `n` positions ahead of the current one without consuming anything,
+    // `peek(0)` being the current token. Returns None past the end of the input.
+    pub fn peek(&self, n: usize) -> Option<&Token<'a>> {
+        self.tokens.get(self.current.checked_add(n)?)
+    }
+
+    // Whether the token `n` positions ahead exists and has the given type.
+    pub fn peek_is(&self, n: usize, token_type: TokenTypes) -> bool {
+        self.peek(n)
+            .is_some_and(|token| token.token_type == token_type)
     }
 
     pub fn get_sql_statement_text(&self) -> String {
         return format_statement_tokens(&self.tokens[self.start..self.current]);
     }
 
+    pub fn position(&self) -> usize {
+        return self.current;
+    }
+
+    // Returns the text of the tokens from the given position up to, but excluding, the current token.
+    pub fn get_text_since(&self, position: usize) -> String {
+        return format_statement_tokens(&self.tokens[position..self.current])
+            .trim()
+            .to_string();
+    }
+
     pub fn advance(&mut self) -> Result<(), String> {
         if let Ok(token) = self.current_token() {
             if token.token_type == TokenTypes::SemiColon {
@@ -81,6 +113,17 @@ impl<'a> Parser<'a> {
         }
     }
 
+    pub fn format_trailing_token_error(&self) -> String {
+        if !self.strict || self.current >= self.tokens.len() {
+            return self.format_error();
+        }
+        let token = &self.tokens[self.current];
+        return format!(
+            "Error at line {:?}, column {:?}: unexpected token '{}'",
+            token.line_num, token.col_num, token.value
+        );
+    }
+
     pub fn format_error_nearby(&self) -> String {
         if self.current < self.tokens.len() {
             let token = &self.tokens[self.current];
@@ -113,6 +156,8 @@ impl<'a> Parser<'a> {
                 (TokenTypes::Rollback, _) => Some(self.builder.build_rollback(self)),
                 (TokenTypes::Savepoint, _) => Some(self.builder.build_savepoint(self)),
                 (TokenTypes::Release, _) => Some(self.builder.build_release(self)),
+                (TokenTypes::Explain, _) => Some(self.builder.build_explain(self)),
+                (TokenTypes::Pragma, _) => Some(self.builder.build_pragma(self)),
                 _ => Some(Err(self.format_error())),
             },
             (Ok(token), Err(_)) => match token.token_type {
@@ -171,6 +216,7 @@ mod tests {
             start: 0,
             current: 0,
             builder: &MockStatementBuilder,
+            strict: false,
         };
         // Create Table
         let result = parser.next_statement();
@@ -178,6 +224,8 @@ mod tests {
             table_name: "users".to_string(),
             existence_check: None,
             columns: vec![],
+            table_constraints: vec![],
+            without_rowid: false,
         })));
         assert_eq!(result, expected);
 
@@ -187,6 +235,8 @@ mod tests {
             table_name: "users".to_string(),
             columns: None,
             values: vec![],
+            last_insert_rowids: vec![],
+            returning: None,
         })));
         assert_eq!(result, expected);
 
@@ -197,12 +247,14 @@ mod tests {
                 SelectStatement {
                     table_name: "users".to_string(),
                     table_aliases: TableAliases(HashMap::new()),
+                    joins: vec![],
                     mode: SelectMode::All,
                     columns: vec![SelectableColumn {
                         selectables: vec![SelectableStackElement::All],
                         column_name: "*".to_string(),
                     }],
                     where_clause: None,
+                    group_by_clause: None,
                     order_by_clause: None,
                     limit_clause: None,
                 },
@@ -230,6 +282,7 @@ mod tests {
             start: 0,
             current: 0,
             builder: &MockStatementBuilder,
+            strict: false,
         };
         let result = parser.next_statement();
         let expected = Some(Err(
@@ -237,4 +290,34 @@ mod tests {
         ));
         assert_eq!(result, expected);
     }
+
+    #[test]
+    fn parser_peek_looks_ahead_without_consuming() {
+        let tokens = vec![
+            token(TokenTypes::Is, "IS"),
+            token(TokenTypes::Not, "NOT"),
+            token(TokenTypes::Null, "NULL"),
+        ];
+        let mut parser = Parser::new(tokens);
+        assert_eq!(parser.peek(0).unwrap().value, "IS");
+        assert_eq!(parser.peek(2).unwrap().value, "NULL");
+        assert!(parser.peek_is(1, TokenTypes::Not));
+        assert!(!parser.peek_is(1, TokenTypes::Null));
+        assert_eq!(parser.position(), 0);
+
+        // Past the last token there is nothing to peek at.
+        assert!(parser.peek(3).is_none());
+        assert!(!parser.peek_is(3, TokenTypes::Null));
+        assert!(parser.peek(usize::MAX).is_none());
+        parser.advance().unwrap();
+        parser.advance().unwrap();
+        assert_eq!(parser.peek(0).unwrap().value, "NULL");
+        assert!(parser.peek(1).is_none());
+        assert!(parser.peek_token().is_err());
+        parser.advance().unwrap();
+        assert!(parser.peek(0).is_none());
+
+        let parser = Parser::new(vec![]);
+        assert!(parser.peek(0).is_none());
+    }
 }
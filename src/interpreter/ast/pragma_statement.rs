@@ -0,0 +1,60 @@
+use crate::interpreter::ast::{
+    PragmaStatement, SqlStatement, helpers::token::expect_token_type, parser::Parser,
+};
+use crate::interpreter::tokenizer::token::TokenTypes;
+
+// Parses `PRAGMA name;` and `PRAGMA name = value;`. The value is kept as written, it is up to
+// the database to interpret it for the given pragma.
+pub fn build(parser: &mut Parser) -> Result<SqlStatement, String> {
+    parser.advance()?;
+    expect_token_type(parser, TokenTypes::Identifier)?;
+    let name = parser.current_token()?.value.to_string();
+    parser.advance()?;
+
+    let mut value = None;
+    if parser.current_token()?.token_type == TokenTypes::Equals {
+        parser.advance()?;
+        let token = parser.current_token()?;
+        match token.token_type {
+            TokenTypes::Identifier
+            | TokenTypes::On
+            | TokenTypes::IntLiteral
+            | TokenTypes::TrueLiteral
+            | TokenTypes::FalseLiteral => value = Some(token.value.to_string()),
+            _ => return Err(parser.format_error()),
+        }
+        parser.advance()?;
+    }
+    expect_token_type(parser, TokenTypes::SemiColon)?;
+    Ok(SqlStatement::Pragma(PragmaStatement { name, value }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::interpreter::tokenizer::tokenize;
+
+    #[test]
+    fn pragma_parses_an_optional_value() {
+        let cases = vec![
+            ("PRAGMA foreign_keys = ON;", Some("ON")),
+            ("PRAGMA foreign_keys = 0;", Some("0")),
+            ("PRAGMA foreign_keys = off;", Some("off")),
+            ("PRAGMA foreign_keys;", None),
+        ];
+        for (sql, value) in cases {
+            let mut parser = Parser::new(tokenize(sql));
+            assert_eq!(
+                build(&mut parser),
+                Ok(SqlStatement::Pragma(PragmaStatement {
+                    name: "foreign_keys".to_string(),
+                    value: value.map(|value| value.to_string()),
+                })),
+                "{}",
+                sql
+            );
+        }
+        let mut parser = Parser::new(tokenize("PRAGMA foreign_keys = ;"));
+        assert!(build(&mut parser).is_err());
+    }
+}
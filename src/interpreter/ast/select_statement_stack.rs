@@ -3,13 +3,26 @@ use crate::interpreter::ast::helpers::limit_clause::get_limit;
 use crate::interpreter::ast::helpers::order_by_clause::get_order_by;
 use crate::interpreter::ast::helpers::select_statement;
 use crate::interpreter::ast::{
-    SelectStackOperators, SelectStatementStack, SelectStatementStackElement, SetOperator,
-    SqlStatement, parser::Parser,
+    SelectStackOperators, SelectStatementStack, SelectStatementStackElement, SelectableColumn,
+    SetOperator, SqlStatement, parser::Parser,
 };
 use crate::interpreter::tokenizer::token::TokenTypes;
 
 // Returns a SelectStatementStack which is an RPN representation of the SELECT statements and set operators.
 pub fn build(parser: &mut Parser) -> Result<SqlStatement, String> {
+    return Ok(SqlStatement::Select(get_statement_stack(parser, false)?));
+}
+
+// Parses a parenthesized subquery, starting at its first SELECT. The closing parenthesis of the
+// subquery is left as the current token.
+pub fn build_subquery(parser: &mut Parser) -> Result<SelectStatementStack, String> {
+    return get_statement_stack(parser, true);
+}
+
+fn get_statement_stack(
+    parser: &mut Parser,
+    is_subquery: bool,
+) -> Result<SelectStatementStack, String> {
     let mut statement_stack = SelectStatementStack {
         elements: vec![],
         order_by_clause: None,
@@ -23,10 +36,18 @@ pub fn build(parser: &mut Parser) -> Result<SqlStatement, String> {
         match token.token_type {
             TokenTypes::Select => {
                 let mut statement = select_statement::get_statement(parser)?;
+                // Only the number of columns has to match, the result takes the names of the
+                // first SELECT. It is checked when running the statement if `*` hides it.
+                let has_wildcard = |columns: &Vec<SelectableColumn>| {
+                    columns.iter().any(|column| column.column_name == "*")
+                };
                 columns = match columns {
                     None => Some(statement.columns.clone()),
                     Some(columns) => {
-                        if statement.columns != columns {
+                        if !has_wildcard(&columns)
+                            && !has_wildcard(&statement.columns)
+                            && statement.columns.len() != columns.len()
+                        {
                             return Err(
                                 "Columns mismatch between SELECT statements in Union".to_string()
                             );
@@ -34,16 +55,16 @@ pub fn build(parser: &mut Parser) -> Result<SqlStatement, String> {
                         Some(columns)
                     }
                 };
-                if parser.current_token()?.token_type != TokenTypes::SemiColon {
+                let is_end = parser.current_token()?.token_type == TokenTypes::SemiColon
+                    || is_subquery_end(parser, is_subquery, &set_operator_stack)?;
+                if !is_end {
                     if statement.order_by_clause.is_some() || statement.limit_clause.is_some() {
                         return Err(
                             "ORDER BY, or LIMIT clause not allowed with UNION SELECT statements"
                                 .to_string(),
                         );
                     }
-                } else if statement_stack.elements.len() > 0
-                    && parser.current_token()?.token_type == TokenTypes::SemiColon
-                {
+                } else if statement_stack.elements.len() > 0 {
                     statement_stack.order_by_clause = statement.order_by_clause.take();
                     statement_stack.limit_clause = statement.limit_clause.take();
                 }
@@ -55,6 +76,11 @@ pub fn build(parser: &mut Parser) -> Result<SqlStatement, String> {
                 set_operator_stack.push(SelectStackOperators::Parentheses(Parentheses::Left));
                 parser.advance()?;
             }
+            TokenTypes::RightParen
+                if is_subquery_end(parser, is_subquery, &set_operator_stack)? =>
+            {
+                break;
+            }
             TokenTypes::RightParen => {
                 while let Some(current_set_operator) = set_operator_stack.pop() {
                     if let SelectStackOperators::Parentheses(_) = current_set_operator {
@@ -122,6 +148,9 @@ pub fn build(parser: &mut Parser) -> Result<SqlStatement, String> {
                 set_operator_stack.push(SelectStackOperators::SetOperator(set_operator));
             }
             TokenTypes::SemiColon => break,
+            _ if !statement_stack.elements.is_empty() => {
+                return Err(parser.format_trailing_token_error());
+            }
             _ => return Err(parser.format_error()),
         }
     }
@@ -135,7 +164,20 @@ pub fn build(parser: &mut Parser) -> Result<SqlStatement, String> {
             return Err("Mismatched parentheses found.".to_string());
         }
     }
-    return Ok(SqlStatement::Select(statement_stack));
+    return Ok(statement_stack);
+}
+
+// A closing parenthesis without a matching opening one ends a subquery.
+fn is_subquery_end(
+    parser: &Parser,
+    is_subquery: bool,
+    set_operator_stack: &[SelectStackOperators],
+) -> Result<bool, String> {
+    Ok(is_subquery
+        && parser.current_token()?.token_type == TokenTypes::RightParen
+        && !set_operator_stack
+            .iter()
+            .any(|operator| matches!(operator, SelectStackOperators::Parentheses(_))))
 }
 
 fn get_set_operator(parser: &mut Parser) -> Result<SetOperator, String> {
@@ -193,6 +235,7 @@ mod tests {
         SelectStatementStackElement::SelectStatement(SelectStatement {
             table_name: "users".to_string(),
             table_aliases: TableAliases(HashMap::new()),
+            joins: vec![],
             mode: SelectMode::All,
             columns: vec![SelectableColumn {
                 selectables: vec![SelectableStackElement::All],
@@ -206,6 +249,7 @@ mod tests {
                 ],
                 column_name: format!("id = {}", id).to_string(),
             }),
+            group_by_clause: None,
             order_by_clause: None,
             limit_clause: None,
         })
@@ -361,6 +405,7 @@ mod tests {
                 SelectStatementStackElement::SelectStatement(SelectStatement {
                     table_name: "employees".to_string(),
                     table_aliases: TableAliases(HashMap::new()),
+                    joins: vec![],
                     mode: SelectMode::All,
                     columns: vec![SelectableColumn {
                         selectables: vec![SelectableStackElement::Column("name".to_string())],
@@ -374,12 +419,14 @@ mod tests {
                         ],
                         column_name: "name = 'Henry'".to_string(),
                     }),
+                    group_by_clause: None,
                     order_by_clause: None,
                     limit_clause: None,
                 }),
                 SelectStatementStackElement::SelectStatement(SelectStatement {
                     table_name: "employees".to_string(),
                     table_aliases: TableAliases(HashMap::new()),
+                    joins: vec![],
                     mode: SelectMode::All,
                     columns: vec![SelectableColumn {
                         selectables: vec![SelectableStackElement::Column("name".to_string())],
@@ -393,6 +440,7 @@ mod tests {
                         ],
                         column_name: "name = 'John'".to_string(),
                     }),
+                    group_by_clause: None,
                     order_by_clause: None,
                     limit_clause: None,
                 }),
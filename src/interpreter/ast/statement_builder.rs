@@ -1,8 +1,9 @@
 use crate::interpreter::ast::SqlStatement;
 use crate::interpreter::ast::parser::Parser;
 use crate::interpreter::ast::{
-    alter_table_statement, create_statement, delete_statement, drop_statement, insert_statement,
-    select_statement_stack, transaction_statements, update_statement,
+    alter_table_statement, create_statement, delete_statement, drop_statement, explain_statement,
+    insert_statement, pragma_statement, select_statement_stack, transaction_statements,
+    update_statement,
 };
 
 pub trait StatementBuilder {
@@ -18,6 +19,8 @@ pub trait StatementBuilder {
     fn build_rollback(&self, parser: &mut Parser) -> Result<SqlStatement, String>;
     fn build_savepoint(&self, parser: &mut Parser) -> Result<SqlStatement, String>;
     fn build_release(&self, parser: &mut Parser) -> Result<SqlStatement, String>;
+    fn build_explain(&self, parser: &mut Parser) -> Result<SqlStatement, String>;
+    fn build_pragma(&self, parser: &mut Parser) -> Result<SqlStatement, String>;
 }
 
 pub struct DefaultStatementBuilder;
@@ -70,6 +73,14 @@ impl StatementBuilder for DefaultStatementBuilder {
     fn build_release(&self, parser: &mut Parser) -> Result<SqlStatement, String> {
         transaction_statements::build_release(parser)
     }
+
+    fn build_explain(&self, parser: &mut Parser) -> Result<SqlStatement, String> {
+        explain_statement::build(parser)
+    }
+
+    fn build_pragma(&self, parser: &mut Parser) -> Result<SqlStatement, String> {
+        pragma_statement::build(parser)
+    }
 }
 
 #[cfg(test)]
@@ -91,6 +102,8 @@ impl StatementBuilder for MockStatementBuilder {
             table_name: "users".to_string(),
             existence_check: None,
             columns: vec![],
+            table_constraints: vec![],
+            without_rowid: false,
         }));
     }
 
@@ -101,6 +114,8 @@ impl StatementBuilder for MockStatementBuilder {
             table_name: "users".to_string(),
             columns: None,
             values: vec![],
+            last_insert_rowids: vec![],
+            returning: None,
         }));
     }
 
@@ -112,12 +127,14 @@ impl StatementBuilder for MockStatementBuilder {
                 SelectStatement {
                     table_name: "users".to_string(),
                     table_aliases: TableAliases(HashMap::new()),
+                    joins: vec![],
                     mode: SelectMode::All,
                     columns: vec![SelectableColumn {
                         selectables: vec![SelectableStackElement::All],
                         column_name: "*".to_string(),
                     }],
                     where_clause: None,
+                    group_by_clause: None,
                     order_by_clause: None,
                     limit_clause: None,
                 },
@@ -162,4 +179,12 @@ impl StatementBuilder for MockStatementBuilder {
     fn build_release(&self, _parser: &mut Parser) -> Result<SqlStatement, String> {
         todo!();
     }
+
+    fn build_explain(&self, _parser: &mut Parser) -> Result<SqlStatement, String> {
+        todo!();
+    }
+
+    fn build_pragma(&self, _parser: &mut Parser) -> Result<SqlStatement, String> {
+        todo!();
+    }
 }
@@ -7,7 +7,7 @@ use crate::interpreter::tokenizer::token::TokenTypes;
 pub fn token(tt: TokenTypes, val: &'static str) -> Token<'static> {
     Token {
         token_type: tt,
-        value: val,
+        value: val.into(),
         col_num: 0,
         line_num: 1,
     }
@@ -22,7 +22,7 @@ pub fn token_with_location(
 ) -> Token<'static> {
     Token {
         token_type: tt,
-        value: val,
+        value: val.into(),
         col_num: col,
         line_num: line,
     }
@@ -35,8 +35,10 @@ pub fn build_rollback(parser: &mut Parser) -> Result<SqlStatement, String> {
     parser.advance()?;
     let name = if expect_token_type(parser, TokenTypes::To).is_ok() {
         parser.advance()?;
-        expect_token_type(parser, TokenTypes::Savepoint)?;
-        parser.advance()?;
+        // The SAVEPOINT keyword is optional, e.g. ROLLBACK TO sp1;
+        if expect_token_type(parser, TokenTypes::Savepoint).is_ok() {
+            parser.advance()?;
+        }
         expect_token_type(parser, TokenTypes::Identifier)?;
         let name = parser.current_token()?.value.to_string();
         parser.advance()?;
@@ -136,7 +138,7 @@ mod tests {
 
     #[test]
     fn build_rollback_with_all_tokens_is_generated_correctly() {
-        // ROLLBACK; ROLLBACK TO savepoint_name;
+        // ROLLBACK; ROLLBACK TO SAVEPOINT savepoint_name; ROLLBACK TO savepoint_name;
         let rollback_tokens = vec![
             token(TokenTypes::Rollback, "ROLLBACK"),
             token(TokenTypes::SemiColon, ";"),
@@ -145,6 +147,10 @@ mod tests {
             token(TokenTypes::Savepoint, "SAVEPOINT"),
             token(TokenTypes::Identifier, "savepoint_name"),
             token(TokenTypes::SemiColon, ";"),
+            token(TokenTypes::Rollback, "ROLLBACK"),
+            token(TokenTypes::To, "TO"),
+            token(TokenTypes::Identifier, "savepoint_name"),
+            token(TokenTypes::SemiColon, ";"),
         ];
         let expected = vec![
             Some(Ok(SqlStatement::Rollback(RollbackStatement {
@@ -153,9 +159,12 @@ mod tests {
             Some(Ok(SqlStatement::Rollback(RollbackStatement {
                 savepoint_name: Some("savepoint_name".to_string()),
             }))),
+            Some(Ok(SqlStatement::Rollback(RollbackStatement {
+                savepoint_name: Some("savepoint_name".to_string()),
+            }))),
         ];
         let mut parser = Parser::new(rollback_tokens);
-        for i in 0..2 {
+        for i in 0..3 {
             let result = parser.next_statement();
             assert_eq!(expected[i], result);
             let _ = parser.advance_past_semicolon();
@@ -3,7 +3,9 @@ use crate::interpreter::ast::{
     ColumnValue, SqlStatement, TableAliases, UpdateStatement,
     helpers::common::get_table_name,
     helpers::token::{expect_token_type, token_to_value},
-    helpers::{limit_clause::get_limit, order_by_clause::get_order_by},
+    helpers::{
+        limit_clause::get_limit, order_by_clause::get_order_by, returning_clause::get_returning,
+    },
     parser::Parser,
 };
 use crate::interpreter::tokenizer::token::TokenTypes;
@@ -23,6 +25,7 @@ pub fn build(parser: &mut Parser) -> Result<SqlStatement, String> {
     let where_clause = get_where_clause(parser)?;
     let order_by_clause = get_order_by(parser)?;
     let limit_clause = get_limit(parser)?;
+    let returning = get_returning(parser)?;
 
     // Ensure SemiColon
     expect_token_type(parser, TokenTypes::SemiColon)?;
@@ -33,6 +36,7 @@ pub fn build(parser: &mut Parser) -> Result<SqlStatement, String> {
         where_clause: where_clause,
         order_by_clause: order_by_clause,
         limit_clause: limit_clause,
+        returning,
     }));
 }
 
@@ -106,6 +110,7 @@ mod tests {
             where_clause: None,
             order_by_clause: None,
             limit_clause: None,
+            returning: None,
         });
         assert_eq!(statement, expected);
     }
@@ -147,6 +152,7 @@ mod tests {
             }),
             order_by_clause: None,
             limit_clause: None,
+            returning: None,
         });
         assert_eq!(statement, expected);
     }
@@ -198,6 +204,7 @@ mod tests {
             }),
             order_by_clause: None,
             limit_clause: None,
+            returning: None,
         });
         assert_eq!(statement, expected);
     }
@@ -256,6 +263,7 @@ mod tests {
                 limit: 10,
                 offset: Some(5),
             }),
+            returning: None,
         });
         assert_eq!(expected, statement);
     }
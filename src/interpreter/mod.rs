@@ -3,38 +3,97 @@ use crate::db::table::core::row::Row;
 pub mod ast;
 mod tokenizer;
 
+// Character quoting identifiers in reconstructed SQL, such as `.schema`, `.dump` and the text of
+// parsed statements. Either '"' or '`', both are read back by the tokenizer.
+pub const IDENTIFIER_QUOTE: char = '"';
+
+// Returns the identifier as it must be written in SQL: unchanged if it reads back as a plain
+// identifier, quoted with `IDENTIFIER_QUOTE` if it is a keyword, contains spaces or other
+// characters, or is empty.
+pub fn quote_identifier(name: &str) -> String {
+    let tokens = tokenizer::tokenize(name);
+    if let [token, _] = tokens.as_slice()
+        && token.token_type == tokenizer::token::TokenTypes::Identifier
+        && token.value == name
+        && name.chars().all(|c| c.is_alphanumeric() || c == '_')
+    {
+        return name.to_string();
+    }
+    let quote = IDENTIFIER_QUOTE.to_string();
+    format!(
+        "{}{}{}",
+        quote,
+        name.replace(&quote, &quote.repeat(2)),
+        quote
+    )
+}
+
+// Returns false if the SQL only contains whitespace and comments.
+pub fn has_statements(sql: &str) -> bool {
+    tokenizer::tokenize(sql)
+        .iter()
+        .any(|token| token.token_type != tokenizer::token::TokenTypes::EOF)
+}
+
+// Parses every statement without running any of them.
+pub fn parse_sql(sql: &str) -> Vec<Result<ast::DatabaseSqlStatement, String>> {
+    ast::generate(tokenizer::tokenize(sql))
+}
+
+// Parses the statements without running them. Each statement keeps the `-- comment` following
+// it on the line it ends on, which makes logged or replayed statements easier to read.
+pub fn parse_sql_with_comments(sql: &str) -> Vec<Result<ast::DatabaseSqlStatement, String>> {
+    ast::generate(tokenizer::tokenize_with_comments(sql))
+}
+
 pub fn run_sql(
     database: &mut db::database::Database,
     sql: &str,
 ) -> Vec<Result<Option<Vec<Row>>, String>> {
-    let tokens = tokenizer::tokenize(sql);
-    let ast = ast::generate(tokens);
-
     let mut sql_results = vec![];
-    for sql_statement in ast {
+    run_sql_each(database, sql, |result, _| sql_results.push(result));
+    return sql_results;
+}
+
+// Runs every statement and passes its result to `on_result`, along with the number of rows it
+// changed if it is a successful INSERT, UPDATE or DELETE.
+pub fn run_sql_each<F>(database: &mut db::database::Database, sql: &str, mut on_result: F)
+where
+    F: FnMut(Result<Option<Vec<Row>>, String>, Option<usize>),
+{
+    for sql_statement in parse_sql(sql) {
         match sql_statement {
             Ok(statement) => {
+                let changes_rows = matches!(
+                    statement.sql_statement,
+                    ast::SqlStatement::InsertInto(_)
+                        | ast::SqlStatement::UpdateStatement(_)
+                        | ast::SqlStatement::DeleteStatement(_)
+                );
                 let result = database.execute(statement.sql_statement);
                 match result {
                     Ok(values) => {
+                        let changes = changes_rows.then_some(database.changes);
                         if let Some(rows) = values {
-                            sql_results.push(Ok(Some(rows)));
+                            on_result(Ok(Some(rows)), changes);
                         } else {
-                            sql_results.push(Ok(None));
+                            on_result(Ok(None), changes);
                         }
                     }
                     Err(error) => {
-                        sql_results.push(Err(format!(
-                            "Execution Error with statement starting on line {} \n Error: {}",
-                            statement.line_num, error
-                        )));
+                        on_result(
+                            Err(format!(
+                                "Execution Error with statement starting on line {} \n Error: {}",
+                                statement.line_num, error
+                            )),
+                            None,
+                        );
                     }
                 }
             }
             Err(parser_error) => {
-                sql_results.push(Err(format!("Parsing Error: {}", parser_error)));
+                on_result(Err(format!("Parsing Error: {}", parser_error)), None);
             }
         }
     }
-    return sql_results;
 }
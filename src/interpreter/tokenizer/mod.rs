@@ -3,8 +3,18 @@ pub mod token;
 use crate::interpreter::tokenizer::scanner::Token;
 
 pub fn tokenize<'a>(line: &'a str) -> Vec<Token<'a>> {
-    let mut tokens: Vec<Token<'a>> = vec![];
+    scan(scanner::Scanner::new(line))
+}
+
+// Like `tokenize`, but `-- comments` are kept as Comment tokens.
+pub fn tokenize_with_comments<'a>(line: &'a str) -> Vec<Token<'a>> {
     let mut tokenizer = scanner::Scanner::new(line);
+    tokenizer.set_retain_comments(true);
+    scan(tokenizer)
+}
+
+fn scan<'a>(mut tokenizer: scanner::Scanner<'a>) -> Vec<Token<'a>> {
+    let mut tokens: Vec<Token<'a>> = vec![];
     loop {
         let next_token = tokenizer.next_token();
         if let Some(next_token) = next_token {
@@ -12,7 +22,7 @@ pub fn tokenize<'a>(line: &'a str) -> Vec<Token<'a>> {
         } else {
             tokens.push(Token {
                 token_type: token::TokenTypes::EOF,
-                value: "",
+                value: "".into(),
                 col_num: 0,
                 line_num: 0,
             });
@@ -30,7 +40,7 @@ mod tests {
     fn token(tt: TokenTypes, val: &'static str, col: usize, line_num: usize) -> Token<'static> {
         Token {
             token_type: tt,
-            value: val,
+            value: val.into(),
             col_num: col,
             line_num: line_num,
         }
@@ -192,6 +202,20 @@ mod tests {
         assert_eq!(expected, result);
     }
 
+    #[test]
+    fn tokenizer_parses_real_literals_with_signed_exponents() {
+        let result = tokenize("1e20 1.5E-10 1e+20 2-1");
+        let expected = vec![
+            token(TokenTypes::RealLiteral, "1e20", 0, 1),
+            token(TokenTypes::RealLiteral, "1.5E-10", 5, 1),
+            token(TokenTypes::RealLiteral, "1e+20", 13, 1),
+            token(TokenTypes::IntLiteral, "2", 19, 1),
+            token(TokenTypes::IntLiteral, "-1", 20, 1),
+            token(TokenTypes::EOF, "", 0, 0),
+        ];
+        assert_eq!(expected, result);
+    }
+
     #[test]
     fn tokenizer_parses_hex_literals() {
         let result = tokenize("X\'0A1A3F\' 12 X\'ZZZZZ\'");
@@ -204,14 +228,66 @@ mod tests {
             token(TokenTypes::EOF, "", 0, 0),
         ];
         assert_eq!(expected, result);
+
+        let result = tokenize("x\'ff\'");
+        let expected = vec![
+            token(TokenTypes::HexLiteral, "ff", 0, 1),
+            token(TokenTypes::EOF, "", 0, 0),
+        ];
+        assert_eq!(expected, result);
+    }
+
+    #[test]
+    fn tokenizer_handles_non_ascii_characters() {
+        // Columns are byte offsets.
+        let result = tokenize("'straße' naïve \"日本\" 'é");
+        let expected = vec![
+            token(TokenTypes::StringLiteral, "straße", 0, 1),
+            token(TokenTypes::Identifier, "naïve", 10, 1),
+            token(TokenTypes::Identifier, "日本", 17, 1),
+            token(TokenTypes::Error, "'é", 26, 1),
+            token(TokenTypes::EOF, "", 0, 0),
+        ];
+        assert_eq!(expected, result);
+
+        let result = tokenize("\"é");
+        assert_eq!(result[0], token(TokenTypes::Error, "\"é", 0, 1));
+        let result = tokenize("1 /* é\n");
+        assert_eq!(result[1], token(TokenTypes::Error, " é\n", 4, 1));
     }
 
     #[test]
     fn tokenizer_parses_string_identifiers() {
-        let result = tokenize("'string' \"identifier\"");
+        let result = tokenize("'string' \"identifier\" `order by`");
         let expected = vec![
             token(TokenTypes::StringLiteral, "string", 0, 1),
             token(TokenTypes::Identifier, "identifier", 9, 1),
+            token(TokenTypes::Identifier, "order by", 22, 1),
+            token(TokenTypes::EOF, "", 0, 0),
+        ];
+        assert_eq!(expected, result);
+    }
+
+    #[test]
+    fn tokenizer_parses_escaped_quotes_in_identifiers() {
+        let result = tokenize("\"a\"\"b\" `c``d` \"\"\"\" \"e\"\"");
+        let expected = vec![
+            token(TokenTypes::Identifier, "a\"b", 0, 1),
+            token(TokenTypes::Identifier, "c`d", 7, 1),
+            token(TokenTypes::Identifier, "\"", 14, 1),
+            token(TokenTypes::Error, "\"e\"\"", 19, 1),
+            token(TokenTypes::EOF, "", 0, 0),
+        ];
+        assert_eq!(expected, result);
+    }
+
+    #[test]
+    fn tokenizer_parses_escaped_quotes_in_strings() {
+        let result = tokenize("'it''s' '''' 'a''");
+        let expected = vec![
+            token(TokenTypes::StringLiteral, "it''s", 0, 1),
+            token(TokenTypes::StringLiteral, "''", 8, 1),
+            token(TokenTypes::Error, "'a''", 13, 1),
             token(TokenTypes::EOF, "", 0, 0),
         ];
         assert_eq!(expected, result);
@@ -363,4 +439,57 @@ mod tests {
         ];
         assert_eq!(expected, result);
     }
+
+    #[test]
+    fn tokenizer_retains_comments_when_asked() {
+        let sql = "SELECT 1; -- first\n-- second\nSELECT 2; --";
+        let result = tokenize_with_comments(sql);
+        let expected = vec![
+            token(TokenTypes::Select, "SELECT", 0, 1),
+            token(TokenTypes::IntLiteral, "1", 7, 1),
+            token(TokenTypes::SemiColon, ";", 8, 1),
+            token(TokenTypes::Comment, " first", 10, 1),
+            token(TokenTypes::Comment, " second", 0, 2),
+            token(TokenTypes::Select, "SELECT", 0, 3),
+            token(TokenTypes::IntLiteral, "2", 7, 3),
+            token(TokenTypes::SemiColon, ";", 8, 3),
+            token(TokenTypes::Comment, "", 10, 3),
+            token(TokenTypes::EOF, "", 0, 0),
+        ];
+        assert_eq!(expected, result);
+        assert!(
+            tokenize(sql)
+                .iter()
+                .all(|token| token.token_type != TokenTypes::Comment)
+        );
+    }
+
+    #[test]
+    fn tokenizer_parses_cast_expressions() {
+        let result = tokenize("CAST(x AS INTEGER)");
+        let expected = vec![
+            token(TokenTypes::Cast, "CAST", 0, 1),
+            token(TokenTypes::LeftParen, "(", 4, 1),
+            token(TokenTypes::Identifier, "x", 5, 1),
+            token(TokenTypes::As, "AS", 7, 1),
+            token(TokenTypes::Integer, "INTEGER", 10, 1),
+            token(TokenTypes::RightParen, ")", 17, 1),
+            token(TokenTypes::EOF, "", 0, 0),
+        ];
+        assert_eq!(expected, result);
+    }
+
+    #[test]
+    fn tokenizer_parses_concatenation() {
+        let result = tokenize("name || '!' | x");
+        let expected = vec![
+            token(TokenTypes::Identifier, "name", 0, 1),
+            token(TokenTypes::Concat, "||", 5, 1),
+            token(TokenTypes::StringLiteral, "!", 8, 1),
+            token(TokenTypes::Error, "|", 12, 1),
+            token(TokenTypes::Identifier, "x", 14, 1),
+            token(TokenTypes::EOF, "", 0, 0),
+        ];
+        assert_eq!(expected, result);
+    }
 }
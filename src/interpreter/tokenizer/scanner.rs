@@ -1,9 +1,11 @@
 use crate::interpreter::tokenizer::token::TokenTypes;
+use std::borrow::Cow;
 
 #[derive(Debug, PartialEq)]
 pub struct Token<'a> {
     pub token_type: TokenTypes,
-    pub value: &'a str,
+    // Borrowed from the input, except for quoted identifiers holding escaped quotes.
+    pub value: Cow<'a, str>,
     pub col_num: usize,
     pub line_num: usize,
 }
@@ -13,6 +15,7 @@ pub struct Scanner<'a> {
     current: usize,
     line_num: usize,
     col_num: usize,
+    retain_comments: bool,
 }
 
 impl<'a> Scanner<'a> {
@@ -22,11 +25,18 @@ impl<'a> Scanner<'a> {
             current: 0,
             line_num: 1,
             col_num: 0,
+            retain_comments: false,
         };
     }
 
+    // When set, `-- comments` are returned as Comment tokens holding the text after the dashes
+    // instead of being skipped. Block comments are always skipped.
+    pub fn set_retain_comments(&mut self, retain_comments: bool) {
+        self.retain_comments = retain_comments;
+    }
+
     fn handle_skips(&mut self) -> bool {
-        if self.current_char() == ' ' {
+        if [' ', '\t', '\r'].contains(&self.current_char()) {
             self.advance();
             return true;
         } else if self.current_char() == '\n' {
@@ -38,8 +48,15 @@ impl<'a> Scanner<'a> {
         return false;
     }
 
+    // Positions are byte offsets into the input, so moving past a character skips all its bytes.
     fn advance(&mut self) {
-        self.current += 1;
+        self.current += self.current_char().len_utf8();
+    }
+
+    // Used when a token runs to the end of the input, so the error token covers the rest of it.
+    fn move_to_last_char(&mut self) {
+        let last_char = self.input.chars().next_back().unwrap_or('\0');
+        self.current = self.input.len() - last_char.len_utf8();
     }
 
     fn current_char(&self) -> char {
@@ -47,7 +64,7 @@ impl<'a> Scanner<'a> {
     }
 
     fn peek_char(&self) -> char {
-        return self.input[self.current + 1..]
+        return self.input[self.current + self.current_char().len_utf8()..]
             .chars()
             .next()
             .unwrap_or('\0');
@@ -57,7 +74,7 @@ impl<'a> Scanner<'a> {
         self.advance();
         return Token {
             token_type: token_type,
-            value: &self.input[start..self.current],
+            value: Cow::Borrowed(&self.input[start..self.current]),
             col_num: start - self.col_num,
             line_num: self.line_num,
         };
@@ -69,7 +86,7 @@ impl<'a> Scanner<'a> {
                 self.advance();
                 Token {
                     token_type: token_type,
-                    value: &self.input[start + 1..self.current - 1],
+                    value: Cow::Borrowed(&self.input[start + 1..self.current - 1]),
                     col_num: start - self.col_num,
                     line_num: self.line_num,
                 }
@@ -79,9 +96,14 @@ impl<'a> Scanner<'a> {
     }
 
     fn build_string_identifier_token(&mut self, start: usize, token_type: TokenTypes) -> Token<'a> {
+        let quote = &self.input[start..start + 1];
+        let value = &self.input[start + 1..self.current - 1];
         return Token {
             token_type: token_type,
-            value: &self.input[start + 1..self.current - 1],
+            value: match value.contains(quote) {
+                true => Cow::Owned(value.replace(&quote.repeat(2), quote)),
+                false => Cow::Borrowed(value),
+            },
             col_num: start - self.col_num,
             line_num: self.line_num,
         };
@@ -93,7 +115,7 @@ impl<'a> Scanner<'a> {
                 self.advance();
                 Token {
                     token_type: token_type,
-                    value: &self.input[start + 2..self.current - 1],
+                    value: Cow::Borrowed(&self.input[start + 2..self.current - 1]),
                     col_num: start - self.col_num,
                     line_num: self.line_num,
                 }
@@ -104,21 +126,28 @@ impl<'a> Scanner<'a> {
 
     fn read_string(&mut self) -> TokenTypes {
         self.advance();
-        while self.current_char() != '\'' {
-            self.advance();
-            if self.current >= self.input.len() {
-                self.current = self.input.len() - 1;
-                return TokenTypes::Error;
+        loop {
+            while self.current_char() != '\'' {
+                self.advance();
+                if self.current >= self.input.len() {
+                    self.move_to_last_char();
+                    return TokenTypes::Error;
+                }
             }
+            // Two quotes in a row are an escaped quote, not the end of the string
+            if self.peek_char() != '\'' {
+                return TokenTypes::StringLiteral;
+            }
+            self.advance();
+            self.advance();
         }
-        return TokenTypes::StringLiteral;
     }
 
     fn read_identifier(&mut self, start: usize) -> TokenTypes {
         while self.peek_char().is_alphanumeric() || self.peek_char() == '_' {
             self.advance();
         }
-        return match &self.input[start..=self.current] {
+        return match &self.input[start..self.current + self.current_char().len_utf8()] {
             slice if slice.eq_ignore_ascii_case("CREATE") => TokenTypes::Create,
             slice if slice.eq_ignore_ascii_case("SELECT") => TokenTypes::Select,
             slice if slice.eq_ignore_ascii_case("INSERT") => TokenTypes::Insert,
@@ -147,6 +176,8 @@ impl<'a> Scanner<'a> {
             slice if slice.eq_ignore_ascii_case("SAVEPOINT") => TokenTypes::Savepoint,
             slice if slice.eq_ignore_ascii_case("RELEASE") => TokenTypes::Release,
             slice if slice.eq_ignore_ascii_case("TRANSACTION") => TokenTypes::Transaction,
+            slice if slice.eq_ignore_ascii_case("EXPLAIN") => TokenTypes::Explain,
+            slice if slice.eq_ignore_ascii_case("PRAGMA") => TokenTypes::Pragma,
             slice if slice.eq_ignore_ascii_case("INTEGER") => TokenTypes::Integer,
             slice if slice.eq_ignore_ascii_case("REAL") => TokenTypes::Real,
             slice if slice.eq_ignore_ascii_case("TEXT") => TokenTypes::Text,
@@ -158,6 +189,17 @@ impl<'a> Scanner<'a> {
             slice if slice.eq_ignore_ascii_case("UNIQUE") => TokenTypes::Unique,
             slice if slice.eq_ignore_ascii_case("DEFAULT") => TokenTypes::Default,
             slice if slice.eq_ignore_ascii_case("AUTOINCREMENT") => TokenTypes::AutoIncrement,
+            slice if slice.eq_ignore_ascii_case("CONFLICT") => TokenTypes::Conflict,
+            slice if slice.eq_ignore_ascii_case("DEFERRABLE") => TokenTypes::Deferrable,
+            slice if slice.eq_ignore_ascii_case("INITIALLY") => TokenTypes::Initially,
+            slice if slice.eq_ignore_ascii_case("WITHOUT") => TokenTypes::Without,
+            slice if slice.eq_ignore_ascii_case("REPLACE") => TokenTypes::Replace,
+            slice if slice.eq_ignore_ascii_case("IGNORE") => TokenTypes::Ignore,
+            slice if slice.eq_ignore_ascii_case("ABORT") => TokenTypes::Abort,
+            slice if slice.eq_ignore_ascii_case("FAIL") => TokenTypes::Fail,
+            slice if slice.eq_ignore_ascii_case("FOREIGN") => TokenTypes::Foreign,
+            slice if slice.eq_ignore_ascii_case("REFERENCES") => TokenTypes::References,
+            slice if slice.eq_ignore_ascii_case("RETURNING") => TokenTypes::Returning,
             slice if slice.eq_ignore_ascii_case("ORDER") => TokenTypes::Order,
             slice if slice.eq_ignore_ascii_case("BY") => TokenTypes::By,
             slice if slice.eq_ignore_ascii_case("GROUP") => TokenTypes::Group,
@@ -168,6 +210,7 @@ impl<'a> Scanner<'a> {
             slice if slice.eq_ignore_ascii_case("ASC") => TokenTypes::Asc,
             slice if slice.eq_ignore_ascii_case("DESC") => TokenTypes::Desc,
             slice if slice.eq_ignore_ascii_case("INNER") => TokenTypes::Inner,
+            slice if slice.eq_ignore_ascii_case("CROSS") => TokenTypes::Cross,
             slice if slice.eq_ignore_ascii_case("LEFT") => TokenTypes::Left,
             slice if slice.eq_ignore_ascii_case("RIGHT") => TokenTypes::Right,
             slice if slice.eq_ignore_ascii_case("FULL") => TokenTypes::Full,
@@ -189,44 +232,85 @@ impl<'a> Scanner<'a> {
             slice if slice.eq_ignore_ascii_case("WHEN") => TokenTypes::When,
             slice if slice.eq_ignore_ascii_case("THEN") => TokenTypes::Then,
             slice if slice.eq_ignore_ascii_case("ELSE") => TokenTypes::Else,
+            slice if slice.eq_ignore_ascii_case("CAST") => TokenTypes::Cast,
             slice if slice.eq_ignore_ascii_case("IS") => TokenTypes::Is,
+            slice if slice.eq_ignore_ascii_case("GLOB") => TokenTypes::Glob,
+            slice if slice.eq_ignore_ascii_case("LIKE") => TokenTypes::Like,
+            slice if slice.eq_ignore_ascii_case("ESCAPE") => TokenTypes::Escape,
+            slice if slice.eq_ignore_ascii_case("BETWEEN") => TokenTypes::Between,
             slice if slice.eq_ignore_ascii_case("COUNT") => TokenTypes::Count,
             slice if slice.eq_ignore_ascii_case("SUM") => TokenTypes::Sum,
             slice if slice.eq_ignore_ascii_case("AVG") => TokenTypes::Avg,
             slice if slice.eq_ignore_ascii_case("MIN") => TokenTypes::Min,
             slice if slice.eq_ignore_ascii_case("MAX") => TokenTypes::Max,
+            slice if slice.eq_ignore_ascii_case("GROUP_CONCAT") => TokenTypes::GroupConcat,
             slice if slice.eq_ignore_ascii_case("DATE") => TokenTypes::Date,
             slice if slice.eq_ignore_ascii_case("TIME") => TokenTypes::Time,
             slice if slice.eq_ignore_ascii_case("DATETIME") => TokenTypes::DateTime,
             slice if slice.eq_ignore_ascii_case("JULIANDAY") => TokenTypes::JulianDay,
             slice if slice.eq_ignore_ascii_case("UNIXEPOCH") => TokenTypes::UnixEpoch,
+            slice if slice.eq_ignore_ascii_case("ABS") => TokenTypes::Abs,
+            slice if slice.eq_ignore_ascii_case("ROUND") => TokenTypes::Round,
+            slice if slice.eq_ignore_ascii_case("RANDOM") => TokenTypes::Random,
+            slice if slice.eq_ignore_ascii_case("QUOTE") => TokenTypes::Quote,
+            slice if slice.eq_ignore_ascii_case("LENGTH") => TokenTypes::Length,
+            slice if slice.eq_ignore_ascii_case("TRIM") => TokenTypes::Trim,
+            slice if slice.eq_ignore_ascii_case("LTRIM") => TokenTypes::Ltrim,
+            slice if slice.eq_ignore_ascii_case("RTRIM") => TokenTypes::Rtrim,
+            slice if slice.eq_ignore_ascii_case("SUBSTR") => TokenTypes::Substr,
+            slice if slice.eq_ignore_ascii_case("SUBSTRING") => TokenTypes::Substr,
+            slice if slice.eq_ignore_ascii_case("UPPER") => TokenTypes::Upper,
+            slice if slice.eq_ignore_ascii_case("LOWER") => TokenTypes::Lower,
+            slice if slice.eq_ignore_ascii_case("COALESCE") => TokenTypes::Coalesce,
+            slice if slice.eq_ignore_ascii_case("IFNULL") => TokenTypes::IfNull,
+            slice if slice.eq_ignore_ascii_case("JSON_EXTRACT") => TokenTypes::JsonExtract,
+            slice if slice.eq_ignore_ascii_case("ROW_NUMBER") => TokenTypes::RowNumber,
+            slice if slice.eq_ignore_ascii_case("LAST_INSERT_ROWID") => TokenTypes::LastInsertRowid,
+            slice if slice.eq_ignore_ascii_case("CHANGES") => TokenTypes::Changes,
+            slice if slice.eq_ignore_ascii_case("TOTAL_CHANGES") => TokenTypes::TotalChanges,
             slice if slice.eq_ignore_ascii_case("TRUE") => TokenTypes::TrueLiteral,
             slice if slice.eq_ignore_ascii_case("FALSE") => TokenTypes::FalseLiteral,
             _ => TokenTypes::Identifier,
         };
     }
 
-    fn read_quoted_identifier(&mut self) -> TokenTypes {
-        while self.current < self.input.len() && self.current_char() != '"' {
+    fn read_quoted_identifier(&mut self, quote: char) -> TokenTypes {
+        loop {
+            while self.current < self.input.len() && self.current_char() != quote {
+                self.advance();
+            }
+            if self.current >= self.input.len() {
+                self.move_to_last_char();
+                return TokenTypes::Error;
+            }
+            // As in strings, two quotes in a row are an escaped quote
+            if self.peek_char() != quote {
+                return TokenTypes::Identifier;
+            }
+            self.advance();
             self.advance();
         }
-        if self.current >= self.input.len() {
-            return TokenTypes::Error;
-        }
-        return TokenTypes::Identifier;
     }
 
     fn read_digit(&mut self) -> TokenTypes {
         let mut token_type = TokenTypes::IntLiteral;
-        while self.peek_char().is_ascii_digit()
-            || self.peek_char() == '.'
-            || self.peek_char() == 'e'
-            || self.peek_char() == '-'
-        {
-            if self.peek_char() == '.' || self.peek_char() == 'e' {
-                token_type = TokenTypes::RealLiteral;
+        loop {
+            match self.peek_char() {
+                c if c.is_ascii_digit() => self.advance(),
+                '.' => {
+                    token_type = TokenTypes::RealLiteral;
+                    self.advance();
+                }
+                'e' | 'E' => {
+                    // The exponent may be signed, as in 1.5e-10 or 1e+20.
+                    token_type = TokenTypes::RealLiteral;
+                    self.advance();
+                    if self.peek_char() == '-' || self.peek_char() == '+' {
+                        self.advance();
+                    }
+                }
+                _ => break,
             }
-            self.advance();
         }
         return token_type;
     }
@@ -245,6 +329,9 @@ impl<'a> Scanner<'a> {
     }
 
     fn read_block_comment(&mut self, start: usize) -> Option<Token<'a>> {
+        // An unterminated comment is reported where it starts.
+        let col_num = start - self.col_num + 2;
+        let line_num = self.line_num;
         self.advance();
         self.advance();
 
@@ -268,9 +355,9 @@ impl<'a> Scanner<'a> {
 
         Some(Token {
             token_type: TokenTypes::Error,
-            value: &self.input[start + 2..self.current],
-            col_num: start - self.col_num + 2,
-            line_num: self.line_num,
+            value: Cow::Borrowed(&self.input[start + 2..self.current]),
+            col_num,
+            line_num,
         })
     }
 
@@ -286,20 +373,20 @@ impl<'a> Scanner<'a> {
                 let token_type = self.read_string();
                 Some(self.build_string_token(start, token_type))
             }
-            '"' => {
+            quote @ ('"' | '`') => {
                 if self.peek_char() == '\0' {
                     return Some(self.build_token(start, TokenTypes::Error));
                 }
                 self.advance();
 
-                let token_type = self.read_quoted_identifier();
+                let token_type = self.read_quoted_identifier(quote);
                 if token_type == TokenTypes::Error || self.current > self.input.len() {
                     return Some(self.build_token(start, TokenTypes::Error));
                 }
                 self.advance();
                 Some(self.build_string_identifier_token(start, token_type))
             }
-            c if c == 'X' && self.peek_char() == '\'' => {
+            c if (c == 'X' || c == 'x') && self.peek_char() == '\'' => {
                 let token_type = self.read_hex_literal();
                 Some(self.build_hex_literal_token(start, token_type))
             }
@@ -325,10 +412,21 @@ impl<'a> Scanner<'a> {
                     Some(self.build_token(start, token_type))
                 } else if self.peek_char() == '-' {
                     self.advance();
-                    if self.peek_char() == ' ' || self.peek_char() == '\n' {
+                    if self.peek_char() == ' '
+                        || self.peek_char() == '\n'
+                        || self.peek_char() == '\0'
+                    {
                         while self.current < self.input.len() && self.current_char() != '\n' {
                             self.advance();
                         }
+                        if self.retain_comments {
+                            return Some(Token {
+                                token_type: TokenTypes::Comment,
+                                value: Cow::Borrowed(&self.input[start + 2..self.current]),
+                                col_num: start - self.col_num,
+                                line_num: self.line_num,
+                            });
+                        }
                         self.next_token()
                     } else {
                         return Some(self.build_token(start, TokenTypes::Error));
@@ -345,6 +443,14 @@ impl<'a> Scanner<'a> {
                 }
             }
             '%' => Some(self.build_token(start, TokenTypes::Modulo)),
+            '|' => {
+                if self.peek_char() == '|' {
+                    self.advance();
+                    Some(self.build_token(start, TokenTypes::Concat))
+                } else {
+                    Some(self.build_token(start, TokenTypes::Error))
+                }
+            }
             '=' => Some(self.build_token(start, TokenTypes::Equals)),
             '!' => {
                 if self.peek_char() == '=' {
@@ -29,6 +29,8 @@ pub enum TokenTypes {
     Savepoint,
     Release,
     Transaction,
+    Explain,
+    Pragma,
     // Data Types
     // TODO: add Type suffix
     Integer,
@@ -43,7 +45,18 @@ pub enum TokenTypes {
     Unique,
     Default,
     AutoIncrement,
+    Conflict,
+    Deferrable,
+    Initially,
+    Without,
+    Replace,
+    Ignore,
+    Abort,
+    Fail,
+    Foreign,
+    References,
     // Clauses
+    Returning,
     Order,
     By,
     Group,
@@ -54,6 +67,7 @@ pub enum TokenTypes {
     Asc,
     Desc,
     Inner,
+    Cross,
     Left,
     Right,
     Full,
@@ -75,7 +89,12 @@ pub enum TokenTypes {
     When,
     Then,
     Else,
+    Cast,
     Is,
+    Glob,
+    Like,
+    Escape,
+    Between,
     Equals,
     NotEquals,
     LessThan,
@@ -88,6 +107,7 @@ pub enum TokenTypes {
     Avg,
     Min,
     Max,
+    GroupConcat,
     // Time and Date Functions
     Date,
     Time,
@@ -95,6 +115,30 @@ pub enum TokenTypes {
     JulianDay,
     UnixEpoch,
     TimeDiff,
+    // Math Functions
+    Abs,
+    Round,
+    Random,
+    // String Functions
+    Quote,
+    Length,
+    Trim,
+    Ltrim,
+    Rtrim,
+    Substr,
+    Upper,
+    Lower,
+    // NULL Handling Functions
+    Coalesce,
+    IfNull,
+    // JSON Functions
+    JsonExtract,
+    // Window-like Functions
+    RowNumber,
+    // Database Functions
+    LastInsertRowid,
+    Changes,
+    TotalChanges,
     // Single Character Tokens
     Asterisk,
     SemiColon,
@@ -108,6 +152,7 @@ pub enum TokenTypes {
     Minus,
     Divide,
     Modulo,
+    Concat,
     // Literals
     StringLiteral,
     IntLiteral,
@@ -117,6 +162,8 @@ pub enum TokenTypes {
     RealLiteral,
     // Others
     Identifier,
+    // A `-- comment`, only produced when the scanner retains comments.
+    Comment,
     EOF,
     Error,
 }
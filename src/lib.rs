@@ -1,3 +1,45 @@
 pub mod cli;
 pub mod db;
 pub mod interpreter;
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+    use std::path::Path;
+
+    // Files that are not declared as a module are never compiled, so edits to them are silently
+    // lost. Every source file must be reachable from the crate root.
+    fn assert_modules_are_declared(directory: &Path, parent: &Path) {
+        let parent_source = fs::read_to_string(parent).unwrap();
+        for entry in fs::read_dir(directory).unwrap() {
+            let path = entry.unwrap().path();
+            let name = path.file_stem().unwrap().to_str().unwrap().to_string();
+            if path.is_dir() {
+                let module = path.join("mod.rs");
+                assert!(module.exists(), "{} has no mod.rs", path.display());
+                assert!(
+                    parent_source.contains(&format!("mod {};", name)),
+                    "{} is not declared in {}",
+                    path.display(),
+                    parent.display()
+                );
+                assert_modules_are_declared(&path, &module);
+            } else if path.extension().is_some_and(|extension| extension == "rs")
+                && !["mod", "lib", "main"].contains(&name.as_str())
+            {
+                assert!(
+                    parent_source.contains(&format!("mod {};", name)),
+                    "{} is not declared in {}",
+                    path.display(),
+                    parent.display()
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn every_source_file_is_part_of_the_module_tree() {
+        let src = Path::new(env!("CARGO_MANIFEST_DIR")).join("src");
+        assert_modules_are_declared(&src, &src.join("lib.rs"));
+    }
+}
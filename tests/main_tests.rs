@@ -1,7 +1,11 @@
 mod common;
 mod suites {
+    pub mod aggregate_functions;
     pub mod basic_crud;
     pub mod datetime_operations;
+    pub mod indexes;
+    pub mod joins;
+    pub mod math_functions;
     pub mod set_operators;
     pub mod transactions;
 }
@@ -0,0 +1,158 @@
+use mollycache::db::database::Database;
+use mollycache::db::table::core::{row::Row, value::Value};
+use mollycache::interpreter::run_sql;
+
+use crate::common::assert_eq_table_rows;
+
+#[test]
+fn test_group_concat_distinct() {
+    let mut database = Database::new();
+    let sql = "
+    CREATE TABLE users (
+        id INTEGER,
+        name TEXT
+    );
+    INSERT INTO users (id, name) VALUES (1, 'John'), (2, 'Jane'), (3, 'John'), (4, NULL), (5, 'Jane'), (6, 'Jim');
+    SELECT group_concat(name) FROM users;
+    SELECT group_concat(DISTINCT name, ' | '), COUNT(DISTINCT name) FROM users WHERE id > 1;
+    ";
+    let mut result = run_sql(&mut database, sql);
+    assert!(result.iter().all(|result| result.is_ok()));
+    let expected = vec![Row(vec![
+        Value::Text("Jane | John | Jim".to_string()),
+        Value::Integer(3),
+    ])];
+    assert_eq_table_rows(expected, result.pop().unwrap().unwrap().unwrap());
+    let expected = vec![Row(vec![Value::Text(
+        "John,Jane,John,Jane,Jim".to_string(),
+    )])];
+    assert_eq_table_rows(expected, result.pop().unwrap().unwrap().unwrap());
+}
+
+#[test]
+fn test_group_by_single_column() {
+    let mut database = Database::new();
+    let sql = "
+    CREATE TABLE users (
+        id INTEGER,
+        name TEXT,
+        age INTEGER
+    );
+    INSERT INTO users (id, name, age) VALUES (1, 'John', 30), (2, 'Jane', 25), (3, 'John', 40), (4, NULL, 50), (5, 'Jane', NULL);
+    SELECT name, COUNT(*), SUM(age), AVG(age), MIN(age), MAX(age) FROM users GROUP BY name;
+    SELECT name, COUNT(*) AS total FROM users WHERE id > 1 GROUP BY name ORDER BY total DESC, name LIMIT 2;
+    ";
+    let mut result = run_sql(&mut database, sql);
+    assert!(result.iter().all(|result| result.is_ok()));
+    let expected = vec![
+        Row(vec![Value::Text("Jane".to_string()), Value::Integer(2)]),
+        Row(vec![Value::Null, Value::Integer(1)]),
+    ];
+    assert_eq_table_rows(expected, result.pop().unwrap().unwrap().unwrap());
+    // Groups come out in the order of their keys, NULL first.
+    let expected = vec![
+        Row(vec![
+            Value::Null,
+            Value::Integer(1),
+            Value::Integer(50),
+            Value::Real(50.0),
+            Value::Integer(50),
+            Value::Integer(50),
+        ]),
+        Row(vec![
+            Value::Text("Jane".to_string()),
+            Value::Integer(2),
+            Value::Integer(25),
+            Value::Real(25.0),
+            Value::Integer(25),
+            Value::Integer(25),
+        ]),
+        Row(vec![
+            Value::Text("John".to_string()),
+            Value::Integer(2),
+            Value::Integer(70),
+            Value::Real(35.0),
+            Value::Integer(30),
+            Value::Integer(40),
+        ]),
+    ];
+    assert_eq_table_rows(expected, result.pop().unwrap().unwrap().unwrap());
+}
+
+#[test]
+fn test_group_by_multiple_columns() {
+    let mut database = Database::new();
+    let sql = "
+    CREATE TABLE sales (
+        region TEXT,
+        product TEXT,
+        amount INTEGER
+    );
+    INSERT INTO sales (region, product, amount) VALUES ('east', 'tea', 5), ('west', 'tea', 3), ('east', 'tea', 2), ('east', 'coffee', 7), ('west', 'tea', 1);
+    SELECT region, product, COUNT(*), SUM(amount) FROM sales GROUP BY region, product;
+    ";
+    let mut result = run_sql(&mut database, sql);
+    assert!(result.iter().all(|result| result.is_ok()));
+    let expected = vec![
+        Row(vec![
+            Value::Text("east".to_string()),
+            Value::Text("coffee".to_string()),
+            Value::Integer(1),
+            Value::Integer(7),
+        ]),
+        Row(vec![
+            Value::Text("east".to_string()),
+            Value::Text("tea".to_string()),
+            Value::Integer(2),
+            Value::Integer(7),
+        ]),
+        Row(vec![
+            Value::Text("west".to_string()),
+            Value::Text("tea".to_string()),
+            Value::Integer(2),
+            Value::Integer(4),
+        ]),
+    ];
+    assert_eq_table_rows(expected, result.pop().unwrap().unwrap().unwrap());
+}
+
+#[test]
+fn test_group_by_on_empty_table_returns_no_rows() {
+    let mut database = Database::new();
+    let sql = "
+    CREATE TABLE users (
+        id INTEGER,
+        name TEXT
+    );
+    SELECT name, COUNT(*) FROM users GROUP BY name;
+    ";
+    let mut result = run_sql(&mut database, sql);
+    assert!(result.iter().all(|result| result.is_ok()));
+    assert_eq!(result.pop().unwrap().unwrap().unwrap().len(), 0);
+}
+
+#[test]
+fn test_aggregates_inside_case_expressions() {
+    let mut database = Database::new();
+    let sql = "
+    CREATE TABLE users (
+        id INTEGER,
+        age INTEGER
+    );
+    INSERT INTO users (id, age) VALUES (1, 10), (2, 20), (3, 30);
+    SELECT CASE WHEN COUNT(*) > 2 THEN 'many' END FROM users;
+    SELECT age > 15, CASE WHEN age > 15 THEN MAX(age) ELSE SUM(age) END FROM users GROUP BY age > 15;
+    SELECT CASE COUNT(*) WHEN 0 THEN 'none' ELSE 'some' END FROM users WHERE id > 5;
+    ";
+    let mut result = run_sql(&mut database, sql);
+    assert!(result.iter().all(|result| result.is_ok()));
+    let expected = vec![Row(vec![Value::Text("none".to_string())])];
+    assert_eq_table_rows(expected, result.pop().unwrap().unwrap().unwrap());
+    let expected = vec![
+        Row(vec![Value::Integer(0), Value::Integer(10)]),
+        Row(vec![Value::Integer(1), Value::Integer(30)]),
+    ];
+    assert_eq_table_rows(expected, result.pop().unwrap().unwrap().unwrap());
+    let expected = vec![Row(vec![Value::Text("many".to_string())])];
+    assert_eq_table_rows(expected, result.pop().unwrap().unwrap().unwrap());
+}
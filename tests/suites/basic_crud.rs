@@ -151,14 +151,14 @@ fn test_parsing_errors() {
         age INTEGER,
         money REAL
     );
-    SELECT * FROM users wherea; 
+    SELECT * FROM users u wherea; 
     SELECT * users;
     ";
     let result = run_sql(&mut database, sql);
     assert!(result.iter().all(|result| result.is_err()));
     let expected = vec![
         Err("Parsing Error: Error at line 3, column 11: Unexpected value: hello".to_string()),
-        Err("Parsing Error: Error at line 8, column 24: Unexpected value: wherea".to_string()),
+        Err("Parsing Error: Error at line 8, column 26: Unexpected value: wherea".to_string()),
         Err("Parsing Error: Error at line 9, column 18: Unexpected value: ;".to_string()),
     ];
     assert_eq_run_sql(expected, result);
@@ -293,6 +293,38 @@ fn test_distinct_mode() {
     );
 }
 
+#[test]
+fn test_distinct_preserves_first_seen_order() {
+    let mut database = Database::new();
+    let sql = "
+    CREATE TABLE users (id INTEGER, name TEXT);
+    INSERT INTO users VALUES (1, 'John'), (2, NULL), (3, 'Alice'), (4, 'John'), (5, NULL), (6, 'Bob');
+    SELECT DISTINCT name FROM users;
+    SELECT name FROM users WHERE id > 3 UNION SELECT name FROM users;
+    ";
+    let mut result = run_sql(&mut database, sql);
+    let text = |name: &str| Row(vec![Value::Text(name.to_string())]);
+    let expected = vec![
+        text("John"),
+        Row(vec![Value::Null]),
+        text("Bob"),
+        text("Alice"),
+    ];
+    let union = result.pop().unwrap().unwrap().unwrap();
+    assert_eq!(union.len(), expected.len());
+    assert_eq_table_rows(expected, union);
+
+    let expected = vec![
+        text("John"),
+        Row(vec![Value::Null]),
+        text("Alice"),
+        text("Bob"),
+    ];
+    let distinct = result.pop().unwrap().unwrap().unwrap();
+    assert_eq!(distinct.len(), expected.len());
+    assert_eq_table_rows(expected, distinct);
+}
+
 #[test]
 fn test_select_clauses_with_literals() {
     let mut database = Database::new();
@@ -482,3 +514,1338 @@ fn test_offset_and_limit_without_order_by() {
     assert!(result.pop().unwrap().unwrap().is_none());
     assert_eq_table_rows(result.pop().unwrap().unwrap().unwrap(), expected_first);
 }
+
+#[test]
+fn test_huge_limit_does_not_overflow() {
+    let mut database = Database::new();
+    let sql = "
+    CREATE TABLE users (id INTEGER);
+    INSERT INTO users VALUES (1), (2), (3);
+    SELECT id FROM users LIMIT 18446744073709551615 OFFSET 1;
+    SELECT id FROM users ORDER BY id DESC LIMIT 9223372036854775807 OFFSET 1;
+    SELECT id FROM users UNION SELECT id FROM users LIMIT 18446744073709551615 OFFSET 1;
+    DELETE FROM users ORDER BY id LIMIT 18446744073709551615 OFFSET 1;
+    SELECT id FROM users;
+    SELECT id FROM users WHERE id > 0 LIMIT 9223372036854775808;
+    ";
+    let result = run_sql(&mut database, sql);
+    let ids = |ids: &[i64]| {
+        Ok(Some(
+            ids.iter()
+                .map(|id| Row(vec![Value::Integer(*id)]))
+                .collect::<Vec<Row>>(),
+        ))
+    };
+    assert_eq!(result[2], ids(&[2, 3]));
+    assert_eq!(result[3], ids(&[2, 1]));
+    assert_eq!(result[4], ids(&[2, 3]));
+    assert!(result[5].is_ok());
+    assert_eq!(result[6], ids(&[1]));
+    assert_eq!(result[7], ids(&[1]));
+}
+
+#[test]
+fn test_column_constraint_conflict_actions() {
+    let mut database = Database::new();
+    let sql = "
+    CREATE TABLE users (
+        id INTEGER PRIMARY KEY ON CONFLICT REPLACE,
+        email TEXT UNIQUE ON CONFLICT IGNORE
+    );
+    INSERT INTO users (id, email) VALUES (1, 'john@example.com');
+    INSERT INTO users (id, email) VALUES (2, 'john@example.com');
+    INSERT INTO users (id, email) VALUES (1, 'jane@example.com');
+    SELECT * FROM users;
+    BEGIN;
+        INSERT INTO users (id, email) VALUES (1, 'jim@example.com');
+        SELECT * FROM users;
+    ROLLBACK;
+    SELECT * FROM users;
+    ";
+    let result = run_sql(&mut database, sql);
+    let jane = vec![Row(vec![
+        Value::Integer(1),
+        Value::Text("jane@example.com".to_string()),
+    ])];
+    let expected = vec![
+        Ok(None),
+        Ok(None),
+        Ok(None),
+        Ok(None),
+        Ok(Some(jane.clone())),
+        Ok(None),
+        Ok(None),
+        Ok(Some(vec![Row(vec![
+            Value::Integer(1),
+            Value::Text("jim@example.com".to_string()),
+        ])])),
+        Ok(None),
+        Ok(Some(jane)),
+    ];
+    assert_eq_run_sql(expected, result);
+}
+
+#[test]
+fn test_composite_primary_key_rejects_duplicate_keys() {
+    let mut database = Database::new();
+    let sql = "
+    CREATE TABLE enrollments (student INTEGER, course INTEGER, PRIMARY KEY (student, course));
+    INSERT INTO enrollments VALUES (1, 1), (1, 2), (2, 1);
+    INSERT INTO enrollments VALUES (2, 2), (1, 2);
+    INSERT INTO enrollments VALUES (2, NULL), (2, NULL);
+    SELECT COUNT(*) FROM enrollments;
+    ";
+    let result = run_sql(&mut database, sql);
+    assert_eq!(
+        result[2],
+        Err(
+            "Execution Error with statement starting on line 4 \n Error: UNIQUE constraint \
+            failed: enrollments.student, enrollments.course"
+                .to_string()
+        )
+    );
+    assert!(result[3].is_ok());
+    assert_eq!(result[4], Ok(Some(vec![Row(vec![Value::Integer(5)])])));
+}
+
+#[test]
+fn test_where_compares_two_columns() {
+    let mut database = Database::new();
+    let sql = "
+    CREATE TABLE users (id INTEGER, name TEXT, age INTEGER, money REAL);
+    INSERT INTO users VALUES (1, 'John', 25, 1000.0), (2, 'Jane', 30, 20.5),
+        (3, 'Jim', 35, NULL), (4, 'Joe', 40, 40.0);
+    SELECT name FROM users WHERE money > age;
+    SELECT name FROM users WHERE age = money OR id * 10 = age - 5;
+    DELETE FROM users WHERE money < age;
+    SELECT id FROM users ORDER BY id;
+    ";
+    let result = run_sql(&mut database, sql);
+    let names = |names: &[&str]| {
+        Ok(Some(
+            names
+                .iter()
+                .map(|name| Row(vec![Value::Text(name.to_string())]))
+                .collect::<Vec<Row>>(),
+        ))
+    };
+    assert_eq!(result[2], names(&["John"]));
+    assert_eq!(result[3], names(&["Jim", "Joe"]));
+    assert_eq!(
+        result[5],
+        Ok(Some(vec![
+            Row(vec![Value::Integer(1)]),
+            Row(vec![Value::Integer(3)]),
+            Row(vec![Value::Integer(4)]),
+        ]))
+    );
+}
+
+#[test]
+fn test_generated_columns_are_computed_from_the_row() {
+    let mut database = Database::new();
+    let sql = "
+    CREATE TABLE items (
+        price INTEGER,
+        quantity INTEGER,
+        total INTEGER GENERATED ALWAYS AS (price * quantity) VIRTUAL
+    );
+    INSERT INTO items VALUES (3, 4), (5, 1);
+    INSERT INTO items (quantity, price) VALUES (2, 10);
+    UPDATE items SET price = 7 WHERE price = 5;
+    SELECT * FROM items ORDER BY total;
+    SELECT price FROM items WHERE total > 10;
+    INSERT INTO items (price, total) VALUES (1, 2);
+    UPDATE items SET total = 1;
+    ";
+    let result = run_sql(&mut database, sql);
+    let row = |values: [i64; 3]| Row(values.iter().map(|v| Value::Integer(*v)).collect());
+    assert_eq!(
+        result[4],
+        Ok(Some(vec![
+            row([7, 1, 7]),
+            row([3, 4, 12]),
+            row([10, 2, 20])
+        ]))
+    );
+    assert_eq!(
+        result[5],
+        Ok(Some(vec![
+            Row(vec![Value::Integer(3)]),
+            Row(vec![Value::Integer(10)]),
+        ]))
+    );
+    assert!(result[6].is_err());
+    assert!(result[7].is_err());
+}
+
+#[test]
+fn test_comparisons_with_null_are_unknown() {
+    let mut database = Database::new();
+    let sql = "
+    SELECT NULL = 1, 1 != NULL, NULL < 1, NULL > 1, NULL <= NULL, 1 >= NULL, NULL GLOB '*', 'a' LIKE NULL;
+    SELECT NULL IS NULL, 1 IS NOT NULL, NOT (NULL = 1), NULL = 1 OR 1 = 1, NULL = 1 AND 1 = 0;
+    CREATE TABLE users (id INTEGER, money REAL);
+    INSERT INTO users VALUES (1, 5.0), (2, NULL), (3, 20.0);
+    SELECT id FROM users WHERE money > 10;
+    SELECT id FROM users WHERE NOT (money > 10);
+    SELECT id FROM users WHERE money != 5.0;
+    SELECT id FROM users WHERE money IS NOT 5.0;
+    ";
+    let mut result = run_sql(&mut database, sql);
+    assert!(result.iter().all(|result| result.is_ok()));
+    let ids = |ids: &[i64]| {
+        ids.iter()
+            .map(|id| Row(vec![Value::Integer(*id)]))
+            .collect::<Vec<Row>>()
+    };
+    assert_eq_table_rows(result.pop().unwrap().unwrap().unwrap(), ids(&[2, 3]));
+    assert_eq_table_rows(result.pop().unwrap().unwrap().unwrap(), ids(&[3]));
+    assert_eq_table_rows(result.pop().unwrap().unwrap().unwrap(), ids(&[1]));
+    assert_eq_table_rows(result.pop().unwrap().unwrap().unwrap(), ids(&[3]));
+    result.pop();
+    result.pop();
+    let expected = vec![Row(vec![
+        Value::Integer(1),
+        Value::Integer(1),
+        Value::Null,
+        Value::Integer(1),
+        Value::Integer(0),
+    ])];
+    assert_eq_table_rows(result.pop().unwrap().unwrap().unwrap(), expected);
+    let expected = vec![Row(vec![Value::Null; 8])];
+    assert_eq_table_rows(result.pop().unwrap().unwrap().unwrap(), expected);
+}
+
+#[test]
+fn test_where_with_mixed_operator_precedence() {
+    let mut database = Database::new();
+    let sql = "
+    CREATE TABLE t (id INTEGER, a INTEGER, b INTEGER, c INTEGER);
+    INSERT INTO t (id, a, b, c) VALUES (1, 1, 1, 1), (2, 1, 2, 1), (3, 5, 1, 0), (4, 5, 1, 1);
+    SELECT id FROM t WHERE a + 1 > b AND c;
+    SELECT id FROM t WHERE NOT a = 1 OR b = 2 AND NOT NOT c;
+    ";
+    let mut result = run_sql(&mut database, sql);
+    assert!(result.iter().all(|result| result.is_ok()));
+    let expected = vec![
+        Row(vec![Value::Integer(2)]),
+        Row(vec![Value::Integer(3)]),
+        Row(vec![Value::Integer(4)]),
+    ];
+    assert_eq_table_rows(expected, result.pop().unwrap().unwrap().unwrap());
+    let expected = vec![Row(vec![Value::Integer(1)]), Row(vec![Value::Integer(4)])];
+    assert_eq_table_rows(expected, result.pop().unwrap().unwrap().unwrap());
+}
+
+#[test]
+fn test_exists_subquery_in_select_list() {
+    let mut database = Database::new();
+    let sql = "
+    CREATE TABLE users (id INTEGER, name TEXT);
+    INSERT INTO users (id, name) VALUES (1, 'John'), (2, 'Jane');
+    SELECT EXISTS(SELECT 1 FROM users WHERE id = 1);
+    SELECT EXISTS (SELECT * FROM users WHERE id = 3), NOT EXISTS(SELECT id FROM users WHERE id = 3 UNION SELECT id FROM users WHERE id = 4) AS missing;
+    SELECT name FROM users WHERE EXISTS(SELECT 1 FROM users WHERE name = 'Jane') AND id = 1;
+    ";
+    let mut result = run_sql(&mut database, sql);
+    assert!(result.iter().all(|result| result.is_ok()), "{:?}", result);
+    let expected = vec![Row(vec![Value::Text("John".to_string())])];
+    assert_eq_table_rows(expected, result.pop().unwrap().unwrap().unwrap());
+    let expected = vec![Row(vec![Value::Integer(0), Value::Integer(1)])];
+    assert_eq_table_rows(expected, result.pop().unwrap().unwrap().unwrap());
+    let expected = vec![Row(vec![Value::Integer(1)])];
+    let rows = result.pop().unwrap().unwrap().unwrap();
+    assert_eq!(rows.len(), 1);
+    assert_eq_table_rows(expected, rows);
+}
+
+#[test]
+fn test_is_distinct_from() {
+    let mut database = Database::new();
+    let sql = "
+    CREATE TABLE users (
+        id INTEGER,
+        name TEXT
+    );
+    INSERT INTO users (id, name) VALUES (1, 'John'), (2, NULL), (3, 'Jane');
+    SELECT NULL IS NOT DISTINCT FROM NULL, 1 IS DISTINCT FROM NULL, NULL IS DISTINCT FROM NULL, 1 IS NOT DISTINCT FROM 1.0;
+    SELECT id FROM users WHERE name IS DISTINCT FROM 'John';
+    SELECT id FROM users WHERE name IS NOT DISTINCT FROM NULL;
+    ";
+    let mut result = run_sql(&mut database, sql);
+    assert!(result.iter().all(|result| result.is_ok()));
+    let expected = vec![Row(vec![Value::Integer(2)])];
+    assert_eq!(Ok(Some(expected)), result.pop().unwrap());
+    let expected = vec![Row(vec![Value::Integer(2)]), Row(vec![Value::Integer(3)])];
+    assert_eq!(Ok(Some(expected)), result.pop().unwrap());
+    let expected = vec![Row(vec![
+        Value::Integer(1),
+        Value::Integer(1),
+        Value::Integer(0),
+        Value::Integer(1),
+    ])];
+    assert_eq!(Ok(Some(expected)), result.pop().unwrap());
+}
+
+#[test]
+fn test_is_null_and_is_not_null() {
+    let mut database = Database::new();
+    let sql = "
+    CREATE TABLE users (
+        id INTEGER,
+        name TEXT,
+        money REAL
+    );
+    CREATE INDEX idx_money ON users (money);
+    INSERT INTO users (id, name, money) VALUES (1, 'Ann', 10.5), (2, NULL, NULL), (3, 'Cid', NULL), (4, NULL, 0.0);
+    SELECT id FROM users WHERE money IS NULL;
+    SELECT id FROM users WHERE money IS NOT NULL;
+    SELECT id FROM users WHERE money IS NULL AND name IS NOT NULL;
+    SELECT id FROM users WHERE money = NULL;
+    SELECT money IS NULL, name IS NOT NULL FROM users WHERE id = 2;
+    ";
+    let mut result = run_sql(&mut database, sql);
+    assert!(result.iter().all(|result| result.is_ok()));
+    let expected = vec![Row(vec![Value::Integer(1), Value::Integer(0)])];
+    assert_eq!(Ok(Some(expected)), result.pop().unwrap());
+    assert_eq!(Ok(Some(vec![])), result.pop().unwrap());
+    let expected = vec![Row(vec![Value::Integer(3)])];
+    assert_eq!(Ok(Some(expected)), result.pop().unwrap());
+    let expected = vec![Row(vec![Value::Integer(1)]), Row(vec![Value::Integer(4)])];
+    assert_eq!(Ok(Some(expected)), result.pop().unwrap());
+    let expected = vec![Row(vec![Value::Integer(2)]), Row(vec![Value::Integer(3)])];
+    assert_eq!(Ok(Some(expected)), result.pop().unwrap());
+}
+
+#[test]
+fn test_is_with_non_null_operands() {
+    let mut database = Database::new();
+    let sql = "
+    CREATE TABLE users (
+        id INTEGER,
+        name TEXT,
+        age INTEGER
+    );
+    INSERT INTO users (id, name, age) VALUES (1, 'John', 25), (2, NULL, 30), (3, 'Jane', NULL);
+    SELECT NULL IS 1, 1 IS 1, 1 IS 1.0, 1 IS NOT 1, NULL IS NOT 1;
+    SELECT id FROM users WHERE age IS 25;
+    SELECT id FROM users WHERE age IS NOT 25;
+    SELECT id FROM users WHERE name IS NULL;
+    ";
+    let mut result = run_sql(&mut database, sql);
+    assert!(result.iter().all(|result| result.is_ok()));
+    let expected = vec![Row(vec![Value::Integer(2)])];
+    assert_eq!(Ok(Some(expected)), result.pop().unwrap());
+    let expected = vec![Row(vec![Value::Integer(2)]), Row(vec![Value::Integer(3)])];
+    assert_eq!(Ok(Some(expected)), result.pop().unwrap());
+    let expected = vec![Row(vec![Value::Integer(1)])];
+    assert_eq!(Ok(Some(expected)), result.pop().unwrap());
+    let expected = vec![Row(vec![
+        Value::Integer(0),
+        Value::Integer(1),
+        Value::Integer(1),
+        Value::Integer(0),
+        Value::Integer(1),
+    ])];
+    assert_eq!(Ok(Some(expected)), result.pop().unwrap());
+}
+
+#[test]
+fn test_insert_default_values() {
+    let mut database = Database::new();
+    let sql = "
+    CREATE TABLE users (
+        id INTEGER,
+        name TEXT
+    );
+    INSERT INTO users DEFAULT VALUES;
+    SELECT * FROM users;
+    CREATE TABLE accounts (id INTEGER NOT NULL);
+    INSERT INTO accounts DEFAULT VALUES;
+    ";
+    let mut result = run_sql(&mut database, sql);
+    assert!(result.pop().unwrap().is_err());
+    result.pop();
+    let expected = vec![Row(vec![Value::Null, Value::Null])];
+    assert_eq_table_rows(result.pop().unwrap().unwrap().unwrap(), expected);
+    assert!(result.iter().all(|result| result.is_ok()));
+}
+
+#[test]
+fn test_column_default_values() {
+    let mut database = Database::new();
+    let sql = "
+    CREATE TABLE users (id INTEGER, name TEXT NOT NULL DEFAULT 'anon', score REAL DEFAULT -1.5);
+    INSERT INTO users DEFAULT VALUES;
+    INSERT INTO users (id) VALUES (2);
+    INSERT INTO users (id, name, score) VALUES (3, 'jo', NULL);
+    ALTER TABLE users ADD COLUMN level INTEGER DEFAULT 1;
+    SELECT * FROM users;
+    CREATE TABLE bad (id INTEGER DEFAULT 'one');
+    CREATE TABLE computed (id INTEGER DEFAULT (1 + 1));
+    ";
+    let mut result = run_sql(&mut database, sql);
+    assert_eq!(
+        result.pop().unwrap(),
+        Err("Parsing Error: Only literal DEFAULT values are supported".to_string())
+    );
+    assert_eq!(
+        result.pop().unwrap(),
+        Err("Parsing Error: Default value of column id is not of type Integer".to_string())
+    );
+    let text = |value: &str| Value::Text(value.to_string());
+    let expected = vec![
+        Row(vec![
+            Value::Null,
+            text("anon"),
+            Value::Real(-1.5),
+            Value::Integer(1),
+        ]),
+        Row(vec![
+            Value::Integer(2),
+            text("anon"),
+            Value::Real(-1.5),
+            Value::Integer(1),
+        ]),
+        Row(vec![
+            Value::Integer(3),
+            text("jo"),
+            Value::Null,
+            Value::Integer(1),
+        ]),
+    ];
+    assert_eq_table_rows(result.pop().unwrap().unwrap().unwrap(), expected);
+    assert!(result.iter().all(|result| result.is_ok()));
+    assert!(database.schema().unwrap().contains(
+        "name TEXT NOT NULL DEFAULT 'anon', score REAL DEFAULT -1.5, level INTEGER DEFAULT 1"
+    ));
+
+    let mut restored = Database::new();
+    let dump = database.dump().unwrap();
+    assert!(
+        run_sql(&mut restored, &dump)
+            .iter()
+            .all(|result| result.is_ok())
+    );
+    assert_eq!(restored.dump().unwrap(), dump);
+}
+
+#[test]
+fn test_duplicate_column_aliases_resolve_to_the_first_column() {
+    let mut database = Database::new();
+    let sql = "
+    CREATE TABLE users (
+        id INTEGER,
+        age INTEGER
+    );
+    INSERT INTO users (id, age) VALUES (1, 30), (2, 10), (3, 20);
+    SELECT id AS x, age AS x FROM users WHERE x > 1 ORDER BY x DESC;
+    ";
+    let mut result = run_sql(&mut database, sql);
+    assert!(result.iter().all(|result| result.is_ok()));
+    let expected = vec![
+        Row(vec![Value::Integer(3), Value::Integer(20)]),
+        Row(vec![Value::Integer(2), Value::Integer(10)]),
+    ];
+    assert_eq_table_rows(result.pop().unwrap().unwrap().unwrap(), expected);
+}
+
+#[test]
+fn test_glob_with_character_classes() {
+    let mut database = Database::new();
+    let sql = "
+    CREATE TABLE users (
+        id INTEGER,
+        name TEXT
+    );
+    INSERT INTO users (id, name) VALUES (1, 'Alice'), (2, 'Bob'), (3, 'Charlie'), (4, 'Jim'), (5, 'Dave'), (6, NULL);
+    SELECT name FROM users WHERE name GLOB '[A-C]*';
+    SELECT name FROM users WHERE name GLOB '[^J]*';
+    ";
+    let mut result = run_sql(&mut database, sql);
+    assert!(result.iter().all(|result| result.is_ok()));
+
+    let expected = vec![
+        Row(vec![Value::Text("Alice".to_string())]),
+        Row(vec![Value::Text("Bob".to_string())]),
+        Row(vec![Value::Text("Charlie".to_string())]),
+        Row(vec![Value::Text("Dave".to_string())]),
+    ];
+    assert_eq_table_rows(result.pop().unwrap().unwrap().unwrap(), expected);
+    let expected = vec![
+        Row(vec![Value::Text("Alice".to_string())]),
+        Row(vec![Value::Text("Bob".to_string())]),
+        Row(vec![Value::Text("Charlie".to_string())]),
+    ];
+    assert_eq_table_rows(result.pop().unwrap().unwrap().unwrap(), expected);
+}
+
+#[test]
+fn test_like_with_wildcards() {
+    let mut database = Database::new();
+    let sql = "
+    CREATE TABLE users (
+        id INTEGER,
+        name TEXT
+    );
+    INSERT INTO users (id, name) VALUES (1, 'Jim'), (2, 'Jane'), (3, 'Tim'), (4, 'jim'), (5, 'Tim.B'), (6, NULL);
+    SELECT id FROM users WHERE name LIKE 'J%';
+    SELECT id FROM users WHERE name LIKE '_im';
+    SELECT id FROM users WHERE name LIKE '%m%';
+    SELECT id FROM users WHERE name LIKE '%.B';
+    SELECT 12 LIKE '1%', 'abc' LIKE 'a_c';
+    ";
+    let mut result = run_sql(&mut database, sql);
+    assert!(result.iter().all(|result| result.is_ok()));
+    let expected = vec![Row(vec![Value::Integer(0), Value::Integer(1)])];
+    assert_eq_table_rows(result.pop().unwrap().unwrap().unwrap(), expected);
+    let expected = vec![Row(vec![Value::Integer(5)])];
+    assert_eq_table_rows(result.pop().unwrap().unwrap().unwrap(), expected);
+    let expected = vec![
+        Row(vec![Value::Integer(1)]),
+        Row(vec![Value::Integer(3)]),
+        Row(vec![Value::Integer(4)]),
+        Row(vec![Value::Integer(5)]),
+    ];
+    assert_eq_table_rows(result.pop().unwrap().unwrap().unwrap(), expected);
+    let expected = vec![
+        Row(vec![Value::Integer(1)]),
+        Row(vec![Value::Integer(3)]),
+        Row(vec![Value::Integer(4)]),
+    ];
+    assert_eq_table_rows(result.pop().unwrap().unwrap().unwrap(), expected);
+    let expected = vec![Row(vec![Value::Integer(1)]), Row(vec![Value::Integer(2)])];
+    assert_eq_table_rows(result.pop().unwrap().unwrap().unwrap(), expected);
+}
+
+#[test]
+fn test_like_with_escape_character() {
+    let mut database = Database::new();
+    let sql = "
+    CREATE TABLE discounts (
+        id INTEGER,
+        label TEXT
+    );
+    INSERT INTO discounts (id, label) VALUES (1, '10% off'), (2, '100 off'), (3, 'a_b'), (4, 'axb');
+    SELECT id FROM discounts WHERE label LIKE '%!%%' ESCAPE '!';
+    SELECT id FROM discounts WHERE label LIKE 'a\\_b' ESCAPE '\\' AND id > 1;
+    SELECT 'a%' LIKE 'a#%' ESCAPE '#', 'ab' LIKE 'a#%' ESCAPE '#', 'a' LIKE 'a' ESCAPE NULL;
+    SELECT 'a' LIKE 'a' ESCAPE 'ab';
+    ";
+    let mut result = run_sql(&mut database, sql);
+    assert_eq!(
+        result.pop().unwrap(),
+        Err(
+            "Execution Error with statement starting on line 10 \n Error: ESCAPE expression must be a single character"
+                .to_string()
+        )
+    );
+    assert!(result.iter().all(|result| result.is_ok()));
+    let expected = vec![Row(vec![Value::Integer(1), Value::Integer(0), Value::Null])];
+    assert_eq_table_rows(result.pop().unwrap().unwrap().unwrap(), expected);
+    let expected = vec![Row(vec![Value::Integer(3)])];
+    assert_eq_table_rows(result.pop().unwrap().unwrap().unwrap(), expected);
+    let expected = vec![Row(vec![Value::Integer(1)])];
+    assert_eq_table_rows(result.pop().unwrap().unwrap().unwrap(), expected);
+}
+
+#[test]
+fn test_between_includes_both_bounds() {
+    let mut database = Database::new();
+    let sql = "
+    CREATE TABLE users (
+        id INTEGER,
+        name TEXT,
+        age INTEGER,
+        money REAL
+    );
+    INSERT INTO users (id, name, age, money) VALUES (1, 'Ann', 25, 10.5), (2, 'Bob', 30, 20.0), (3, 'Cid', 35, NULL), (4, 'Dan', 40, 7.0), (5, NULL, NULL, 1.0);
+    SELECT id FROM users WHERE age BETWEEN 25 AND 35;
+    SELECT id FROM users WHERE age NOT BETWEEN 25 AND 35;
+    SELECT id FROM users WHERE age BETWEEN 35 AND 25;
+    SELECT id FROM users WHERE money BETWEEN 7 AND 10.5 AND id > 1;
+    SELECT id FROM users WHERE name BETWEEN 'B' AND 'D';
+    SELECT 1 BETWEEN NULL AND 0, 1 BETWEEN NULL AND 2;
+    ";
+    let mut result = run_sql(&mut database, sql);
+    assert!(result.iter().all(|result| result.is_ok()));
+    let expected = vec![Row(vec![Value::Integer(0), Value::Null])];
+    assert_eq_table_rows(result.pop().unwrap().unwrap().unwrap(), expected);
+    let expected = vec![Row(vec![Value::Integer(2)]), Row(vec![Value::Integer(3)])];
+    assert_eq_table_rows(result.pop().unwrap().unwrap().unwrap(), expected);
+    let expected = vec![Row(vec![Value::Integer(4)])];
+    assert_eq_table_rows(result.pop().unwrap().unwrap().unwrap(), expected);
+    assert_eq!(result.pop().unwrap(), Ok(Some(vec![])));
+    let expected = vec![Row(vec![Value::Integer(4)])];
+    assert_eq_table_rows(result.pop().unwrap().unwrap().unwrap(), expected);
+    let expected = vec![
+        Row(vec![Value::Integer(1)]),
+        Row(vec![Value::Integer(2)]),
+        Row(vec![Value::Integer(3)]),
+    ];
+    assert_eq_table_rows(result.pop().unwrap().unwrap().unwrap(), expected);
+}
+
+#[test]
+fn test_order_by_parenthesized_expressions() {
+    let mut database = Database::new();
+    let sql = "
+    CREATE TABLE users (
+        id INTEGER,
+        age INTEGER,
+        money REAL
+    );
+    INSERT INTO users (id, age, money) VALUES (1, 10, 5.0), (2, 30, 1.0), (3, 20, 2.0);
+    SELECT id FROM users ORDER BY (age + money) DESC;
+    SELECT id FROM users WHERE EXISTS (SELECT id FROM users ORDER BY (id) DESC) ORDER BY ((age - money) * 2);
+    ";
+    let mut result = run_sql(&mut database, sql);
+    assert!(result.iter().all(|result| result.is_ok()));
+    let expected = vec![
+        Row(vec![Value::Integer(1)]),
+        Row(vec![Value::Integer(3)]),
+        Row(vec![Value::Integer(2)]),
+    ];
+    assert_eq_table_rows(result.pop().unwrap().unwrap().unwrap(), expected);
+    let expected = vec![
+        Row(vec![Value::Integer(2)]),
+        Row(vec![Value::Integer(3)]),
+        Row(vec![Value::Integer(1)]),
+    ];
+    assert_eq_table_rows(result.pop().unwrap().unwrap().unwrap(), expected);
+}
+
+#[test]
+fn test_returning_clause() {
+    let mut database = Database::new();
+    let sql = "
+    CREATE TABLE users (
+        id INTEGER,
+        name TEXT
+    );
+    INSERT INTO users (id, name) VALUES (1, 'Alice'), (2, 'Bob') RETURNING id;
+    UPDATE users SET name = 'Robert' WHERE id = 2 RETURNING *, id AS n;
+    DELETE FROM users WHERE id=1 RETURNING *;
+    SELECT * FROM users;
+    ";
+    let mut result = run_sql(&mut database, sql);
+    assert!(result.iter().all(|result| result.is_ok()));
+    let expected = vec![Row(vec![
+        Value::Integer(2),
+        Value::Text("Robert".to_string()),
+    ])];
+    assert_eq_table_rows(result.pop().unwrap().unwrap().unwrap(), expected);
+    let expected = vec![Row(vec![
+        Value::Integer(1),
+        Value::Text("Alice".to_string()),
+    ])];
+    assert_eq_table_rows(result.pop().unwrap().unwrap().unwrap(), expected);
+    let expected = vec![Row(vec![
+        Value::Integer(2),
+        Value::Text("Robert".to_string()),
+        Value::Integer(2),
+    ])];
+    assert_eq_table_rows(result.pop().unwrap().unwrap().unwrap(), expected);
+    let expected = vec![Row(vec![Value::Integer(1)]), Row(vec![Value::Integer(2)])];
+    assert_eq_table_rows(result.pop().unwrap().unwrap().unwrap(), expected);
+}
+
+#[test]
+fn test_row_value_in_values_list() {
+    let mut database = Database::new();
+    let sql = "
+    CREATE TABLE pairs (
+        a INTEGER,
+        b INTEGER,
+        label TEXT
+    );
+    INSERT INTO pairs VALUES (1, 2, 'x'), (3, 4, 'y'), (1, 4, 'z');
+    SELECT label FROM pairs WHERE (a, b) IN (VALUES (1, 2), (3, 4));
+    SELECT label FROM pairs WHERE a IN (1, '3') AND b IN (4);
+    SELECT label FROM pairs WHERE (a, b) IN (VALUES (1, 2, 3));
+    ";
+    let mut result = run_sql(&mut database, sql);
+    assert!(result.pop().unwrap().is_err());
+    let expected = vec![
+        Row(vec![Value::Text("y".to_string())]),
+        Row(vec![Value::Text("z".to_string())]),
+    ];
+    assert_eq_table_rows(result.pop().unwrap().unwrap().unwrap(), expected);
+    let expected = vec![
+        Row(vec![Value::Text("x".to_string())]),
+        Row(vec![Value::Text("y".to_string())]),
+    ];
+    assert_eq_table_rows(result.pop().unwrap().unwrap().unwrap(), expected);
+}
+
+#[test]
+fn test_foreign_keys_are_enforced_on_insert_when_enabled() {
+    let mut database = Database::new();
+    let sql = "
+    CREATE TABLE users (id INTEGER PRIMARY KEY, name TEXT);
+    CREATE TABLE orders (
+        id INTEGER,
+        user_id INTEGER REFERENCES users(id),
+        FOREIGN KEY (id) REFERENCES users
+    );
+    INSERT INTO users VALUES (1, 'John'), (2, 'Jane');
+    INSERT INTO orders VALUES (1, 3);
+    PRAGMA foreign_keys = ON;
+    PRAGMA foreign_keys;
+    INSERT INTO orders VALUES (2, 1), (1, NULL);
+    INSERT INTO orders (id, user_id) VALUES (2, 3);
+    INSERT INTO orders (user_id) VALUES (4);
+    INSERT INTO orders VALUES (3, 1);
+    PRAGMA foreign_keys = OFF;
+    INSERT INTO orders VALUES (5, 5);
+    SELECT * FROM orders;
+    ";
+    let mut result = run_sql(&mut database, sql).into_iter();
+    for _ in 0..4 {
+        assert!(result.next().unwrap().is_ok());
+    }
+    assert_eq!(result.next().unwrap(), Ok(None));
+    assert_eq_table_rows(
+        result.next().unwrap().unwrap().unwrap(),
+        vec![Row(vec![Value::Integer(1)])],
+    );
+    assert!(result.next().unwrap().is_ok());
+    for _ in 0..3 {
+        let error = result.next().unwrap().unwrap_err();
+        assert!(error.contains("FOREIGN KEY constraint failed"), "{}", error);
+    }
+    assert!(result.next().unwrap().is_ok());
+    assert!(result.next().unwrap().is_ok());
+    let expected = vec![
+        Row(vec![Value::Integer(1), Value::Integer(3)]),
+        Row(vec![Value::Integer(2), Value::Integer(1)]),
+        Row(vec![Value::Integer(1), Value::Null]),
+        Row(vec![Value::Integer(5), Value::Integer(5)]),
+    ];
+    assert_eq_table_rows(result.next().unwrap().unwrap().unwrap(), expected);
+}
+
+#[test]
+fn test_in_subquery_with_empty_and_null_results() {
+    let mut database = Database::new();
+    let sql = "
+    CREATE TABLE t (a INTEGER);
+    CREATE TABLE empty (b INTEGER);
+    CREATE TABLE nulls (c INTEGER);
+    INSERT INTO t VALUES (1), (2), (NULL);
+    INSERT INTO nulls VALUES (1), (NULL);
+    SELECT a FROM t WHERE a IN (SELECT b FROM empty);
+    SELECT a FROM t WHERE a NOT IN (SELECT b FROM empty);
+    SELECT a FROM t WHERE a IN (SELECT c FROM nulls);
+    SELECT a FROM t WHERE a NOT IN (SELECT c FROM nulls);
+    SELECT a FROM t WHERE a NOT IN (SELECT c FROM nulls WHERE c IS NOT NULL);
+    SELECT a FROM t WHERE a IN (SELECT c, c FROM nulls);
+    ";
+    let mut result = run_sql(&mut database, sql).into_iter().skip(5);
+    let mut next_rows = || result.next().unwrap().unwrap().unwrap();
+    assert!(next_rows().is_empty());
+    let rows = next_rows();
+    assert_eq!(rows.len(), 3);
+    let expected = vec![
+        Row(vec![Value::Integer(1)]),
+        Row(vec![Value::Integer(2)]),
+        Row(vec![Value::Null]),
+    ];
+    assert_eq_table_rows(rows, expected);
+    let rows = next_rows();
+    assert_eq!(rows.len(), 1);
+    assert_eq_table_rows(rows, vec![Row(vec![Value::Integer(1)])]);
+    // 2 might equal the NULL of the subquery, so it isn't known to be NOT IN.
+    assert!(next_rows().is_empty());
+    let rows = next_rows();
+    assert_eq!(rows.len(), 1);
+    assert_eq_table_rows(rows, vec![Row(vec![Value::Integer(2)])]);
+    assert!(result.next().unwrap().is_err());
+}
+
+#[test]
+fn test_json_extract_reads_values_from_json_text() {
+    let mut database = Database::new();
+    let sql = r#"
+    CREATE TABLE cache (name TEXT, payload TEXT);
+    INSERT INTO cache (name, payload) VALUES ('a', '{"hits": 3, "tags": ["x", "y"]}'), ('b', 'oops');
+    SELECT name, json_extract(payload, '$.hits'), json_extract(payload, '$.tags[1]') FROM cache;
+    "#;
+    let mut result = run_sql(&mut database, sql);
+    assert!(result.iter().all(|result| result.is_ok()));
+    let expected = vec![
+        Row(vec![
+            Value::Text("a".to_string()),
+            Value::Integer(3),
+            Value::Text("y".to_string()),
+        ]),
+        Row(vec![Value::Text("b".to_string()), Value::Null, Value::Null]),
+    ];
+    assert_eq_table_rows(expected, result.pop().unwrap().unwrap().unwrap());
+}
+
+#[test]
+fn test_non_ascii_text_in_statements() {
+    let mut database = Database::new();
+    let sql = r#"
+    CREATE TABLE café (naïve TEXT);
+    INSERT INTO café (naïve) VALUES ('straße'), ('{"clé": "日本"}');
+    SELECT upper(naïve), lower('ÀÉÎ'), length(naïve) FROM café WHERE naïve = 'straße';
+    SELECT json_extract(naïve, '$.clé') FROM café WHERE naïve LIKE '{%';
+    "#;
+    let mut result = run_sql(&mut database, sql);
+    assert!(result.iter().all(|result| result.is_ok()));
+    let text = |value: &str| Value::Text(value.to_string());
+    assert_eq_table_rows(
+        vec![Row(vec![text("日本")])],
+        result.pop().unwrap().unwrap().unwrap(),
+    );
+    assert_eq_table_rows(
+        vec![Row(vec![text("STRASSE"), text("àéî"), Value::Integer(6)])],
+        result.pop().unwrap().unwrap().unwrap(),
+    );
+}
+
+#[test]
+fn test_cast_converts_values() {
+    let mut database = Database::new();
+    let sql = "
+    CREATE TABLE versions (name TEXT);
+    INSERT INTO versions (name) VALUES ('10'), ('9'), ('100'), (NULL);
+    SELECT CAST('12abc' AS INTEGER), CAST(1.9 AS INTEGER), CAST('1.5e3' AS REAL), CAST(3 AS TEXT), CAST('ab' AS BLOB);
+    SELECT name, CAST(name AS INTEGER) AS number FROM versions WHERE CAST(name AS INTEGER) >= 10 OR name IS NULL ORDER BY CAST(name AS INTEGER) DESC;
+    ";
+    let mut result = run_sql(&mut database, sql);
+    assert!(result.iter().all(|result| result.is_ok()));
+    let expected = vec![
+        Row(vec![Value::Text("100".to_string()), Value::Integer(100)]),
+        Row(vec![Value::Text("10".to_string()), Value::Integer(10)]),
+        Row(vec![Value::Null, Value::Null]),
+    ];
+    assert_eq_table_rows(expected, result.pop().unwrap().unwrap().unwrap());
+    let expected = vec![Row(vec![
+        Value::Integer(12),
+        Value::Integer(1),
+        Value::Real(1500.0),
+        Value::Text("3".to_string()),
+        Value::Blob(vec![b'a', b'b']),
+    ])];
+    assert_eq_table_rows(expected, result.pop().unwrap().unwrap().unwrap());
+}
+
+#[test]
+fn test_scalar_subquery_in_where_clause() {
+    let mut database = Database::new();
+    let sql = "
+    CREATE TABLE users (id INTEGER, name TEXT, money REAL);
+    INSERT INTO users VALUES (1, 'Ann', 10.0), (2, 'Bob', 20.0), (3, 'Cid', 60.0), (4, 'Dan', NULL);
+    SELECT id FROM users WHERE money > (SELECT AVG(money) FROM users);
+    SELECT name, money = (SELECT MAX(money) FROM users) / 3 FROM users WHERE id < 4;
+    SELECT (SELECT name FROM users WHERE id = 9), (SELECT name FROM users ORDER BY id DESC);
+    SELECT id FROM users WHERE money = (SELECT id, money FROM users);
+    ";
+    let mut result = run_sql(&mut database, sql).into_iter().skip(2);
+    let mut next_rows = || result.next().unwrap().unwrap().unwrap();
+    assert_eq_table_rows(next_rows(), vec![Row(vec![Value::Integer(3)])]);
+    let expected = vec![
+        Row(vec![Value::Text("Ann".to_string()), Value::Integer(0)]),
+        Row(vec![Value::Text("Bob".to_string()), Value::Integer(1)]),
+        Row(vec![Value::Text("Cid".to_string()), Value::Integer(0)]),
+    ];
+    assert_eq_table_rows(next_rows(), expected);
+    let expected = vec![Row(vec![Value::Null, Value::Text("Dan".to_string())])];
+    assert_eq_table_rows(next_rows(), expected);
+    assert_eq!(
+        result.next().unwrap(),
+        Err(
+            "Execution Error with statement starting on line 7 \n Error: sub-select returns 2 columns - expected 1"
+                .to_string()
+        )
+    );
+}
+
+#[test]
+fn test_case_when_expressions() {
+    let mut database = Database::new();
+    let sql = "
+    CREATE TABLE users (id INTEGER, name TEXT, age INTEGER);
+    INSERT INTO users VALUES (1, 'Ann', 25), (2, 'Bob', 35), (3, 'Cid', NULL), (4, 'Dan', 70);
+    SELECT name, CASE WHEN age > 30 THEN 'old' ELSE 'young' END FROM users;
+    SELECT id, CASE WHEN age < 30 THEN 1 WHEN age < 60 THEN 2 END AS bucket FROM users ORDER BY bucket DESC, id;
+    SELECT id, CASE WHEN age IS NULL THEN 'unknown' ELSE CASE WHEN age > 60 THEN 'senior' ELSE 'adult' END END FROM users WHERE CASE id WHEN 1 THEN 0 ELSE 1 END = 1;
+    SELECT SUM(CASE WHEN age > 30 THEN 1 ELSE 0 END), CASE WHEN 1 THEN 1 ELSE 1 / 0 END FROM users;
+    ";
+    let mut result = run_sql(&mut database, sql).into_iter().skip(2);
+    let mut next_rows = || result.next().unwrap().unwrap().unwrap();
+    let text = |value: &str| Value::Text(value.to_string());
+    let expected = vec![
+        Row(vec![text("Ann"), text("young")]),
+        Row(vec![text("Bob"), text("old")]),
+        Row(vec![text("Cid"), text("young")]),
+        Row(vec![text("Dan"), text("old")]),
+    ];
+    assert_eq_table_rows(next_rows(), expected);
+    let expected = vec![
+        Row(vec![Value::Integer(2), Value::Integer(2)]),
+        Row(vec![Value::Integer(1), Value::Integer(1)]),
+        Row(vec![Value::Integer(3), Value::Null]),
+        Row(vec![Value::Integer(4), Value::Null]),
+    ];
+    assert_eq_table_rows(next_rows(), expected);
+    let expected = vec![
+        Row(vec![Value::Integer(2), text("adult")]),
+        Row(vec![Value::Integer(3), text("unknown")]),
+        Row(vec![Value::Integer(4), text("senior")]),
+    ];
+    assert_eq_table_rows(next_rows(), expected);
+    let expected = vec![Row(vec![Value::Integer(2), Value::Integer(1)])];
+    assert_eq_table_rows(next_rows(), expected);
+}
+
+#[test]
+fn test_row_number_counts_output_rows() {
+    let mut database = Database::new();
+    let sql = "
+    CREATE TABLE scores (name TEXT, score INTEGER);
+    INSERT INTO scores (name, score) VALUES ('a', 30), ('b', 10), ('c', 20), ('d', 40);
+    SELECT row_number(), name FROM scores WHERE score > 10;
+    SELECT name, row_number() * 10 AS rank FROM scores ORDER BY score DESC LIMIT 3;
+    SELECT row_number(), name FROM scores LIMIT 2 OFFSET 1;
+    SELECT name, row_number() FROM scores ORDER BY score LIMIT 2 OFFSET 2;
+    SELECT row_number() AS r, name FROM scores ORDER BY r DESC;
+    SELECT name FROM scores ORDER BY row_number();
+    SELECT row_number(), COUNT(*) FROM scores;
+    SELECT row_number(), score FROM scores GROUP BY score;
+    ";
+    let mut result = run_sql(&mut database, sql);
+    let text = |value: &str| Value::Text(value.to_string());
+    let grouped = "row_number() can't be used with GROUP BY or aggregate functions";
+    let ordered = "ORDER BY can't refer to row_number(), which numbers the rows in output order";
+    for (line, message) in [(11, grouped), (10, grouped), (9, ordered), (8, ordered)] {
+        assert_eq!(
+            result.pop().unwrap(),
+            Err(format!(
+                "Execution Error with statement starting on line {} \n Error: {}",
+                line, message
+            ))
+        );
+    }
+    // The first row after OFFSET is numbered 1.
+    let expected = vec![
+        Row(vec![text("a"), Value::Integer(1)]),
+        Row(vec![text("d"), Value::Integer(2)]),
+    ];
+    assert_eq!(expected, result.pop().unwrap().unwrap().unwrap());
+    let expected = vec![
+        Row(vec![Value::Integer(1), text("b")]),
+        Row(vec![Value::Integer(2), text("c")]),
+    ];
+    assert_eq!(expected, result.pop().unwrap().unwrap().unwrap());
+    let expected = vec![
+        Row(vec![text("d"), Value::Integer(10)]),
+        Row(vec![text("a"), Value::Integer(20)]),
+        Row(vec![text("c"), Value::Integer(30)]),
+    ];
+    assert_eq!(expected, result.pop().unwrap().unwrap().unwrap());
+    let expected = vec![
+        Row(vec![Value::Integer(1), text("a")]),
+        Row(vec![Value::Integer(2), text("c")]),
+        Row(vec![Value::Integer(3), text("d")]),
+    ];
+    assert_eq!(expected, result.pop().unwrap().unwrap().unwrap());
+}
+
+#[test]
+fn test_concatenation_operator() {
+    let mut database = Database::new();
+    let sql = "
+    CREATE TABLE users (name TEXT, age INTEGER, score REAL);
+    INSERT INTO users (name, age, score) VALUES ('John', 30, 1.5), (NULL, 25, 2.5);
+    SELECT name || '!', name || ' is ' || age, age || score, 'x' || NULL FROM users;
+    ";
+    let mut result = run_sql(&mut database, sql);
+    assert!(result.iter().all(|result| result.is_ok()));
+    let text = |value: &str| Value::Text(value.to_string());
+    let expected = vec![
+        Row(vec![
+            text("John!"),
+            text("John is 30"),
+            text("301.5"),
+            Value::Null,
+        ]),
+        Row(vec![Value::Null, Value::Null, text("252.5"), Value::Null]),
+    ];
+    assert_eq_table_rows(expected, result.pop().unwrap().unwrap().unwrap());
+}
+
+#[test]
+fn test_last_insert_rowid_follows_inserts() {
+    let mut database = Database::new();
+    let sql = "
+    SELECT last_insert_rowid();
+    CREATE TABLE users (id INTEGER PRIMARY KEY ON CONFLICT IGNORE, name TEXT);
+    CREATE TABLE logs (message TEXT);
+    INSERT INTO users (id, name) VALUES (10, 'John');
+    SELECT last_insert_rowid();
+    INSERT INTO users (id, name) VALUES (20, 'Jane'), (15, 'Jim');
+    SELECT last_insert_rowid();
+    INSERT INTO logs (message) VALUES ('a'), ('b'), ('c');
+    SELECT last_insert_rowid() + 1;
+    INSERT INTO users (id, name) VALUES (10, 'Duplicate');
+    SELECT last_insert_rowid();
+    ";
+    let result = run_sql(&mut database, sql);
+    assert!(result.iter().all(|result| result.is_ok()));
+    let values = result
+        .into_iter()
+        .filter_map(|result| result.unwrap())
+        .map(|rows| rows[0][0].clone())
+        .collect::<Vec<Value>>();
+    assert_eq!(
+        values,
+        vec![
+            Value::Integer(0),
+            Value::Integer(10),
+            Value::Integer(15),
+            Value::Integer(4),
+            Value::Integer(3),
+        ]
+    );
+}
+
+#[test]
+fn test_integer_primary_key_is_the_rowid_of_inserted_rows() {
+    let mut database = Database::new();
+    let sql = "
+    CREATE TABLE users (id INTEGER PRIMARY KEY, name TEXT);
+    CREATE TABLE posts (user_id INTEGER, title TEXT);
+    INSERT INTO users (name) VALUES ('John');
+    INSERT INTO users VALUES (10, 'Jane');
+    INSERT INTO users (id, name) VALUES (NULL, 'Jim'), (NULL, 'Joe');
+    INSERT INTO posts VALUES (last_insert_rowid(), 'Hello'), (LAST_INSERT_ROWID ( ), 'Again');
+    INSERT INTO posts (title, user_id) VALUES ('Third', last_insert_rowid());
+    SELECT * FROM users;
+    SELECT * FROM posts;
+    ";
+    let mut result = run_sql(&mut database, sql);
+    assert!(result.iter().all(|result| result.is_ok()));
+    let text = |value: &str| Value::Text(value.to_string());
+    let expected = vec![
+        Row(vec![Value::Integer(12), text("Hello")]),
+        Row(vec![Value::Integer(12), text("Again")]),
+        Row(vec![Value::Integer(2), text("Third")]),
+    ];
+    assert_eq_table_rows(result.pop().unwrap().unwrap().unwrap(), expected);
+    let expected = vec![
+        Row(vec![Value::Integer(1), text("John")]),
+        Row(vec![Value::Integer(10), text("Jane")]),
+        Row(vec![Value::Integer(11), text("Jim")]),
+        Row(vec![Value::Integer(12), text("Joe")]),
+    ];
+    assert_eq_table_rows(result.pop().unwrap().unwrap().unwrap(), expected);
+}
+
+#[test]
+fn test_order_by_mixed_types_orders_by_storage_class() {
+    let mut database = Database::new();
+    // Columns hold a single type, so the values are mixed through UNION ALL.
+    let sql = "
+    CREATE TABLE numbers (value INTEGER);
+    CREATE TABLE texts (value TEXT);
+    CREATE TABLE blobs (value BLOB);
+    INSERT INTO numbers VALUES (3), (NULL), (-1);
+    INSERT INTO texts VALUES ('b'), ('a');
+    INSERT INTO blobs VALUES (X'01'), (X'00FF'), (X'00');
+    SELECT value FROM blobs UNION ALL SELECT value FROM texts UNION ALL SELECT value FROM numbers ORDER BY value;
+    SELECT value FROM blobs UNION ALL SELECT value FROM numbers UNION ALL SELECT value FROM texts ORDER BY value DESC;
+    ";
+    let mut result = run_sql(&mut database, sql);
+    assert!(result.iter().all(|result| result.is_ok()));
+    let mut expected = vec![
+        Row(vec![Value::Null]),
+        Row(vec![Value::Integer(-1)]),
+        Row(vec![Value::Integer(3)]),
+        Row(vec![Value::Text("a".to_string())]),
+        Row(vec![Value::Text("b".to_string())]),
+        Row(vec![Value::Blob(vec![0x00])]),
+        Row(vec![Value::Blob(vec![0x00, 0xff])]),
+        Row(vec![Value::Blob(vec![0x01])]),
+    ];
+    let descending = result.pop().unwrap().unwrap().unwrap();
+    assert_eq_table_rows(expected.clone(), result.pop().unwrap().unwrap().unwrap());
+    expected.reverse();
+    assert_eq_table_rows(expected, descending);
+}
+
+#[test]
+fn test_comments_between_tokens_inside_statements() {
+    let mut database = Database::new();
+    let sql = "
+    CREATE TABLE users (id INTEGER /* key */, name TEXT -- display name
+    );
+    INSERT INTO users /* columns */ (id, name) VALUES (1 /* id */, 'a' /* name */), /* next */ (2, -- id
+    'b');
+    SELECT /* columns */ id, name FROM users WHERE /* filter */ id /* column */ >= 1 -- done
+    ;
+    ";
+    let mut result = run_sql(&mut database, sql);
+    assert!(result.iter().all(|result| result.is_ok()));
+    let expected = vec![
+        Row(vec![Value::Integer(1), Value::Text("a".to_string())]),
+        Row(vec![Value::Integer(2), Value::Text("b".to_string())]),
+    ];
+    assert_eq!(expected, result.pop().unwrap().unwrap().unwrap());
+}
+
+#[test]
+fn test_unique_column_rejects_duplicates_but_not_nulls() {
+    let mut database = Database::new();
+    let sql = "
+    CREATE TABLE users (id INTEGER, email TEXT UNIQUE);
+    INSERT INTO users (id, email) VALUES (1, 'a@x.com'), (2, NULL);
+    INSERT INTO users (id, email) VALUES (3, 'a@x.com');
+    INSERT INTO users (id, email) VALUES (4, 'b@x.com'), (5, 'b@x.com');
+    INSERT INTO users (id, email) VALUES (6, NULL), (7, NULL);
+    SELECT * FROM users;
+    ";
+    let mut result = run_sql(&mut database, sql);
+    let error = |line: usize| {
+        Err(format!(
+            "Execution Error with statement starting on line {} \n Error: UNIQUE constraint failed: users.email",
+            line
+        ))
+    };
+    assert_eq!(result[2], error(4));
+    // A conflict between two new rows rejects the whole statement.
+    assert_eq!(result[3], error(5));
+    let expected = vec![
+        Row(vec![Value::Integer(1), Value::Text("a@x.com".to_string())]),
+        Row(vec![Value::Integer(2), Value::Null]),
+        Row(vec![Value::Integer(6), Value::Null]),
+        Row(vec![Value::Integer(7), Value::Null]),
+    ];
+    assert_eq_table_rows(expected, result.pop().unwrap().unwrap().unwrap());
+    assert!(result[4].is_ok());
+}
+
+#[test]
+fn test_autoincrement_never_reuses_values() {
+    let mut database = Database::new();
+    let sql = "
+    CREATE TABLE users (id INTEGER PRIMARY KEY AUTOINCREMENT, name TEXT);
+    INSERT INTO users (name) VALUES ('a'), ('b');
+    INSERT INTO users VALUES (NULL, 'c');
+    DELETE FROM users WHERE id = 3;
+    INSERT INTO users (name) VALUES ('d');
+    INSERT INTO users (id, name) VALUES (10, 'e');
+    DELETE FROM users WHERE id = 10;
+    INSERT INTO users (name) VALUES ('f');
+    SELECT * FROM users;
+    CREATE TABLE notes (id TEXT PRIMARY KEY AUTOINCREMENT);
+    CREATE TABLE logs (id INTEGER AUTOINCREMENT);
+    ";
+    let mut result = run_sql(&mut database, sql);
+    assert!(result.pop().unwrap().is_err());
+    assert_eq!(
+        result.pop().unwrap(),
+        Err("Parsing Error: AUTOINCREMENT is only allowed on an INTEGER PRIMARY KEY".to_string())
+    );
+    assert!(result.iter().all(|result| result.is_ok()));
+    let text = |value: &str| Value::Text(value.to_string());
+    // 3 and 10 were deleted but are not given again.
+    let expected = vec![
+        Row(vec![Value::Integer(1), text("a")]),
+        Row(vec![Value::Integer(2), text("b")]),
+        Row(vec![Value::Integer(4), text("d")]),
+        Row(vec![Value::Integer(11), text("f")]),
+    ];
+    assert_eq_table_rows(expected, result.pop().unwrap().unwrap().unwrap());
+    assert!(
+        database
+            .schema()
+            .unwrap()
+            .contains("id INTEGER PRIMARY KEY AUTOINCREMENT")
+    );
+}
+
+#[test]
+fn test_autoincrement_sequence_follows_updates_and_dump() {
+    let mut database = Database::new();
+    let sql = "
+    CREATE TABLE users (id INTEGER PRIMARY KEY AUTOINCREMENT, name TEXT);
+    INSERT INTO users (name) VALUES ('a'), ('b');
+    UPDATE users SET id = 20 WHERE name = 'b';
+    DELETE FROM users WHERE id = 20;
+    INSERT INTO users (name) VALUES ('c');
+    DELETE FROM users WHERE id = 21;
+    ";
+    assert!(
+        run_sql(&mut database, sql)
+            .iter()
+            .all(|result| result.is_ok())
+    );
+    let dump = database.dump().unwrap();
+    assert!(dump.contains("INSERT INTO sqlite_sequence VALUES ('users', 21);"));
+
+    let mut restored = Database::new();
+    let sql = format!(
+        "{}
+        INSERT INTO users (name) VALUES ('d');
+        SELECT * FROM users;
+        INSERT INTO sqlite_sequence VALUES ('missing', 1);
+        ",
+        dump
+    );
+    let mut result = run_sql(&mut restored, &sql);
+    assert!(
+        result
+            .pop()
+            .unwrap()
+            .unwrap_err()
+            .ends_with("Table `missing` does not exist")
+    );
+    let text = |value: &str| Value::Text(value.to_string());
+    // 20 was given by the UPDATE and 21 deleted before the dump, neither is given again.
+    let expected = vec![
+        Row(vec![Value::Integer(1), text("a")]),
+        Row(vec![Value::Integer(22), text("d")]),
+    ];
+    assert_eq_table_rows(expected, result.pop().unwrap().unwrap().unwrap());
+    assert!(result.iter().all(|result| result.is_ok()));
+}
+
+#[test]
+fn test_sqlite_master_lists_tables_and_indexes() {
+    let mut database = Database::new();
+    let sql = "
+    CREATE TABLE users (id INTEGER PRIMARY KEY, name TEXT);
+    CREATE TABLE orders (id INTEGER, user_id INTEGER);
+    CREATE INDEX idx_orders_user ON orders (user_id);
+    CREATE TABLE dropped (id INTEGER);
+    DROP TABLE dropped;
+    SELECT name FROM sqlite_master WHERE type = 'table' ORDER BY name;
+    SELECT type, name, tbl_name, sql FROM sqlite_master WHERE type = 'index';
+    CREATE TABLE sqlite_master (id INTEGER);
+    DELETE FROM sqlite_master;
+    ";
+    let mut result = run_sql(&mut database, sql);
+    assert!(result.pop().unwrap().is_err());
+    assert_eq!(
+        result.pop().unwrap(),
+        Err(
+            "Execution Error with statement starting on line 9 \n Error: Object name reserved for internal use: sqlite_master"
+                .to_string()
+        )
+    );
+    let text = |value: &str| Value::Text(value.to_string());
+    let expected = vec![Row(vec![
+        text("index"),
+        text("idx_orders_user"),
+        text("orders"),
+        text("CREATE INDEX idx_orders_user ON orders (user_id)"),
+    ])];
+    assert_eq!(expected, result.pop().unwrap().unwrap().unwrap());
+    let expected = vec![Row(vec![text("orders")]), Row(vec![text("users")])];
+    assert_eq!(expected, result.pop().unwrap().unwrap().unwrap());
+    assert!(result.iter().all(|result| result.is_ok()));
+}
+
+#[test]
+fn test_reserved_table_names_ignore_case() {
+    let mut database = Database::new();
+    let sql = "
+    CREATE TABLE users (id INTEGER);
+    SELECT name FROM SQLITE_MASTER;
+    ALTER TABLE users RENAME TO sqlite_master;
+    ALTER TABLE users RENAME TO Sqlite_Sequence;
+    CREATE TABLE SQLITE_MASTER (id INTEGER);
+    SELECT name FROM Sqlite_Master;
+    ";
+    let error = |line: usize, name: &str| {
+        Err(format!(
+            "Execution Error with statement starting on line {} \n Error: Object name reserved for internal use: {}",
+            line, name
+        ))
+    };
+    let expected = vec![
+        Ok(None),
+        Ok(Some(vec![Row(vec![Value::Text("users".to_string())])])),
+        error(4, "sqlite_master"),
+        error(5, "Sqlite_Sequence"),
+        error(6, "SQLITE_MASTER"),
+        Ok(Some(vec![Row(vec![Value::Text("users".to_string())])])),
+    ];
+    assert_eq!(expected, run_sql(&mut database, sql));
+}
+
+#[test]
+fn test_update_enforces_unique_and_not_null() {
+    let mut database = Database::new();
+    let sql = "
+    CREATE TABLE users (id INTEGER PRIMARY KEY, name TEXT UNIQUE, age INTEGER NOT NULL);
+    INSERT INTO users (id, name, age) VALUES (1, 'a', 20), (2, 'b', 30);
+    UPDATE users SET name = 'b' WHERE id = 1;
+    UPDATE users SET age = NULL WHERE id = 2;
+    UPDATE users SET name = 'c';
+    UPDATE users SET name = 'c' WHERE id = 1;
+    SELECT * FROM users;
+    ";
+    let mut result = run_sql(&mut database, sql);
+    let user = |id: i64, name: &str, age: i64| {
+        Row(vec![
+            Value::Integer(id),
+            Value::Text(name.to_string()),
+            Value::Integer(age),
+        ])
+    };
+    assert_eq!(
+        result.pop().unwrap(),
+        Ok(Some(vec![user(1, "c", 20), user(2, "b", 30)]))
+    );
+    assert!(result.pop().unwrap().is_ok());
+    let expected = [
+        "Execution Error with statement starting on line 6 \n Error: UNIQUE constraint failed: users.name",
+        "Execution Error with statement starting on line 5 \n Error: NOT NULL constraint failed: users.age",
+        "Execution Error with statement starting on line 4 \n Error: UNIQUE constraint failed: users.name",
+    ];
+    for error in expected {
+        assert_eq!(result.pop().unwrap(), Err(error.to_string()));
+    }
+    assert!(result.iter().all(|result| result.is_ok()));
+}
+
+#[test]
+fn test_qualified_columns_on_a_single_table() {
+    let mut database = Database::new();
+    let sql = "
+    CREATE TABLE users (id INTEGER PRIMARY KEY, name TEXT);
+    INSERT INTO users VALUES (1, 'a'), (2, 'b'), (3, 'c');
+    UPDATE users SET name = 'z' WHERE users.id = 1;
+    DELETE FROM users AS u WHERE u.id = 3;
+    SELECT users.name FROM users ORDER BY users.id;
+    SELECT u.* FROM users u WHERE u.id = 2;
+    SELECT upper(u.name), COUNT(*) FROM users u GROUP BY u.name ORDER BY u.name;
+    SELECT m.name FROM sqlite_master m;
+    SELECT users.name FROM users u;
+    SELECT x.* FROM users;
+    ";
+    let text = |value: &str| Value::Text(value.to_string());
+    let expected = vec![
+        Ok(None),
+        Ok(None),
+        Ok(None),
+        Ok(None),
+        Ok(Some(vec![Row(vec![text("z")]), Row(vec![text("b")])])),
+        Ok(Some(vec![Row(vec![Value::Integer(2), text("b")])])),
+        Ok(Some(vec![
+            Row(vec![text("B"), Value::Integer(1)]),
+            Row(vec![text("Z"), Value::Integer(1)]),
+        ])),
+        Ok(Some(vec![Row(vec![text("users")])])),
+        // Once aliased, the table is only known by its alias.
+        Err("Execution Error with statement starting on line 10 \n Error: Invalid column name: users.name".to_string()),
+        Err("Execution Error with statement starting on line 11 \n Error: No such table: x".to_string()),
+    ];
+    assert_eq_run_sql(expected, run_sql(&mut database, sql));
+}
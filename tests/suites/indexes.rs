@@ -0,0 +1,168 @@
+use mollycache::db::database::Database;
+use mollycache::db::table::core::{row::Row, value::Value};
+use mollycache::interpreter::run_sql;
+
+use crate::common::{assert_eq_run_sql, assert_eq_table_rows};
+
+#[test]
+fn test_create_and_drop_index() {
+    let mut database = Database::new();
+    let sql = "
+    CREATE TABLE users (
+        id INTEGER,
+        name TEXT
+    );
+    INSERT INTO users (id, name) VALUES (1, 'John'), (2, 'Jane');
+    CREATE INDEX idx_users_name ON users (name);
+    CREATE INDEX idx_users_name ON users (id);
+    CREATE INDEX IF NOT EXISTS idx_users_name ON users (id);
+    DROP INDEX idx_users_name;
+    DROP INDEX idx_users_name;
+    DROP INDEX IF EXISTS idx_users_name;
+    ";
+    let result = run_sql(&mut database, sql);
+    let expected = vec![
+        Ok(None),
+        Ok(None),
+        Ok(None),
+        Err("Execution Error with statement starting on line 8 \n Error: Index `idx_users_name` already exists".to_string()),
+        Ok(None),
+        Ok(None),
+        Err("Execution Error with statement starting on line 11 \n Error: Index `idx_users_name` does not exist".to_string()),
+        Ok(None),
+    ];
+    assert_eq_run_sql(expected, result);
+    assert!(
+        !database
+            .get_table("users")
+            .unwrap()
+            .has_index("idx_users_name")
+    );
+}
+
+#[test]
+fn test_drop_index_rollback_restores_index() {
+    let mut database = Database::new();
+    let sql = "
+    CREATE TABLE users (id INTEGER, name TEXT);
+    CREATE INDEX idx_users_name ON users (name);
+    BEGIN;
+        DROP INDEX idx_users_name;
+        CREATE INDEX idx_users_id ON users (id);
+    ROLLBACK;
+    ";
+    let result = run_sql(&mut database, sql);
+    assert!(result.iter().all(|result| result.is_ok()));
+    let table = database.get_table("users").unwrap();
+    assert!(table.has_index("idx_users_name"));
+    assert!(!table.has_index("idx_users_id"));
+}
+
+#[test]
+fn test_select_with_index_lookup() {
+    let mut database = Database::new();
+    let sql = "
+    CREATE TABLE users (id INTEGER, name TEXT);
+    INSERT INTO users (id, name) VALUES (1, 'John'), (2, 'Jane'), (3, 'Jim'), (4, 'Jack'), (5, 'Jill');
+    CREATE INDEX idx_users_id ON users (id);
+    SELECT name FROM users WHERE id = 3.0 AND name = 'Jim';
+    SELECT id + 1 AS id, name FROM users WHERE id = 3;
+    SELECT COUNT(*) FROM users WHERE 2 = id;
+    ";
+    let result = run_sql(&mut database, sql);
+    let expected = vec![
+        Ok(None),
+        Ok(None),
+        Ok(None),
+        Ok(Some(vec![Row(vec![Value::Text("Jim".to_string())])])),
+        Ok(Some(vec![Row(vec![
+            Value::Integer(3),
+            Value::Text("Jane".to_string()),
+        ])])),
+        Ok(Some(vec![Row(vec![Value::Integer(1)])])),
+    ];
+    assert_eq_run_sql(expected, result);
+}
+
+#[test]
+fn test_unique_index_rejects_duplicate_keys() {
+    let mut database = Database::new();
+    let sql = "
+    CREATE TABLE users (id INTEGER, email TEXT, name TEXT);
+    INSERT INTO users (id, email, name) VALUES (1, 'john@example.com', 'John'), (2, 'jane@example.com', 'John');
+    CREATE UNIQUE INDEX idx_users_name ON users (name);
+    CREATE UNIQUE INDEX idx_users_email ON users (email);
+    INSERT INTO users (id, email, name) VALUES (3, 'john@example.com', 'Jim');
+    INSERT INTO users (id, email, name) VALUES (3, NULL, 'Jim'), (4, NULL, 'Jill');
+    UPDATE users SET email = 'jane@example.com' WHERE id = 1;
+    UPDATE users SET email = 'jim@example.com' WHERE id = 3;
+    SELECT id, email FROM users;
+    ";
+    let result = run_sql(&mut database, sql);
+    let expected = vec![
+        Ok(None),
+        Ok(None),
+        Err("Execution Error with statement starting on line 4 \n Error: UNIQUE constraint failed: index idx_users_name".to_string()),
+        Ok(None),
+        Err("Execution Error with statement starting on line 6 \n Error: UNIQUE constraint failed: index idx_users_email".to_string()),
+        Ok(None),
+        Err("Execution Error with statement starting on line 8 \n Error: UNIQUE constraint failed: index idx_users_email".to_string()),
+        Ok(None),
+        Ok(Some(vec![
+            Row(vec![
+                Value::Integer(1),
+                Value::Text("john@example.com".to_string()),
+            ]),
+            Row(vec![
+                Value::Integer(2),
+                Value::Text("jane@example.com".to_string()),
+            ]),
+            Row(vec![
+                Value::Integer(3),
+                Value::Text("jim@example.com".to_string()),
+            ]),
+            Row(vec![Value::Integer(4), Value::Null]),
+        ])),
+    ];
+    assert_eq_run_sql(expected, result);
+    let table = database.get_table("users").unwrap();
+    assert!(!table.has_index("idx_users_name"));
+    assert!(table.get_index("idx_users_email").unwrap().unique);
+}
+
+#[test]
+fn test_explain_query_plan_shows_index_usage() {
+    let mut database = Database::new();
+    let sql = "
+    CREATE TABLE users (id INTEGER, name TEXT);
+    INSERT INTO users VALUES (1, 'a'), (2, 'b'), (3, 'c'), (4, 'd'), (5, 'e');
+    EXPLAIN QUERY PLAN SELECT * FROM users WHERE id = 3;
+    CREATE INDEX idx_id ON users (id);
+    EXPLAIN QUERY PLAN SELECT * FROM users WHERE id = 3;
+    EXPLAIN QUERY PLAN SELECT name FROM users WHERE id > 3 UNION SELECT name FROM users WHERE id = 1;
+    EXPLAIN QUERY PLAN SELECT 1;
+    ";
+    let mut result = run_sql(&mut database, sql);
+    assert!(result.iter().all(|result| result.is_ok()));
+    let plan = |line: &str| Row(vec![Value::Text(line.to_string())]);
+    assert_eq_table_rows(
+        result.pop().unwrap().unwrap().unwrap(),
+        vec![plan("SCAN CONSTANT ROW")],
+    );
+    assert_eq_table_rows(
+        result.pop().unwrap().unwrap().unwrap(),
+        vec![
+            plan("SCAN users"),
+            plan("SEARCH users USING INDEX idx_id (id=?)"),
+        ],
+    );
+    assert_eq_table_rows(
+        result.pop().unwrap().unwrap().unwrap(),
+        vec![plan("SEARCH users USING INDEX idx_id (id=?)")],
+    );
+    result.pop();
+    assert_eq_table_rows(
+        result.pop().unwrap().unwrap().unwrap(),
+        vec![plan("SCAN users")],
+    );
+}
@@ -0,0 +1,192 @@
+use mollycache::db::database::Database;
+use mollycache::db::table::core::{row::Row, value::Value};
+use mollycache::interpreter::run_sql;
+
+use crate::common::{assert_eq_table_rows, assert_eq_table_rows_unordered};
+
+#[test]
+fn test_cross_join_returns_every_combination_of_rows() {
+    let mut database = Database::new();
+    let sql = "
+    CREATE TABLE colors (id INTEGER, color TEXT);
+    CREATE TABLE sizes (id INTEGER, size TEXT);
+    INSERT INTO colors VALUES (1, 'red'), (2, 'green'), (3, 'blue');
+    INSERT INTO sizes VALUES (1, 'S'), (2, 'L');
+    SELECT * FROM colors, sizes;
+    SELECT * FROM colors CROSS JOIN sizes;
+    SELECT color, size FROM colors AS c CROSS JOIN sizes WHERE c.id = 2 AND sizes.id = 2;
+    SELECT id FROM colors, sizes;
+    ";
+    let mut result = run_sql(&mut database, sql);
+    assert!(result.pop().unwrap().is_err());
+    let expected = vec![Row(vec![
+        Value::Text("green".to_string()),
+        Value::Text("L".to_string()),
+    ])];
+    assert_eq_table_rows(expected, result.pop().unwrap().unwrap().unwrap());
+    let cross_join = result.pop().unwrap().unwrap().unwrap();
+    let comma = result.pop().unwrap().unwrap().unwrap();
+    assert_eq!(comma.len(), 3 * 2);
+    assert_eq!(cross_join, comma);
+    assert_eq!(
+        comma[1],
+        Row(vec![
+            Value::Integer(1),
+            Value::Text("red".to_string()),
+            Value::Integer(2),
+            Value::Text("L".to_string()),
+        ])
+    );
+    assert!(
+        result
+            .into_iter()
+            .all(|result| result.is_ok() && result.unwrap().is_none())
+    );
+}
+
+fn users_and_orders() -> Database {
+    let mut database = Database::new();
+    let sql = "
+    CREATE TABLE users (id INTEGER, name TEXT);
+    CREATE TABLE orders (id INTEGER, user_id INTEGER, amount INTEGER);
+    INSERT INTO users VALUES (1, 'Ann'), (2, 'Bob'), (3, 'Cid');
+    INSERT INTO orders VALUES (10, 1, 5), (11, 1, 7), (12, 2, 3), (13, 9, 1);
+    ";
+    assert!(
+        run_sql(&mut database, sql)
+            .iter()
+            .all(|result| result.is_ok())
+    );
+    database
+}
+
+#[test]
+fn test_inner_join_keeps_matching_rows() {
+    let mut database = users_and_orders();
+    let sql = "
+    SELECT u.name, o.amount FROM users u INNER JOIN orders o ON u.id = o.user_id;
+    SELECT name, amount FROM users JOIN orders ON users.id = orders.user_id WHERE amount > 3 ORDER BY amount DESC;
+    ";
+    let mut result = run_sql(&mut database, sql);
+    let expected = vec![
+        Row(vec![Value::Text("Ann".to_string()), Value::Integer(7)]),
+        Row(vec![Value::Text("Ann".to_string()), Value::Integer(5)]),
+    ];
+    assert_eq!(expected, result.pop().unwrap().unwrap().unwrap());
+    // Ann has two orders, Cid has none and the order of user 9 has no user.
+    let expected = vec![
+        Row(vec![Value::Text("Ann".to_string()), Value::Integer(5)]),
+        Row(vec![Value::Text("Ann".to_string()), Value::Integer(7)]),
+        Row(vec![Value::Text("Bob".to_string()), Value::Integer(3)]),
+    ];
+    assert_eq!(expected, result.pop().unwrap().unwrap().unwrap());
+}
+
+#[test]
+fn test_inner_join_without_matches_returns_no_rows() {
+    let mut database = users_and_orders();
+    let sql = "
+    SELECT u.name FROM users AS u JOIN orders AS o ON u.id = o.user_id AND o.amount > 100;
+    SELECT COUNT(*) FROM users u JOIN orders o ON u.id = o.id;
+    SELECT id FROM users JOIN orders ON users.id = orders.user_id;
+    ";
+    let mut result = run_sql(&mut database, sql);
+    assert_eq!(
+        result.pop().unwrap(),
+        Err(
+            "Execution Error with statement starting on line 4 \n Error: Ambiguous column name: id"
+                .to_string()
+        )
+    );
+    assert_eq!(
+        result.pop().unwrap(),
+        Ok(Some(vec![Row(vec![Value::Integer(0)])]))
+    );
+    assert_eq!(result.pop().unwrap(), Ok(Some(vec![])));
+}
+
+#[test]
+fn test_left_join_pads_unmatched_rows_with_nulls() {
+    let mut database = users_and_orders();
+    let sql = "
+    SELECT u.name, o.amount FROM users u LEFT JOIN orders o ON u.id = o.user_id;
+    SELECT u.name, o.id FROM users u LEFT OUTER JOIN orders o ON u.id = o.user_id AND o.amount > u.id * 4 WHERE o.id IS NULL;
+    CREATE TABLE refunds (order_id INTEGER);
+    SELECT o.id, r.order_id FROM orders o LEFT JOIN refunds r ON o.id = r.order_id;
+    ";
+    let mut result = run_sql(&mut database, sql);
+    assert!(result.iter().all(|result| result.is_ok()));
+    let expected = vec![
+        Row(vec![Value::Integer(10), Value::Null]),
+        Row(vec![Value::Integer(11), Value::Null]),
+        Row(vec![Value::Integer(12), Value::Null]),
+        Row(vec![Value::Integer(13), Value::Null]),
+    ];
+    assert_eq_table_rows(expected, result.pop().unwrap().unwrap().unwrap());
+    result.pop();
+    let expected = vec![
+        Row(vec![Value::Text("Bob".to_string()), Value::Null]),
+        Row(vec![Value::Text("Cid".to_string()), Value::Null]),
+    ];
+    assert_eq_table_rows(expected, result.pop().unwrap().unwrap().unwrap());
+    let expected = vec![
+        Row(vec![Value::Text("Ann".to_string()), Value::Integer(5)]),
+        Row(vec![Value::Text("Ann".to_string()), Value::Integer(7)]),
+        Row(vec![Value::Text("Bob".to_string()), Value::Integer(3)]),
+        Row(vec![Value::Text("Cid".to_string()), Value::Null]),
+    ];
+    assert_eq_table_rows(expected, result.pop().unwrap().unwrap().unwrap());
+}
+
+#[test]
+fn test_join_selects_all_columns_of_one_table() {
+    let mut database = Database::new();
+    let sql = "
+    CREATE TABLE users (id INTEGER, name TEXT);
+    CREATE TABLE orders (id INTEGER, user_id INTEGER);
+    INSERT INTO users (id, name) VALUES (1, 'a'), (2, 'b');
+    INSERT INTO orders (id, user_id) VALUES (7, 2);
+    SELECT o.*, u.name FROM users u JOIN orders o ON u.id = o.user_id;
+    ";
+    let mut result = run_sql(&mut database, sql);
+    assert!(result.iter().all(|result| result.is_ok()));
+    assert_eq_table_rows(
+        vec![Row(vec![
+            Value::Integer(7),
+            Value::Integer(2),
+            Value::Text("b".to_string()),
+        ])],
+        result.pop().unwrap().unwrap().unwrap(),
+    );
+}
+
+#[test]
+fn test_join_with_group_by_and_aggregates_on_qualified_columns() {
+    let mut database = Database::new();
+    let sql = "
+    CREATE TABLE users (id INTEGER, name TEXT);
+    CREATE TABLE orders (id INTEGER, user_id INTEGER, amount INTEGER);
+    INSERT INTO users (id, name) VALUES (1, 'a'), (2, 'b'), (3, 'c');
+    INSERT INTO orders (id, user_id, amount) VALUES (1, 1, 10), (2, 1, 5), (3, 2, 7);
+    SELECT u.name, SUM(o.amount), COUNT(o.id) FROM users u JOIN orders o ON u.id = o.user_id GROUP BY u.name;
+    SELECT u.name, MAX(o.amount) FROM users u LEFT JOIN orders o ON u.id = o.user_id GROUP BY u.name;
+    ";
+    let mut result = run_sql(&mut database, sql);
+    assert!(result.iter().all(|result| result.is_ok()));
+    let text = |value: &str| Value::Text(value.to_string());
+    assert_eq_table_rows_unordered(
+        vec![
+            Row(vec![text("a"), Value::Integer(10)]),
+            Row(vec![text("b"), Value::Integer(7)]),
+            Row(vec![text("c"), Value::Null]),
+        ],
+        result.pop().unwrap().unwrap().unwrap(),
+    );
+    assert_eq_table_rows_unordered(
+        vec![
+            Row(vec![text("a"), Value::Integer(15), Value::Integer(2)]),
+            Row(vec![text("b"), Value::Integer(7), Value::Integer(1)]),
+        ],
+        result.pop().unwrap().unwrap().unwrap(),
+    );
+}
@@ -0,0 +1,160 @@
+use mollycache::db::database::Database;
+use mollycache::db::table::core::{row::Row, value::Value};
+use mollycache::db::table::operations::helpers::math_functions::seed_random;
+use mollycache::interpreter::run_sql;
+
+#[test]
+fn test_round_and_abs() {
+    let mut database = Database::new();
+    let sql = "
+    CREATE TABLE prices (
+        id INTEGER,
+        price REAL
+    );
+    INSERT INTO prices (id, price) VALUES (-3, -2.71828), (4, NULL);
+    SELECT ROUND(3.14159, 2), ROUND(NULL), ROUND(7), ROUND(2.5);
+    SELECT ABS(id), ABS(price), ROUND(price, 3) FROM prices;
+    ";
+    let mut result = run_sql(&mut database, sql);
+    assert!(result.iter().all(|result| result.is_ok()));
+
+    let rows = result.pop().unwrap().unwrap().unwrap();
+    let expected = vec![
+        Row(vec![
+            Value::Integer(3),
+            Value::Real(2.71828),
+            Value::Real(-2.718),
+        ]),
+        Row(vec![Value::Integer(4), Value::Null, Value::Null]),
+    ];
+    assert_rows_exactly_equal(expected, rows);
+
+    let rows = result.pop().unwrap().unwrap().unwrap();
+    let expected = vec![Row(vec![
+        Value::Real(3.14),
+        Value::Null,
+        Value::Integer(7),
+        Value::Real(3.0),
+    ])];
+    assert_rows_exactly_equal(expected, rows);
+}
+
+// Compares values and their types, so an Integer result doesn't match an equal Real.
+fn assert_rows_exactly_equal(expected: Vec<Row>, actual: Vec<Row>) {
+    assert_eq!(expected.len(), actual.len());
+    for (expected, actual) in expected.iter().zip(actual.iter()) {
+        assert_eq!(expected.len(), actual.len());
+        for (expected, actual) in expected.iter().zip(actual.iter()) {
+            assert!(
+                expected.exactly_equal(actual),
+                "{:?} != {:?}",
+                expected,
+                actual
+            );
+        }
+    }
+}
+
+#[test]
+fn test_null_propagation_in_scalar_functions() {
+    let mut database = Database::new();
+    let sql = "
+    CREATE TABLE prices (
+        id INTEGER,
+        name TEXT,
+        price REAL
+    );
+    INSERT INTO prices (id, name, price) VALUES (1, 'apple', NULL), (2, NULL, 2.5);
+    SELECT ABS(price), LENGTH(name), COALESCE(price, id), IFNULL(name, 'unknown') FROM prices;
+    ";
+    let mut result = run_sql(&mut database, sql);
+    assert!(result.iter().all(|result| result.is_ok()));
+
+    let rows = result.pop().unwrap().unwrap().unwrap();
+    let expected = vec![
+        Row(vec![
+            Value::Null,
+            Value::Integer(5),
+            Value::Integer(1),
+            Value::Text("apple".to_string()),
+        ]),
+        Row(vec![
+            Value::Real(2.5),
+            Value::Null,
+            Value::Real(2.5),
+            Value::Text("unknown".to_string()),
+        ]),
+    ];
+    assert_rows_exactly_equal(expected, rows);
+}
+
+#[test]
+fn test_null_propagation_in_arithmetic() {
+    let mut database = Database::new();
+    let sql = "
+    CREATE TABLE users (
+        id INTEGER,
+        age INTEGER
+    );
+    INSERT INTO users (id, age) VALUES (1, 25), (2, NULL);
+    SELECT age + NULL, age - 1, NULL * age, age / NULL, age % NULL FROM users;
+    SELECT id FROM users WHERE age + NULL = 25;
+    SELECT id FROM users WHERE NOT (age - 5 = 20);
+    SELECT id FROM users WHERE age * 2 = 50;
+    ";
+    let mut result = run_sql(&mut database, sql);
+    assert!(result.iter().all(|result| result.is_ok()));
+
+    // A WHERE evaluating to NULL filters the row out, even under NOT.
+    let id_rows = |ids: Vec<i64>| {
+        ids.into_iter()
+            .map(|id| Row(vec![Value::Integer(id)]))
+            .collect::<Vec<Row>>()
+    };
+    assert_rows_exactly_equal(id_rows(vec![1]), result.pop().unwrap().unwrap().unwrap());
+    assert_rows_exactly_equal(id_rows(vec![]), result.pop().unwrap().unwrap().unwrap());
+    assert_rows_exactly_equal(id_rows(vec![]), result.pop().unwrap().unwrap().unwrap());
+
+    let rows = result.pop().unwrap().unwrap().unwrap();
+    let expected = vec![
+        Row(vec![
+            Value::Null,
+            Value::Integer(24),
+            Value::Null,
+            Value::Null,
+            Value::Null,
+        ]),
+        Row(vec![Value::Null; 5]),
+    ];
+    assert_rows_exactly_equal(expected, rows);
+}
+
+#[test]
+fn test_order_by_random_with_a_seed() {
+    let mut database = Database::new();
+    let sql = "
+    CREATE TABLE numbers (id INTEGER);
+    INSERT INTO numbers VALUES (1), (2), (3), (4), (5), (6);
+    ";
+    assert!(
+        run_sql(&mut database, sql)
+            .iter()
+            .all(|result| result.is_ok())
+    );
+
+    let mut shuffle = || {
+        seed_random(42);
+        run_sql(&mut database, "SELECT id FROM numbers ORDER BY RANDOM();")
+            .pop()
+            .unwrap()
+            .unwrap()
+            .unwrap()
+    };
+    let first = shuffle();
+    assert_eq!(first, shuffle());
+    let expected = [1, 6, 5, 2, 3, 4]
+        .into_iter()
+        .map(|id| Row(vec![Value::Integer(id)]))
+        .collect::<Vec<Row>>();
+    assert_rows_exactly_equal(expected, first);
+}
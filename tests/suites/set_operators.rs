@@ -83,13 +83,22 @@ fn test_set_operators_with_different_tables_and_clause() {
     INSERT INTO users2 (id, name) VALUES (1, 'Fletcher'), (2, 'Jane'), (3, 'Jim'), (4, 'Fletcher');
     SELECT name FROM users1 UNION SELECT name FROM users2;
     SELECT * FROM users1 UNION SELECT * FROM users3;
+    SELECT * FROM users1 UNION SELECT name FROM users2;
     ";
     let mut result = run_sql(&mut database, sql);
-    let first_result = result.pop().unwrap();
-    assert!(first_result.is_err());
-    let expected_second = "Execution Error with statement starting on line 17 \n Error: Columns mismatch between SELECT statements in Union".to_string();
-    assert_eq!(expected_second, first_result.unwrap_err());
+    let last_result = result.pop().unwrap();
+    assert!(last_result.is_err());
+    let expected_last = "Execution Error with statement starting on line 18 \n Error: Columns mismatch between SELECT statements in Union".to_string();
+    assert_eq!(expected_last, last_result.unwrap_err());
     assert!(result.iter().all(|result| result.is_ok()));
+    // users3 is empty, only the arity of its columns matters.
+    let expected_second = vec![
+        Row(vec![Value::Integer(1), Value::Text("John".to_string())]),
+        Row(vec![Value::Integer(2), Value::Text("Jane".to_string())]),
+        Row(vec![Value::Integer(3), Value::Text("Jim".to_string())]),
+        Row(vec![Value::Integer(4), Value::Text("Jack".to_string())]),
+    ];
+    assert_eq_table_rows_unordered(expected_second, result.pop().unwrap().unwrap().unwrap());
     let expected_first = vec![
         Row(vec![Value::Text("John".to_string())]),
         Row(vec![Value::Text("Fletcher".to_string())]),
@@ -107,3 +116,142 @@ fn test_set_operators_with_different_tables_and_clause() {
 
 // ADD TESTS with two seperate tables with different columns and using SELECT *
 // two tables with same columns and using SELECT *
+
+#[test]
+fn test_set_operators_with_null_rows() {
+    let mut database = Database::new();
+    let sql = "
+    CREATE TABLE users1 (id INTEGER, name TEXT);
+    CREATE TABLE users2 (id INTEGER, name TEXT);
+    INSERT INTO users1 (id, name) VALUES (NULL, NULL), (1, 'John');
+    INSERT INTO users2 (id, name) VALUES (NULL, NULL), (2, 'Jane');
+    SELECT * FROM users1 INTERSECT SELECT * FROM users2;
+    SELECT * FROM users1 EXCEPT SELECT * FROM users2;
+    SELECT * FROM users1 UNION SELECT * FROM users2;
+    SELECT DISTINCT name FROM users1 UNION ALL SELECT name FROM users2;
+    ";
+    let mut result = run_sql(&mut database, sql);
+    assert!(result.iter().all(|result| result.is_ok()));
+    let union_all = result.pop().unwrap().unwrap().unwrap();
+    assert_eq!(union_all.len(), 4);
+    let union = result.pop().unwrap().unwrap().unwrap();
+    let expected_union = vec![
+        Row(vec![Value::Null, Value::Null]),
+        Row(vec![Value::Integer(1), Value::Text("John".to_string())]),
+        Row(vec![Value::Integer(2), Value::Text("Jane".to_string())]),
+    ];
+    assert_eq!(union.len(), expected_union.len());
+    assert_eq_table_rows_unordered(expected_union, union);
+    let except = result.pop().unwrap().unwrap().unwrap();
+    let expected_except = vec![Row(vec![
+        Value::Integer(1),
+        Value::Text("John".to_string()),
+    ])];
+    assert_eq!(except.len(), expected_except.len());
+    assert_eq_table_rows(expected_except, except);
+    let intersect = result.pop().unwrap().unwrap().unwrap();
+    assert_eq!(intersect.len(), 1);
+    assert_eq_table_rows(vec![Row(vec![Value::Null, Value::Null])], intersect);
+}
+
+#[test]
+fn test_set_operators_multi_column_order_by() {
+    let mut database = Database::new();
+    let sql = "
+    CREATE TABLE t (
+        a INTEGER,
+        b TEXT
+    );
+    CREATE TABLE s (
+        a INTEGER,
+        b TEXT
+    );
+    INSERT INTO t (a, b) VALUES (2, 'x'), (1, 'y'), (3, 'x');
+    INSERT INTO s (a, b) VALUES (2, 'z'), (1, 'a'), (2, 'x');
+    SELECT a, b FROM t UNION SELECT a, b FROM s ORDER BY a ASC, b DESC;
+    SELECT b, a FROM t UNION ALL SELECT b, a FROM s ORDER BY b DESC, a ASC;
+    ";
+    let mut result = run_sql(&mut database, sql);
+    assert!(result.iter().all(|result| result.is_ok()));
+
+    // The first key is the column named by the ORDER BY term, not the first selected column.
+    let expected = vec![
+        Row(vec![Value::Text("z".to_string()), Value::Integer(2)]),
+        Row(vec![Value::Text("y".to_string()), Value::Integer(1)]),
+        Row(vec![Value::Text("x".to_string()), Value::Integer(2)]),
+        Row(vec![Value::Text("x".to_string()), Value::Integer(2)]),
+        Row(vec![Value::Text("x".to_string()), Value::Integer(3)]),
+        Row(vec![Value::Text("a".to_string()), Value::Integer(1)]),
+    ];
+    let rows = result.pop().unwrap().unwrap().unwrap();
+    assert_eq!(expected, rows);
+
+    // Ties on `a` are broken by `b` in descending order.
+    let expected = vec![
+        Row(vec![Value::Integer(1), Value::Text("y".to_string())]),
+        Row(vec![Value::Integer(1), Value::Text("a".to_string())]),
+        Row(vec![Value::Integer(2), Value::Text("z".to_string())]),
+        Row(vec![Value::Integer(2), Value::Text("x".to_string())]),
+        Row(vec![Value::Integer(3), Value::Text("x".to_string())]),
+    ];
+    let rows = result.pop().unwrap().unwrap().unwrap();
+    assert_eq!(expected, rows);
+}
+
+#[test]
+fn test_set_operators_order_by_aliased_column() {
+    let mut database = Database::new();
+    let sql = "
+    CREATE TABLE users (
+        id INTEGER,
+        name TEXT
+    );
+    INSERT INTO users (id, name) VALUES (1, 'John'), (2, 'Jane'), (3, 'Jim');
+    SELECT id * 10 AS score, name FROM users WHERE id < 3 UNION SELECT id * 10 AS score, name FROM users WHERE id > 1 ORDER BY score DESC;
+    SELECT id * 10 AS score FROM users UNION SELECT id * 10 AS score FROM users ORDER BY id * 10 ASC LIMIT 1;
+    SELECT id AS score FROM users UNION SELECT id AS score FROM users ORDER BY missing;
+    ";
+    let mut result = run_sql(&mut database, sql);
+    assert_eq!(
+        result.pop().unwrap(),
+        Err("Execution Error with statement starting on line 9 \n Error: ORDER BY term `missing` does not match any column in the result set".to_string())
+    );
+    let expected = vec![Row(vec![Value::Integer(10)])];
+    assert_eq_table_rows(expected, result.pop().unwrap().unwrap().unwrap());
+    let expected = vec![
+        Row(vec![Value::Integer(30), Value::Text("Jim".to_string())]),
+        Row(vec![Value::Integer(20), Value::Text("Jane".to_string())]),
+        Row(vec![Value::Integer(10), Value::Text("John".to_string())]),
+    ];
+    assert_eq!(Ok(Some(expected)), result.pop().unwrap());
+}
+
+#[test]
+fn test_set_operators_only_require_matching_column_counts() {
+    let mut database = Database::new();
+    let sql = "
+    CREATE TABLE users (id INTEGER, name TEXT);
+    CREATE TABLE employees (employee_id INTEGER, full_name TEXT);
+    INSERT INTO users (id, name) VALUES (1, 'John'), (2, 'Jane');
+    INSERT INTO employees (employee_id, full_name) VALUES (2, 'Jane'), (3, 'Jim');
+    SELECT id, name FROM users UNION SELECT employee_id, full_name FROM employees ORDER BY id;
+    SELECT id AS user_id FROM users INTERSECT SELECT employee_id FROM employees ORDER BY user_id;
+    SELECT id FROM users UNION SELECT employee_id, full_name FROM employees;
+    ";
+    let mut result = run_sql(&mut database, sql);
+    assert_eq!(
+        result.pop().unwrap(),
+        Err("Parsing Error: Columns mismatch between SELECT statements in Union".to_string())
+    );
+    assert_eq!(
+        vec![Row(vec![Value::Integer(2)])],
+        result.pop().unwrap().unwrap().unwrap()
+    );
+    let expected = vec![
+        Row(vec![Value::Integer(1), Value::Text("John".to_string())]),
+        Row(vec![Value::Integer(2), Value::Text("Jane".to_string())]),
+        Row(vec![Value::Integer(3), Value::Text("Jim".to_string())]),
+    ];
+    assert_eq!(expected, result.pop().unwrap().unwrap().unwrap());
+    assert!(result.iter().all(|result| result.is_ok()));
+}
@@ -2,7 +2,7 @@ use mollycache::db::database::Database;
 use mollycache::db::table::core::{row::Row, value::Value};
 use mollycache::interpreter::run_sql;
 
-use crate::common::assert_eq_run_sql_unordered;
+use crate::common::{assert_eq_run_sql, assert_eq_run_sql_unordered};
 
 #[test]
 fn test_transaction() {
@@ -472,8 +472,8 @@ fn test_incorrect_order_of_transaction_statements() {
     ";
     let result = run_sql(&mut database, sql);
     let expected = vec![
-        Err("Execution Error with statement starting on line 2 \n Error: No transaction is currently active".to_string()),
-        Err("Execution Error with statement starting on line 3 \n Error: No transaction is currently active".to_string()),
+        Err("Execution Error with statement starting on line 2 \n Error: cannot commit - no transaction is active".to_string()),
+        Err("Execution Error with statement starting on line 3 \n Error: cannot rollback - no transaction is active".to_string()),
         Ok(None),
         Err("Execution Error with statement starting on line 5 \n Error: Nested transactions are not allowed".to_string()),
         Ok(None),
@@ -565,3 +565,260 @@ fn test_transactions_with_multiple_tables() {
     ];
     assert_eq_run_sql_unordered(expected, result);
 }
+
+#[test]
+fn test_deferred_unique_violation_fails_at_commit() {
+    let mut database = Database::new();
+    let sql = "
+    CREATE TABLE users (
+        id INTEGER,
+        email TEXT UNIQUE DEFERRABLE INITIALLY DEFERRED
+    );
+    INSERT INTO users (id, email) VALUES (1, 'a@b.com');
+    BEGIN;
+        INSERT INTO users (id, email) VALUES (2, 'a@b.com');
+        UPDATE users SET email = 'c@d.com' WHERE id = 2;
+    COMMIT;
+    BEGIN;
+        INSERT INTO users (id, email) VALUES (3, 'c@d.com');
+        INSERT INTO users (id, email) VALUES (4, 'e@f.com');
+    COMMIT;
+    COMMIT;
+    SELECT * FROM users;
+    INSERT INTO users (id, email) VALUES (5, 'a@b.com');
+    ";
+    let mut result = run_sql(&mut database, sql);
+    assert_eq!(
+        result.pop().unwrap(),
+        Err("Execution Error with statement starting on line 17 \n Error: UNIQUE constraint failed: users.email".to_string())
+    );
+    // The failed COMMIT rolled back every statement of its transaction.
+    let expected = vec![
+        Row(vec![Value::Integer(1), Value::Text("a@b.com".to_string())]),
+        Row(vec![Value::Integer(2), Value::Text("c@d.com".to_string())]),
+    ];
+    assert_eq!(result.pop().unwrap(), Ok(Some(expected)));
+    assert_eq!(
+        result.pop().unwrap(),
+        Err("Execution Error with statement starting on line 15 \n Error: cannot commit - no transaction is active".to_string())
+    );
+    assert_eq!(
+        result.pop().unwrap(),
+        Err("Execution Error with statement starting on line 14 \n Error: UNIQUE constraint failed: users.email".to_string())
+    );
+    assert!(result.iter().all(|result| result.is_ok()));
+}
+
+#[test]
+fn test_commit_and_rollback_without_a_transaction() {
+    let mut database = Database::new();
+    let sql = "
+    CREATE TABLE users (id INTEGER);
+    COMMIT;
+    ROLLBACK;
+    BEGIN;
+    INSERT INTO users (id) VALUES (1);
+    COMMIT;
+    COMMIT;
+    ROLLBACK;
+    SELECT * FROM users;
+    ";
+    let result = run_sql(&mut database, sql);
+    let expected = vec![
+        Ok(None),
+        Err("Execution Error with statement starting on line 3 \n Error: cannot commit - no transaction is active".to_string()),
+        Err("Execution Error with statement starting on line 4 \n Error: cannot rollback - no transaction is active".to_string()),
+        Ok(None),
+        Ok(None),
+        Ok(None),
+        Err("Execution Error with statement starting on line 8 \n Error: cannot commit - no transaction is active".to_string()),
+        Err("Execution Error with statement starting on line 9 \n Error: cannot rollback - no transaction is active".to_string()),
+        Ok(Some(vec![Row(vec![Value::Integer(1)])])),
+    ];
+    assert_eq_run_sql(expected, result);
+}
+
+#[test]
+fn test_commit_can_restore_insertion_order() {
+    let sql = "
+    CREATE TABLE users (id INTEGER);
+    BEGIN;
+    INSERT INTO users (id) VALUES (1), (2), (3), (4);
+    DELETE FROM users WHERE id = 2;
+    INSERT INTO users (id) VALUES (5);
+    COMMIT;
+    SELECT * FROM users;
+    ";
+    let rows = |ids: Vec<i64>| {
+        Ok(Some(
+            ids.into_iter()
+                .map(|id| Row(vec![Value::Integer(id)]))
+                .collect::<Vec<Row>>(),
+        ))
+    };
+
+    // Deleting swapped the last row into the place of the deleted one.
+    let mut database = Database::new();
+    let mut result = run_sql(&mut database, sql);
+    assert_eq!(result.pop().unwrap(), rows(vec![1, 4, 3, 5]));
+
+    let mut database = Database::new();
+    database.restore_insertion_order_on_commit = true;
+    let mut result = run_sql(&mut database, sql);
+    assert!(result.iter().all(|result| result.is_ok()));
+    assert_eq!(result.pop().unwrap(), rows(vec![1, 3, 4, 5]));
+
+    // The deleted row is gone for good once committed.
+    let result = run_sql(
+        &mut database,
+        "INSERT INTO users (id) VALUES (6); SELECT * FROM users;",
+    );
+    assert_eq!(result[1], rows(vec![1, 3, 4, 5, 6]));
+}
+
+#[test]
+fn test_rollback_restores_the_state_before_begin() {
+    let mut database = Database::new();
+    let setup = "
+    CREATE TABLE users (id INTEGER PRIMARY KEY, name TEXT, age INTEGER);
+    CREATE TABLE orders (id INTEGER, user_id INTEGER);
+    CREATE INDEX idx_users_age ON users (age);
+    INSERT INTO users (id, name, age) VALUES (1, 'a', 20), (2, 'b', 30), (3, 'c', 40);
+    INSERT INTO orders (id, user_id) VALUES (1, 1), (2, 3);
+    ";
+    let result = run_sql(&mut database, setup);
+    assert!(result.iter().all(|result| result.is_ok()));
+    let before = database.dump().unwrap();
+
+    let sql = "
+    BEGIN;
+    UPDATE users SET age = 60 WHERE id > 1;
+    DELETE FROM users WHERE id = 1;
+    INSERT INTO users (id, name, age) VALUES (4, 'd', 50);
+    UPDATE users SET name = 'e' WHERE id = 2;
+    ALTER TABLE users ADD COLUMN email TEXT;
+    ALTER TABLE users RENAME COLUMN name TO full_name;
+    DROP INDEX idx_users_age;
+    ALTER TABLE orders RENAME TO purchases;
+    DELETE FROM purchases;
+    CREATE TABLE logs (id INTEGER);
+    INSERT INTO logs (id) VALUES (1);
+    DROP TABLE logs;
+    ROLLBACK;
+    ";
+    let result = run_sql(&mut database, sql);
+    assert!(result.iter().all(|result| result.is_ok()));
+    assert_eq!(database.dump().unwrap(), before);
+    for table_name in ["users", "orders"] {
+        let table = database.get_table_mut(table_name).unwrap();
+        assert!(
+            table
+                .get_row_stacks_mut()
+                .iter()
+                .all(|row_stack| row_stack.stack.len() == 1)
+        );
+    }
+
+    // The restored tables keep working, and another rollback errors.
+    let sql = "
+    SELECT name FROM users WHERE age = 30;
+    SELECT * FROM purchases;
+    ROLLBACK;
+    ";
+    let result = run_sql(&mut database, sql);
+    let expected = vec![
+        Ok(Some(vec![Row(vec![Value::Text("b".to_string())])])),
+        Err("Execution Error with statement starting on line 3 \n Error: Table `purchases` does not exist".to_string()),
+        Err("Execution Error with statement starting on line 4 \n Error: cannot rollback - no transaction is active".to_string()),
+    ];
+    assert_eq_run_sql(expected, result);
+}
+
+#[test]
+fn test_rollback_to_savepoint_keeps_the_savepoint() {
+    let mut database = Database::new();
+    let sql = "
+    CREATE TABLE users (id INTEGER, name TEXT);
+    INSERT INTO users (id, name) VALUES (1, 'John');
+    BEGIN;
+        SAVEPOINT sp1;
+        UPDATE users SET name = 'Johnny' WHERE id = 1;
+        INSERT INTO users (id, name) VALUES (2, 'Jane');
+        SAVEPOINT sp2;
+        DELETE FROM users WHERE id = 1;
+        INSERT INTO users (id, name) VALUES (3, 'Jim');
+        SELECT * FROM users;
+        ROLLBACK TO sp1;
+        SELECT * FROM users;
+        ROLLBACK TO sp2;
+        INSERT INTO users (id, name) VALUES (4, 'Jack');
+        ROLLBACK TO SAVEPOINT sp1;
+        INSERT INTO users (id, name) VALUES (5, 'Jill');
+    COMMIT;
+    SELECT * FROM users;
+    ";
+    let result = run_sql(&mut database, sql);
+    let user = |id: i64, name: &str| Row(vec![Value::Integer(id), Value::Text(name.to_string())]);
+    let expected = vec![
+        Ok(None),
+        Ok(None),
+        Ok(None),
+        Ok(None),
+        Ok(None),
+        Ok(None),
+        Ok(None),
+        Ok(None),
+        Ok(None),
+        Ok(Some(vec![user(2, "Jane"), user(3, "Jim")])),
+        Ok(None),
+        Ok(Some(vec![user(1, "John")])),
+        // sp2 was after sp1, so it is gone.
+        Err("Execution Error with statement starting on line 14 \n Error: Savepoint `sp2` does not exist".to_string()),
+        Ok(None),
+        Ok(None),
+        Ok(None),
+        Ok(None),
+        Ok(Some(vec![user(1, "John"), user(5, "Jill")])),
+    ];
+    assert_eq_run_sql_unordered(expected, result);
+}
+
+#[test]
+fn test_commit_checks_only_the_rows_changed_by_the_transaction() {
+    let mut database = Database::new();
+    let sql = "
+    CREATE TABLE users (
+        id INTEGER,
+        email TEXT UNIQUE DEFERRABLE INITIALLY DEFERRED
+    );
+    INSERT INTO users (id, email) VALUES (1, 'a'), (2, 'b'), (3, 'c'), (4, 'd');
+    BEGIN;
+        UPDATE users SET email = 'x' WHERE id = 4;
+        DELETE FROM users WHERE id = 1;
+        INSERT INTO users (id, email) VALUES (5, 'a');
+    COMMIT;
+    BEGIN;
+        UPDATE users SET email = 'b' WHERE id = 5;
+        DELETE FROM users WHERE id = 2;
+        INSERT INTO users (id, email) VALUES (6, 'c');
+    COMMIT;
+    SELECT * FROM users;
+    ";
+    let mut result = run_sql(&mut database, sql);
+    let user = |id: i64, email: &str| Row(vec![Value::Integer(id), Value::Text(email.to_string())]);
+    assert_eq_run_sql_unordered(
+        vec![Ok(Some(vec![
+            user(2, "b"),
+            user(3, "c"),
+            user(4, "x"),
+            user(5, "a"),
+        ]))],
+        vec![result.pop().unwrap()],
+    );
+    // The inserted row conflicts with a row from before the transaction.
+    assert_eq!(
+        result.pop().unwrap(),
+        Err("Execution Error with statement starting on line 16 \n Error: UNIQUE constraint failed: users.email".to_string())
+    );
+    assert!(result.iter().all(|result| result.is_ok()));
+}